@@ -0,0 +1,214 @@
+//  ( /   @ @    ()  Removes ANSI escape sequences from formatted output
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+const ESC: u8 = 0x1B;
+const BEL: u8 = 0x07;
+
+// Where the stripping state machine is between write_str() calls
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    Text,            // Plain text that is forwarded
+    Esc,             // Just seen ESC
+    EscIntermediate, // ESC followed by intermediate bytes (e.g. `ESC ( B`)
+    Csi,             // Inside `ESC [ ... final-byte`
+    Osc,             // Inside `ESC ] ... BEL` or `ESC ] ... ESC \`
+    OscEsc,          // ESC seen inside OSC, maybe the start of ST
+}
+
+/// Impl of [fmt::Write] that removes ANSI escape sequences before forwarding to inner stream.
+///
+/// Removed are CSI sequences (`ESC [` params final-byte, this includes SGR colors), OSC
+/// sequences terminated by BEL or `ESC \` and bare `ESC` sequences like `ESC ( B`. A sequence
+/// may be split across several `write_str` calls. A malformed sequence (a byte that is not
+/// allowed inside it) is dropped up to that byte, the byte itself is treated as text.
+/// A sequence that is still unterminated when the input ends is dropped.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 16];
+/// let mut w = stackfmt::AnsiStripWriter::new(stackfmt::WriteTo::new(&mut buf));
+/// write!(w, "\x1b[31mred\x1b[0m").unwrap();
+/// assert_eq!(w.into_inner().as_str(), "red");
+/// ```
+pub struct AnsiStripWriter<W: fmt::Write> {
+    inner: W,
+    state: State,
+}
+
+impl<W: fmt::Write> AnsiStripWriter<W> {
+    /// Creates stream forwarding plain text to `inner`.
+    pub fn new(inner: W) -> Self {
+        AnsiStripWriter {
+            inner,
+            state: State::Text,
+        }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for AnsiStripWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let raw_s = s.as_bytes();
+        let mut text_start = 0; // Start of the text run to forward if state is Text
+        let mut i = 0;
+
+        while i < raw_s.len() {
+            let b = raw_s[i];
+            match self.state {
+                State::Text => {
+                    if b == ESC {
+                        self.inner.write_str(&s[text_start..i])?;
+                        self.state = State::Esc;
+                    }
+                }
+                State::Esc => match b {
+                    b'[' => self.state = State::Csi,
+                    b']' => self.state = State::Osc,
+                    0x20..=0x2F => self.state = State::EscIntermediate,
+                    0x30..=0x7E => {
+                        self.state = State::Text;
+                        text_start = i + 1;
+                    }
+                    _ => {
+                        // malformed, process this byte as text
+                        self.state = State::Text;
+                        text_start = i;
+                        continue;
+                    }
+                },
+                State::EscIntermediate | State::Csi => {
+                    let (keep, last) = if self.state == State::Csi {
+                        (0x20..=0x3F, 0x40..=0x7E)
+                    } else {
+                        (0x20..=0x2F, 0x30..=0x7E)
+                    };
+                    if last.contains(&b) {
+                        self.state = State::Text;
+                        text_start = i + 1;
+                    } else if !keep.contains(&b) {
+                        // malformed, process this byte as text
+                        self.state = State::Text;
+                        text_start = i;
+                        continue;
+                    }
+                }
+                State::Osc => {
+                    if b == BEL {
+                        self.state = State::Text;
+                        text_start = i + 1;
+                    } else if b == ESC {
+                        self.state = State::OscEsc;
+                    }
+                }
+                State::OscEsc => {
+                    if b == b'\\' {
+                        self.state = State::Text;
+                        text_start = i + 1;
+                    } else {
+                        // ESC not followed by `\` terminates OSC and starts a new sequence
+                        self.state = State::Esc;
+                        continue;
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        if self.state == State::Text {
+            self.inner.write_str(&s[text_start..])?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes formatted string with ANSI escape sequences removed into the buffer truncating if
+/// needed.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_strip_ansi(&mut buf, format_args!("\x1b[1;32m{}\x1b[0m", "OK"));
+/// assert_eq!(s, "OK");
+/// ```
+pub fn fmt_strip_ansi<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = AnsiStripWriter::new(WriteTo::new(buffer));
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnsiStripWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn strip_colored_text() {
+        let mut buf = [0u8; 64];
+        let formatted = super::fmt_strip_ansi(
+            &mut buf,
+            format_args!("\x1b[1;31mERROR\x1b[0m: \x1b[38;5;208m{}\x1b[m done", 42),
+        );
+        assert_eq!(formatted, "ERROR: 42 done");
+    }
+
+    #[test]
+    fn strip_split_sequence() {
+        let mut buf = [0u8; 64];
+        let mut w = AnsiStripWriter::new(WriteTo::new(&mut buf));
+        w.write_str("a\x1b").unwrap();
+        w.write_str("[3").unwrap();
+        w.write_str("1mb\x1b[").unwrap();
+        w.write_str("0mc").unwrap();
+        assert_eq!(w.into_inner().as_str(), "abc");
+    }
+
+    #[test]
+    fn strip_bare_esc_sequences() {
+        let mut buf = [0u8; 64];
+        let formatted = super::fmt_strip_ansi(&mut buf, format_args!("\x1b(Ba\x1b7b\x1b8"));
+        assert_eq!(formatted, "ab");
+    }
+
+    #[test]
+    fn strip_malformed_sequence() {
+        let mut buf = [0u8; 64];
+        // newline is not allowed inside CSI, so the sequence is abandoned
+        let formatted = super::fmt_strip_ansi(&mut buf, format_args!("a\x1b[12\nb\x1b\u{20AC}c"));
+        assert_eq!(formatted, "a\nb\u{20AC}c");
+    }
+
+    #[test]
+    fn strip_osc_sequences() {
+        let mut buf = [0u8; 64];
+        let formatted = super::fmt_strip_ansi(
+            &mut buf,
+            format_args!("\x1b]0;title \u{20AC}\x07text\x1b]8;;http://x\x1b\\link"),
+        );
+        assert_eq!(formatted, "textlink");
+    }
+
+    #[test]
+    fn strip_unterminated_dropped() {
+        let mut buf = [0u8; 64];
+        let formatted = super::fmt_strip_ansi(&mut buf, format_args!("text\x1b[31"));
+        assert_eq!(formatted, "text");
+    }
+
+    #[test]
+    fn strip_truncate() {
+        let mut buf = [0u8; 4];
+        let formatted = super::fmt_strip_ansi(&mut buf, format_args!("\x1b[31mHello\x1b[0m"));
+        assert_eq!(formatted, "Hell");
+    }
+}
@@ -0,0 +1,136 @@
+//  ( /   @ @    ()  Non-ASCII chars escaped as \uXXXX
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+
+/// Impl of [fmt::Write] that passes ASCII through and writes every other char as a JSON
+/// style `\uXXXX` escape, chars above U+FFFF as a UTF-16 surrogate pair `\uXXXX\uXXXX`.
+///
+/// A backslash is written as `\\`, so the original text can be restored. Escapes are written
+/// with [WriteWhole], after truncation an escape is either fully present or absent.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 32];
+/// let mut w = stackfmt::AsciiEscapeWriter::new(stackfmt::WriteTo::new(&mut buf));
+/// write!(w, "caf\u{E9} {}\u{B0}C", 21).unwrap();
+/// assert_eq!(w.into_inner().as_str(), "caf\\u00e9 21\\u00b0C");
+/// ```
+pub struct AsciiEscapeWriter<W: WriteWhole> {
+    inner: W,
+}
+
+impl<W: WriteWhole> AsciiEscapeWriter<W> {
+    /// Creates stream escaping non-ASCII chars into `inner`.
+    pub fn new(inner: W) -> Self {
+        AsciiEscapeWriter { inner }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_escape(&mut self, ch: char) -> fmt::Result {
+        if ch == '\\' {
+            return self.inner.write_whole("\\\\");
+        }
+        let mut units = [0u16; 2];
+        let mut buf = [0u8; 12];
+        let mut len = 0;
+        for unit in ch.encode_utf16(&mut units).iter() {
+            buf[len] = b'\\';
+            buf[len + 1] = b'u';
+            for i in 0..4 {
+                let nibble = (unit >> (12 - 4 * i)) & 0xF;
+                buf[len + 2 + i] = b"0123456789abcdef"[nibble as usize];
+            }
+            len += 6;
+        }
+        // only ASCII is written to buf
+        self.inner
+            .write_whole(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+    }
+}
+
+impl<W: WriteWhole> fmt::Write for AsciiEscapeWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, ch) in s.char_indices() {
+            if ch.is_ascii() && ch != '\\' {
+                continue;
+            }
+            self.inner.write_str(&s[run_start..i])?;
+            self.write_escape(ch)?;
+            run_start = i + ch.len_utf8();
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+impl<W: WriteWhole> WriteWhole for AsciiEscapeWriter<W> {}
+
+/// Writes formatted text into the buffer with non-ASCII chars escaped as `\uXXXX`,
+/// truncating if needed without cutting an escape.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_ascii_escaped(&mut buf, format_args!("{}\u{2603}", "snow"));
+/// assert_eq!(s, "snow\\u2603");
+/// ```
+pub fn fmt_ascii_escaped<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = AsciiEscapeWriter::new(WriteTo::new(buffer));
+    let _ = fmt::write(&mut w, args);
+    w.into_inner().as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_ascii_escaped, AsciiEscapeWriter};
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn ascii_escape_mixed() {
+        let mut buf = [0u8; 64];
+        let s = fmt_ascii_escaped(&mut buf, format_args!("{} \u{416}{}", "na\u{EF}ve", 1));
+        assert_eq!(s, "na\\u00efve \\u04161");
+        let s = fmt_ascii_escaped(&mut buf, format_args!("plain ascii {}", 42));
+        assert_eq!(s, "plain ascii 42");
+    }
+
+    #[test]
+    fn ascii_escape_surrogate_pair() {
+        let mut buf = [0u8; 64];
+        let s = fmt_ascii_escaped(&mut buf, format_args!("ok \u{1F600}!"));
+        assert_eq!(s, "ok \\ud83d\\ude00!");
+    }
+
+    #[test]
+    fn ascii_escape_backslash() {
+        let mut buf = [0u8; 64];
+        let s = fmt_ascii_escaped(&mut buf, format_args!("{}", r"C:\u00e9"));
+        assert_eq!(s, r"C:\\u00e9");
+    }
+
+    #[test]
+    fn ascii_escape_truncation() {
+        let mut buf = [0u8; 8];
+        let s = fmt_ascii_escaped(&mut buf, format_args!("abc\u{E9}def"));
+        assert_eq!(s, "abc");
+        let mut buf = [0u8; 12];
+        let s = fmt_ascii_escaped(&mut buf, format_args!("ab\u{1F600}"));
+        assert_eq!(s, "ab");
+        // nothing more is written after an escape was dropped
+        let mut buf = [0u8; 10];
+        let mut w = AsciiEscapeWriter::new(WriteTo::new(&mut buf));
+        write!(w, "abcdefg\u{E9}").unwrap();
+        write!(w, "xy").unwrap();
+        let out = w.into_inner();
+        assert!(out.is_truncated());
+        assert_eq!(out.as_str(), "abcdefg");
+    }
+}
@@ -0,0 +1,247 @@
+//  ( /   @ @    ()  Formatted output into an async byte sink through a small buffer
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use embedded_io_async::Write;
+
+// Keeps bytes `skip..skip + buf.len()` of the output, fails once the buffer is full so the
+// formatting stops early
+struct Window<'b> {
+    buf: &'b mut [u8],
+    skip: usize,
+    len: usize,
+}
+
+impl<'b> fmt::Write for Window<'b> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut bytes = s.as_bytes();
+        let skipped = self.skip.min(bytes.len());
+        self.skip -= skipped;
+        bytes = &bytes[skipped..];
+        let n = bytes.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+        if self.len == self.buf.len() {
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+/// Formats `args` into an `embedded_io_async::Write` sink in pieces of `scratch` size, so the
+/// whole output never has to fit into a buffer.
+///
+/// As [fmt::write] is synchronous and can not wait for the sink in the middle, the output
+/// is rendered again for every piece: the pass `k` skips the bytes already sent and stops
+/// formatting as soon as `scratch` is full. An output of `n` bytes costs about
+/// `n² / (2 × scratch.len())` bytes of formatting, so the scratch should be large relative
+/// to the usual message. The arguments must show the same text every time they are
+/// formatted. The sink gets a byte stream: a piece may end in the middle of a UTF-8 char.
+/// Nothing is written with an empty `scratch`, a sink accepting 0 bytes ends the output.
+///
+/// ```
+/// # fn block_on<F: core::future::Future>(fut: F) -> F::Output {
+/// #     let mut fut = core::pin::pin!(fut);
+/// #     let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+/// #     loop {
+/// #         if let core::task::Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+/// #             return output;
+/// #         }
+/// #     }
+/// # }
+/// use core::convert::Infallible;
+///
+/// struct Uart(usize);
+/// impl embedded_io_async::ErrorType for Uart {
+///     type Error = Infallible;
+/// }
+/// impl embedded_io_async::Write for Uart {
+///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Infallible> {
+///         self.0 += buf.len();
+///         Ok(buf.len())
+///     }
+/// }
+///
+/// let mut uart = Uart(0);
+/// let mut scratch = [0u8; 8];
+/// let (id, state) = (17, "ready");
+/// block_on(async {
+///     let sink = &mut uart;
+///     stackfmt::fmt_to_async(sink, &mut scratch, format_args!("node {} is {}\r\n", id, state))
+///         .await
+/// })
+/// .unwrap();
+/// assert_eq!(uart.0, 18);
+/// ```
+pub async fn fmt_to_async<W: Write>(
+    sink: &mut W,
+    scratch: &mut [u8],
+    args: fmt::Arguments<'_>,
+) -> Result<(), W::Error> {
+    if scratch.is_empty() {
+        return Ok(());
+    }
+    let mut sent = 0;
+    loop {
+        let mut window = Window {
+            buf: &mut *scratch,
+            skip: sent,
+            len: 0,
+        };
+        let _ = fmt::write(&mut window, args);
+        let len = window.len;
+
+        let mut pos = 0;
+        while pos < len {
+            match sink.write(&scratch[pos..len]).await? {
+                0 => return Ok(()),
+                n => pos += n,
+            }
+        }
+        sent += len;
+        if len < scratch.len() {
+            return sink.flush().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fmt_to_async;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use embedded_io_async::{ErrorKind, ErrorType, Write};
+
+    // Runs the future to completion, the mock sink wakes up immediately
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    // Returns Pending once before it is ready
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: core::pin::Pin<&mut Self>, _: &mut Context) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            Poll::Pending
+        }
+    }
+
+    // Accepts at most `per_call` bytes a call, fails after `fail_after` bytes
+    struct MockSink {
+        data: [u8; 128],
+        len: usize,
+        per_call: usize,
+        calls: usize,
+        flushes: usize,
+        fail_after: Option<usize>,
+    }
+
+    impl MockSink {
+        fn new(per_call: usize, fail_after: Option<usize>) -> Self {
+            MockSink {
+                data: [0u8; 128],
+                len: 0,
+                per_call,
+                calls: 0,
+                flushes: 0,
+                fail_after,
+            }
+        }
+
+        fn text(&self) -> &str {
+            core::str::from_utf8(&self.data[..self.len]).unwrap()
+        }
+    }
+
+    // Bytes sent before the failure
+    #[derive(PartialEq, Eq, Debug)]
+    struct Failed(usize);
+
+    impl embedded_io_async::Error for Failed {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    impl ErrorType for MockSink {
+        type Error = Failed;
+    }
+
+    impl Write for MockSink {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Failed> {
+            YieldOnce(false).await;
+            self.calls += 1;
+            if self.fail_after.is_some_and(|n| self.len >= n) {
+                return Err(Failed(self.len));
+            }
+            let n = buf.len().min(self.per_call);
+            self.data[self.len..self.len + n].copy_from_slice(&buf[..n]);
+            self.len += n;
+            Ok(n)
+        }
+
+        async fn flush(&mut self) -> Result<(), Failed> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn async_few_bytes_per_call() {
+        let mut sink = MockSink::new(3, None);
+        let mut scratch = [0u8; 8];
+        let (t, unit) = (-12, "\u{B0}C");
+        block_on(fmt_to_async(
+            &mut sink,
+            &mut scratch,
+            format_args!("temp={}{} ok", t, unit),
+        ))
+        .unwrap();
+        assert_eq!(sink.text(), "temp=-12\u{B0}C ok");
+        assert_eq!(sink.calls, 5); // 3+3+2, 3+3
+        assert_eq!(sink.flushes, 1);
+    }
+
+    #[test]
+    fn async_exact_multiple_of_scratch() {
+        let mut sink = MockSink::new(64, None);
+        let mut scratch = [0u8; 4];
+        let n = 12345678;
+        block_on(fmt_to_async(&mut sink, &mut scratch, format_args!("{}", n))).unwrap();
+        assert_eq!(sink.text(), "12345678");
+        assert_eq!(sink.calls, 2);
+
+        let mut sink = MockSink::new(64, None);
+        block_on(fmt_to_async(&mut sink, &mut [], format_args!("{}", n))).unwrap();
+        assert_eq!(sink.text(), "");
+    }
+
+    #[test]
+    fn async_sink_error() {
+        let mut sink = MockSink::new(2, Some(5));
+        let mut scratch = [0u8; 16];
+        let n = 42;
+        let result = block_on(fmt_to_async(
+            &mut sink,
+            &mut scratch,
+            format_args!("answer is {}", n),
+        ));
+        assert_eq!(result, Err(Failed(6)));
+        assert_eq!(sink.text(), "answer");
+        assert_eq!(sink.flushes, 0);
+    }
+}
@@ -0,0 +1,250 @@
+//  ( /   @ @    ()  AT commands for modems with quoted parameters
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [AtCommandWriter::finish].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AtError {
+    /// No command, a second command, or an argument of a query
+    OutOfOrder,
+    /// Argument has CR or LF
+    InvalidArgument,
+}
+
+impl fmt::Display for AtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            AtError::OutOfOrder => "AT command or argument out of order",
+            AtError::InvalidArgument => "CR or LF in AT command argument",
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Part {
+    Start,
+    Command,
+    Args,
+    Query,
+    Finished,
+}
+
+/// Writes an AT command line into a [WriteTo], like `AT+CMGS="+15550100",145\r`.
+///
+/// Arguments follow the command after `=` and are separated with commas: string arguments
+/// are quoted with the inner quotes doubled, raw arguments are written as given. The first
+/// error is remembered and reported by [finish](AtCommandWriter::finish).
+///
+/// ```
+/// use stackfmt::{AtCommandWriter, WriteTo};
+///
+/// let mut buf = [0u8; 64];
+/// let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+/// at.command("+CGDCONT").arg_int(1).arg_str("IP").arg_str("internet");
+/// assert_eq!(at.finish(), Ok(("AT+CGDCONT=1,\"IP\",\"internet\"\r", false)));
+/// ```
+pub struct AtCommandWriter<'a> {
+    out: WriteTo<'a>,
+    part: Part,
+    error: Option<AtError>,
+}
+
+impl<'a> AtCommandWriter<'a> {
+    /// Creates writer putting the command into `out`.
+    pub fn new(out: WriteTo<'a>) -> Self {
+        AtCommandWriter {
+            out,
+            part: Part::Start,
+            error: None,
+        }
+    }
+
+    /// Starts the command line with `AT` and `name`, like `+CSQ`.
+    pub fn command(&mut self, name: &str) -> &mut Self {
+        self.start(name, Part::Command);
+        self
+    }
+
+    /// Writes the query form `AT<name>?`, it has no arguments.
+    pub fn command_query(&mut self, name: &str) -> &mut Self {
+        if self.start(name, Part::Query) {
+            let _ = self.out.write_char('?');
+        }
+        self
+    }
+
+    /// Adds integer argument.
+    pub fn arg_int(&mut self, value: i64) -> &mut Self {
+        self.arg(format_args!("{}", value))
+    }
+
+    /// Adds quoted string argument, `"` inside is doubled.
+    pub fn arg_str(&mut self, value: &str) -> &mut Self {
+        if value.contains(['\r', '\n']) {
+            return self.fail(AtError::InvalidArgument);
+        }
+        self.arg(format_args!("\"{}\"", Quoted(value)))
+    }
+
+    /// Adds argument written as is, like a hex string or a bare `0`.
+    pub fn arg_raw(&mut self, value: &str) -> &mut Self {
+        if value.contains(['\r', '\n']) {
+            return self.fail(AtError::InvalidArgument);
+        }
+        self.arg(format_args!("{}", value))
+    }
+
+    /// Ends the command with `\r` and returns it with the flag if it did not fit.
+    ///
+    /// The command is ended only once, further calls return the same result.
+    pub fn finish(&mut self) -> Result<(&str, bool), AtError> {
+        if self.part == Part::Start {
+            self.fail(AtError::OutOfOrder);
+        }
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.part != Part::Finished {
+            let _ = self.out.write_char('\r');
+            self.part = Part::Finished;
+        }
+        Ok((self.out.to_str(), self.out.is_truncated()))
+    }
+
+    // Writes `AT<name>`, false if it is not allowed
+    fn start(&mut self, name: &str, part: Part) -> bool {
+        if self.part != Part::Start {
+            self.fail(AtError::OutOfOrder);
+            return false;
+        }
+        if name.contains(['\r', '\n']) {
+            self.fail(AtError::InvalidArgument);
+            return false;
+        }
+        let _ = write!(self.out, "AT{}", name);
+        self.part = part;
+        true
+    }
+
+    fn arg(&mut self, value: fmt::Arguments) -> &mut Self {
+        let separator = match self.part {
+            Part::Command => '=',
+            Part::Args => ',',
+            _ => return self.fail(AtError::OutOfOrder),
+        };
+        let _ = write!(self.out, "{}{}", separator, value);
+        self.part = Part::Args;
+        self
+    }
+
+    // Keeps the first error
+    fn fail(&mut self, error: AtError) -> &mut Self {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        self
+    }
+}
+
+// Shows a string with `"` doubled
+struct Quoted<'s>(&'s str);
+
+impl<'s> fmt::Display for Quoted<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pieces = self.0.split('"');
+        if let Some(first) = pieces.next() {
+            f.write_str(first)?;
+        }
+        for piece in pieces {
+            f.write_str("\"\"")?;
+            f.write_str(piece)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtCommandWriter, AtError};
+    use crate::WriteTo;
+
+    #[test]
+    fn at_mixed_arguments() {
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("+QIOPEN")
+            .arg_int(1)
+            .arg_int(-2)
+            .arg_str("TCP")
+            .arg_raw("0A1F");
+        assert_eq!(at.finish(), Ok(("AT+QIOPEN=1,-2,\"TCP\",0A1F\r", false)));
+
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("");
+        assert_eq!(at.finish(), Ok(("AT\r", false)));
+
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command_query("+CREG");
+        assert_eq!(at.finish(), Ok(("AT+CREG?\r", false)));
+        assert_eq!(at.finish(), Ok(("AT+CREG?\r", false)));
+    }
+
+    #[test]
+    fn at_string_with_quotes_and_commas() {
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("+CMD")
+            .arg_int(1)
+            .arg_str("text with \"quotes\", commas");
+        assert_eq!(
+            at.finish(),
+            Ok(("AT+CMD=1,\"text with \"\"quotes\"\", commas\"\r", false))
+        );
+    }
+
+    #[test]
+    fn at_empty_string() {
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("+COPS").arg_str("").arg_str("\"");
+        assert_eq!(at.finish(), Ok(("AT+COPS=\"\",\"\"\"\"\r", false)));
+    }
+
+    #[test]
+    fn at_errors() {
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("+CMGS").arg_str("a\r\nAT+CFUN=0");
+        assert_eq!(at.finish(), Err(AtError::InvalidArgument));
+
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command_query("+CSQ").arg_int(1);
+        assert_eq!(at.finish(), Err(AtError::OutOfOrder));
+
+        let mut buf = [0u8; 64];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.arg_int(1);
+        assert_eq!(at.finish(), Err(AtError::OutOfOrder));
+    }
+
+    #[test]
+    fn at_overflow() {
+        let mut buf = [0u8; 16];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("+CMGS").arg_str("+15550100").arg_int(145);
+        assert_eq!(at.finish(), Ok(("AT+CMGS=\"+155501", true)));
+
+        // the final \r does not fit
+        let mut buf = [0u8; 9];
+        let mut at = AtCommandWriter::new(WriteTo::new(&mut buf));
+        at.command("+CFUN").arg_int(1);
+        assert_eq!(at.finish(), Ok(("AT+CFUN=1", true)));
+    }
+}
@@ -0,0 +1,242 @@
+//  ( /   @ @    ()  Crockford base32 encoding of binary data
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+use core::str::from_utf8_unchecked;
+
+// Crockford symbols for values 0..32 (no I, L, O, U)
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+// Check symbols for values 0..37, the 5 extra symbols are used only for the check
+const CHECK_ALPHABET: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+/// The longest group of symbols supported by [Base32Writer::group]
+pub const BASE32_MAX_GROUP: usize = 16;
+
+/// Streaming Crockford base32 encoder that writes symbols into a [fmt::Write] stream.
+///
+/// Bytes are fed with [Base32Writer::write_bytes] and packed 5 bits per symbol, the most
+/// significant bit first. The last symbol is padded with zero bits, no `=` padding is used.
+/// Grouped output is written whole group at a time via [WriteWhole], so when the
+/// destination is a [WriteTo] the truncation only happens on a group boundary.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let mut enc = stackfmt::Base32Writer::new(stackfmt::WriteTo::new(&mut buf))
+///     .group(4)
+///     .check_symbol(true);
+/// enc.write_bytes(&[0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]).unwrap();
+/// let w = enc.finish().unwrap();
+/// assert_eq!(w.as_str(), "04HM-ASW9-NF6Y-YM");
+/// ```
+pub struct Base32Writer<W: WriteWhole> {
+    inner: W,
+    bits: u16,    // Pending bits that are not yet encoded, right aligned
+    bits_len: u8, // Count of pending bits, always < 5 between calls
+    group: usize, // Symbols per group or 0 if grouping is disabled
+    pending: [u8; BASE32_MAX_GROUP + 2], // Hyphen + group symbols + check symbol
+    pending_len: usize, // Bytes used in `pending`
+    in_group: usize, // Symbols in the current group
+    started: bool, // If any group was already written (and next needs a hyphen)
+    check: Option<u8>, // Running value mod 37 if check symbol is requested
+}
+
+impl<W: WriteWhole> Base32Writer<W> {
+    /// Creates encoder without grouping and check symbol.
+    pub fn new(inner: W) -> Self {
+        Base32Writer {
+            inner,
+            bits: 0,
+            bits_len: 0,
+            group: 0,
+            pending: [0u8; BASE32_MAX_GROUP + 2],
+            pending_len: 0,
+            in_group: 0,
+            started: false,
+            check: None,
+        }
+    }
+
+    /// Separates every `n` symbols with a hyphen, 0 disables grouping.
+    ///
+    /// Panics if `n` is greater than [BASE32_MAX_GROUP].
+    pub fn group(mut self, n: usize) -> Self {
+        assert!(n <= BASE32_MAX_GROUP, "Base32Writer group is too long");
+        self.group = n;
+        self
+    }
+
+    /// Appends Crockford check symbol (the encoded number modulo 37) at the end.
+    pub fn check_symbol(mut self, enabled: bool) -> Self {
+        self.check = if enabled { Some(0) } else { None };
+        self
+    }
+
+    /// Encodes the next portion of the input.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> fmt::Result {
+        for &b in bytes {
+            self.bits = (self.bits << 8) | b as u16;
+            self.bits_len += 8;
+            while self.bits_len >= 5 {
+                self.bits_len -= 5;
+                let value = (self.bits >> self.bits_len) as u8 & 0x1F;
+                self.push_symbol(value)?;
+            }
+            self.bits &= (1 << self.bits_len) - 1;
+        }
+        Ok(())
+    }
+
+    /// Encodes the trailing bits and the check symbol and returns the inner stream.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        if self.bits_len > 0 {
+            let value = (self.bits << (5 - self.bits_len)) as u8 & 0x1F;
+            self.bits_len = 0;
+            self.push_symbol(value)?;
+        }
+        if let Some(check) = self.check {
+            self.pending[self.pending_len] = CHECK_ALPHABET[check as usize];
+            self.pending_len += 1;
+        }
+        self.flush_pending()?;
+        Ok(self.inner)
+    }
+
+    fn push_symbol(&mut self, value: u8) -> fmt::Result {
+        if let Some(check) = self.check {
+            self.check = Some(((check as u16 * 32 + value as u16) % 37) as u8);
+        }
+
+        if self.group == 0 {
+            let sym = [ALPHABET[value as usize]];
+            // the alphabet is ASCII
+            return self.inner.write_str(unsafe { from_utf8_unchecked(&sym) });
+        }
+
+        if self.in_group == self.group {
+            self.flush_pending()?;
+        }
+        if self.in_group == 0 && self.started {
+            self.pending[self.pending_len] = b'-';
+            self.pending_len += 1;
+        }
+        self.pending[self.pending_len] = ALPHABET[value as usize];
+        self.pending_len += 1;
+        self.in_group += 1;
+        Ok(())
+    }
+
+    fn flush_pending(&mut self) -> fmt::Result {
+        if self.pending_len == 0 {
+            return Ok(());
+        }
+        // pending contains only ASCII symbols and hyphens
+        let s = unsafe { from_utf8_unchecked(&self.pending[..self.pending_len]) };
+        self.inner.write_whole(s)?;
+        self.pending_len = 0;
+        self.in_group = 0;
+        self.started = true;
+        Ok(())
+    }
+}
+
+/// Encodes `bytes` with Crockford base32 into the buffer truncating if needed.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// assert_eq!(stackfmt::fmt_base32_crockford(&mut buf, b"foobar"), "CSQPYRK1E8");
+/// ```
+pub fn fmt_base32_crockford<'a>(buffer: &'a mut [u8], bytes: &[u8]) -> &'a str {
+    let mut enc = Base32Writer::new(WriteTo::new(buffer));
+    match enc.write_bytes(bytes) {
+        Ok(_) => match enc.finish() {
+            Ok(w) => w.as_str(),
+            Err(_) => "",
+        },
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base32Writer;
+    use crate::WriteTo;
+
+    fn encode<'a>(buf: &'a mut [u8], bytes: &[u8], group: usize, check: bool) -> &'a str {
+        let mut enc = Base32Writer::new(WriteTo::new(buf))
+            .group(group)
+            .check_symbol(check);
+        enc.write_bytes(bytes).unwrap();
+        enc.finish().unwrap().as_str()
+    }
+
+    #[test]
+    fn base32_known_vectors() {
+        let mut buf = [0u8; 32];
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b""), "");
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b"f"), "CR");
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b"fo"), "CSQG");
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b"foo"), "CSQPY");
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b"foob"), "CSQPYRG");
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b"fooba"), "CSQPYRK1");
+        assert_eq!(
+            super::fmt_base32_crockford(&mut buf, b"foobar"),
+            "CSQPYRK1E8"
+        );
+    }
+
+    #[test]
+    fn base32_device_ids() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            super::fmt_base32_crockford(&mut buf, &[0xFF; 8]),
+            "ZZZZZZZZZZZZY"
+        );
+        let uid96 = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        assert_eq!(
+            super::fmt_base32_crockford(&mut buf, &uid96),
+            "041061050R3GG28A1C60"
+        );
+    }
+
+    #[test]
+    fn base32_split_input() {
+        // bit packing must continue across write_bytes() calls
+        let mut buf = [0u8; 32];
+        let mut enc = Base32Writer::new(WriteTo::new(&mut buf));
+        enc.write_bytes(b"f").unwrap();
+        enc.write_bytes(b"oob").unwrap();
+        enc.write_bytes(b"ar").unwrap();
+        assert_eq!(enc.finish().unwrap().as_str(), "CSQPYRK1E8");
+    }
+
+    #[test]
+    fn base32_check_symbol() {
+        let mut buf = [0u8; 32];
+        assert_eq!(encode(&mut buf, b"f", 0, true), "CR1");
+        assert_eq!(encode(&mut buf, b"fooba", 0, true), "CSQPYRK1U");
+        assert_eq!(encode(&mut buf, b"foobar", 0, true), "CSQPYRK1E8R");
+        assert_eq!(encode(&mut buf, &[0], 0, true), "000");
+    }
+
+    #[test]
+    fn base32_grouping() {
+        let mut buf = [0u8; 32];
+        assert_eq!(encode(&mut buf, b"foobar", 4, false), "CSQP-YRK1-E8");
+        assert_eq!(encode(&mut buf, b"fooba", 4, false), "CSQP-YRK1");
+        assert_eq!(encode(&mut buf, b"foob", 5, true), "CSQPY-RGV");
+    }
+
+    #[test]
+    fn base32_truncate_on_group_boundary() {
+        let mut buf = [0u8; 11];
+        assert_eq!(encode(&mut buf, b"foobar", 4, false), "CSQP-YRK1");
+        let mut buf = [0u8; 8];
+        assert_eq!(encode(&mut buf, b"foobar", 4, false), "CSQP");
+        // no grouping: plain truncation
+        let mut buf = [0u8; 7];
+        assert_eq!(super::fmt_base32_crockford(&mut buf, b"foobar"), "CSQPYRK");
+    }
+}
@@ -0,0 +1,130 @@
+//  ( /   @ @    ()  Several messages packed into one buffer
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Formats `items` one after another into the buffer and returns the text of each item and
+/// whether some items were dropped.
+///
+/// An item that does not fit is dropped whole and so are the items after it, their texts
+/// are empty. See [fmt_batch_with] for a separator between items.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let (items, dropped) = stackfmt::fmt_batch(
+///     &mut buf,
+///     &[format_args!("T={}", 21), format_args!("H={}", 40), format_args!("P={}", 1013)],
+/// );
+/// assert_eq!(items, ["T=21", "H=40", "P=1013"]);
+/// assert!(!dropped);
+/// ```
+pub fn fmt_batch<'a, const N: usize>(
+    buffer: &'a mut [u8],
+    items: &[fmt::Arguments; N],
+) -> ([&'a str; N], bool) {
+    fmt_batch_with(buffer, "", items)
+}
+
+/// Same as [fmt_batch] but writes `separator` between items, the separator is not part of
+/// the item texts.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let (items, dropped) = stackfmt::fmt_batch_with(
+///     &mut buf,
+///     "\n",
+///     &[format_args!("ok {}", 1), format_args!("ok {}", 2)],
+/// );
+/// assert_eq!(items, ["ok 1", "ok 2"]);
+/// assert!(!dropped);
+/// assert_eq!(&buf[..9], b"ok 1\nok 2");
+/// ```
+pub fn fmt_batch_with<'a, const N: usize>(
+    buffer: &'a mut [u8],
+    separator: &str,
+    items: &[fmt::Arguments; N],
+) -> ([&'a str; N], bool) {
+    let mut ranges = [(0, 0); N];
+    let mut dropped = false;
+    let mut w = WriteTo::new(&mut *buffer);
+    for (i, args) in items.iter().enumerate() {
+        let start = w.written_bytes();
+        if i > 0 {
+            let _ = w.write_str(separator);
+        }
+        let item_start = w.written_bytes();
+        let _ = w.write_fmt(*args);
+        if w.is_truncated() {
+            w.truncate(start);
+            dropped = true;
+            break;
+        }
+        ranges[i] = (item_start, w.written_bytes());
+    }
+
+    let buffer: &'a [u8] = buffer;
+    // the ranges are formatted text of whole items
+    (
+        ranges.map(|(start, end)| unsafe { core::str::from_utf8_unchecked(&buffer[start..end]) }),
+        dropped,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_batch, fmt_batch_with};
+
+    #[test]
+    fn batch_all_fit() {
+        let mut buf = [0u8; 32];
+        let (items, dropped) = fmt_batch_with(
+            &mut buf,
+            ", ",
+            &[
+                format_args!("a={}", 1),
+                format_args!(""),
+                format_args!("c={}", 3),
+            ],
+        );
+        assert_eq!(items, ["a=1", "", "c=3"]);
+        assert!(!dropped);
+        assert_eq!(&buf[..10], b"a=1, , c=3");
+    }
+
+    #[test]
+    fn batch_third_dropped() {
+        let mut buf = [0u8; 12];
+        let (items, dropped) = fmt_batch_with(
+            &mut buf,
+            ";",
+            &[
+                format_args!("one {}", 1),
+                format_args!("two"),
+                format_args!("three {}", 3),
+                format_args!("4"),
+            ],
+        );
+        assert_eq!(items, ["one 1", "two", "", ""]);
+        assert!(dropped);
+        // exact fit of the last item is not dropped
+        let mut buf = [0u8; 6];
+        let (items, dropped) = fmt_batch(&mut buf, &[format_args!("abc"), format_args!("def")]);
+        assert_eq!(items, ["abc", "def"]);
+        assert!(!dropped);
+    }
+
+    #[test]
+    fn batch_empty() {
+        let mut buf = [0u8; 4];
+        let (items, dropped) = fmt_batch(&mut buf, &[]);
+        assert_eq!(items.len(), 0);
+        assert!(!dropped);
+        let mut empty = [0u8; 0];
+        let (items, dropped) = fmt_batch(&mut empty, &[format_args!("x")]);
+        assert_eq!(items, [""]);
+        assert!(dropped);
+    }
+}
@@ -0,0 +1,169 @@
+//  ( /   @ @    ()  Names of the bits set in a register or status word
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows the names of set flags from a (mask, name) table.
+///
+/// A flag is set when all the bits of its mask are set. Set flags are joined with `|` in
+/// table order, masks may overlap. Bits not covered by any mask of the table are appended
+/// as `+0x..`, or shown as `0x..` alone if no named flag is set. Zero value is shown as
+/// `none`.
+///
+/// ```
+/// const STATUS: &[(u32, &str)] = &[(0x01, "RX_RDY"), (0x02, "TX_EMPTY"), (0x10, "ERR")];
+///
+/// let mut buf = [0u8; 64];
+/// let status = 0x0013;
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("STATUS=0x{:04x} [{}]", status, stackfmt::BitNames(status, STATUS)),
+/// );
+/// assert_eq!(s, "STATUS=0x0013 [RX_RDY|TX_EMPTY|ERR]");
+/// ```
+#[derive(Clone, Copy)]
+pub struct BitNames<'a>(pub u32, pub &'a [(u32, &'a str)]);
+
+impl<'a> BitNames<'a> {
+    /// Returns adapter that shows every named flag, the unset ones in lowercase.
+    pub fn with_unset(self) -> BitNamesAll<'a> {
+        BitNamesAll(self)
+    }
+
+    fn is_set(&self, mask: u32) -> bool {
+        mask != 0 && self.0 & mask == mask
+    }
+
+    fn residual(&self) -> u32 {
+        let covered = self.1.iter().fold(0, |acc, (mask, _)| acc | mask);
+        self.0 & !covered
+    }
+}
+
+impl<'a> fmt::Display for BitNames<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            return f.write_str("none");
+        }
+
+        let mut any = false;
+        for (_, name) in self.1.iter().filter(|(mask, _)| self.is_set(*mask)) {
+            if any {
+                f.write_str("|")?;
+            }
+            f.write_str(name)?;
+            any = true;
+        }
+
+        let residual = self.residual();
+        if residual != 0 {
+            write!(f, "{}0x{:x}", if any { "+" } else { "" }, residual)?;
+        }
+        Ok(())
+    }
+}
+
+/// Display adapter created by [BitNames::with_unset].
+///
+/// Shows all the named flags in table order, set ones as in the table and unset ones in
+/// ASCII lowercase, e.g. `RX_RDY|tx_empty|ERR`. Uncovered bits are appended as by
+/// [BitNames].
+#[derive(Clone, Copy)]
+pub struct BitNamesAll<'a>(BitNames<'a>);
+
+impl<'a> fmt::Display for BitNamesAll<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = &self.0;
+        for (n, (mask, name)) in bits.1.iter().enumerate() {
+            if n > 0 {
+                f.write_str("|")?;
+            }
+            if bits.is_set(*mask) {
+                f.write_str(name)?;
+            } else {
+                for ch in name.chars() {
+                    f.write_char(ch.to_ascii_lowercase())?;
+                }
+            }
+        }
+
+        let residual = bits.residual();
+        if residual != 0 {
+            let sep = if bits.1.is_empty() { "" } else { "+" };
+            write!(f, "{}0x{:x}", sep, residual)?;
+        } else if bits.1.is_empty() {
+            f.write_str("none")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitNames;
+
+    const NAMES: &[(u32, &str)] = &[(0x01, "RX_RDY"), (0x02, "TX_EMPTY"), (0x10, "ERR")];
+
+    #[test]
+    fn bit_names_none() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0, NAMES)));
+        assert_eq!(s, "none");
+    }
+
+    #[test]
+    fn bit_names_all_named() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0x13, NAMES)));
+        assert_eq!(s, "RX_RDY|TX_EMPTY|ERR");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0x10, NAMES)));
+        assert_eq!(s, "ERR");
+    }
+
+    #[test]
+    fn bit_names_residual() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0x8001_0011, NAMES)));
+        assert_eq!(s, "RX_RDY|ERR+0x80010000");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0x20, NAMES)));
+        assert_eq!(s, "0x20");
+    }
+
+    #[test]
+    fn bit_names_overlapping_masks() {
+        let mut buf = [0u8; 64];
+        let names: &[(u32, &str)] = &[(0x0C, "MODE_FULL"), (0x04, "MODE_A"), (0x08, "MODE_B")];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0x0C, names)));
+        assert_eq!(s, "MODE_FULL|MODE_A|MODE_B");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0x04, names)));
+        assert_eq!(s, "MODE_A");
+    }
+
+    #[test]
+    fn bit_names_empty_table() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0, &[])));
+        assert_eq!(s, "none");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0xA5, &[])));
+        assert_eq!(s, "0xa5");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", BitNames(0, &[]).with_unset()));
+        assert_eq!(s, "none");
+    }
+
+    #[test]
+    fn bit_names_with_unset() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", BitNames(0x111, NAMES).with_unset()),
+        );
+        assert_eq!(s, "RX_RDY|tx_empty|ERR+0x100");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", BitNames(0, NAMES).with_unset()),
+        );
+        assert_eq!(s, "rx_rdy|tx_empty|err");
+    }
+}
@@ -0,0 +1,180 @@
+//  ( /   @ @    ()  Binary numbers with grouped digits
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use core::fmt;
+
+/// Display adapter that shows an integer in binary with the digits split into groups, like
+/// `1010_1100_0000_0011`.
+///
+/// The number is zero padded to the width of its type or to [Bits::width]; a width shorter
+/// than the value does not clamp it, all significant bits are shown like with `{:04b}`.
+/// Groups are counted from the least significant bit. The alternate flag `{:#}` adds the
+/// `0b` prefix. Width, fill and alignment of the format string apply to the whole text.
+///
+/// ```
+/// use stackfmt::Bits;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Bits::new(0xAC03).width(16)));
+/// assert_eq!(s, "1010_1100_0000_0011");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:#}", Bits::new_u8(5).group(0)));
+/// assert_eq!(s, "0b00000101");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bits<'s> {
+    value: u64,
+    width: usize,
+    group: usize,
+    separator: &'s str,
+}
+
+impl<'s> Bits<'s> {
+    fn with_width(value: u64, width: usize) -> Self {
+        Bits {
+            value,
+            width,
+            group: 4,
+            separator: "_",
+        }
+    }
+
+    /// Creates adapter for a u32 shown with 32 digits.
+    pub fn new(value: u32) -> Self {
+        Self::with_width(value as u64, 32)
+    }
+
+    /// Creates adapter for a u8 shown with 8 digits.
+    pub fn new_u8(value: u8) -> Self {
+        Self::with_width(value as u64, 8)
+    }
+
+    /// Creates adapter for a u16 shown with 16 digits.
+    pub fn new_u16(value: u16) -> Self {
+        Self::with_width(value as u64, 16)
+    }
+
+    /// Creates adapter for a u64 shown with 64 digits.
+    pub fn new_u64(value: u64) -> Self {
+        Self::with_width(value, 64)
+    }
+
+    /// Sets the minimal number of digits, 0 shows only the significant bits.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the number of digits in a group, 0 disables grouping. Default is 4.
+    pub fn group(mut self, group: usize) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Sets the text between groups. Default is `_`.
+    pub fn separator(mut self, separator: &'s str) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+impl<'s> fmt::Display for Bits<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let significant = (64 - self.value.leading_zeros() as usize).max(1);
+        let digits = significant.max(self.width);
+        let separators = match self.group {
+            0 => 0,
+            group => (digits - 1) / group,
+        };
+        let prefix = if f.alternate() { "0b" } else { "" };
+        let len = prefix.len() + digits + separators * self.separator.chars().count();
+
+        pad_with(f, len, fmt::Alignment::Right, |f| {
+            f.write_str(prefix)?;
+            for i in (0..digits).rev() {
+                let bit = i < 64 && self.value >> i & 1 == 1;
+                f.write_str(if bit { "1" } else { "0" })?;
+                if self.group > 0 && i > 0 && i % self.group == 0 {
+                    f.write_str(self.separator)?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bits;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn bits_full_width() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Bits::new(0x8000_0001))),
+            "1000_0000_0000_0000_0000_0000_0000_0001"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:#}", Bits::new_u16(0x00F0))),
+            "0b0000_0000_1111_0000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>12}]", Bits::new_u8(0x3C))),
+            "[   0011_1100]"
+        );
+    }
+
+    #[test]
+    fn bits_short_width_does_not_clamp() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Bits::new(0b110101).width(4))),
+            "11_0101"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Bits::new(0).width(0))),
+            "0"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Bits::new_u8(1).width(10))),
+            "00_0000_0001"
+        );
+    }
+
+    #[test]
+    fn bits_group_of_8() {
+        let mut buf = [0u8; 128];
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Bits::new(0x0506_0708).group(8))
+            ),
+            "00000101_00000110_00000111_00001000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Bits::new_u16(0x1FF).group(8))),
+            "00000001_11111111"
+        );
+    }
+
+    #[test]
+    fn bits_long_separator() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Bits::new_u16(0xA5F0).separator(" \u{B7} "))
+            ),
+            "1010 \u{B7} 0101 \u{B7} 1111 \u{B7} 0000"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("[{:^20}]", Bits::new_u8(0xA5).separator("\u{B7}\u{B7}"))
+            ),
+            "[     1010\u{B7}\u{B7}0101     ]"
+        );
+    }
+}
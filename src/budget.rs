@@ -0,0 +1,188 @@
+//  ( /   @ @    ()  Byte budget shared by several buffers
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::cell::Cell;
+use core::fmt;
+
+/// Number of bytes that several [BudgetedWriteTo] streams may write together.
+///
+/// The counter is behind a [Cell] so streams share it by `&Budget`.
+#[derive(Debug, Default)]
+pub struct Budget {
+    remaining: Cell<usize>,
+}
+
+impl Budget {
+    /// Creates budget of `bytes`.
+    pub fn new(bytes: usize) -> Self {
+        Budget {
+            remaining: Cell::new(bytes),
+        }
+    }
+
+    /// Bytes that still can be written
+    pub fn remaining(&self) -> usize {
+        self.remaining.get()
+    }
+}
+
+/// Which limit made [BudgetedWriteTo] truncate.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LimitHit {
+    /// The own buffer of the stream is full
+    Buffer,
+    /// The shared budget is used up
+    Budget,
+}
+
+/// Impl of [fmt::Write] like [WriteTo] that also takes the written bytes from a shared
+/// [Budget].
+///
+/// Truncation happens on a char boundary when either the buffer or the budget is exhausted,
+/// only bytes actually stored are taken from the budget.
+///
+/// ```
+/// use core::fmt::Write;
+/// use stackfmt::{Budget, BudgetedWriteTo, LimitHit};
+///
+/// let budget = Budget::new(12);
+/// let (mut header, mut body) = ([0u8; 16], [0u8; 16]);
+/// let mut h = BudgetedWriteTo::new(&mut header, &budget);
+/// let mut b = BudgetedWriteTo::new(&mut body, &budget);
+/// write!(h, "id=42;").unwrap();
+/// write!(b, "temp=21.5C").unwrap();
+/// assert_eq!(b.limit_hit(), Some(LimitHit::Budget));
+/// assert_eq!((h.as_str(), b.as_str()), ("id=42;", "temp=2"));
+/// ```
+pub struct BudgetedWriteTo<'a> {
+    inner: WriteTo<'a>,
+    budget: &'a Budget,
+    hit: Option<LimitHit>,
+}
+
+impl<'a> BudgetedWriteTo<'a> {
+    /// Creates stream writing into `buffer` within `budget`.
+    pub fn new(buffer: &'a mut [u8], budget: &'a Budget) -> Self {
+        BudgetedWriteTo {
+            inner: WriteTo::new(buffer),
+            budget,
+            hit: None,
+        }
+    }
+
+    /// How many bytes were written into the buffer
+    pub fn written_bytes(&self) -> usize {
+        self.inner.written_bytes()
+    }
+
+    /// True if some data did not fit and was dropped
+    pub fn is_truncated(&self) -> bool {
+        self.hit.is_some()
+    }
+
+    /// The limit that caused truncation, if any
+    pub fn limit_hit(&self) -> Option<LimitHit> {
+        self.hit
+    }
+
+    /// Returns buffer view as &str
+    pub fn as_str(self) -> &'a str {
+        self.inner.as_str()
+    }
+}
+
+impl<'a> fmt::Write for BudgetedWriteTo<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.hit.is_some() {
+            return Ok(()); // skip further inputs
+        }
+
+        let budget = self.budget.remaining();
+        let mut piece = s;
+        if s.len() > budget {
+            let mut cut = budget;
+            while !s.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            piece = &s[..cut];
+        }
+        let before = self.inner.written_bytes();
+        self.inner.write_str(piece)?;
+        self.budget
+            .remaining
+            .set(budget - (self.inner.written_bytes() - before));
+
+        if self.inner.is_truncated() {
+            self.hit = Some(LimitHit::Buffer);
+        } else if piece.len() < s.len() {
+            self.hit = Some(LimitHit::Budget);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, BudgetedWriteTo, LimitHit};
+    use core::fmt::Write;
+
+    #[test]
+    fn budget_second_writer_squeezed() {
+        let budget = Budget::new(10);
+        let (mut one, mut two) = ([0u8; 8], [0u8; 8]);
+        let mut first = BudgetedWriteTo::new(&mut one, &budget);
+        let mut second = BudgetedWriteTo::new(&mut two, &budget);
+        write!(first, "abcdef").unwrap();
+        assert_eq!(budget.remaining(), 4);
+        write!(second, "123456").unwrap();
+        assert_eq!(budget.remaining(), 0);
+        assert_eq!(first.limit_hit(), None);
+        assert_eq!(second.limit_hit(), Some(LimitHit::Budget));
+        assert_eq!((first.as_str(), second.as_str()), ("abcdef", "1234"));
+    }
+
+    #[test]
+    fn budget_buffer_limit_commits_only_stored() {
+        let budget = Budget::new(100);
+        let mut buf = [0u8; 4];
+        let mut w = BudgetedWriteTo::new(&mut buf, &budget);
+        write!(w, "ab\u{20AC}").unwrap();
+        assert_eq!(w.limit_hit(), Some(LimitHit::Buffer));
+        assert_eq!(budget.remaining(), 98);
+        assert_eq!(w.as_str(), "ab");
+    }
+
+    #[test]
+    fn budget_char_boundary() {
+        // exhausted exactly after a multi-byte char
+        let budget = Budget::new(4);
+        let mut buf = [0u8; 16];
+        let mut w = BudgetedWriteTo::new(&mut buf, &budget);
+        write!(w, "a\u{20AC}").unwrap();
+        assert!(!w.is_truncated());
+        write!(w, "b").unwrap();
+        assert_eq!(w.limit_hit(), Some(LimitHit::Budget));
+        assert_eq!(w.as_str(), "a\u{20AC}");
+
+        // budget ends inside a multi-byte char, the remainder stays in the budget
+        let budget = Budget::new(3);
+        let mut w = BudgetedWriteTo::new(&mut buf, &budget);
+        write!(w, "a\u{20AC}").unwrap();
+        assert_eq!(budget.remaining(), 2);
+        assert_eq!(w.as_str(), "a");
+    }
+
+    #[test]
+    fn budget_zero() {
+        let budget = Budget::new(0);
+        let mut buf = [0u8; 16];
+        let mut w = BudgetedWriteTo::new(&mut buf, &budget);
+        write!(w, "").unwrap();
+        assert!(!w.is_truncated());
+        write!(w, "x").unwrap();
+        assert_eq!(w.limit_hit(), Some(LimitHit::Budget));
+        assert_eq!(w.as_str(), "");
+    }
+}
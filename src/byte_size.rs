@@ -0,0 +1,123 @@
+//  ( /   @ @    ()  Human readable byte sizes (KiB/MiB/GiB or kB/MB/GB)
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::fmt_truncate;
+use core::fmt;
+
+const BINARY_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+const DECIMAL_UNITS: [&str; 7] = ["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+/// Display adapter that shows a byte count in the largest binary unit, like `12.4 MiB`.
+///
+/// A value of at least one KiB is shown with one decimal place, rounded half up using
+/// integer math only; a value that rounds up to 1024 of a unit is shown in the next unit.
+/// Values under 1024 are shown as `N B`. The alternate flag `{:#}` selects SI decimal units
+/// (kB, MB, ...) based on 1000. Width and alignment apply to the whole text.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", stackfmt::ByteSize(13002342)));
+/// assert_eq!(s, "12.4 MiB");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:#}", stackfmt::ByteSize(13002342)));
+/// assert_eq!(s, "13.0 MB");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ByteSize(pub u64);
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (base, units) = if f.alternate() {
+            (1000u128, &DECIMAL_UNITS)
+        } else {
+            (1024u128, &BINARY_UNITS)
+        };
+
+        let value = self.0 as u128;
+        let mut unit = 0;
+        let mut divisor = 1u128;
+        while unit + 1 < units.len() && value >= divisor * base {
+            divisor *= base;
+            unit += 1;
+        }
+
+        let mut buf = [0u8; 24];
+        let text = if unit == 0 {
+            fmt_truncate(&mut buf, format_args!("{} B", value))
+        } else {
+            let mut tenths = (value * 10 + divisor / 2) / divisor;
+            if tenths >= base * 10 && unit + 1 < units.len() {
+                // rounded up to the next unit
+                divisor *= base;
+                unit += 1;
+                tenths = (value * 10 + divisor / 2) / divisor;
+            }
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}.{} {}", tenths / 10, tenths % 10, units[unit]),
+            )
+        };
+        f.pad(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ByteSize;
+
+    #[test]
+    fn byte_size_binary() {
+        let table: &[(u64, &str)] = &[
+            (0, "0 B"),
+            (1, "1 B"),
+            (1023, "1023 B"),
+            (1024, "1.0 KiB"),
+            (1536, "1.5 KiB"),
+            (1075, "1.0 KiB"),    // 1.0498
+            (1076, "1.1 KiB"),    // 1.0508
+            (1048575, "1.0 MiB"), // rounds up to 1024.0 KiB
+            (1048576, "1.0 MiB"),
+            (13002342, "12.4 MiB"),
+            (1 << 30, "1.0 GiB"),
+            (1 << 40, "1.0 TiB"),
+            (1 << 50, "1.0 PiB"),
+            (1 << 60, "1.0 EiB"),
+            (u64::MAX, "16.0 EiB"),
+        ];
+        let mut buf = [0u8; 32];
+        for (value, expected) in table {
+            let s = crate::fmt_truncate(&mut buf, format_args!("{}", ByteSize(*value)));
+            assert_eq!(s, *expected, "for {}", value);
+        }
+    }
+
+    #[test]
+    fn byte_size_decimal() {
+        let table: &[(u64, &str)] = &[
+            (0, "0 B"),
+            (999, "999 B"),
+            (1000, "1.0 kB"),
+            (1024, "1.0 kB"),
+            (1050, "1.1 kB"),
+            (999_949, "999.9 kB"),
+            (999_950, "1.0 MB"),
+            (13002342, "13.0 MB"),
+            (1_000_000_000, "1.0 GB"),
+            (u64::MAX, "18.4 EB"),
+        ];
+        let mut buf = [0u8; 32];
+        for (value, expected) in table {
+            let s = crate::fmt_truncate(&mut buf, format_args!("{:#}", ByteSize(*value)));
+            assert_eq!(s, *expected, "for {}", value);
+        }
+    }
+
+    #[test]
+    fn byte_size_width() {
+        let mut buf = [0u8; 32];
+        let s = crate::fmt_truncate(&mut buf, format_args!("[{:>10}]", ByteSize(1536)));
+        assert_eq!(s, "[   1.5 KiB]");
+        let s = crate::fmt_truncate(&mut buf, format_args!("[{:<6}]", ByteSize(5)));
+        assert_eq!(s, "[5 B   ]");
+    }
+}
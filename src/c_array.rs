@@ -0,0 +1,218 @@
+//  ( /   @ @    ()  Bytes as a C array initializer
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+// Most values on a line, so a line fits into the buffer of CArrayDump::lines()
+const MAX_PER_LINE: usize = 32;
+
+// Values, separators and some indent and name
+const LINE_BUFFER_SIZE: usize = 256;
+
+/// Display adapter that shows bytes as a C array initializer, like `0x12, 0x34, 0x56,`.
+///
+/// Values are put `per_line` on a line (12 by default, at most 32), every line starts with
+/// the indent (4 spaces by default). The last value has a comma too unless
+/// [trailing_comma](CArrayDump::trailing_comma) is off. With a [name](CArrayDump::name)
+/// the values are wrapped in `const uint8_t name[] = {` and `};` lines. Lines are separated
+/// with `\n`, there is none after the last one; use [CArrayDump::lines] for an output larger
+/// than the destination buffer.
+///
+/// ```
+/// use stackfmt::CArrayDump;
+///
+/// let mut buf = [0u8; 128];
+/// let dump = CArrayDump::new(&[0xDE, 0xAD, 0xBE, 0xEF, 0x01]).per_line(4).name("magic");
+/// assert_eq!(
+///     stackfmt::fmt_truncate(&mut buf, format_args!("{}", dump)),
+///     "const uint8_t magic[] = {\n    0xde, 0xad, 0xbe, 0xef,\n    0x01,\n};"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CArrayDump<'a> {
+    bytes: &'a [u8],
+    per_line: usize,
+    name: Option<&'a str>,
+    indent: &'a str,
+    uppercase: bool,
+    trailing_comma: bool,
+}
+
+impl<'a> CArrayDump<'a> {
+    /// Creates adapter for `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        CArrayDump {
+            bytes,
+            per_line: 12,
+            name: None,
+            indent: "    ",
+            uppercase: false,
+            trailing_comma: true,
+        }
+    }
+
+    /// Sets how many values are on a line, clamped to 1..=32.
+    pub fn per_line(mut self, per_line: usize) -> Self {
+        self.per_line = per_line.clamp(1, MAX_PER_LINE);
+        self
+    }
+
+    /// Wraps the values in a `const uint8_t name[]` declaration.
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the text at the start of every line with values.
+    pub fn indent(mut self, indent: &'a str) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Shows hex digits in uppercase, the `0x` prefix stays lowercase.
+    pub fn uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// Sets if the last value is followed by a comma.
+    pub fn trailing_comma(mut self, trailing_comma: bool) -> Self {
+        self.trailing_comma = trailing_comma;
+        self
+    }
+
+    /// Passes every line (without newline) to `emit`, using only a line-sized buffer.
+    ///
+    /// A line longer than 256 bytes, possible only with a long indent or name, is truncated.
+    pub fn lines<F: FnMut(&str)>(&self, mut emit: F) {
+        for i in 0..self.line_count() {
+            let mut buf = [0u8; LINE_BUFFER_SIZE];
+            let mut w = WriteTo::new(&mut buf);
+            let _ = self.write_line(&mut w, i);
+            emit(w.as_str());
+        }
+    }
+
+    fn value_lines(&self) -> usize {
+        self.bytes.len().div_ceil(self.per_line)
+    }
+
+    fn line_count(&self) -> usize {
+        self.value_lines() + if self.name.is_some() { 2 } else { 0 }
+    }
+
+    // Writes line `index` of line_count()
+    fn write_line<W: fmt::Write>(&self, w: &mut W, mut index: usize) -> fmt::Result {
+        if let Some(name) = self.name {
+            if index == 0 {
+                return write!(w, "const uint8_t {}[] = {{", name);
+            }
+            if index == self.value_lines() + 1 {
+                return w.write_str("};");
+            }
+            index -= 1;
+        }
+        let start = index * self.per_line;
+        let end = (start + self.per_line).min(self.bytes.len());
+        w.write_str(self.indent)?;
+        for (i, b) in self.bytes[start..end].iter().enumerate() {
+            if i > 0 {
+                w.write_char(' ')?;
+            }
+            if self.uppercase {
+                write!(w, "0x{:02X}", b)?;
+            } else {
+                write!(w, "0x{:02x}", b)?;
+            }
+            if start + i + 1 < self.bytes.len() || self.trailing_comma {
+                w.write_char(',')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for CArrayDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.line_count() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            self.write_line(f, i)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CArrayDump;
+    use crate::{fmt_truncate, WriteTo};
+    use core::fmt::Write;
+
+    #[test]
+    fn c_array_empty() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", CArrayDump::new(&[]))),
+            ""
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", CArrayDump::new(&[]).name("e"))),
+            "const uint8_t e[] = {\n};"
+        );
+    }
+
+    #[test]
+    fn c_array_one_line() {
+        let mut buf = [0u8; 128];
+        let bytes = [0x12, 0x34, 0x56];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", CArrayDump::new(&bytes))),
+            "    0x12, 0x34, 0x56,"
+        );
+        let dump = CArrayDump::new(&bytes)
+            .per_line(3)
+            .indent("")
+            .uppercase(true)
+            .trailing_comma(false);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", dump)),
+            "0x12, 0x34, 0x56"
+        );
+    }
+
+    #[test]
+    fn c_array_partial_last_line() {
+        let mut buf = [0u8; 128];
+        let bytes = [0xAB; 5];
+        let dump = CArrayDump::new(&bytes).per_line(2).indent("\t");
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", dump)),
+            "\t0xab, 0xab,\n\t0xab, 0xab,\n\t0xab,"
+        );
+        let dump = CArrayDump::new(&bytes).per_line(0).trailing_comma(false);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", dump)),
+            "    0xab,\n    0xab,\n    0xab,\n    0xab,\n    0xab"
+        );
+    }
+
+    #[test]
+    fn c_array_named_lines() {
+        let mut out = [0u8; 256];
+        let mut log = WriteTo::new(&mut out);
+        let bytes: [u8; 14] = core::array::from_fn(|i| i as u8 * 17);
+        CArrayDump::new(&bytes).name("font_data").lines(|line| {
+            let _ = writeln!(log, "{}", line);
+        });
+        assert_eq!(
+            log.as_str(),
+            "const uint8_t font_data[] = {\n    \
+             0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb,\n    \
+             0xcc, 0xdd,\n};\n"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//  ( /   @ @    ()  CAN frames in candump text format
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Maximum data length of a CAN FD frame, longer data is clamped.
+pub const CAN_FD_MAX_DATA: usize = 64;
+
+/// Display adapter that shows a CAN frame the way `candump` prints it, like
+/// `can0  123   [8]  DE AD BE EF 00 11 22 33`.
+///
+/// A standard ID is 3 hex digits and an extended one is 8 digits. Data longer than 8 bytes
+/// is a CAN FD frame with the length shown as 2 digits, data longer than
+/// [CAN_FD_MAX_DATA] bytes is clamped to it. A remote request frame set with
+/// [CanFrame::rtr] shows `remote request` instead of the data, with the data length as
+/// DLC.
+///
+/// ```
+/// use stackfmt::CanFrame;
+///
+/// let mut buf = [0u8; 64];
+/// let frame = CanFrame::new("can0", 0x123, false, &[0xDE, 0xAD, 0xBE, 0xEF]);
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", frame));
+/// assert_eq!(s, "can0  123   [4]  DE AD BE EF");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CanFrame<'a> {
+    iface: &'a str,
+    id: u32,
+    extended: bool,
+    data: &'a [u8],
+    rtr: bool,
+}
+
+impl<'a> CanFrame<'a> {
+    /// Creates adapter for a frame on `iface`, the ID is masked to 11 or 29 bits.
+    pub fn new(iface: &'a str, id: u32, extended: bool, data: &'a [u8]) -> Self {
+        CanFrame {
+            iface,
+            id,
+            extended,
+            data: &data[..data.len().min(CAN_FD_MAX_DATA)],
+            rtr: false,
+        }
+    }
+
+    /// Shows the frame as a remote transmission request.
+    pub fn rtr(mut self, rtr: bool) -> Self {
+        self.rtr = rtr;
+        self
+    }
+}
+
+impl<'a> fmt::Display for CanFrame<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.iface)?;
+        if self.extended {
+            write!(f, "  {:08X}", self.id & 0x1FFF_FFFF)?;
+        } else {
+            write!(f, "  {:03X}", self.id & 0x7FF)?;
+        }
+        let len = self.data.len();
+        if len > 8 {
+            write!(f, "  [{:02}]", len)?;
+        } else {
+            write!(f, "   [{}]", len)?;
+        }
+        if self.rtr {
+            return f.write_str("  remote request");
+        }
+        for (i, b) in self.data.iter().enumerate() {
+            let lead = if i == 0 { "  " } else { " " };
+            write!(f, "{}{:02X}", lead, b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a CAN frame in `candump` format into the buffer truncating if needed, see
+/// [CanFrame].
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let s = stackfmt::fmt_can_frame(&mut buf, "can1", 0x18DAF110, true, &[0x02, 0x10]);
+/// assert_eq!(s, "can1  18DAF110   [2]  02 10");
+/// ```
+pub fn fmt_can_frame<'a>(
+    buffer: &'a mut [u8],
+    iface: &str,
+    id: u32,
+    extended: bool,
+    data: &[u8],
+) -> &'a str {
+    let mut w = WriteTo::new(buffer);
+    let frame = CanFrame::new(iface, id, extended, data);
+    match fmt::write(&mut w, format_args!("{}", frame)) {
+        Ok(_) => w.as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_can_frame, CanFrame};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn can_standard_id() {
+        let mut buf = [0u8; 64];
+        let data = [0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x11, 0x22, 0x33];
+        assert_eq!(
+            fmt_can_frame(&mut buf, "can0", 0x123, false, &data),
+            "can0  123   [8]  DE AD BE EF 00 11 22 33"
+        );
+        // bits above 11 are dropped
+        assert_eq!(
+            fmt_can_frame(&mut buf, "can0", 0xF805, false, &[1]),
+            "can0  005   [1]  01"
+        );
+    }
+
+    #[test]
+    fn can_extended_id() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_can_frame(&mut buf, "vcan0", 0x1, true, &[0xAB]),
+            "vcan0  00000001   [1]  AB"
+        );
+        assert_eq!(
+            fmt_can_frame(&mut buf, "can0", 0xFFFF_FFFF, true, &[]),
+            "can0  1FFFFFFF   [0]"
+        );
+    }
+
+    #[test]
+    fn can_zero_length_and_rtr() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_can_frame(&mut buf, "can0", 0x7FF, false, &[]),
+            "can0  7FF   [0]"
+        );
+        let frame = CanFrame::new("can0", 0x100, false, &[0; 4]).rtr(true);
+        let s = fmt_truncate(&mut buf, format_args!("{}", frame));
+        assert_eq!(s, "can0  100   [4]  remote request");
+    }
+
+    #[test]
+    fn can_fd_length() {
+        let mut buf = [0u8; 256];
+        let data = [0x5A; 80];
+        let s = fmt_can_frame(&mut buf, "can0", 0x42, false, &data[..12]);
+        assert_eq!(s, "can0  042  [12]  5A 5A 5A 5A 5A 5A 5A 5A 5A 5A 5A 5A");
+        // clamped to 64 bytes
+        let s = fmt_can_frame(&mut buf, "can0", 0x42, false, &data);
+        assert!(s.starts_with("can0  042  [64]  5A 5A"));
+        assert_eq!(s.len(), "can0  042  [64]  ".len() + 64 * 3 - 1);
+    }
+}
@@ -0,0 +1,130 @@
+//  ( /   @ @    ()  ASCII case conversion of formatted output
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::str::from_utf8_unchecked;
+
+// Size of the block converted at once
+const BLOCK_SIZE: usize = 32;
+
+/// Target case of [CaseWriter].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+/// Impl of [fmt::Write] that converts ASCII letters to the given case.
+///
+/// All the other bytes, including every byte of multi-byte UTF-8 chars, are forwarded
+/// untouched.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// assert_eq!(stackfmt::fmt_ascii_upper(&mut buf, format_args!("at+cmd={}", 1)), "AT+CMD=1");
+/// ```
+pub struct CaseWriter<W: fmt::Write> {
+    inner: W,
+    case: Case,
+}
+
+impl<W: fmt::Write> CaseWriter<W> {
+    /// Creates stream converting to `case`.
+    pub fn new(inner: W, case: Case) -> Self {
+        CaseWriter { inner, case }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for CaseWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+        while start < s.len() {
+            let mut end = (start + BLOCK_SIZE).min(s.len());
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            let mut block = [0u8; BLOCK_SIZE];
+            let block = &mut block[..end - start];
+            block.copy_from_slice(&s.as_bytes()[start..end]);
+            match self.case {
+                Case::Upper => block.make_ascii_uppercase(),
+                Case::Lower => block.make_ascii_lowercase(),
+            }
+            // only ASCII letters were changed and the block ends on a char boundary
+            self.inner
+                .write_str(unsafe { from_utf8_unchecked(block) })?;
+            start = end;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_case<'a>(buffer: &'a mut [u8], args: fmt::Arguments, case: Case) -> &'a str {
+    let mut w = CaseWriter::new(WriteTo::new(buffer), case);
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+/// Writes formatted string with ASCII letters in uppercase into the buffer truncating if
+/// needed.
+pub fn fmt_ascii_upper<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    fmt_case(buffer, args, Case::Upper)
+}
+
+/// Writes formatted string with ASCII letters in lowercase into the buffer truncating if
+/// needed.
+pub fn fmt_ascii_lower<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    fmt_case(buffer, args, Case::Lower)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn case_mixed_ascii() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_ascii_upper(&mut buf, format_args!("Hello {}", "World"));
+        assert_eq!(s, "HELLO WORLD");
+        let s = super::fmt_ascii_lower(&mut buf, format_args!("Hello {}", "World"));
+        assert_eq!(s, "hello world");
+    }
+
+    #[test]
+    fn case_digits_punctuation() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_ascii_upper(&mut buf, format_args!("0-9 @[`{{~ {}", 42));
+        assert_eq!(s, "0-9 @[`{~ 42");
+        let s = super::fmt_ascii_lower(&mut buf, format_args!("0-9 @[`{{~ {}", 42));
+        assert_eq!(s, "0-9 @[`{~ 42");
+    }
+
+    #[test]
+    fn case_unicode_pass_through() {
+        let mut buf = [0u8; 128];
+        // continuation bytes of these chars are in 0x80..=0xBF and must stay untouched
+        let text = "stra\u{DF}e \u{E9}t\u{E9} \u{20AC}x \u{10348}y \u{C0}";
+        let s = super::fmt_ascii_upper(&mut buf, format_args!("{}", text));
+        assert_eq!(s, "STRA\u{DF}E \u{E9}T\u{E9} \u{20AC}X \u{10348}Y \u{C0}");
+        let mut buf2 = [0u8; 128];
+        let s = super::fmt_ascii_lower(&mut buf2, format_args!("{}", s));
+        assert_eq!(s, "stra\u{DF}e \u{E9}t\u{E9} \u{20AC}x \u{10348}y \u{C0}");
+    }
+
+    #[test]
+    fn case_long_input_blocks() {
+        // block boundaries fall inside multi-byte chars
+        let mut buf = [0u8; 128];
+        let text = "a\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}b\u{10348}\u{10348}\u{10348}c";
+        let s = super::fmt_ascii_upper(&mut buf, format_args!("{}", text));
+        assert_eq!(s, "A\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}\u{20AC}B\u{10348}\u{10348}\u{10348}C");
+    }
+}
@@ -0,0 +1,152 @@
+//  ( /   @ @    ()  A value padded or cut to an exact width
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::{write_padded, CharCounter};
+use crate::truncated::Limiter;
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows a value in exactly `width` chars, for table-like output.
+///
+/// A shorter value is padded with the fill char (space by default) according to the
+/// alignment (left by default), a longer one is cut on a char boundary. Unlike `{:width$}`
+/// the width is never exceeded. The value is formatted twice: to measure and to write.
+///
+/// ```
+/// use core::fmt::Alignment;
+/// use stackfmt::Cell;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("|{}|", Cell::new("temperature", 6)));
+/// assert_eq!(s, "|temper|");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("|{}|", Cell::new(42, 6).align(Alignment::Right)));
+/// assert_eq!(s, "|    42|");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Cell<T> {
+    value: T,
+    width: usize,
+    align: fmt::Alignment,
+    fill: char,
+}
+
+impl<T: fmt::Display> Cell<T> {
+    /// Creates adapter showing `value` in `width` chars.
+    pub fn new(value: T, width: usize) -> Self {
+        Cell {
+            value,
+            width,
+            align: fmt::Alignment::Left,
+            fill: ' ',
+        }
+    }
+
+    /// Sets where the value is placed when it is shorter than the width.
+    pub fn align(mut self, align: fmt::Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets the char used for padding.
+    pub fn fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Cell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut counter = CharCounter {
+            count: 0,
+            limit: self.width,
+        };
+        write!(counter, "{}", self.value)?;
+        write_padded(f, self.width - counter.count, self.fill, self.align, |f| {
+            let mut limiter = Limiter::new(&mut *f, counter.count, true, "");
+            write!(limiter, "{}", self.value)?;
+            limiter.finish().map(|_| ())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+    use crate::fmt_truncate;
+    use core::fmt::Alignment;
+
+    #[test]
+    fn cell_exact_fit() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("|{}|", Cell::new("abcd", 4))),
+            "|abcd|"
+        );
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("|{}|", Cell::new(1234, 4).align(Alignment::Right)),
+        );
+        assert_eq!(s, "|1234|");
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("|{}|", Cell::new("", 0))),
+            "||"
+        );
+    }
+
+    #[test]
+    fn cell_shorter() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("|{}|", Cell::new("ab", 4))),
+            "|ab  |"
+        );
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("|{}|", Cell::new(7, 4).align(Alignment::Right).fill('0')),
+        );
+        assert_eq!(s, "|0007|");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("|{}|", Cell::new("", 3).fill('\u{B7}')),
+        );
+        assert_eq!(s, "|\u{B7}\u{B7}\u{B7}|");
+    }
+
+    #[test]
+    fn cell_longer() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("|{}|", Cell::new("abcdef", 4))),
+            "|abcd|"
+        );
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("|{}|", Cell::new("\u{20AC}1\u{20AC}2\u{20AC}", 3)),
+        );
+        assert_eq!(s, "|\u{20AC}1\u{20AC}|");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("|{}|", Cell::new(-123456, 4).align(Alignment::Right)),
+        );
+        assert_eq!(s, "|-123|");
+    }
+
+    #[test]
+    fn cell_center() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("|{}|", Cell::new("ab", 5).align(Alignment::Center)),
+        );
+        assert_eq!(s, "| ab  |");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "|{}|",
+                Cell::new("\u{20AC}", 4).align(Alignment::Center).fill('-')
+            ),
+        );
+        assert_eq!(s, "|-\u{20AC}--|");
+    }
+}
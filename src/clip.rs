@@ -0,0 +1,177 @@
+//  ( /   @ @    ()  Clips every line of formatted output at a column
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Impl of [fmt::Write] that forwards at most `width` chars of every line.
+///
+/// The rest of a longer line is dropped until the next `\n`, which is forwarded. Every char
+/// counts as one column. With an indicator set, the last column of a clipped line shows the
+/// indicator instead of the original char; since it is not known whether the line is clipped
+/// until the next char arrives, that char is held back and [ClipWriter::finish] must be
+/// called at the end of the output.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_clip(&mut buf, format_args!("temperature\nok"), 4);
+/// assert_eq!(s, "temp\nok");
+/// ```
+pub struct ClipWriter<W: fmt::Write> {
+    inner: W,
+    width: usize,
+    indicator: Option<char>,
+    column: usize,      // Chars of the current line that were accepted
+    dropping: bool,     // If the rest of the current line is dropped
+    held: Option<char>, // Char in the last column, held while the indicator may replace it
+}
+
+impl<W: fmt::Write> ClipWriter<W> {
+    /// Creates stream clipping lines at `width` chars.
+    pub fn new(inner: W, width: usize) -> Self {
+        ClipWriter {
+            inner,
+            width,
+            indicator: None,
+            column: 0,
+            dropping: false,
+            held: None,
+        }
+    }
+
+    /// Marks clipped lines with `indicator` in the last column.
+    pub fn indicator(mut self, indicator: char) -> Self {
+        self.indicator = Some(indicator);
+        self
+    }
+
+    /// Writes the held char (if any) and returns the inner stream.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        self.release_held()?;
+        Ok(self.inner)
+    }
+
+    fn release_held(&mut self) -> fmt::Result {
+        match self.held.take() {
+            Some(ch) => self.inner.write_char(ch),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for ClipWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = None; // Start of the part to forward
+
+        for (i, ch) in s.char_indices() {
+            if ch == '\n' {
+                self.release_held()?;
+                run_start.get_or_insert(i);
+                self.column = 0;
+                self.dropping = false;
+            } else if self.dropping {
+                // skip till the end of the line
+            } else if self.column == self.width {
+                // the line is too long
+                if let Some(start) = run_start.take() {
+                    self.inner.write_str(&s[start..i])?;
+                }
+                if self.held.take().is_some() {
+                    self.inner.write_char(self.indicator.unwrap_or(ch))?;
+                }
+                self.dropping = true;
+            } else if self.indicator.is_some() && self.column + 1 == self.width {
+                if let Some(start) = run_start.take() {
+                    self.inner.write_str(&s[start..i])?;
+                }
+                self.held = Some(ch);
+                self.column += 1;
+            } else {
+                run_start.get_or_insert(i);
+                self.column += 1;
+            }
+        }
+
+        match run_start {
+            Some(start) => self.inner.write_str(&s[start..]),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes formatted string with every line clipped to `width` chars into the buffer
+/// truncating if needed.
+pub fn fmt_clip<'a>(buffer: &'a mut [u8], args: fmt::Arguments, width: usize) -> &'a str {
+    let mut w = ClipWriter::new(WriteTo::new(buffer), width);
+    match fmt::write(&mut w, args) {
+        Ok(_) => match w.finish() {
+            Ok(w) => w.as_str(),
+            Err(_) => "",
+        },
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClipWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    fn clip_with_indicator<'a>(buf: &'a mut [u8], chunks: &[&str], width: usize) -> &'a str {
+        let mut w = ClipWriter::new(WriteTo::new(buf), width).indicator('>');
+        for chunk in chunks {
+            w.write_str(chunk).unwrap();
+        }
+        w.finish().unwrap().as_str()
+    }
+
+    #[test]
+    fn clip_short_equal_long_lines() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_clip(&mut buf, format_args!("ab\nabcd\nabcdefg\n"), 4);
+        assert_eq!(s, "ab\nabcd\nabcd\n");
+    }
+
+    #[test]
+    fn clip_multi_byte() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_clip(&mut buf, format_args!("\u{20AC}\u{10348}\u{20AC}x\ny"), 2);
+        assert_eq!(s, "\u{20AC}\u{10348}\ny");
+    }
+
+    #[test]
+    fn clip_indicator_mode() {
+        let mut buf = [0u8; 64];
+        let s = clip_with_indicator(
+            &mut buf,
+            &["ab\nabcd\nabcdefg\n\u{20AC}\u{20AC}\u{20AC}\u{20AC}x"],
+            4,
+        );
+        assert_eq!(s, "ab\nabcd\nabc>\n\u{20AC}\u{20AC}\u{20AC}>");
+        let s = clip_with_indicator(&mut buf, &["abcd"], 4);
+        assert_eq!(s, "abcd");
+    }
+
+    #[test]
+    fn clip_chunks_mid_dropped_region() {
+        let mut buf = [0u8; 64];
+        let mut w = ClipWriter::new(WriteTo::new(&mut buf), 3);
+        w.write_str("abcdef").unwrap();
+        w.write_str("ghi").unwrap();
+        w.write_str("jk\nlm").unwrap();
+        w.write_str("nop\n").unwrap();
+        assert_eq!(w.finish().unwrap().as_str(), "abc\nlmn\n");
+
+        let s = clip_with_indicator(&mut buf, &["ab", "c", "d", "ef\nxy", "z"], 3);
+        assert_eq!(s, "ab>\nxyz");
+    }
+
+    #[test]
+    fn clip_zero_width() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_clip(&mut buf, format_args!("ab\ncd"), 0);
+        assert_eq!(s, "\n");
+    }
+}
@@ -0,0 +1,129 @@
+//  ( /   @ @    ()  Squeezes runs of whitespace in formatted output
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Impl of [fmt::Write] that replaces every run of spaces and tabs with a single space.
+///
+/// A run split across several `write_str` calls is still collapsed. Newlines are forwarded
+/// as is and end the run. Optionally whitespace at the start of every line (including the
+/// first one) is removed completely. Trailing whitespace becomes a single space.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_collapse_ws(&mut buf, format_args!("a {} {} b", "", "\t"));
+/// assert_eq!(s, "a b");
+/// ```
+pub struct CollapseSpacesWriter<W: fmt::Write> {
+    inner: W,
+    trim_leading: bool,
+    in_run: bool,     // If the last byte was whitespace
+    line_start: bool, // If nothing but whitespace was seen in the current line
+}
+
+impl<W: fmt::Write> CollapseSpacesWriter<W> {
+    /// Creates stream that keeps the leading whitespace of a line as a single space.
+    pub fn new(inner: W) -> Self {
+        CollapseSpacesWriter {
+            inner,
+            trim_leading: false,
+            in_run: false,
+            line_start: true,
+        }
+    }
+
+    /// Removes whitespace at the start of every line when `trim` is true.
+    pub fn trim_leading(mut self, trim: bool) -> Self {
+        self.trim_leading = trim;
+        self
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for CollapseSpacesWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            match b {
+                b' ' | b'\t' => {
+                    let skip = self.in_run || (self.trim_leading && self.line_start);
+                    self.inner.write_str(&s[run_start..i])?;
+                    run_start = i + 1;
+                    if !skip {
+                        self.inner.write_str(" ")?;
+                    }
+                    self.in_run = true;
+                }
+                b'\n' => {
+                    self.in_run = false;
+                    self.line_start = true;
+                }
+                _ => {
+                    self.in_run = false;
+                    self.line_start = false;
+                }
+            }
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+/// Writes formatted string with whitespace runs collapsed into the buffer truncating if
+/// needed.
+pub fn fmt_collapse_ws<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = CollapseSpacesWriter::new(WriteTo::new(buffer));
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CollapseSpacesWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn collapse_within_chunk() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_collapse_ws(&mut buf, format_args!("a    b  c d"));
+        assert_eq!(s, "a b c d");
+    }
+
+    #[test]
+    fn collapse_across_chunks() {
+        let mut buf = [0u8; 64];
+        let mut w = CollapseSpacesWriter::new(WriteTo::new(&mut buf));
+        w.write_str("a  ").unwrap();
+        w.write_str("").unwrap();
+        w.write_str("   b ").unwrap();
+        w.write_str(" c").unwrap();
+        assert_eq!(w.into_inner().as_str(), "a b c");
+    }
+
+    #[test]
+    fn collapse_leading_trailing() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_collapse_ws(&mut buf, format_args!("  a  \n   b  "));
+        assert_eq!(s, " a \n b ");
+
+        let mut w = CollapseSpacesWriter::new(WriteTo::new(&mut buf)).trim_leading(true);
+        w.write_str("  a  \n \t b  ").unwrap();
+        w.write_str("\n\n  c").unwrap();
+        assert_eq!(w.into_inner().as_str(), "a \nb \n\nc");
+    }
+
+    #[test]
+    fn collapse_tabs_and_spaces() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_collapse_ws(&mut buf, format_args!("a\t \tb\t\u{20AC}"));
+        assert_eq!(s, "a b \u{20AC}");
+    }
+}
@@ -0,0 +1,166 @@
+//  ( /   @ @    ()  Substring search in formatted output without storing it
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+// Prefix lengths of the KMP failure function kept in the table, longer ones are computed
+const TABLE_SIZE: usize = 64;
+
+/// Impl of [fmt::Write] that searches the formatted output for a pattern without storing it.
+///
+/// A Knuth-Morris-Pratt search runs over the bytes as they come, so a match split between
+/// `write_str()` calls is found. The failure table covers the first 64 bytes of the pattern,
+/// for a longer pattern the rest is computed when needed. An empty pattern is found at
+/// offset 0.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut w = stackfmt::ContainsWriter::new("ERROR");
+/// write!(w, "dev {} state ERR", 7).unwrap();
+/// write!(w, "OR: {}", "timeout").unwrap();
+/// assert_eq!(w.match_offset(), Some(12));
+/// ```
+pub struct ContainsWriter<'p> {
+    pattern: &'p [u8],
+    table: [u8; TABLE_SIZE],
+    matched: usize,  // bytes of the pattern matched so far
+    position: usize, // bytes seen so far
+    found: Option<usize>,
+}
+
+impl<'p> ContainsWriter<'p> {
+    /// Creates stream searching for `pattern`.
+    pub fn new(pattern: &'p str) -> Self {
+        let pattern = pattern.as_bytes();
+        let mut table = [0u8; TABLE_SIZE];
+        // table[i] is the failure value for prefix length i + 1
+        let mut k = 0;
+        for i in 1..pattern.len().min(TABLE_SIZE) {
+            while k > 0 && pattern[i] != pattern[k] {
+                k = table[k - 1] as usize;
+            }
+            if pattern[i] == pattern[k] {
+                k += 1;
+            }
+            table[i] = k as u8;
+        }
+        ContainsWriter {
+            pattern,
+            table,
+            matched: 0,
+            position: 0,
+            found: if pattern.is_empty() { Some(0) } else { None },
+        }
+    }
+
+    /// True if the pattern was found.
+    pub fn found(&self) -> bool {
+        self.found.is_some()
+    }
+
+    /// Byte offset of the first match in the whole output.
+    pub fn match_offset(&self) -> Option<usize> {
+        self.found
+    }
+
+    // Longest proper prefix of pattern[..len] that is also its suffix
+    fn failure(&self, len: usize) -> usize {
+        if len <= TABLE_SIZE {
+            return self.table[len - 1] as usize;
+        }
+        (1..len)
+            .rev()
+            .find(|k| self.pattern[..*k] == self.pattern[len - k..len])
+            .unwrap_or(0)
+    }
+}
+
+impl<'p> fmt::Write for ContainsWriter<'p> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.found.is_some() {
+            return Ok(()); // the first match is known
+        }
+        for (i, b) in s.bytes().enumerate() {
+            while self.matched > 0 && self.pattern[self.matched] != b {
+                self.matched = self.failure(self.matched);
+            }
+            if self.pattern[self.matched] == b {
+                self.matched += 1;
+            }
+            if self.matched == self.pattern.len() {
+                self.found = Some(self.position + i + 1 - self.pattern.len());
+                return Ok(());
+            }
+        }
+        self.position += s.len();
+        Ok(())
+    }
+}
+
+/// True if the formatted text contains `pattern`.
+///
+/// ```
+/// assert!(stackfmt::fmt_contains(format_args!("id={:04x}", 0xbeef), "beef"));
+/// ```
+pub fn fmt_contains(args: fmt::Arguments, pattern: &str) -> bool {
+    let mut w = ContainsWriter::new(pattern);
+    let _ = fmt::write(&mut w, args);
+    w.found()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_contains, ContainsWriter};
+    use core::fmt::Write;
+
+    fn search(pattern: &str, chunks: &[&str]) -> Option<usize> {
+        let mut w = ContainsWriter::new(pattern);
+        for chunk in chunks {
+            w.write_str(chunk).unwrap();
+        }
+        w.match_offset()
+    }
+
+    #[test]
+    fn contains_in_one_chunk() {
+        assert_eq!(search("ERROR", &["an ERROR here"]), Some(3));
+        assert_eq!(search("ERROR", &["ERROR"]), Some(0));
+        assert_eq!(search("ERROR", &["no error"]), None);
+        assert!(fmt_contains(format_args!("{}-{}", "dev", 42), "v-4"));
+        assert!(!fmt_contains(format_args!("{}", 1234), "5"));
+    }
+
+    #[test]
+    fn contains_split_across_chunks() {
+        assert_eq!(search("ERROR", &["an ER", "ROR"]), Some(3));
+        assert_eq!(search("ERROR", &["E", "R", "", "R", "O", "R!"]), Some(0));
+        assert_eq!(search("\u{20AC}42", &["x\u{20AC}", "4", "2"]), Some(1));
+        // first of several matches
+        assert_eq!(search("ab", &["xa", "bab"]), Some(1));
+    }
+
+    #[test]
+    fn contains_near_misses() {
+        assert_eq!(search("aab", &["aaaa", "aaab"]), Some(5));
+        assert_eq!(search("abab", &["aba", "cabab"]), Some(4));
+        assert_eq!(search("ERROR", &["ERRO", "ERRORR"]), Some(4));
+        assert_eq!(search("aaa", &["aa", "ba", "a"]), None);
+    }
+
+    #[test]
+    fn contains_long_pattern() {
+        // longer than the failure table
+        let pattern = "abababababababababababababababababababababababababababababababababababac";
+        let text = "ababababababababababababababababababababababababababababababababababababababac";
+        assert_eq!(search(pattern, &[&text[..40], &text[40..]]), Some(6));
+    }
+
+    #[test]
+    fn contains_empty_pattern() {
+        assert_eq!(search("", &[]), Some(0));
+        assert_eq!(search("", &["abc"]), Some(0));
+        assert!(fmt_contains(format_args!(""), ""));
+    }
+}
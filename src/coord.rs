@@ -0,0 +1,248 @@
+//  ( /   @ @    ()  Latitude and longitude in decimal degrees or degrees, minutes, seconds
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use crate::stackfmt::fmt_truncate;
+use core::fmt;
+
+const MICRO: u64 = 1_000_000;
+
+/// Which of the coordinates a [Coord] is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    /// North (positive) or south, up to 90°
+    Latitude,
+    /// East (positive) or west, up to 180°
+    Longitude,
+}
+
+/// Display adapter that shows a coordinate given in signed micro-degrees, like `47.123456°N`
+/// or `47°07'24.4"N`.
+///
+/// Decimal degrees are shown with 6 decimals, [Coord::dms] or the alternate flag (`{:#}`)
+/// switches to degrees, minutes and seconds with 1 decimal. A precision from the format
+/// string sets the decimals (up to 6 for degrees, 3 for seconds), the last digit is rounded
+/// half up with the carry into minutes and degrees. Zero is north or east. Values beyond
+/// 90° or 180° are shown as the limit, [Coord::is_out_of_range] tells about them. Width and
+/// alignment apply to the whole text. Only integer math is used.
+///
+/// ```
+/// use stackfmt::{Axis, Coord};
+///
+/// let mut buf = [0u8; 32];
+/// let lat = Coord::new(47_123_456, Axis::Latitude);
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", lat));
+/// assert_eq!(s, "47.123456\u{B0}N");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", lat.dms()));
+/// assert_eq!(s, "47\u{B0}07'24.4\"N");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:.2}", Coord::new(-8_545_000, Axis::Longitude)));
+/// assert_eq!(s, "8.55\u{B0}W");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Coord {
+    microdeg: i32,
+    axis: Axis,
+    dms: bool,
+}
+
+impl Coord {
+    /// Creates adapter for `microdeg` millionths of a degree of `axis`.
+    pub fn new(microdeg: i32, axis: Axis) -> Self {
+        Coord {
+            microdeg,
+            axis,
+            dms: false,
+        }
+    }
+
+    /// Shows degrees, minutes and seconds.
+    pub fn dms(mut self) -> Self {
+        self.dms = true;
+        self
+    }
+
+    /// True if the value is beyond 90° for latitude or 180° for longitude.
+    pub fn is_out_of_range(&self) -> bool {
+        self.microdeg.unsigned_abs() as u64 > self.limit()
+    }
+
+    fn limit(&self) -> u64 {
+        match self.axis {
+            Axis::Latitude => 90 * MICRO,
+            Axis::Longitude => 180 * MICRO,
+        }
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let magnitude = (self.microdeg.unsigned_abs() as u64).min(self.limit());
+        let mut buf = [0u8; 32];
+        let (text, rounded_zero) = if self.dms || f.alternate() {
+            let decimals = f.precision().unwrap_or(1).min(3) as u32;
+            let pow = 10u64.pow(decimals);
+            // seconds in units of the last shown digit
+            let units = (magnitude * 3600 * pow + MICRO / 2) / MICRO;
+            let (degrees, minutes) = (units / (3600 * pow), units / (60 * pow) % 60);
+            let seconds = units % (60 * pow);
+            let text = if decimals > 0 {
+                fmt_truncate(
+                    &mut buf,
+                    format_args!(
+                        "{}\u{B0}{:02}'{:02}.{:0w$}\"",
+                        degrees,
+                        minutes,
+                        seconds / pow,
+                        seconds % pow,
+                        w = decimals as usize
+                    ),
+                )
+            } else {
+                fmt_truncate(
+                    &mut buf,
+                    format_args!("{}\u{B0}{:02}'{:02}\"", degrees, minutes, seconds),
+                )
+            };
+            (text.len(), units == 0)
+        } else {
+            let decimals = f.precision().unwrap_or(6).min(6) as u32;
+            let (pow, divisor) = (10u64.pow(decimals), 10u64.pow(6 - decimals));
+            let units = (magnitude + divisor / 2) / divisor;
+            let text = if decimals > 0 {
+                fmt_truncate(
+                    &mut buf,
+                    format_args!(
+                        "{}.{:0w$}\u{B0}",
+                        units / pow,
+                        units % pow,
+                        w = decimals as usize
+                    ),
+                )
+            } else {
+                fmt_truncate(&mut buf, format_args!("{}\u{B0}", units))
+            };
+            (text.len(), units == 0)
+        };
+
+        let negative = self.microdeg < 0 && !rounded_zero;
+        let hemisphere = match (self.axis, negative) {
+            (Axis::Latitude, false) => 'N',
+            (Axis::Latitude, true) => 'S',
+            (Axis::Longitude, false) => 'E',
+            (Axis::Longitude, true) => 'W',
+        };
+        buf[text] = hemisphere as u8;
+        // the buffer holds the str followed by an ASCII letter
+        let text = unsafe { core::str::from_utf8_unchecked(&buf[..text + 1]) };
+        // not f.pad() as it takes the precision as the max width
+        pad_with(f, text.chars().count(), fmt::Alignment::Left, |f| {
+            f.write_str(text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axis, Coord};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn coord_zero() {
+        let mut buf = [0u8; 32];
+        let zero = Coord::new(0, Axis::Latitude);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", zero)),
+            "0.000000\u{B0}N"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:#}", zero)),
+            "0\u{B0}00'00.0\"N"
+        );
+        // -0.4 rounds to zero and is east
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{:.0}", Coord::new(-400_000, Axis::Longitude)),
+        );
+        assert_eq!(s, "0\u{B0}E");
+    }
+
+    #[test]
+    fn coord_negative() {
+        let mut buf = [0u8; 32];
+        let lat = Coord::new(-33_868_820, Axis::Latitude);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", lat)),
+            "33.868820\u{B0}S"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", lat.dms())),
+            "33\u{B0}52'07.8\"S"
+        );
+        let lon = Coord::new(-151_209_296, Axis::Longitude);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.3}", lon)),
+            "151.209\u{B0}W"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.0}", lon.dms())),
+            "151\u{B0}12'33\"W"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>12.2}]", lon)),
+            "[    151.21\u{B0}W]"
+        );
+    }
+
+    #[test]
+    fn coord_seconds_carry() {
+        let mut buf = [0u8; 32];
+        // 10.999999° is 10°59'59.9964"
+        let c = Coord::new(10_999_999, Axis::Latitude);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", c.dms())),
+            "11\u{B0}00'00.0\"N"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.3}", c.dms())),
+            "10\u{B0}59'59.996\"N"
+        );
+        // 1.016666° is 1°00'59.9976"
+        let c = Coord::new(1_016_666, Axis::Longitude);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", c.dms())),
+            "1\u{B0}01'00.0\"E"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:.5}", Coord::new(9_999_999, Axis::Latitude))
+            ),
+            "10.00000\u{B0}N"
+        );
+    }
+
+    #[test]
+    fn coord_range() {
+        let mut buf = [0u8; 32];
+        let lat = Coord::new(91_000_000, Axis::Latitude);
+        assert!(lat.is_out_of_range());
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.0}", lat)),
+            "90\u{B0}N"
+        );
+        let lon = Coord::new(91_000_000, Axis::Longitude);
+        assert!(!lon.is_out_of_range());
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.0}", lon)),
+            "91\u{B0}E"
+        );
+        let lon = Coord::new(i32::MIN, Axis::Longitude);
+        assert!(lon.is_out_of_range());
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:#}", lon)),
+            "180\u{B0}00'00.0\"W"
+        );
+        assert!(!Coord::new(-180_000_000, Axis::Longitude).is_out_of_range());
+    }
+}
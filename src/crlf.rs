@@ -0,0 +1,107 @@
+//  ( /   @ @    ()  Converts LF line endings of formatted output into CRLF
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+
+/// Impl of [fmt::Write] that rewrites every `\n` into `\r\n`.
+///
+/// Existing `\r\n` pairs are left untouched even when the `\r` and `\n` arrive in different
+/// `write_str` calls. The inserted pair is written with [WriteWhole], so the truncation never
+/// leaves a bare `\r` introduced by this writer.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_crlf(&mut buf, format_args!("a\nb\r\nc"));
+/// assert_eq!(s, "a\r\nb\r\nc");
+/// ```
+pub struct CrlfWriter<W: WriteWhole> {
+    inner: W,
+    last_cr: bool, // If the last forwarded byte was `\r`
+}
+
+impl<W: WriteWhole> CrlfWriter<W> {
+    /// Creates stream forwarding the converted output to `inner`.
+    pub fn new(inner: W) -> Self {
+        CrlfWriter {
+            inner,
+            last_cr: false,
+        }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: WriteWhole> fmt::Write for CrlfWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            if b == b'\n' && !self.last_cr {
+                self.inner.write_str(&s[run_start..i])?;
+                self.inner.write_whole("\r\n")?;
+                run_start = i + 1;
+            }
+            self.last_cr = b == b'\r';
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+impl<W: WriteWhole> WriteWhole for CrlfWriter<W> {}
+
+/// Writes formatted string with `\n` converted to `\r\n` into the buffer truncating if needed.
+pub fn fmt_crlf<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = CrlfWriter::new(WriteTo::new(buffer));
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CrlfWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn crlf_mixed_input() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_crlf(&mut buf, format_args!("a\nb\r\n\n\rc\n{}", 1));
+        assert_eq!(s, "a\r\nb\r\n\r\n\rc\r\n1");
+    }
+
+    #[test]
+    fn crlf_split_across_chunks() {
+        let mut buf = [0u8; 64];
+        let mut w = CrlfWriter::new(WriteTo::new(&mut buf));
+        w.write_str("a\r").unwrap();
+        w.write_str("\nb\n").unwrap();
+        w.write_str("\n").unwrap();
+        assert_eq!(w.into_inner().as_str(), "a\r\nb\r\n\r\n");
+    }
+
+    #[test]
+    fn crlf_truncate_after_inserted_cr() {
+        // only `\r` of the inserted pair would fit
+        let mut buf = [0u8; 4];
+        let s = super::fmt_crlf(&mut buf, format_args!("abc\nd"));
+        assert_eq!(s, "abc");
+
+        let mut buf = [0u8; 5];
+        let s = super::fmt_crlf(&mut buf, format_args!("abc\nd"));
+        assert_eq!(s, "abc\r\n");
+    }
+
+    #[test]
+    fn crlf_truncate_original_cr() {
+        // the original `\r` is kept as the usual truncation does
+        let mut buf = [0u8; 4];
+        let s = super::fmt_crlf(&mut buf, format_args!("abc\r\nd"));
+        assert_eq!(s, "abc\r");
+    }
+}
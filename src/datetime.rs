@@ -0,0 +1,251 @@
+//  ( /   @ @    ()  Date and time components formatted with a strftime-like pattern
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Date and time as read from an RTC.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DateTimeParts {
+    pub year: u16,
+    /// 1..=12
+    pub month: u8,
+    /// 1..=31
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTimeParts {
+    // Day of the year, 1 for January 1
+    fn day_of_year(&self) -> u16 {
+        const BEFORE_MONTH: [u16; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+        let year = self.year;
+        let leap =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let month = self.month.clamp(1, 12);
+        let extra = if leap && month > 2 { 1 } else { 0 };
+        BEFORE_MONTH[month as usize - 1] + self.day as u16 + extra
+    }
+}
+
+/// Error of [fmt_datetime] and [Pattern::new].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PatternError {
+    /// `%` followed by a char that is not a supported specifier
+    UnknownSpecifier { pos: usize },
+    /// `%` at the end of the pattern
+    TrailingPercent { pos: usize },
+    /// Writing into the output failed
+    Format,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::UnknownSpecifier { pos } => write!(f, "unknown specifier at {}", pos),
+            PatternError::TrailingPercent { pos } => write!(f, "trailing '%' at {}", pos),
+            PatternError::Format => f.write_str("formatting failed"),
+        }
+    }
+}
+
+// Writes `parts` according to `pattern`, or with `out` None only checks the pattern
+fn write_pattern(
+    mut out: Option<&mut dyn fmt::Write>,
+    parts: &DateTimeParts,
+    pattern: &str,
+) -> Result<(), PatternError> {
+    let mut rest = pattern;
+    while let Some(percent) = rest.find('%') {
+        let pos = pattern.len() - rest.len() + percent;
+        let spec = match rest[percent + 1..].chars().next() {
+            Some(spec) => spec,
+            None => return Err(PatternError::TrailingPercent { pos }),
+        };
+        if !"YymdHMSj%".contains(spec) {
+            return Err(PatternError::UnknownSpecifier { pos });
+        }
+        if let Some(w) = out.as_mut() {
+            w.write_str(&rest[..percent])
+                .map_err(|_| PatternError::Format)?;
+            let written = match spec {
+                'Y' => write!(w, "{:04}", parts.year),
+                'y' => write!(w, "{:02}", parts.year % 100),
+                'm' => write!(w, "{:02}", parts.month),
+                'd' => write!(w, "{:02}", parts.day),
+                'H' => write!(w, "{:02}", parts.hour),
+                'M' => write!(w, "{:02}", parts.minute),
+                'S' => write!(w, "{:02}", parts.second),
+                'j' => write!(w, "{:03}", parts.day_of_year()),
+                _ => w.write_char('%'),
+            };
+            written.map_err(|_| PatternError::Format)?;
+        }
+        rest = &rest[percent + 1 + spec.len_utf8()..];
+    }
+    match out {
+        Some(w) => w.write_str(rest).map_err(|_| PatternError::Format),
+        None => Ok(()),
+    }
+}
+
+/// Writes `parts` into the buffer according to a strftime-like `pattern`.
+///
+/// Supported specifiers, all zero padded: `%Y` year (4 digits), `%y` year (2 digits), `%m`
+/// month, `%d` day, `%H` hour, `%M` minute, `%S` second, `%j` day of the year (3 digits)
+/// and `%%` for `%`. Any other specifier is an error. The output is truncated if it does
+/// not fit.
+///
+/// ```
+/// use stackfmt::DateTimeParts;
+///
+/// let parts = DateTimeParts { year: 2024, month: 6, day: 1, hour: 9, minute: 5, second: 0 };
+/// let mut buf = [0u8; 32];
+/// assert_eq!(stackfmt::fmt_datetime(&mut buf, &parts, "%d.%m.%y %H:%M"), Ok("01.06.24 09:05"));
+/// ```
+pub fn fmt_datetime<'a>(
+    buffer: &'a mut [u8],
+    parts: &DateTimeParts,
+    pattern: &str,
+) -> Result<&'a str, PatternError> {
+    let mut w = WriteTo::new(buffer);
+    write_pattern(Some(&mut w), parts, pattern)?;
+    Ok(w.as_str())
+}
+
+/// Display adapter that shows [DateTimeParts] according to a pattern, see [fmt_datetime].
+///
+/// The pattern is validated by [Pattern::new], so the formatting fails only if the output
+/// does.
+///
+/// ```
+/// use stackfmt::{DateTimeParts, Pattern};
+///
+/// let parts = DateTimeParts { year: 2024, month: 6, day: 1, hour: 9, minute: 5, second: 0 };
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("[{}] up", Pattern::new(&parts, "%H:%M:%S")?));
+/// assert_eq!(s, "[09:05:00] up");
+/// # Ok::<(), stackfmt::PatternError>(())
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Pattern<'a> {
+    parts: &'a DateTimeParts,
+    pattern: &'a str,
+}
+
+impl<'a> Pattern<'a> {
+    /// Creates adapter showing `parts` according to `pattern`, fails if the pattern has
+    /// an unsupported specifier.
+    pub fn new(parts: &'a DateTimeParts, pattern: &'a str) -> Result<Self, PatternError> {
+        write_pattern(None, parts, pattern)?;
+        Ok(Pattern { parts, pattern })
+    }
+}
+
+impl<'a> fmt::Display for Pattern<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_pattern(Some(f), self.parts, self.pattern).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_datetime, DateTimeParts, Pattern, PatternError};
+
+    const PARTS: DateTimeParts = DateTimeParts {
+        year: 2024,
+        month: 12,
+        day: 31,
+        hour: 23,
+        minute: 59,
+        second: 58,
+    };
+
+    #[test]
+    fn datetime_each_specifier() {
+        let mut buf = [0u8; 32];
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%Y"), Ok("2024"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%y"), Ok("24"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%m"), Ok("12"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%d"), Ok("31"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%H"), Ok("23"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%M"), Ok("59"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%S"), Ok("58"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%j"), Ok("366"));
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "100%%"), Ok("100%"));
+        let parts = DateTimeParts {
+            year: 1900,
+            month: 3,
+            day: 1,
+            ..PARTS
+        };
+        assert_eq!(fmt_datetime(&mut buf, &parts, "%j"), Ok("060"));
+    }
+
+    #[test]
+    fn datetime_combined() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_datetime(&mut buf, &PARTS, "%Y-%m-%d %H:%M:%S"),
+            Ok("2024-12-31 23:59:58")
+        );
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%d.%m.%y"), Ok("31.12.24"));
+        assert_eq!(
+            fmt_datetime(&mut buf, &PARTS, "\u{23F0} %H%M\u{2014}"),
+            Ok("\u{23F0} 2359\u{2014}")
+        );
+        // truncated
+        let mut buf = [0u8; 6];
+        assert_eq!(fmt_datetime(&mut buf, &PARTS, "%H:%M:%S"), Ok("23:59:"));
+    }
+
+    #[test]
+    fn datetime_invalid_specifier() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_datetime(&mut buf, &PARTS, "%H:%q"),
+            Err(PatternError::UnknownSpecifier { pos: 3 })
+        );
+        assert_eq!(
+            fmt_datetime(&mut buf, &PARTS, "%H %\u{E9}"),
+            Err(PatternError::UnknownSpecifier { pos: 3 })
+        );
+        assert_eq!(
+            fmt_datetime(&mut buf, &PARTS, "50%"),
+            Err(PatternError::TrailingPercent { pos: 2 })
+        );
+        assert_eq!(
+            Pattern::new(&PARTS, "%Y-%x").unwrap_err(),
+            PatternError::UnknownSpecifier { pos: 3 }
+        );
+        assert_eq!(
+            Pattern::new(&PARTS, "%H%").unwrap_err(),
+            PatternError::TrailingPercent { pos: 2 }
+        );
+        let pattern = Pattern::new(&PARTS, "%Y-%m").unwrap();
+        assert_eq!(
+            crate::fmt_truncate(&mut buf, format_args!("{}", pattern)),
+            "2024-12"
+        );
+    }
+
+    #[test]
+    fn datetime_single_digits_padded() {
+        let parts = DateTimeParts {
+            year: 7,
+            month: 1,
+            day: 2,
+            hour: 3,
+            minute: 4,
+            second: 5,
+        };
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_datetime(&mut buf, &parts, "%Y-%m-%d %H:%M:%S %y %j"),
+            Ok("0007-01-02 03:04:05 07 002")
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//  ( /   @ @    ()  Capped hex preview of a byte slice
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Display adapter that shows at most the first `max` bytes of a slice in hex.
+///
+/// A slice that does not fit is shown as `head … (N bytes)` where N is the total length, or
+/// as `head … tail (N bytes)` when a tail window is requested. A slice that fits is shown as
+/// the space separated bytes only. `Debug` output is the same as `Display`.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let data = [0x5au8; 512];
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("{}", stackfmt::DebugBytes::new(&data).max(4)),
+/// );
+/// assert_eq!(s, "5a 5a 5a 5a \u{2026} (512 bytes)");
+/// ```
+#[derive(Clone, Copy)]
+pub struct DebugBytes<'a> {
+    bytes: &'a [u8],
+    max: usize,
+    tail: usize,
+    uppercase: bool,
+}
+
+impl<'a> DebugBytes<'a> {
+    /// Creates adapter showing up to 16 bytes.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        DebugBytes {
+            bytes,
+            max: 16,
+            tail: 0,
+            uppercase: false,
+        }
+    }
+
+    /// Sets the number of bytes shown from the start of a slice.
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Also shows the last `tail` bytes of a slice that does not fit.
+    pub fn tail(mut self, tail: usize) -> Self {
+        self.tail = tail;
+        self
+    }
+
+    /// Uses uppercase hex digits.
+    pub fn uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+
+    fn write_bytes(&self, f: &mut fmt::Formatter, bytes: &[u8], first: bool) -> fmt::Result {
+        for (n, b) in bytes.iter().enumerate() {
+            if n > 0 || !first {
+                f.write_str(" ")?;
+            }
+            if self.uppercase {
+                write!(f, "{:02X}", b)?;
+            } else {
+                write!(f, "{:02x}", b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for DebugBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let len = self.bytes.len();
+        if len <= self.max.saturating_add(self.tail) {
+            return self.write_bytes(f, self.bytes, true);
+        }
+
+        let head = &self.bytes[..self.max];
+        self.write_bytes(f, head, true)?;
+        f.write_str(if head.is_empty() {
+            "\u{2026}"
+        } else {
+            " \u{2026}"
+        })?;
+        self.write_bytes(f, &self.bytes[len - self.tail..], false)?;
+        write!(f, " ({} bytes)", len)
+    }
+}
+
+impl<'a> fmt::Debug for DebugBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DebugBytes;
+
+    #[test]
+    fn debug_bytes_shorter_than_cap() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", DebugBytes::new(&[0x5a, 0xf3, 0])),
+        );
+        assert_eq!(s, "5a f3 00");
+        let s = crate::fmt_truncate(&mut buf, format_args!("[{:?}]", DebugBytes::new(&[])));
+        assert_eq!(s, "[]");
+    }
+
+    #[test]
+    fn debug_bytes_exactly_at_cap() {
+        let mut buf = [0u8; 64];
+        let bytes = [1u8, 2, 3, 0xAB];
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", DebugBytes::new(&bytes).max(4).uppercase(true)),
+        );
+        assert_eq!(s, "01 02 03 AB");
+    }
+
+    #[test]
+    fn debug_bytes_larger() {
+        let mut buf = [0u8; 128];
+        let mut bytes = [0u8; 512];
+        for (n, b) in bytes.iter_mut().enumerate() {
+            *b = n as u8;
+        }
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:?}", DebugBytes::new(&bytes)));
+        assert_eq!(
+            s,
+            "00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f \u{2026} (512 bytes)"
+        );
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", DebugBytes::new(&bytes).max(0)));
+        assert_eq!(s, "\u{2026} (512 bytes)");
+    }
+
+    #[test]
+    fn debug_bytes_head_tail() {
+        let mut buf = [0u8; 128];
+        let mut bytes = [0u8; 512];
+        for (n, b) in bytes.iter_mut().enumerate() {
+            *b = n as u8;
+        }
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", DebugBytes::new(&bytes).max(2).tail(3)),
+        );
+        assert_eq!(s, "00 01 \u{2026} fd fe ff (512 bytes)");
+        // everything fits into head and tail together
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", DebugBytes::new(&bytes[..5]).max(2).tail(3)),
+        );
+        assert_eq!(s, "00 01 02 03 04");
+    }
+
+    #[test]
+    fn debug_bytes_truncated_by_buffer() {
+        let mut buf = [0u8; 10];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", DebugBytes::new(&[0xff; 64])));
+        assert_eq!(s, "ff ff ff f");
+    }
+}
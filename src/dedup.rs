@@ -0,0 +1,215 @@
+//  ( /   @ @    ()  Repeated lines collapsed into a count
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::plural::Plural;
+use crate::stackfmt::fmt_truncate;
+use core::fmt;
+
+/// Writer that passes lines to a sink but replaces repeats of the same line with a count.
+///
+/// Each completed line (with its `\n`) is compared with the previous one kept in the caller
+/// buffer. A repeat is only counted; when a different line arrives or on
+/// [DedupWriter::flush] the sink gets `… last message repeated N times` line first. The
+/// sink gets each line in a single call, except a line longer than the buffer: it is passed
+/// in pieces as it comes and is never treated as a repeat.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut out = [0u8; 128];
+/// let mut log = stackfmt::WriteTo::new(&mut out);
+/// let mut buf = [0u8; 32];
+/// {
+///     let mut w = stackfmt::DedupWriter::new(&mut buf, |line: &str| {
+///         let _ = log.write_str(line);
+///     });
+///     for _ in 0..3 {
+///         writeln!(w, "sensor timeout").unwrap();
+///     }
+///     writeln!(w, "sensor ok").unwrap();
+/// }
+/// assert_eq!(
+///     log.as_str(),
+///     "sensor timeout\n\u{2026} last message repeated 2 times\nsensor ok\n"
+/// );
+/// ```
+pub struct DedupWriter<'b, F: FnMut(&str)> {
+    buffer: &'b mut [u8],
+    sink: F,
+    len: usize,              // bytes of the current line in the buffer
+    prev_len: Option<usize>, // previous line if it is known
+    matched: bool,           // the current line so far equals the previous one
+    oversized: bool,         // the current line did not fit and is passed directly
+    repeats: usize,
+}
+
+impl<'b, F: FnMut(&str)> DedupWriter<'b, F> {
+    /// Creates writer passing lines to `sink` and keeping the last line in `buffer`.
+    pub fn new(buffer: &'b mut [u8], sink: F) -> Self {
+        DedupWriter {
+            buffer,
+            sink,
+            len: 0,
+            prev_len: None,
+            matched: false,
+            oversized: false,
+            repeats: 0,
+        }
+    }
+
+    /// Passes the count of repeats not reported yet to the sink.
+    pub fn flush(&mut self) {
+        if self.repeats > 0 {
+            let mut buf = [0u8; 64];
+            let plural = Plural::new(self.repeats as u64, "time", "times");
+            (self.sink)(fmt_truncate(
+                &mut buf,
+                format_args!("\u{2026} last message repeated {}\n", plural),
+            ));
+            self.repeats = 0;
+        }
+    }
+
+    // the buffer holds whole chars of a str
+    fn line(buffer: &[u8], len: usize) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&buffer[..len]) }
+    }
+
+    fn push(&mut self, piece: &str) {
+        if self.oversized {
+            (self.sink)(piece);
+            return;
+        }
+
+        let room = self.buffer.len() - self.len;
+        if piece.len() > room {
+            let mut cut = room;
+            while !piece.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            self.buffer[self.len..self.len + cut].copy_from_slice(&piece.as_bytes()[..cut]);
+            self.flush();
+            (self.sink)(Self::line(self.buffer, self.len + cut));
+            (self.sink)(&piece[cut..]);
+            self.oversized = true;
+            self.prev_len = None;
+            return;
+        }
+
+        let end = self.len + piece.len();
+        // until the first mismatch the buffer is overwritten with the same bytes
+        self.matched = self.matched
+            && self.prev_len.is_some_and(|prev_len| end <= prev_len)
+            && &self.buffer[self.len..end] == piece.as_bytes();
+        self.buffer[self.len..end].copy_from_slice(piece.as_bytes());
+        self.len = end;
+    }
+
+    fn end_line(&mut self) {
+        if !self.oversized {
+            if self.matched && self.prev_len == Some(self.len) {
+                self.repeats += 1;
+            } else {
+                self.flush();
+                (self.sink)(Self::line(self.buffer, self.len));
+                self.prev_len = Some(self.len);
+            }
+        }
+        self.oversized = false;
+        self.len = 0;
+        self.matched = self.prev_len.is_some();
+    }
+}
+
+impl<'b, F: FnMut(&str)> fmt::Write for DedupWriter<'b, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for piece in s.split_inclusive('\n') {
+            self.push(piece);
+            if piece.ends_with('\n') {
+                self.end_line();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    // Writes `lines` through DedupWriter with a buffer of `N` bytes, flushes if asked
+    fn dedup<'o, const N: usize>(out: &'o mut [u8], lines: &[&str], flush: bool) -> &'o str {
+        let mut log = WriteTo::new(out);
+        let mut buf = [0u8; N];
+        {
+            let mut w = DedupWriter::new(&mut buf, |s: &str| {
+                let _ = log.write_str(s);
+            });
+            for line in lines {
+                w.write_str(line).unwrap();
+            }
+            if flush {
+                w.flush();
+            }
+        }
+        log.as_str()
+    }
+
+    #[test]
+    fn dedup_runs() {
+        let mut out = [0u8; 256];
+        let s = dedup::<16>(&mut out, &["a\n", "a\n", "a\n", "b\n", "b\n", "c\n"], false);
+        assert_eq!(
+            s,
+            "a\n\u{2026} last message repeated 2 times\nb\n\u{2026} last message repeated 1 time\nc\n"
+        );
+        // a line written in pieces is the same line
+        let s = dedup::<16>(
+            &mut out,
+            &["temp=", "21\n", "temp=21", "\n", "temp=2", "2\n"],
+            false,
+        );
+        assert_eq!(
+            s,
+            "temp=21\n\u{2026} last message repeated 1 time\ntemp=22\n"
+        );
+    }
+
+    #[test]
+    fn dedup_alternating() {
+        let mut out = [0u8; 256];
+        let s = dedup::<16>(&mut out, &["up\nup down\nup\n", "up down\n"], true);
+        assert_eq!(s, "up\nup down\nup\nup down\n");
+        // prefix of the previous line is not a repeat
+        let s = dedup::<16>(&mut out, &["abc\n", "ab\n", "abc\n"], true);
+        assert_eq!(s, "abc\nab\nabc\n");
+    }
+
+    #[test]
+    fn dedup_flush_pending() {
+        let mut out = [0u8; 256];
+        let s = dedup::<16>(&mut out, &["x\nx\nx\nx\n"], true);
+        assert_eq!(s, "x\n\u{2026} last message repeated 3 times\n");
+        let s = dedup::<16>(&mut out, &["x\nx\nx\nx\n"], false);
+        assert_eq!(s, "x\n");
+        // nothing is pending after flush
+        let s = dedup::<16>(&mut out, &["x\n"], true);
+        assert_eq!(s, "x\n");
+    }
+
+    #[test]
+    fn dedup_oversized_line() {
+        let mut out = [0u8; 256];
+        let long = "0123456789\u{20AC}\n";
+        let s = dedup::<11>(&mut out, &[long, long, "ok\n", "ok\n"], true);
+        assert_eq!(
+            s,
+            "0123456789\u{20AC}\n0123456789\u{20AC}\nok\n\u{2026} last message repeated 1 time\n"
+        );
+        let s = dedup::<4>(&mut out, &["ab\n", "ab\n", "abcdef\n", "ab\n"], true);
+        assert_eq!(s, "ab\n\u{2026} last message repeated 1 time\nabcdef\nab\n");
+    }
+}
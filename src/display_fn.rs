@@ -0,0 +1,93 @@
+//  ( /   @ @    ()  Display and Debug implemented by a closure
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Adapter implementing [fmt::Display] and [fmt::Debug] by calling a closure, see
+/// [display_fn].
+#[derive(Clone, Copy)]
+pub struct DisplayFn<F>(F);
+
+/// Creates an ad-hoc Display adapter from a closure.
+///
+/// The closure gets the formatter, so it can use the width, precision and flags of the
+/// placeholder the adapter is used with. This is a stable replacement for
+/// `core::fmt::from_fn`.
+///
+/// ```
+/// use stackfmt::display_fn;
+///
+/// let (major, minor) = (1, 4);
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("v{}", display_fn(|f| write!(f, "{}-{}", major, minor))),
+/// );
+/// assert_eq!(s, "v1-4");
+/// ```
+pub fn display_fn<F: Fn(&mut fmt::Formatter) -> fmt::Result>(f: F) -> DisplayFn<F> {
+    DisplayFn(f)
+}
+
+impl<F: Fn(&mut fmt::Formatter) -> fmt::Result> fmt::Display for DisplayFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+impl<F: Fn(&mut fmt::Formatter) -> fmt::Result> fmt::Debug for DisplayFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::display_fn;
+    use crate::fmt_truncate;
+    use core::fmt;
+
+    #[test]
+    fn display_fn_display_and_debug() {
+        let mut buf = [0u8; 32];
+        let d = display_fn(|f| f.write_str("id"));
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}={:?}", d, d)),
+            "id=id"
+        );
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{:?}", Some(display_fn(|f| f.write_str("x")))),
+        );
+        assert_eq!(s, "Some(x)");
+    }
+
+    #[test]
+    fn display_fn_uses_formatter_flags() {
+        let mut buf = [0u8; 32];
+        let name = "temperature";
+        let d = display_fn(|f: &mut fmt::Formatter| match f.precision() {
+            Some(p) => f.pad(&name[..p.min(name.len())]),
+            None => f.pad(name),
+        });
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>6.4}]", d)),
+            "[  temp]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", d)),
+            "[temperature]"
+        );
+
+        let d = display_fn(|f| {
+            let width = f.width().unwrap_or(0);
+            write!(f, "{:0width$}|{}", 42, f.alternate(), width = width)
+        });
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:5}", d)),
+            "00042|false"
+        );
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{:#}", d)), "42|true");
+    }
+}
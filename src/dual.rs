@@ -0,0 +1,192 @@
+//  ( /   @ @    ()  Header and trailer written from both ends of a buffer
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Writes text forward from the start of a buffer and, independently, a trailer kept
+/// right-aligned at the end of the same buffer.
+///
+/// [DualWriter::front] appends at the start as [crate::WriteTo] does. [DualWriter::back]
+/// appends to the trailer too, in reading order, moving the trailer left to keep it at the
+/// end of the buffer. When the two would collide the text is cut at a char boundary and
+/// both sides stop accepting data. [DualWriter::finish] returns both parts and the gap
+/// between them, [DualWriter::compact] joins them.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 32];
+/// let mut d = stackfmt::DualWriter::new(&mut buf);
+/// write!(d.front(), "HDR ").unwrap();
+/// for i in 0..3 {
+///     write!(d.front(), "{},", i).unwrap();
+/// }
+/// write!(d.back(), " n={}", 3).unwrap();
+/// assert_eq!(d.compact(), "HDR 0,1,2, n=3");
+/// ```
+pub struct DualWriter<'a> {
+    buffer: &'a mut [u8],
+    front: usize, // bytes written at the start
+    back: usize,  // bytes written at the end
+    overflow: bool,
+}
+
+impl<'a> DualWriter<'a> {
+    /// Creates writer with empty front and back parts.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        DualWriter {
+            buffer,
+            front: 0,
+            back: 0,
+            overflow: false,
+        }
+    }
+
+    /// Returns stream appending to the front part.
+    pub fn front(&mut self) -> DualFront<'_, 'a> {
+        DualFront(self)
+    }
+
+    /// Returns stream appending to the back part.
+    pub fn back(&mut self) -> DualBack<'_, 'a> {
+        DualBack(self)
+    }
+
+    /// True if some text did not fit because the parts collided.
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    /// Returns the front part, the back part and the count of unused bytes between them.
+    pub fn finish(self) -> (&'a str, &'a str, usize) {
+        let len = self.buffer.len();
+        let gap = len - self.front - self.back;
+        let (front, rest) = self.buffer.split_at(self.front);
+        // both parts have whole chars of a str
+        unsafe {
+            (
+                core::str::from_utf8_unchecked(front),
+                core::str::from_utf8_unchecked(&rest[gap..]),
+                gap,
+            )
+        }
+    }
+
+    /// Moves the back part right after the front part and returns the joined text.
+    pub fn compact(self) -> &'a str {
+        let len = self.buffer.len();
+        self.buffer.copy_within(len - self.back.., self.front);
+        let joined = &self.buffer[..self.front + self.back];
+        // both parts have whole chars of a str
+        unsafe { core::str::from_utf8_unchecked(joined) }
+    }
+
+    // Bytes of `s` that fit into the gap cut at a char boundary, sets overflow if cut
+    fn fitting<'s>(&mut self, s: &'s str) -> &'s str {
+        if self.overflow {
+            return "";
+        }
+        let mut room = self.buffer.len() - self.front - self.back;
+        if s.len() <= room {
+            return s;
+        }
+        self.overflow = true;
+        while !s.is_char_boundary(room) {
+            room -= 1;
+        }
+        &s[..room]
+    }
+}
+
+/// Stream returned by [DualWriter::front].
+pub struct DualFront<'w, 'a>(&'w mut DualWriter<'a>);
+
+impl<'w, 'a> fmt::Write for DualFront<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let d = &mut *self.0;
+        let s = d.fitting(s);
+        d.buffer[d.front..d.front + s.len()].copy_from_slice(s.as_bytes());
+        d.front += s.len();
+        Ok(())
+    }
+}
+
+/// Stream returned by [DualWriter::back].
+pub struct DualBack<'w, 'a>(&'w mut DualWriter<'a>);
+
+impl<'w, 'a> fmt::Write for DualBack<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let d = &mut *self.0;
+        let s = d.fitting(s);
+        let len = d.buffer.len();
+        let start = len - d.back;
+        d.buffer.copy_within(start.., start - s.len());
+        d.buffer[len - s.len()..].copy_from_slice(s.as_bytes());
+        d.back += s.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DualWriter;
+    use core::fmt::Write;
+
+    #[test]
+    fn dual_no_collision() {
+        let mut buf = [0u8; 16];
+        let mut d = DualWriter::new(&mut buf);
+        write!(d.back(), "<{}", 9).unwrap();
+        write!(d.front(), "ab").unwrap();
+        write!(d.back(), "{}>", 7).unwrap();
+        assert!(!d.is_truncated());
+        assert_eq!(d.finish(), ("ab", "<97>", 10));
+        assert_eq!(&buf[12..], b"<97>");
+    }
+
+    #[test]
+    fn dual_exact_meeting() {
+        let mut buf = [0u8; 8];
+        let mut d = DualWriter::new(&mut buf);
+        write!(d.front(), "1234").unwrap();
+        write!(d.back(), "5678").unwrap();
+        assert!(!d.is_truncated());
+        write!(d.back(), "").unwrap();
+        assert!(!d.is_truncated());
+        assert_eq!(d.finish(), ("1234", "5678", 0));
+    }
+
+    #[test]
+    fn dual_collision_mid_char() {
+        let mut buf = [0u8; 7];
+        let mut d = DualWriter::new(&mut buf);
+        write!(d.back(), "\u{20AC}").unwrap();
+        write!(d.front(), "ab\u{20AC}").unwrap();
+        assert!(d.is_truncated());
+        // nothing more is accepted on either side
+        write!(d.back(), "c").unwrap();
+        write!(d.front(), "d").unwrap();
+        assert_eq!(d.finish(), ("ab", "\u{20AC}", 2));
+
+        let mut buf = [0u8; 7];
+        let mut d = DualWriter::new(&mut buf);
+        write!(d.front(), "abc").unwrap();
+        write!(d.back(), "x\u{E9}\u{E9}").unwrap();
+        assert!(d.is_truncated());
+        assert_eq!(d.finish(), ("abc", "x\u{E9}", 1));
+    }
+
+    #[test]
+    fn dual_compact() {
+        let mut buf = [0u8; 16];
+        let mut d = DualWriter::new(&mut buf);
+        write!(d.front(), "len=").unwrap();
+        write!(d.back(), ";crc={:02X}", 0xAB).unwrap();
+        assert_eq!(d.compact(), "len=;crc=AB");
+
+        let mut buf = [0u8; 4];
+        let d = DualWriter::new(&mut buf);
+        assert_eq!(d.compact(), "");
+    }
+}
@@ -0,0 +1,286 @@
+//  ( /   @ @    ()  Human readable and ISO-8601 durations
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{fmt_truncate, WriteTo};
+use core::fmt;
+use core::fmt::Write;
+use core::time::Duration;
+
+/// Display adapter that shows a [Duration] with a granularity matching its length.
+///
+/// | duration        | shown as       |
+/// |-----------------|----------------|
+/// | under 1 µs      | `250ns`        |
+/// | under 1 ms      | `999µs`        |
+/// | under 1 s       | `250ms`        |
+/// | under 1 minute  | `59s`          |
+/// | under 1 hour    | `3m 05s`       |
+/// | under 1 day     | `1h 03m 05s`   |
+/// | longer          | `2d 04h 10m`   |
+///
+/// The smaller units are always truncated, never rounded, so a duration is never shown
+/// longer than it is (59.9 s is `59s`). The alternate flag `{:#}` selects the fixed
+/// `HH:MM:SS` form where hours are not limited to two digits. Width and alignment apply to
+/// the whole text.
+///
+/// ```
+/// use core::time::Duration;
+/// use stackfmt::DurationHuman;
+///
+/// let mut buf = [0u8; 32];
+/// let d = Duration::from_secs(3785);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", DurationHuman(d))), "1h 03m 05s");
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{:#}", DurationHuman(d))), "01:03:05");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DurationHuman(pub Duration);
+
+impl fmt::Display for DurationHuman {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let nanos = self.0.subsec_nanos();
+        let (days, hours) = (secs / 86400, secs / 3600 % 24);
+        let (minutes, seconds) = (secs / 60 % 60, secs % 60);
+
+        let mut buf = [0u8; 40];
+        let text = if f.alternate() {
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:02}:{:02}:{:02}", secs / 3600, minutes, seconds),
+            )
+        } else if secs == 0 && nanos < 1_000 {
+            fmt_truncate(&mut buf, format_args!("{}ns", nanos))
+        } else if secs == 0 && nanos < 1_000_000 {
+            fmt_truncate(&mut buf, format_args!("{}\u{B5}s", nanos / 1_000))
+        } else if secs == 0 {
+            fmt_truncate(&mut buf, format_args!("{}ms", nanos / 1_000_000))
+        } else if secs < 60 {
+            fmt_truncate(&mut buf, format_args!("{}s", secs))
+        } else if secs < 3600 {
+            fmt_truncate(&mut buf, format_args!("{}m {:02}s", minutes, seconds))
+        } else if days == 0 {
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}h {:02}m {:02}s", hours, minutes, seconds),
+            )
+        } else {
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}d {:02}h {:02}m", days, hours, minutes),
+            )
+        };
+        f.pad(text)
+    }
+}
+
+/// Display adapter that shows a [Duration] in the ISO-8601 form like `PT1H2M3S`.
+///
+/// Zero components are omitted and a zero duration is `PT0S`. Fractional seconds are shown
+/// only when non-zero, in groups of three digits down to nanoseconds (`PT0.250S`). Days are
+/// split out of hours (`P2DT3H`) only if asked with [DurationIso::with_days], as a day is
+/// not always 24 hours for the reader. Width and alignment apply to the whole text.
+///
+/// ```
+/// use core::time::Duration;
+/// use stackfmt::DurationIso;
+///
+/// let mut buf = [0u8; 32];
+/// let d = Duration::from_millis(3_723_250);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", DurationIso::new(d))), "PT1H2M3.250S");
+/// let d = Duration::from_secs(2 * 86400 + 3 * 3600);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", DurationIso::with_days(d))), "P2DT3H");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DurationIso {
+    duration: Duration,
+    days: bool,
+}
+
+impl DurationIso {
+    /// Creates adapter showing hours, minutes and seconds.
+    pub fn new(duration: Duration) -> Self {
+        DurationIso {
+            duration,
+            days: false,
+        }
+    }
+
+    /// Creates adapter showing days, hours, minutes and seconds.
+    pub fn with_days(duration: Duration) -> Self {
+        DurationIso {
+            duration,
+            days: true,
+        }
+    }
+}
+
+impl fmt::Display for DurationIso {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.duration.as_secs();
+        let nanos = self.duration.subsec_nanos();
+        let (days, hours) = match self.days {
+            true => (secs / 86400, secs / 3600 % 24),
+            false => (0, secs / 3600),
+        };
+        let (minutes, seconds) = (secs / 60 % 60, secs % 60);
+
+        let mut buf = [0u8; 48];
+        let mut w = WriteTo::new(&mut buf);
+        let _ = w.write_char('P');
+        if days > 0 {
+            let _ = write!(w, "{}D", days);
+        }
+        if hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 || days == 0 {
+            let _ = w.write_char('T');
+        }
+        if hours > 0 {
+            let _ = write!(w, "{}H", hours);
+        }
+        if minutes > 0 {
+            let _ = write!(w, "{}M", minutes);
+        }
+        if nanos > 0 {
+            let (fraction, digits) = match nanos {
+                _ if nanos.is_multiple_of(1_000_000) => (nanos / 1_000_000, 3),
+                _ if nanos.is_multiple_of(1_000) => (nanos / 1_000, 6),
+                _ => (nanos, 9),
+            };
+            let _ = write!(w, "{}.{:0digits$}S", seconds, fraction, digits = digits);
+        } else if seconds > 0 || secs == 0 {
+            let _ = write!(w, "{}S", seconds);
+        }
+        f.pad(w.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DurationHuman, DurationIso};
+    use core::time::Duration;
+
+    fn human(buf: &mut [u8], d: Duration) -> &str {
+        crate::fmt_truncate(buf, format_args!("{}", DurationHuman(d)))
+    }
+
+    fn fixed(buf: &mut [u8], d: Duration) -> &str {
+        crate::fmt_truncate(buf, format_args!("{:#}", DurationHuman(d)))
+    }
+
+    #[test]
+    fn duration_zero() {
+        let mut buf = [0u8; 64];
+        assert_eq!(human(&mut buf, Duration::ZERO), "0ns");
+        assert_eq!(fixed(&mut buf, Duration::ZERO), "00:00:00");
+    }
+
+    #[test]
+    fn duration_sub_second() {
+        let mut buf = [0u8; 64];
+        assert_eq!(human(&mut buf, Duration::from_nanos(999)), "999ns");
+        assert_eq!(human(&mut buf, Duration::from_micros(999)), "999\u{B5}s");
+        assert_eq!(human(&mut buf, Duration::from_nanos(999_999)), "999\u{B5}s");
+        assert_eq!(human(&mut buf, Duration::from_millis(250)), "250ms");
+    }
+
+    #[test]
+    fn duration_seconds_truncated() {
+        let mut buf = [0u8; 64];
+        assert_eq!(human(&mut buf, Duration::from_millis(59_900)), "59s");
+        assert_eq!(human(&mut buf, Duration::from_secs(60)), "1m 00s");
+        assert_eq!(human(&mut buf, Duration::from_secs(3599)), "59m 59s");
+    }
+
+    #[test]
+    fn duration_one_hour() {
+        let mut buf = [0u8; 64];
+        assert_eq!(human(&mut buf, Duration::from_secs(3600)), "1h 00m 00s");
+        assert_eq!(fixed(&mut buf, Duration::from_secs(3600)), "01:00:00");
+    }
+
+    #[test]
+    fn duration_multi_day() {
+        let mut buf = [0u8; 64];
+        let d = Duration::from_secs(2 * 86400 + 4 * 3600 + 10 * 60 + 59);
+        assert_eq!(human(&mut buf, d), "2d 04h 10m");
+        assert_eq!(fixed(&mut buf, d), "52:10:59");
+    }
+
+    #[test]
+    fn duration_max() {
+        let mut buf = [0u8; 64];
+        assert_eq!(human(&mut buf, Duration::MAX), "213503982334601d 07h 00m");
+        assert_eq!(fixed(&mut buf, Duration::MAX), "5124095576030431:00:15");
+    }
+
+    #[test]
+    fn duration_width() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("[{:>6}]", DurationHuman(Duration::from_millis(5))),
+        );
+        assert_eq!(s, "[   5ms]");
+    }
+
+    fn iso(buf: &mut [u8], d: Duration, days: bool) -> &str {
+        let iso = match days {
+            true => DurationIso::with_days(d),
+            false => DurationIso::new(d),
+        };
+        crate::fmt_truncate(buf, format_args!("{}", iso))
+    }
+
+    #[test]
+    fn duration_iso_zero_and_sub_second() {
+        let mut buf = [0u8; 64];
+        assert_eq!(iso(&mut buf, Duration::ZERO, false), "PT0S");
+        assert_eq!(iso(&mut buf, Duration::ZERO, true), "PT0S");
+        assert_eq!(iso(&mut buf, Duration::from_millis(250), false), "PT0.250S");
+        assert_eq!(
+            iso(&mut buf, Duration::from_micros(1500), false),
+            "PT0.001500S"
+        );
+        assert_eq!(
+            iso(&mut buf, Duration::from_nanos(1), false),
+            "PT0.000000001S"
+        );
+    }
+
+    #[test]
+    fn duration_iso_components() {
+        let mut buf = [0u8; 64];
+        assert_eq!(iso(&mut buf, Duration::from_secs(3600), false), "PT1H");
+        assert_eq!(iso(&mut buf, Duration::from_secs(3605), false), "PT1H5S");
+        assert_eq!(iso(&mut buf, Duration::from_secs(120), true), "PT2M");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("[{:>6}]", DurationIso::new(Duration::from_secs(7))),
+        );
+        assert_eq!(s, "[  PT7S]");
+    }
+
+    #[test]
+    fn duration_iso_days() {
+        let mut buf = [0u8; 64];
+        let d = Duration::from_secs(2 * 86400 + 3 * 3600);
+        assert_eq!(iso(&mut buf, d, true), "P2DT3H");
+        assert_eq!(iso(&mut buf, d, false), "PT51H");
+        assert_eq!(iso(&mut buf, Duration::from_secs(86400), true), "P1D");
+        let d = Duration::new(86400 + 61, 500_000_000);
+        assert_eq!(iso(&mut buf, d, true), "P1DT1M1.500S");
+    }
+
+    #[test]
+    fn duration_iso_max() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            iso(&mut buf, Duration::MAX, false),
+            "PT5124095576030431H15.999999999S"
+        );
+        assert_eq!(
+            iso(&mut buf, Duration::MAX, true),
+            "P213503982334601DT7H15.999999999S"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//  ( /   @ @    ()  Errors shown together with their sources
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::error::Error;
+use core::fmt;
+use core::fmt::Write;
+
+/// How many errors of a chain are shown by default.
+pub const ERROR_CHAIN_MAX_DEPTH: usize = 16;
+
+/// Display adapter that shows an error followed by its `source()` chain, like
+/// `outer: middle: inner`.
+///
+/// At most [ERROR_CHAIN_MAX_DEPTH] errors are shown (see [ErrorChain::max_depth]), if the
+/// chain is longer, for example because the sources make a cycle, it ends with the
+/// separator and `…`. Written into [WriteTo] its [WriteTo::is_truncated] tells if the chain
+/// did not fit.
+///
+/// ```
+/// use core::fmt;
+///
+/// #[derive(Debug)]
+/// struct Timeout;
+/// impl fmt::Display for Timeout {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.write_str("timeout")
+///     }
+/// }
+/// impl core::error::Error for Timeout {}
+///
+/// #[derive(Debug)]
+/// struct ReadFailed(Timeout);
+/// impl fmt::Display for ReadFailed {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.write_str("sensor read failed")
+///     }
+/// }
+/// impl core::error::Error for ReadFailed {
+///     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+///
+/// let mut buf = [0u8; 64];
+/// let chain = stackfmt::ErrorChain::new(&ReadFailed(Timeout));
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("error: {}", chain));
+/// assert_eq!(s, "error: sensor read failed: timeout");
+/// ```
+#[derive(Clone, Copy)]
+pub struct ErrorChain<'e> {
+    err: &'e dyn Error,
+    separator: &'e str,
+    max_depth: usize,
+}
+
+impl<'e> ErrorChain<'e> {
+    /// Creates adapter for `err` and its sources separated with `: `.
+    pub fn new(err: &'e dyn Error) -> Self {
+        ErrorChain {
+            err,
+            separator: ": ",
+            max_depth: ERROR_CHAIN_MAX_DEPTH,
+        }
+    }
+
+    /// Sets the text between errors.
+    pub fn separator(mut self, separator: &'e str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets how many errors are shown at most.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+impl<'e> fmt::Display for ErrorChain<'e> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut next = Some(self.err);
+        let mut depth = 0;
+        while let Some(err) = next {
+            if depth > 0 {
+                f.write_str(self.separator)?;
+            }
+            if depth == self.max_depth {
+                return f.write_char('\u{2026}');
+            }
+            write!(f, "{}", err)?;
+            next = err.source();
+            depth += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `err` and its sources separated with `sep` into the buffer, see [ErrorChain].
+///
+/// The output is truncated if it does not fit, use [ErrorChain] with [WriteTo] to find it out.
+pub fn fmt_error_chain<'a>(buffer: &'a mut [u8], err: &dyn Error, sep: &str) -> &'a str {
+    let mut w = WriteTo::new(buffer);
+    match write!(w, "{}", ErrorChain::new(err).separator(sep)) {
+        Ok(_) => w.as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_error_chain, ErrorChain, ERROR_CHAIN_MAX_DEPTH};
+    use crate::WriteTo;
+    use core::error::Error;
+    use core::fmt;
+    use core::fmt::Write;
+
+    // Error with a message and an optional source
+    #[derive(Debug)]
+    struct TestError {
+        msg: &'static str,
+        source: Option<&'static TestError>,
+    }
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(self.msg)
+        }
+    }
+
+    impl Error for TestError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.source.map(|e| e as &(dyn Error + 'static))
+        }
+    }
+
+    static INNER: TestError = TestError {
+        msg: "i2c nack",
+        source: None,
+    };
+    static MIDDLE: TestError = TestError {
+        msg: "read register",
+        source: Some(&INNER),
+    };
+    static OUTER: TestError = TestError {
+        msg: "init sensor",
+        source: Some(&MIDDLE),
+    };
+
+    // Error that is its own source
+    #[derive(Debug)]
+    struct Cycle;
+
+    impl fmt::Display for Cycle {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("again")
+        }
+    }
+
+    impl Error for Cycle {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&Cycle)
+        }
+    }
+
+    #[test]
+    fn error_chain_three_levels() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_error_chain(&mut buf, &OUTER, ": "),
+            "init sensor: read register: i2c nack"
+        );
+        assert_eq!(
+            fmt_error_chain(&mut buf, &OUTER, " <- "),
+            "init sensor <- read register <- i2c nack"
+        );
+    }
+
+    #[test]
+    fn error_chain_cycle() {
+        let mut buf = [0u8; 256];
+        let s = fmt_error_chain(&mut buf, &Cycle, "/");
+        assert_eq!(s.matches("again").count(), ERROR_CHAIN_MAX_DEPTH);
+        assert!(s.ends_with("again/\u{2026}"));
+
+        let mut buf = [0u8; 64];
+        let mut w = WriteTo::new(&mut buf);
+        write!(w, "{}", ErrorChain::new(&OUTER).max_depth(2)).unwrap();
+        assert_eq!(w.as_str(), "init sensor: read register: \u{2026}");
+    }
+
+    #[test]
+    fn error_chain_single() {
+        let mut buf = [0u8; 64];
+        assert_eq!(fmt_error_chain(&mut buf, &INNER, ": "), "i2c nack");
+    }
+
+    #[test]
+    fn error_chain_truncated() {
+        let mut buf = [0u8; 18];
+        let mut w = WriteTo::new(&mut buf);
+        write!(w, "{}", ErrorChain::new(&OUTER)).unwrap();
+        assert!(w.is_truncated());
+        assert_eq!(w.as_str(), "init sensor: read ");
+
+        let mut buf = [0u8; 18];
+        assert_eq!(
+            fmt_error_chain(&mut buf, &OUTER, ": "),
+            "init sensor: read "
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//  ( /   @ @    ()  ESC/POS receipt printer commands and text
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+const ESC: u8 = 0x1B;
+const GS: u8 = 0x1D;
+
+// Writes ASCII as is, other chars are not in the code page
+fn ascii_only(c: char) -> Option<u8> {
+    c.is_ascii().then_some(c as u8)
+}
+
+/// Builds ESC/POS data for a receipt printer: text in the printer code page mixed with
+/// commands.
+///
+/// Text is formatted from [fmt::Arguments] and every char is converted with the char map set
+/// by [EscPosWriter::code_page] (ASCII only by default), chars without a byte are printed
+/// as `?`. A command is written whole or not at all: once the buffer is full everything
+/// after is dropped and [EscPosWriter::finish] reports the truncation.
+///
+/// ```
+/// use core::fmt::Alignment;
+///
+/// let mut buf = [0u8; 64];
+/// let mut p = stackfmt::EscPosWriter::new(&mut buf);
+/// p.init().align(Alignment::Center).bold(true).line(format_args!("Total {}", 42));
+/// p.bold(false).feed(2).cut();
+/// let (data, truncated) = p.finish();
+/// assert_eq!(data, b"\x1B@\x1Ba\x01\x1BE\x01Total 42\n\x1BE\x00\x1Bd\x02\x1DV\x00");
+/// assert!(!truncated);
+/// ```
+pub struct EscPosWriter<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+    overflow: bool,
+    map: fn(char) -> Option<u8>,
+}
+
+impl<'a> EscPosWriter<'a> {
+    /// Creates writer of printer data into `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        EscPosWriter {
+            buffer,
+            used: 0,
+            overflow: false,
+            map: ascii_only,
+        }
+    }
+
+    /// Sets the conversion of chars to bytes of the printer code page.
+    ///
+    /// ```
+    /// // the chars of CP437 that are needed
+    /// fn cp437(c: char) -> Option<u8> {
+    ///     match c {
+    ///         '\u{E9}' => Some(0x82),
+    ///         '\u{FC}' => Some(0x81),
+    ///         _ => c.is_ascii().then_some(c as u8),
+    ///     }
+    /// }
+    /// let mut buf = [0u8; 16];
+    /// let mut p = stackfmt::EscPosWriter::new(&mut buf).code_page(cp437);
+    /// p.text(format_args!("Caf\u{E9}"));
+    /// assert_eq!(p.finish().0, b"Caf\x82");
+    /// ```
+    pub fn code_page(mut self, map: fn(char) -> Option<u8>) -> Self {
+        self.map = map;
+        self
+    }
+
+    /// Writes formatted text.
+    pub fn text(&mut self, args: fmt::Arguments) -> &mut Self {
+        let _ = fmt::write(&mut Transcode(self), args);
+        self
+    }
+
+    /// Writes formatted text and a line feed.
+    pub fn line(&mut self, args: fmt::Arguments) -> &mut Self {
+        self.text(args).command(b"\n")
+    }
+
+    /// Resets the printer settings: `ESC @`.
+    pub fn init(&mut self) -> &mut Self {
+        self.command(&[ESC, b'@'])
+    }
+
+    /// Turns emphasized (bold) text on or off: `ESC E n`.
+    pub fn bold(&mut self, on: bool) -> &mut Self {
+        self.command(&[ESC, b'E', on as u8])
+    }
+
+    /// Sets the justification of the following lines: `ESC a n`.
+    pub fn align(&mut self, align: fmt::Alignment) -> &mut Self {
+        let n = match align {
+            fmt::Alignment::Left => 0,
+            fmt::Alignment::Center => 1,
+            fmt::Alignment::Right => 2,
+        };
+        self.command(&[ESC, b'a', n])
+    }
+
+    /// Prints the buffered line and feeds `lines` lines: `ESC d n`.
+    pub fn feed(&mut self, lines: u8) -> &mut Self {
+        self.command(&[ESC, b'd', lines])
+    }
+
+    /// Full cut of the paper: `GS V 0`.
+    pub fn cut(&mut self) -> &mut Self {
+        self.command(&[GS, b'V', 0])
+    }
+
+    /// Writes raw bytes of a command not covered by the other methods, all or nothing.
+    pub fn command(&mut self, bytes: &[u8]) -> &mut Self {
+        if !self.overflow && bytes.len() <= self.buffer.len() - self.used {
+            self.buffer[self.used..self.used + bytes.len()].copy_from_slice(bytes);
+            self.used += bytes.len();
+        } else {
+            self.overflow = true;
+        }
+        self
+    }
+
+    /// Returns the data and the flag if some of it was dropped.
+    pub fn finish(self) -> (&'a [u8], bool) {
+        (&self.buffer[..self.used], self.overflow)
+    }
+}
+
+// Converts formatted chars with the code page of the writer
+struct Transcode<'w, 'a>(&'w mut EscPosWriter<'a>);
+
+impl<'w, 'a> fmt::Write for Transcode<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let byte = (self.0.map)(c).unwrap_or(b'?');
+            self.0.command(&[byte]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EscPosWriter;
+    use core::fmt::Alignment;
+
+    fn cp437(c: char) -> Option<u8> {
+        match c {
+            '\u{E9}' => Some(0x82),
+            '\u{C4}' => Some(0x8E),
+            _ => c.is_ascii().then_some(c as u8),
+        }
+    }
+
+    #[test]
+    fn escpos_receipt() {
+        let mut buf = [0u8; 128];
+        let mut p = EscPosWriter::new(&mut buf).code_page(cp437);
+        let (item, price) = ("Caf\u{E9}", 350);
+        p.init()
+            .align(Alignment::Center)
+            .bold(true)
+            .line(format_args!("BISTRO"))
+            .bold(false)
+            .align(Alignment::Left)
+            .line(format_args!("{:<8}{:>4}", item, price))
+            .align(Alignment::Right)
+            .line(format_args!("Sum {}", price))
+            .feed(3)
+            .cut();
+        let (data, truncated) = p.finish();
+        let expected: &[u8] = &[
+            0x1B, 0x40, // init
+            0x1B, 0x61, 0x01, // center
+            0x1B, 0x45, 0x01, // bold on
+            b'B', b'I', b'S', b'T', b'R', b'O', 0x0A, //
+            0x1B, 0x45, 0x00, // bold off
+            0x1B, 0x61, 0x00, // left
+            b'C', b'a', b'f', 0x82, b' ', b' ', b' ', b' ', b' ', b'3', b'5', b'0', 0x0A, //
+            0x1B, 0x61, 0x02, // right
+            b'S', b'u', b'm', b' ', b'3', b'5', b'0', 0x0A, //
+            0x1B, 0x64, 0x03, // feed 3
+            0x1D, 0x56, 0x00, // cut
+        ];
+        assert_eq!(data, expected);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn escpos_unmapped_chars() {
+        let mut buf = [0u8; 16];
+        let mut p = EscPosWriter::new(&mut buf);
+        p.text(format_args!("{}", "\u{C4}\u{20AC}"));
+        assert_eq!(p.finish(), (&b"??"[..], false));
+        let mut p = EscPosWriter::new(&mut buf).code_page(cp437);
+        p.text(format_args!("{}", "\u{C4}\u{20AC}"));
+        assert_eq!(p.finish(), (&b"\x8E?"[..], false));
+    }
+
+    #[test]
+    fn escpos_truncation_keeps_commands_whole() {
+        let mut buf = [0u8; 8];
+        let mut p = EscPosWriter::new(&mut buf);
+        p.text(format_args!("abcdef"))
+            .bold(true)
+            .text(format_args!("x"));
+        assert_eq!(p.finish(), (&b"abcdef"[..], true));
+
+        let mut buf = [0u8; 8];
+        let mut p = EscPosWriter::new(&mut buf);
+        p.bold(true).text(format_args!("0123456789")).cut();
+        assert_eq!(p.finish(), (&b"\x1BE\x0101234"[..], true));
+    }
+}
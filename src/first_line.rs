@@ -0,0 +1,157 @@
+//  ( /   @ @    ()  Keeps only the first line of formatted output
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Impl of [fmt::Write] that forwards text up to the first line break and drops the rest.
+///
+/// The line break (`\n` or `\r\n`, also split between writes) is not forwarded.
+/// [FirstLineWriter::saw_more] tells if there was text after it, e.g. to append `…`. A `\r`
+/// at the end of a write is held back until the next char is known, call
+/// [FirstLineWriter::finish] to get it forwarded when the input ends with it.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let (s, more) = stackfmt::fmt_first_line(&mut buf, format_args!("{:#?}", (1, 2)));
+/// assert_eq!(s, "(");
+/// assert!(more);
+/// ```
+pub struct FirstLineWriter<W: fmt::Write> {
+    inner: W,
+    pending_cr: bool,
+    line_done: bool,
+    saw_more: bool,
+}
+
+impl<W: fmt::Write> FirstLineWriter<W> {
+    /// Creates stream forwarding the first line into `inner`.
+    pub fn new(inner: W) -> Self {
+        FirstLineWriter {
+            inner,
+            pending_cr: false,
+            line_done: false,
+            saw_more: false,
+        }
+    }
+
+    /// True if there was text after the first line break.
+    pub fn saw_more(&self) -> bool {
+        self.saw_more
+    }
+
+    /// Forwards the held back `\r` and returns the inner stream.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        if self.pending_cr {
+            self.inner.write_char('\r')?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for FirstLineWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.line_done {
+            self.saw_more |= !s.is_empty();
+            return Ok(());
+        }
+        if s.is_empty() {
+            return Ok(());
+        }
+
+        if self.pending_cr {
+            self.pending_cr = false;
+            if !s.starts_with('\n') {
+                self.inner.write_char('\r')?;
+            }
+        }
+        match s.find('\n') {
+            Some(end) => {
+                self.line_done = true;
+                self.saw_more = end + 1 < s.len();
+                self.inner
+                    .write_str(s[..end].strip_suffix('\r').unwrap_or(&s[..end]))
+            }
+            None => match s.strip_suffix('\r') {
+                Some(line) => {
+                    self.pending_cr = true;
+                    self.inner.write_str(line)
+                }
+                None => self.inner.write_str(s),
+            },
+        }
+    }
+}
+
+/// Writes the first line of formatted text into the buffer truncating if needed, returns
+/// true if there was text after the first line break.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let (s, more) = stackfmt::fmt_first_line(&mut buf, format_args!("ok\r\n"));
+/// assert_eq!((s, more), ("ok", false));
+/// ```
+pub fn fmt_first_line<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> (&'a str, bool) {
+    let mut w = FirstLineWriter::new(WriteTo::new(buffer));
+    let _ = fmt::write(&mut w, args);
+    let more = w.saw_more();
+    match w.finish() {
+        Ok(inner) => (inner.as_str(), more),
+        Err(_) => ("", more),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_first_line, FirstLineWriter};
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    // Writes each chunk separately
+    fn first_line<'a>(buf: &'a mut [u8], chunks: &[&str]) -> (&'a str, bool) {
+        let mut w = FirstLineWriter::new(WriteTo::new(buf));
+        for chunk in chunks {
+            w.write_str(chunk).unwrap();
+        }
+        let more = w.saw_more();
+        (w.finish().unwrap().as_str(), more)
+    }
+
+    #[test]
+    fn first_line_single() {
+        let mut buf = [0u8; 16];
+        assert_eq!(
+            fmt_first_line(&mut buf, format_args!("id={}", 5)),
+            ("id=5", false)
+        );
+        assert_eq!(first_line(&mut buf, &["ab", "", "cd"]), ("abcd", false));
+        assert_eq!(first_line(&mut buf, &["abc\n"]), ("abc", false));
+    }
+
+    #[test]
+    fn first_line_newline_mid_chunk() {
+        let mut buf = [0u8; 16];
+        assert_eq!(first_line(&mut buf, &["ab\ncd", "ef"]), ("ab", true));
+        assert_eq!(first_line(&mut buf, &["ab", "c\nd"]), ("abc", true));
+        assert_eq!(first_line(&mut buf, &["ab", "\n", "", "x"]), ("ab", true));
+    }
+
+    #[test]
+    fn first_line_crlf() {
+        let mut buf = [0u8; 16];
+        assert_eq!(first_line(&mut buf, &["ab\r\ncd"]), ("ab", true));
+        assert_eq!(first_line(&mut buf, &["ab\r", "\ncd"]), ("ab", true));
+        assert_eq!(first_line(&mut buf, &["ab\r", "c"]), ("ab\rc", false));
+        assert_eq!(first_line(&mut buf, &["ab\r"]), ("ab\r", false));
+        assert_eq!(first_line(&mut buf, &["a\rb\r\n"]), ("a\rb", false));
+    }
+
+    #[test]
+    fn first_line_starts_with_newline() {
+        let mut buf = [0u8; 16];
+        assert_eq!(first_line(&mut buf, &["\nabc"]), ("", true));
+        assert_eq!(first_line(&mut buf, &["\r\n"]), ("", false));
+        assert_eq!(first_line(&mut buf, &["\n", "\n"]), ("", true));
+    }
+}
@@ -0,0 +1,259 @@
+//  ( /   @ @    ()  Scaled integers shown with implied decimal places
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows an integer with implied decimal places, like `3.300` for 3300
+/// millivolts.
+///
+/// The fraction is zero padded (`-5` with 2 decimals is `-0.05`). A precision from the
+/// format string (`{:.1}`) rounds half away from zero or appends zeros; a value that
+/// rounds to zero is shown without the minus sign. Width, fill, alignment, `+` and `0`
+/// flags work as for integers. Only integer math is used.
+///
+/// ```
+/// use stackfmt::FixedPoint;
+///
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{} V", FixedPoint::new(3300, 3)));
+/// assert_eq!(s, "3.300 V");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:.1}", FixedPoint::new(-2155, 2)));
+/// assert_eq!(s, "-21.6");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedPoint {
+    value: i64,
+    decimals: u8,
+}
+
+impl FixedPoint {
+    /// Creates adapter for `value × 10^-decimals`.
+    pub fn new(value: i64, decimals: u8) -> Self {
+        FixedPoint { value, decimals }
+    }
+}
+
+impl fmt::Display for FixedPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimals = self.decimals as usize;
+        let shown = f.precision().unwrap_or(decimals);
+        let mut magnitude = self.value.unsigned_abs() as u128;
+        let mut frac_digits = decimals;
+        if shown < decimals {
+            let drop = decimals - shown;
+            // i64 magnitude has at most 20 digits
+            magnitude = if drop > 20 {
+                0
+            } else {
+                let divisor = 10u128.pow(drop as u32);
+                (magnitude + divisor / 2) / divisor
+            };
+            frac_digits = shown;
+        }
+        let (int_part, frac) = if frac_digits > 20 {
+            (0, magnitude)
+        } else {
+            let pow = 10u128.pow(frac_digits as u32);
+            (magnitude / pow, magnitude % pow)
+        };
+
+        let sign = match (self.value < 0 && magnitude != 0, f.sign_plus()) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        let mut int_digits = 1;
+        while int_digits < 39 && int_part >= 10u128.pow(int_digits) {
+            int_digits += 1;
+        }
+        let frac_len = if shown > 0 { 1 + shown } else { 0 };
+        let len = sign.len() + int_digits as usize + frac_len;
+
+        // the digits are streamed, a precision can be longer than any buffer
+        let digits = |f: &mut fmt::Formatter| {
+            write!(f, "{}", int_part)?;
+            if shown > 0 {
+                f.write_char('.')?;
+                if frac_digits > 0 {
+                    write!(f, "{:0width$}", frac, width = frac_digits)?;
+                }
+                for _ in frac_digits..shown {
+                    f.write_char('0')?;
+                }
+            }
+            Ok(())
+        };
+        if f.sign_aware_zero_pad() {
+            f.write_str(sign)?;
+            for _ in len..f.width().unwrap_or(0) {
+                f.write_char('0')?;
+            }
+            return digits(f);
+        }
+        pad_with(f, len, fmt::Alignment::Right, |f| {
+            f.write_str(sign)?;
+            digits(f)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedPoint;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn fixed_point_zero_decimals() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(42, 0))),
+            "42"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(-42, 0))),
+            "-42"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(0, 0))),
+            "0"
+        );
+    }
+
+    #[test]
+    fn fixed_point_fraction() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(3300, 3))),
+            "3.300"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(5, 3))),
+            "0.005"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(0, 2))),
+            "0.00"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(7, 25))),
+            "0.0000000000000000000000007"
+        );
+    }
+
+    #[test]
+    fn fixed_point_negative() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(-5, 2))),
+            "-0.05"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(-2155, 2))),
+            "-21.55"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.1}", FixedPoint::new(-4, 2))),
+            "0.0"
+        );
+    }
+
+    #[test]
+    fn fixed_point_precision() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.1}", FixedPoint::new(2155, 2))),
+            "21.6"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.1}", FixedPoint::new(-2155, 2))),
+            "-21.6"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.0}", FixedPoint::new(1999, 3))),
+            "2"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.0}", FixedPoint::new(499, 3))),
+            "0"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.4}", FixedPoint::new(33, 1))),
+            "3.3000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.2}", FixedPoint::new(5, 0))),
+            "5.00"
+        );
+    }
+
+    #[test]
+    fn fixed_point_width_and_flags() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>7}]", FixedPoint::new(-5, 2))),
+            "[  -0.05]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:<6}]", FixedPoint::new(15, 1))),
+            "[1.5   ]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:07.2}", FixedPoint::new(-15, 1))),
+            "-001.50"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:+}", FixedPoint::new(15, 1))),
+            "+1.5"
+        );
+    }
+
+    #[test]
+    fn fixed_point_extremes() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(i64::MIN, 3))),
+            "-9223372036854775.808"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(i64::MIN, 19))),
+            "-0.9223372036854775808"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:.0}", FixedPoint::new(i64::MAX, 0))
+            ),
+            "9223372036854775807"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:.1}", FixedPoint::new(i64::MIN, 255))
+            ),
+            "0.0"
+        );
+    }
+
+    #[test]
+    fn fixed_point_long_fraction() {
+        let mut buf = [0u8; 512];
+        let s = fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(1, 130)));
+        assert_eq!(s.len(), 132);
+        assert!(s.starts_with("0.000") && s.ends_with("0001"));
+        let s = fmt_truncate(&mut buf, format_args!("{}", FixedPoint::new(-15, 255)));
+        assert_eq!(s.len(), 1 + 2 + 255);
+        assert!(s.starts_with("-0.000") && s.ends_with("015"));
+        let s = fmt_truncate(&mut buf, format_args!("{:.200}", FixedPoint::new(15, 1)));
+        assert_eq!(s.len(), 202);
+        assert!(s.starts_with("1.50000"));
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{:>300.200}", FixedPoint::new(15, 1)),
+        );
+        assert_eq!(s.len(), 300);
+        assert!(s.starts_with("  ") && s.ends_with("00"));
+    }
+}
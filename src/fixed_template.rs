@@ -0,0 +1,221 @@
+//  ( /   @ @    ()  Text line with fields patched in place
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::ops::Range;
+use core::str::from_utf8_unchecked;
+
+/// Error of [FixedTemplate] operations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldError {
+    /// The buffer is smaller than the template
+    BufferTooSmall,
+    /// The field range is outside of the template or not on a char boundary
+    BadRange { field: usize },
+    /// No field with such index or name
+    UnknownField,
+    /// The formatted value has more bytes than the field
+    TooLong { field: usize, len: usize },
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldError::BufferTooSmall => f.write_str("buffer is smaller than template"),
+            FieldError::BadRange { field } => write!(f, "field {} has bad range", field),
+            FieldError::UnknownField => f.write_str("unknown field"),
+            FieldError::TooLong { field, len } => {
+                write!(f, "value of {} bytes does not fit field {}", len, field)
+            }
+        }
+    }
+}
+
+/// A line of text in a caller buffer where only the fields at fixed byte ranges change.
+///
+/// The template is copied into the buffer once, [FixedTemplate::set] formats a value into
+/// the byte range of a field, pads it with spaces and leaves the rest of the line
+/// untouched. A value longer than the field is rejected and the field keeps its content,
+/// it is formatted twice for that: to measure and to write.
+///
+/// ```
+/// use stackfmt::FixedTemplate;
+///
+/// let mut buf = [0u8; 32];
+/// let fields = [("t", 2..7), ("h", 11..15)];
+/// let mut line = FixedTemplate::new(&mut buf, "T:___._C H:__._%", &fields).unwrap();
+/// line.set_named("t", format_args!("{:5.1}", 21.55)).unwrap();
+/// line.set(1, format_args!("{:4.1}", 40.0)).unwrap();
+/// assert_eq!(line.as_str(), "T: 21.6C H:40.0%");
+/// ```
+pub struct FixedTemplate<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+    fields: &'a [(&'a str, Range<usize>)],
+}
+
+impl<'a> FixedTemplate<'a> {
+    /// Copies `template` into `buffer`, `fields` are (name, byte range) in the template.
+    pub fn new(
+        buffer: &'a mut [u8],
+        template: &str,
+        fields: &'a [(&'a str, Range<usize>)],
+    ) -> Result<Self, FieldError> {
+        if buffer.len() < template.len() {
+            return Err(FieldError::BufferTooSmall);
+        }
+        for (field, (_, range)) in fields.iter().enumerate() {
+            if range.start > range.end
+                || !template.is_char_boundary(range.start)
+                || !template.is_char_boundary(range.end)
+            {
+                return Err(FieldError::BadRange { field });
+            }
+        }
+        buffer[..template.len()].copy_from_slice(template.as_bytes());
+        Ok(FixedTemplate {
+            buffer,
+            len: template.len(),
+            fields,
+        })
+    }
+
+    /// Formats `args` into the field with index `field`.
+    pub fn set(&mut self, field: usize, args: fmt::Arguments) -> Result<(), FieldError> {
+        let range = match self.fields.get(field) {
+            Some((_, range)) => range.clone(),
+            None => return Err(FieldError::UnknownField),
+        };
+
+        let mut counter = ByteCounter(0);
+        let _ = fmt::write(&mut counter, args);
+        if counter.0 > range.len() {
+            return Err(FieldError::TooLong {
+                field,
+                len: counter.0,
+            });
+        }
+
+        let slot = &mut self.buffer[range];
+        let mut w = WriteTo::new(slot);
+        let _ = fmt::write(&mut w, args);
+        let written = w.written_bytes();
+        slot[written..].fill(b' ');
+        Ok(())
+    }
+
+    /// Formats `args` into the field called `name`.
+    pub fn set_named(&mut self, name: &str, args: fmt::Arguments) -> Result<(), FieldError> {
+        match self.fields.iter().position(|(field, _)| *field == name) {
+            Some(field) => self.set(field, args),
+            None => Err(FieldError::UnknownField),
+        }
+    }
+
+    /// Returns the whole line.
+    pub fn as_str(&self) -> &str {
+        // the template is str and fields are filled with str on its char boundaries
+        unsafe { from_utf8_unchecked(&self.buffer[..self.len]) }
+    }
+}
+
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldError, FixedTemplate};
+
+    #[test]
+    fn fixed_template_fields() {
+        let mut buf = [0u8; 32];
+        let fields = [("t", 2..7), ("h", 11..15), ("p", 19..23)];
+        let mut line = FixedTemplate::new(&mut buf, "T:___._C H:__._% P:____hPa", &fields).unwrap();
+        assert_eq!(line.as_str(), "T:___._C H:__._% P:____hPa");
+        line.set(0, format_args!("{:5.1}", -3.25)).unwrap();
+        line.set_named("h", format_args!("{:.1}", 5.5)).unwrap();
+        line.set_named("p", format_args!("{:>4}", 1013)).unwrap();
+        assert_eq!(line.as_str(), "T: -3.2C H:5.5 % P:1013hPa");
+        // refresh with shorter text
+        line.set_named("p", format_args!("{}", 7)).unwrap();
+        assert_eq!(line.as_str(), "T: -3.2C H:5.5 % P:7   hPa");
+    }
+
+    #[test]
+    fn fixed_template_too_long() {
+        let mut buf = [0u8; 16];
+        let fields = [("v", 2..4)];
+        let mut line = FixedTemplate::new(&mut buf, "v=__;", &fields).unwrap();
+        line.set(0, format_args!("{}", 42)).unwrap();
+        assert_eq!(
+            line.set(0, format_args!("{}", 100)),
+            Err(FieldError::TooLong { field: 0, len: 3 })
+        );
+        assert_eq!(line.as_str(), "v=42;");
+        assert_eq!(
+            line.set(1, format_args!("x")),
+            Err(FieldError::UnknownField)
+        );
+        assert_eq!(
+            line.set_named("w", format_args!("x")),
+            Err(FieldError::UnknownField)
+        );
+    }
+
+    #[test]
+    fn fixed_template_adjacent() {
+        let mut buf = [0u8; 16];
+        let fields = [("hh", 0..2), ("mm", 2..4), ("ss", 4..6)];
+        let mut line = FixedTemplate::new(&mut buf, "000000", &fields).unwrap();
+        line.set(0, format_args!("{:02}", 9)).unwrap();
+        line.set(1, format_args!("{:02}", 5)).unwrap();
+        line.set(2, format_args!("{:02}", 59)).unwrap();
+        assert_eq!(line.as_str(), "090559");
+        line.set(1, format_args!("")).unwrap();
+        assert_eq!(line.as_str(), "09  59");
+    }
+
+    #[test]
+    fn fixed_template_multi_byte_around() {
+        let mut buf = [0u8; 32];
+        // "€ " is 4 bytes, "°C" starts at 8
+        let fields = [("t", 4..8)];
+        let mut line =
+            FixedTemplate::new(&mut buf, "\u{20AC} ____\u{B0}C\u{2191}", &fields).unwrap();
+        line.set(0, format_args!("{:4}", 21)).unwrap();
+        assert_eq!(line.as_str(), "\u{20AC}   21\u{B0}C\u{2191}");
+        // multi-byte value: 3 bytes of the 4 byte field
+        line.set(0, format_args!("\u{2212}")).unwrap();
+        assert_eq!(line.as_str(), "\u{20AC} \u{2212} \u{B0}C\u{2191}");
+        assert_eq!(
+            line.set(0, format_args!("\u{2212}\u{2212}")),
+            Err(FieldError::TooLong { field: 0, len: 6 })
+        );
+    }
+
+    #[test]
+    fn fixed_template_bad_ranges() {
+        let mut buf = [0u8; 32];
+        assert!(matches!(
+            FixedTemplate::new(&mut buf, "\u{20AC}__", &[("a", 1..3)]),
+            Err(FieldError::BadRange { field: 0 })
+        ));
+        assert!(matches!(
+            FixedTemplate::new(&mut buf, "__", &[("a", 0..2), ("b", 1..9)]),
+            Err(FieldError::BadRange { field: 1 })
+        ));
+        let mut small = [0u8; 2];
+        assert!(matches!(
+            FixedTemplate::new(&mut small, "abc", &[]),
+            Err(FieldError::BufferTooSmall)
+        ));
+    }
+}
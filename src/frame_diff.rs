@@ -0,0 +1,172 @@
+//  ( /   @ @    ()  Changed chars between two frames of a character display
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Calls `emit` with the char index and the new char for every char of `new` that differs
+/// from `old`, so only the changed cells of a character display are written.
+///
+/// Chars are compared as whole chars, the index counts chars. The frame that is shorter is
+/// taken as padded with spaces: the cells after the end of a shorter `new` are emitted as
+/// spaces if they were not spaces.
+///
+/// ```
+/// let mut changes = [(0, ' '); 4];
+/// let mut n = 0;
+/// stackfmt::diff_chars("T=21C", "T=22C", |i, c| {
+///     changes[n] = (i, c);
+///     n += 1;
+/// });
+/// assert_eq!(&changes[..n], &[(3, '2')]);
+/// ```
+pub fn diff_chars(old: &str, new: &str, mut emit: impl FnMut(usize, char)) {
+    let (mut old, mut new) = (old.chars(), new.chars());
+    let mut index = 0;
+    loop {
+        let (o, n) = match (old.next(), new.next()) {
+            (None, None) => break,
+            (o, n) => (o.unwrap_or(' '), n.unwrap_or(' ')),
+        };
+        if o != n {
+            emit(index, n);
+        }
+        index += 1;
+    }
+}
+
+/// Keeps the previous frame of a character display to report only the changed cells of
+/// the next one, see [diff_chars].
+///
+/// Each [FrameDiff::update] formats the new frame into one of the two caller buffers and
+/// compares it with the frame in the other, then the new frame becomes the previous one.
+/// Before the first update the display is taken as blank (all spaces). A frame that does
+/// not fit is truncated like by [WriteTo]. The char index is linear, mapping it to rows and
+/// columns is up to the caller.
+///
+/// ```
+/// let (mut a, mut b) = ([0u8; 80], [0u8; 80]);
+/// let mut frames = stackfmt::FrameDiff::new(&mut a, &mut b);
+/// let mut count = 0;
+/// frames.update(format_args!("RPM {:4}", 900), |_, _| count += 1);
+/// assert_eq!(count, 6); // the spaces are already on the display
+/// count = 0;
+/// frames.update(format_args!("RPM {:4}", 1200), |_, _| count += 1);
+/// assert_eq!(count, 2); // " 9" became "12"
+/// ```
+pub struct FrameDiff<'b> {
+    previous: &'b mut [u8],
+    current: &'b mut [u8],
+    previous_len: usize,
+}
+
+impl<'b> FrameDiff<'b> {
+    /// Creates helper keeping frames in `previous` and `current` buffers.
+    pub fn new(previous: &'b mut [u8], current: &'b mut [u8]) -> Self {
+        FrameDiff {
+            previous,
+            current,
+            previous_len: 0,
+        }
+    }
+
+    /// Formats the new frame and calls `emit` with the index and the char of every changed
+    /// cell, returns true if the frame was truncated.
+    pub fn update(&mut self, args: fmt::Arguments, emit: impl FnMut(usize, char)) -> bool {
+        let mut w = WriteTo::new(self.current);
+        let _ = fmt::write(&mut w, args);
+        let truncated = w.is_truncated();
+        let len = w.written_bytes();
+        diff_chars(self.previous_str(), Self::text(self.current, len), emit);
+        core::mem::swap(&mut self.previous, &mut self.current);
+        self.previous_len = len;
+        truncated
+    }
+
+    /// The last frame
+    pub fn previous_str(&self) -> &str {
+        Self::text(self.previous, self.previous_len)
+    }
+
+    /// Forgets the last frame, the next update reports all the non-space chars as after
+    /// clearing the display.
+    pub fn reset(&mut self) {
+        self.previous_len = 0;
+    }
+
+    // the buffer holds whole chars of a str written by WriteTo
+    fn text(buffer: &[u8], len: usize) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&buffer[..len]) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_chars, FrameDiff};
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    // Collects changes as "index:char " text
+    fn diff<'a>(out: &'a mut [u8], old: &str, new: &str) -> &'a str {
+        let mut w = WriteTo::new(out);
+        diff_chars(old, new, |i, c| {
+            let _ = write!(w, "{}:{} ", i, c);
+        });
+        w.as_str()
+    }
+
+    #[test]
+    fn diff_identical() {
+        let mut out = [0u8; 64];
+        assert_eq!(diff(&mut out, "Temp 21\u{B0}C", "Temp 21\u{B0}C"), "");
+        assert_eq!(diff(&mut out, "", ""), "");
+        // missing cells are spaces
+        assert_eq!(diff(&mut out, "ab", "ab  "), "");
+    }
+
+    #[test]
+    fn diff_single_cell() {
+        let mut out = [0u8; 64];
+        assert_eq!(diff(&mut out, "Temp 21C", "Temp 23C"), "6:3 ");
+        assert_eq!(diff(&mut out, "", "ok"), "0:o 1:k ");
+    }
+
+    #[test]
+    fn diff_shorter_new() {
+        let mut out = [0u8; 64];
+        assert_eq!(
+            diff(&mut out, "ERROR 12", "OK"),
+            "0:O 1:K 2:  3:  4:  6:  7:  "
+        );
+    }
+
+    #[test]
+    fn diff_multi_byte() {
+        let mut out = [0u8; 64];
+        // 'a' is 1 byte and '\u{B0}' is 2 bytes, '\u{20AC}' is 3: the cells after do not move
+        assert_eq!(diff(&mut out, "1a2", "1\u{B0}2"), "1:\u{B0} ");
+        assert_eq!(diff(&mut out, "\u{B0}x", "\u{20AC}x"), "0:\u{20AC} ");
+        assert_eq!(diff(&mut out, "\u{20AC}\u{20AC}", "\u{20AC}"), "1:  ");
+    }
+
+    #[test]
+    fn frame_diff_updates() {
+        let (mut a, mut b) = ([0u8; 16], [0u8; 16]);
+        let mut frames = FrameDiff::new(&mut a, &mut b);
+        let mut out = [0u8; 64];
+        let mut w = WriteTo::new(&mut out);
+        frames.update(format_args!("V={}", 12), |i, c| {
+            let _ = write!(w, "{}:{} ", i, c);
+        });
+        frames.update(format_args!("V={}", 9), |i, c| {
+            let _ = write!(w, "{}:{} ", i, c);
+        });
+        assert_eq!(frames.previous_str(), "V=9");
+        frames.reset();
+        assert!(frames.update(format_args!("{:20}", "x"), |i, c| {
+            let _ = write!(w, "{}:{} ", i, c);
+        }));
+        assert_eq!(w.as_str(), "0:V 1:= 2:1 3:2 2:9 3:  0:x ");
+    }
+}
@@ -0,0 +1,106 @@
+//  ( /   @ @    ()  Formatted text placed at the end of a buffer
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+// Keeps the last bytes written, dropping whole chars at the front
+struct TailWriter<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+}
+
+impl<'a> fmt::Write for TailWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let capacity = self.buffer.len();
+        let bytes = s.as_bytes();
+        if bytes.len() >= capacity {
+            let mut start = bytes.len() - capacity;
+            while !s.is_char_boundary(start) {
+                start += 1;
+            }
+            self.buffer[..bytes.len() - start].copy_from_slice(&bytes[start..]);
+            self.used = bytes.len() - start;
+            return Ok(());
+        }
+
+        if self.used + bytes.len() > capacity {
+            let mut drop = self.used + bytes.len() - capacity;
+            while drop < self.used && self.buffer[drop] & 0xC0 == 0x80 {
+                drop += 1; // continuation byte of a dropped char
+            }
+            self.buffer.copy_within(drop..self.used, 0);
+            self.used -= drop;
+        }
+        self.buffer[self.used..self.used + bytes.len()].copy_from_slice(bytes);
+        self.used += bytes.len();
+        Ok(())
+    }
+}
+
+/// Writes formatted string flush against the end of the buffer and returns it, dropping
+/// chars at the front if it does not fit.
+///
+/// The text is formatted once, forward from the start of the buffer, and moved to the end
+/// at the finish. While the text fits this costs one extra copy; once the buffer is full
+/// every further piece of the output shifts the buffer to drop the oldest chars, so a text
+/// much longer than the buffer costs a copy of the buffer per piece. Formatting twice, to
+/// measure and then to write, would avoid the shifting but call the `Display` impls twice.
+///
+/// ```
+/// let mut buf = [0u8; 8];
+/// let s = stackfmt::fmt_from_end(&mut buf, format_args!("${}", 42));
+/// assert_eq!(s, "$42");
+/// assert_eq!(&buf[5..], b"$42");
+/// let s = stackfmt::fmt_from_end(&mut buf, format_args!("total ${}", 123456));
+/// assert_eq!(s, " $123456");
+/// ```
+pub fn fmt_from_end<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = TailWriter { buffer, used: 0 };
+    let _ = fmt::write(&mut w, args);
+    let (buffer, used) = (w.buffer, w.used);
+    let start = buffer.len() - used;
+    buffer.copy_within(..used, start);
+    // the tail has whole chars of a str
+    unsafe { core::str::from_utf8_unchecked(&buffer[start..]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fmt_from_end;
+
+    #[test]
+    fn from_end_short() {
+        let mut buf = [b'.'; 10];
+        let s = fmt_from_end(&mut buf, format_args!("{}:{}", 7, "ok"));
+        assert_eq!(s, "7:ok");
+        assert_eq!(&buf[6..], b"7:ok");
+        let s = fmt_from_end(&mut buf, format_args!(""));
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn from_end_exact_fill() {
+        let mut buf = [0u8; 6];
+        let s = fmt_from_end(&mut buf, format_args!("{}{}", "abc", 123));
+        assert_eq!(s, "abc123");
+        let mut buf = [0u8; 5];
+        let s = fmt_from_end(&mut buf, format_args!("{}{}", "abc", 123));
+        assert_eq!(s, "bc123");
+    }
+
+    #[test]
+    fn from_end_unicode_front_truncated() {
+        // euro sign is 3 bytes and can not be cut
+        let mut buf = [0u8; 6];
+        let s = fmt_from_end(&mut buf, format_args!("{}{}", "\u{20AC}\u{20AC}", 42));
+        assert_eq!(s, "\u{20AC}42");
+        let s = fmt_from_end(&mut buf, format_args!("\u{20AC}\u{20AC}\u{20AC}"));
+        assert_eq!(s, "\u{20AC}\u{20AC}");
+        let s = fmt_from_end(&mut buf, format_args!("{}{}", 1, "\u{20AC}\u{20AC}x"));
+        assert_eq!(s, "\u{20AC}x");
+        let mut buf = [0u8; 2];
+        let s = fmt_from_end(&mut buf, format_args!("{}", "\u{1F600}"));
+        assert_eq!(s, "");
+    }
+}
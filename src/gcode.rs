@@ -0,0 +1,244 @@
+//  ( /   @ @    ()  G-code lines with line numbers and checksums
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::fixed_point::FixedPoint;
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [GcodeWriter::finish].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GcodeError {
+    /// Letter is not ASCII alphabetic, or a value or comment has a char that ends the line
+    /// or starts a checksum or comment
+    InvalidWord,
+    /// Line number after a word, or a line without words
+    OutOfOrder,
+    /// The line does not fit into the buffer
+    Overflow,
+}
+
+impl fmt::Display for GcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GcodeError::InvalidWord => "invalid G-code word",
+            GcodeError::OutOfOrder => "G-code line number out of order or no words",
+            GcodeError::Overflow => "G-code line does not fit",
+        })
+    }
+}
+
+/// Writes G-code lines with the RepRap checksum into a [WriteTo], like `N3 T0*57`.
+///
+/// Words are separated with spaces and the checksum is the XOR of all bytes of the line
+/// before `*`, computed while they are written. A comment goes after the checksum and is not
+/// part of it. A line that has an error or does not fit is removed by
+/// [finish](GcodeWriter::finish), so a line with a wrong checksum is never left in the
+/// buffer.
+///
+/// ```
+/// use stackfmt::{GcodeWriter, WriteTo};
+///
+/// let mut buf = [0u8; 64];
+/// let mut g = GcodeWriter::new(WriteTo::new(&mut buf));
+/// g.line_number(3).word('T', 0);
+/// g.finish().unwrap();
+/// g.line_number(4).word('G', 1).word_fixed('X', 105, 1).word('Y', 20);
+/// g.finish_with_comment("move").unwrap();
+/// assert_eq!(g.into_inner().as_str(), "N3 T0*57\nN4 G1 X10.5 Y20*53 ;move\n");
+/// ```
+pub struct GcodeWriter<'a> {
+    out: WriteTo<'a>,
+    line_start: usize,
+    checksum: u8,
+    words: usize,
+    error: Option<GcodeError>,
+}
+
+impl<'a> GcodeWriter<'a> {
+    /// Creates writer appending lines to `out`.
+    pub fn new(out: WriteTo<'a>) -> Self {
+        GcodeWriter {
+            out,
+            line_start: 0,
+            checksum: 0,
+            words: 0,
+            error: None,
+        }
+    }
+
+    /// Starts the line with `N<number>`.
+    pub fn line_number(&mut self, number: u32) -> &mut Self {
+        if self.words > 0 {
+            return self.fail(GcodeError::OutOfOrder);
+        }
+        self.word('N', number)
+    }
+
+    /// Adds word `<letter><value>`.
+    pub fn word<T: fmt::Display>(&mut self, letter: char, value: T) -> &mut Self {
+        if !letter.is_ascii_alphabetic() {
+            return self.fail(GcodeError::InvalidWord);
+        }
+        let separator = if self.words > 0 { " " } else { "" };
+        let mut w = Checksummed {
+            out: &mut self.out,
+            checksum: &mut self.checksum,
+        };
+        if write!(w, "{}{}{}", separator, letter, value).is_err() {
+            return self.fail(GcodeError::InvalidWord);
+        }
+        self.words += 1;
+        self
+    }
+
+    /// Adds word with value `value × 10^-decimals`, like 105 with 1 decimal for `X10.5`.
+    pub fn word_fixed(&mut self, letter: char, value: i64, decimals: u8) -> &mut Self {
+        self.word(letter, FixedPoint::new(value, decimals))
+    }
+
+    /// Ends the line with the checksum and `\n`, or removes it if it has an error or did not
+    /// fit.
+    pub fn finish(&mut self) -> Result<(), GcodeError> {
+        self.end_line(None)
+    }
+
+    /// Same as [GcodeWriter::finish] but with ` ;comment` after the checksum.
+    pub fn finish_with_comment(&mut self, comment: &str) -> Result<(), GcodeError> {
+        self.end_line(Some(comment))
+    }
+
+    /// Returns the underlying stream with the lines written so far.
+    pub fn into_inner(self) -> WriteTo<'a> {
+        self.out
+    }
+
+    fn end_line(&mut self, comment: Option<&str>) -> Result<(), GcodeError> {
+        if self.words == 0 {
+            self.fail(GcodeError::OutOfOrder);
+        }
+        let _ = write!(self.out, "*{}", self.checksum);
+        if let Some(comment) = comment {
+            if comment.contains(['\r', '\n']) {
+                self.fail(GcodeError::InvalidWord);
+            }
+            let _ = write!(self.out, " ;{}", comment);
+        }
+        let _ = self.out.write_char('\n');
+        if self.out.is_truncated() {
+            self.fail(GcodeError::Overflow);
+        }
+
+        let result = match self.error.take() {
+            Some(error) => {
+                self.out.truncate(self.line_start);
+                Err(error)
+            }
+            None => Ok(()),
+        };
+        self.line_start = self.out.written_bytes();
+        self.checksum = 0;
+        self.words = 0;
+        result
+    }
+
+    // Keeps the first error of the line
+    fn fail(&mut self, error: GcodeError) -> &mut Self {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        self
+    }
+}
+
+// Accumulates the checksum of bytes written, fails on chars that would break the line
+struct Checksummed<'w, 'a> {
+    out: &'w mut WriteTo<'a>,
+    checksum: &'w mut u8,
+}
+
+impl<'w, 'a> fmt::Write for Checksummed<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.contains(['\r', '\n', ';', '*', '(']) {
+            return Err(fmt::Error);
+        }
+        *self.checksum = s.bytes().fold(*self.checksum, |sum, b| sum ^ b);
+        self.out.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GcodeError, GcodeWriter};
+    use crate::WriteTo;
+
+    #[test]
+    fn gcode_known_checksums() {
+        let mut buf = [0u8; 128];
+        let mut g = GcodeWriter::new(WriteTo::new(&mut buf));
+        g.line_number(3).word('T', 0);
+        g.finish().unwrap();
+        g.line_number(123)
+            .word('G', 1)
+            .word_fixed('X', 105, 1)
+            .word('Y', 20);
+        g.finish().unwrap();
+        g.line_number(0).word('M', 110);
+        g.finish().unwrap();
+        g.word('G', 1).word_fixed('X', -125, 2).word('F', 1500);
+        g.finish().unwrap();
+        assert_eq!(
+            g.into_inner().as_str(),
+            "N3 T0*57\nN123 G1 X10.5 Y20*49\nN0 M110*35\nG1 X-1.25 F1500*89\n"
+        );
+    }
+
+    #[test]
+    fn gcode_comment_not_in_checksum() {
+        let mut buf = [0u8; 64];
+        let mut g = GcodeWriter::new(WriteTo::new(&mut buf));
+        g.line_number(1).word('G', 28);
+        g.finish_with_comment("home all; *axes*").unwrap();
+        assert_eq!(g.into_inner().as_str(), "N1 G28*18 ;home all; *axes*\n");
+
+        let mut buf = [0u8; 64];
+        let mut g = GcodeWriter::new(WriteTo::new(&mut buf));
+        g.line_number(1).word('G', 28);
+        assert_eq!(
+            g.finish_with_comment("a\nG1 X0"),
+            Err(GcodeError::InvalidWord)
+        );
+        assert_eq!(g.into_inner().as_str(), "");
+    }
+
+    #[test]
+    fn gcode_invalid() {
+        let mut buf = [0u8; 64];
+        let mut g = GcodeWriter::new(WriteTo::new(&mut buf));
+        g.word('G', 1).line_number(5);
+        assert_eq!(g.finish(), Err(GcodeError::OutOfOrder));
+        assert_eq!(g.finish(), Err(GcodeError::OutOfOrder));
+        g.word('1', 1);
+        assert_eq!(g.finish(), Err(GcodeError::InvalidWord));
+        g.word('M', "117 hi;x");
+        assert_eq!(g.finish(), Err(GcodeError::InvalidWord));
+        g.word('M', 105);
+        assert_eq!(g.finish(), Ok(()));
+        assert_eq!(g.into_inner().as_str(), "M105*121\n");
+    }
+
+    #[test]
+    fn gcode_overflow() {
+        let mut buf = [0u8; 20];
+        let mut g = GcodeWriter::new(WriteTo::new(&mut buf));
+        g.line_number(7).word('G', 1);
+        g.finish().unwrap();
+        g.line_number(8).word('G', 1).word_fixed('X', 105, 1);
+        assert_eq!(g.finish(), Err(GcodeError::Overflow));
+        // the checksum itself does not fit
+        g.line_number(8).word('G', 1).word('X', 5);
+        assert_eq!(g.finish(), Err(GcodeError::Overflow));
+        assert_eq!(g.into_inner().as_str(), "N7 G1*47\n");
+    }
+}
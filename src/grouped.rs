@@ -0,0 +1,188 @@
+//  ( /   @ @    ()  Integers with thousands separators
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows an integer with digits grouped by three, like `12,345,678`.
+///
+/// The separator is `,` by default and can be any char with [Grouped::separator], e.g.
+/// `' '`, `'.'` or the thin space `'\u{2009}'`. Width, fill, alignment and the `+` and `0`
+/// flags work as for integers, the width counts chars.
+///
+/// ```
+/// use stackfmt::Grouped;
+///
+/// let mut buf = [0u8; 24];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Grouped::new(12345678)));
+/// assert_eq!(s, "12,345,678");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:>12}", Grouped::new(-1234).separator(' ')));
+/// assert_eq!(s, "      -1 234");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Grouped {
+    value: i128,
+    separator: char,
+}
+
+impl Grouped {
+    /// Creates adapter for `value` with `,` as separator.
+    pub fn new(value: i128) -> Self {
+        Grouped {
+            value,
+            separator: ',',
+        }
+    }
+
+    /// Sets the char placed between groups of three digits.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+impl fmt::Display for Grouped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut digits = [0u8; 39];
+        let mut magnitude = self.value.unsigned_abs();
+        let mut start = digits.len();
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (magnitude % 10) as u8;
+            magnitude /= 10;
+            if magnitude == 0 {
+                break;
+            }
+        }
+
+        // 39 digits and 12 separators of up to 4 bytes
+        let mut buf = [0u8; 39 + 12 * 4];
+        let mut w = WriteTo::new(&mut buf);
+        let count = digits.len() - start;
+        for (i, digit) in digits[start..].iter().enumerate() {
+            if i > 0 && (count - i) % 3 == 0 {
+                let _ = w.write_char(self.separator);
+            }
+            let _ = w.write_char(*digit as char);
+        }
+        let digits = w.as_str();
+        let sign = match (self.value < 0, f.sign_plus()) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+
+        // Formatter::pad_integral counts bytes, multi-byte separators need chars counted
+        let len = sign.len() + digits.chars().count();
+        let pad = f.width().unwrap_or(0).saturating_sub(len);
+        if f.sign_aware_zero_pad() {
+            f.write_str(sign)?;
+            for _ in 0..pad {
+                f.write_char('0')?;
+            }
+            return f.write_str(digits);
+        }
+        pad_with(f, len, fmt::Alignment::Right, |f| {
+            f.write_str(sign)?;
+            f.write_str(digits)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grouped;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn grouped_digit_counts() {
+        let table: &[(i128, &str)] = &[
+            (0, "0"),
+            (7, "7"),
+            (123, "123"),
+            (1234, "1,234"),
+            (123456, "123,456"),
+            (1234567, "1,234,567"),
+            (12345678, "12,345,678"),
+        ];
+        let mut buf = [0u8; 64];
+        for (value, expected) in table {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", Grouped::new(*value))),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn grouped_negative() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Grouped::new(-123))),
+            "-123"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Grouped::new(-123456))),
+            "-123,456"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Grouped::new(-1234567))),
+            "-1,234,567"
+        );
+    }
+
+    #[test]
+    fn grouped_extremes() {
+        let mut buf = [0u8; 80];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Grouped::new(i128::MAX))),
+            "170,141,183,460,469,231,731,687,303,715,884,105,727"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Grouped::new(i128::MIN))),
+            "-170,141,183,460,469,231,731,687,303,715,884,105,728"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Grouped::new(i128::MIN).separator('\u{2009}'))),
+            "-170\u{2009}141\u{2009}183\u{2009}460\u{2009}469\u{2009}231\u{2009}731\u{2009}687\u{2009}303\u{2009}715\u{2009}884\u{2009}105\u{2009}728"
+        );
+    }
+
+    #[test]
+    fn grouped_separators() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Grouped::new(12345678).separator('.')),
+        );
+        assert_eq!(s, "12.345.678");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Grouped::new(1234).separator('\u{2009}')),
+        );
+        assert_eq!(s, "1\u{2009}234");
+    }
+
+    #[test]
+    fn grouped_width() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(&mut buf, format_args!("[{:>15}]", Grouped::new(12345678)));
+        assert_eq!(s, "[     12,345,678]");
+        let s = fmt_truncate(&mut buf, format_args!("[{:<8}]", Grouped::new(-1234)));
+        assert_eq!(s, "[-1,234  ]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:^9}]", Grouped::new(1234).separator('\u{2009}')),
+        );
+        assert_eq!(s, "[  1\u{2009}234  ]");
+        let s = fmt_truncate(&mut buf, format_args!("{:+}", Grouped::new(1000)));
+        assert_eq!(s, "+1,000");
+        let s = fmt_truncate(&mut buf, format_args!("{:*>7}", Grouped::new(1000)));
+        assert_eq!(s, "**1,000");
+        let s = fmt_truncate(&mut buf, format_args!("{:08}", Grouped::new(-1000)));
+        assert_eq!(s, "-001,000");
+    }
+}
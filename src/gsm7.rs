@@ -0,0 +1,290 @@
+//  ( /   @ @    ()  Formatted output encoded with the GSM 03.38 default alphabet
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Septets that fit into a single SMS with the GSM 7-bit alphabet.
+pub const GSM7_SMS_SEPTETS: usize = 160;
+
+// Escape to the extension table
+const ESC: u8 = 0x1B;
+
+// Chars of the GSM 03.38 basic table by code, ESC is never matched
+#[rustfmt::skip]
+const BASIC: [char; 128] = [
+    '@', '\u{A3}', '$', '\u{A5}', '\u{E8}', '\u{E9}', '\u{F9}', '\u{EC}',
+    '\u{F2}', '\u{C7}', '\n', '\u{D8}', '\u{F8}', '\r', '\u{C5}', '\u{E5}',
+    '\u{394}', '_', '\u{3A6}', '\u{393}', '\u{39B}', '\u{3A9}', '\u{3A0}', '\u{3A8}',
+    '\u{3A3}', '\u{398}', '\u{39E}', '\u{1B}', '\u{C6}', '\u{E6}', '\u{DF}', '\u{C9}',
+    ' ', '!', '"', '#', '\u{A4}', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '\u{A1}', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '\u{C4}', '\u{D6}', '\u{D1}', '\u{DC}', '\u{A7}',
+    '\u{BF}', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '\u{E4}', '\u{F6}', '\u{F1}', '\u{FC}', '\u{E0}',
+];
+
+// Chars of the extension table with their codes after ESC
+const EXTENSION: [(char, u8); 10] = [
+    ('\u{C}', 0x0A),
+    ('^', 0x14),
+    ('{', 0x28),
+    ('}', 0x29),
+    ('\\', 0x2F),
+    ('[', 0x3C),
+    ('~', 0x3D),
+    (']', 0x3E),
+    ('|', 0x40),
+    ('\u{20AC}', 0x65),
+];
+
+/// Impl of [fmt::Write] that encodes formatted text with the GSM 03.38 default alphabet
+/// used for SMS, storing one septet per byte into the provided buffer.
+///
+/// Chars of the extension table like `{`, `[`, `~` or `€` take two septets: `0x1B` and the
+/// code. Other chars are replaced with the fallback char (`?` by default), which must be in
+/// the basic table. [Gsm7Writer::septets_used] counts all the septets of the text, also the
+/// ones that did not fit into the buffer, so it tells the size of the message.
+/// [Gsm7Writer::pack] packs the septets into octets as sent over the air.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buffer = [0u8; 160];
+/// let mut w = stackfmt::Gsm7Writer::new(&mut buffer);
+/// write!(w, "T={} {}", 21, "\u{20AC}").unwrap();
+/// assert_eq!(w.as_bytes(), [0x54, 0x3D, 0x32, 0x31, 0x20, 0x1B, 0x65]);
+/// assert_eq!(w.septets_used(), 7);
+/// assert!(!w.exceeds_sms());
+/// ```
+pub struct Gsm7Writer<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+    septets: usize,
+    overflow: bool,
+    fallback: u8,
+    unmappable: Option<char>,
+}
+
+impl<'a> Gsm7Writer<'a> {
+    /// Creates stream writing septets into `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Gsm7Writer {
+            buffer,
+            used: 0,
+            septets: 0,
+            overflow: false,
+            fallback: b'?',
+            unmappable: None,
+        }
+    }
+
+    /// Sets the char written for chars that are not in the alphabet, it is ignored if it
+    /// is not in the basic table.
+    pub fn fallback(mut self, c: char) -> Self {
+        if let Some(code) = basic_code(c) {
+            self.fallback = code;
+        }
+        self
+    }
+
+    /// The first char that is not in the alphabet
+    pub fn unmappable(&self) -> Option<char> {
+        self.unmappable
+    }
+
+    /// Septets of the whole text, including the ones that did not fit into the buffer
+    pub fn septets_used(&self) -> usize {
+        self.septets
+    }
+
+    /// True if the text does not fit into a single SMS of [GSM7_SMS_SEPTETS]
+    pub fn exceeds_sms(&self) -> bool {
+        self.septets > GSM7_SMS_SEPTETS
+    }
+
+    /// Septets written so far, one per byte
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.used]
+    }
+
+    /// True if some septets did not fit into the buffer and were dropped
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    /// Returns the written septets and the flag if some were dropped.
+    pub fn finish(self) -> (&'a [u8], bool) {
+        (&self.buffer[..self.used], self.overflow)
+    }
+
+    /// Packs the written septets into `out`, 8 septets into 7 octets with the first septet
+    /// in the low bits. Returns `None` if `out` is too small.
+    ///
+    /// ```
+    /// let mut buffer = [0u8; 16];
+    /// let mut w = stackfmt::Gsm7Writer::new(&mut buffer);
+    /// core::fmt::Write::write_str(&mut w, "hellohello").unwrap();
+    /// let mut out = [0u8; 16];
+    /// assert_eq!(
+    ///     w.pack(&mut out),
+    ///     Some(&[0xE8, 0x32, 0x9B, 0xFD, 0x46, 0x97, 0xD9, 0xEC, 0x37][..])
+    /// );
+    /// ```
+    pub fn pack<'o>(&self, out: &'o mut [u8]) -> Option<&'o [u8]> {
+        let len = (self.used * 7).div_ceil(8);
+        let out = out.get_mut(..len)?;
+        out.fill(0);
+        for (i, &septet) in self.as_bytes().iter().enumerate() {
+            let (byte, shift) = (i * 7 / 8, i * 7 % 8);
+            out[byte] |= septet << shift;
+            if shift > 1 {
+                out[byte + 1] |= septet >> (8 - shift);
+            }
+        }
+        Some(out)
+    }
+
+    fn push(&mut self, septets: &[u8]) {
+        self.septets += septets.len();
+        if self.overflow {
+            return;
+        }
+        // ESC and its code are not separated
+        if self.buffer.len() - self.used < septets.len() {
+            self.overflow = true;
+            return;
+        }
+        self.buffer[self.used..self.used + septets.len()].copy_from_slice(septets);
+        self.used += septets.len();
+    }
+}
+
+impl<'a> fmt::Write for Gsm7Writer<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if let Some(code) = basic_code(c) {
+                self.push(&[code]);
+            } else if let Some(&(_, code)) = EXTENSION.iter().find(|(x, _)| *x == c) {
+                self.push(&[ESC, code]);
+            } else {
+                self.unmappable.get_or_insert(c);
+                self.push(&[self.fallback]);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn basic_code(c: char) -> Option<u8> {
+    if c.is_ascii_alphanumeric() {
+        return Some(c as u8); // the same codes as ASCII
+    }
+    match BASIC.iter().position(|&x| x == c) {
+        Some(code) if code != ESC as usize => Some(code as u8),
+        _ => None,
+    }
+}
+
+/// Encodes formatted text with the GSM 03.38 alphabet into the buffer truncating if needed,
+/// returns the septets and the count of septets of the whole text.
+///
+/// ```
+/// let mut buf = [0u8; 160];
+/// let (septets, count) = stackfmt::fmt_gsm7(&mut buf, format_args!("[{}]", 42));
+/// assert_eq!(septets, [0x1B, 0x3C, 0x34, 0x32, 0x1B, 0x3E]);
+/// assert_eq!(count, 6);
+/// ```
+pub fn fmt_gsm7<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> (&'a [u8], usize) {
+    let mut w = Gsm7Writer::new(buffer);
+    let _ = fmt::write(&mut w, args);
+    let count = w.septets_used();
+    (w.finish().0, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_gsm7, Gsm7Writer};
+    use core::fmt::Write;
+
+    #[test]
+    fn gsm7_ascii() {
+        let mut buf = [0u8; 32];
+        let (septets, count) = fmt_gsm7(&mut buf, format_args!("Hi {}!", 7));
+        assert_eq!(septets, b"Hi 7!");
+        assert_eq!(count, 5);
+        // ASCII chars with other codes
+        let (septets, _) = fmt_gsm7(&mut buf, format_args!("a@b_$\n"));
+        assert_eq!(septets, [0x61, 0x00, 0x62, 0x11, 0x02, 0x0A]);
+        let (septets, _) = fmt_gsm7(&mut buf, format_args!("\u{E9}\u{DC}\u{3A9}\u{A3}"));
+        assert_eq!(septets, [0x05, 0x5E, 0x15, 0x01]);
+    }
+
+    #[test]
+    fn gsm7_extension() {
+        let mut buf = [0u8; 32];
+        let (septets, count) = fmt_gsm7(&mut buf, format_args!("{{~}}\\|^\u{20AC}"));
+        assert_eq!(
+            septets,
+            [0x1B, 0x28, 0x1B, 0x3D, 0x1B, 0x29, 0x1B, 0x2F, 0x1B, 0x40, 0x1B, 0x14, 0x1B, 0x65]
+        );
+        assert_eq!(count, 14);
+        // the escape is not split by the end of the buffer
+        let mut buf = [0u8; 4];
+        let mut w = Gsm7Writer::new(&mut buf);
+        w.write_str("abc[").unwrap();
+        assert_eq!(w.septets_used(), 5);
+        assert_eq!(w.finish(), (&b"abc"[..], true));
+    }
+
+    #[test]
+    fn gsm7_unmappable() {
+        let mut buf = [0u8; 32];
+        let mut w = Gsm7Writer::new(&mut buf);
+        w.write_str("ok \u{1F600}`").unwrap();
+        assert_eq!(w.unmappable(), Some('\u{1F600}'));
+        assert_eq!(w.as_bytes(), b"ok ??");
+        let mut w = Gsm7Writer::new(&mut buf).fallback(' ');
+        w.write_str("a\u{1F600}b").unwrap();
+        assert_eq!(w.as_bytes(), b"a b");
+        // fallback must be in the basic table
+        let mut w = Gsm7Writer::new(&mut buf).fallback('\u{20AC}');
+        w.write_str("\u{1F600}").unwrap();
+        assert_eq!(w.as_bytes(), b"?");
+    }
+
+    #[test]
+    fn gsm7_sms_boundary() {
+        let mut buf = [0u8; 200];
+        let mut w = Gsm7Writer::new(&mut buf);
+        for _ in 0..158 {
+            w.write_char('x').unwrap();
+        }
+        w.write_char('\u{20AC}').unwrap();
+        assert_eq!(w.septets_used(), 160);
+        assert!(!w.exceeds_sms());
+        w.write_char('x').unwrap();
+        assert_eq!(w.septets_used(), 161);
+        assert!(w.exceeds_sms());
+        // counted also when the buffer is full
+        let mut buf = [0u8; 8];
+        let (septets, count) = fmt_gsm7(&mut buf, format_args!("{:161}", ""));
+        assert_eq!((septets.len(), count), (8, 161));
+    }
+
+    #[test]
+    fn gsm7_pack() {
+        let mut buf = [0u8; 32];
+        let mut out = [0u8; 32];
+        let mut w = Gsm7Writer::new(&mut buf);
+        w.write_str("12345678").unwrap();
+        assert_eq!(
+            w.pack(&mut out),
+            Some(&[0x31, 0xD9, 0x8C, 0x56, 0xB3, 0xDD, 0x70][..])
+        );
+        assert_eq!(w.pack(&mut out[..6]), None);
+        let w = Gsm7Writer::new(&mut buf);
+        assert_eq!(w.pack(&mut out), Some(&[][..]));
+    }
+}
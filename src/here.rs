@@ -0,0 +1,150 @@
+//  ( /   @ @    ()  Source location of the caller in formatted messages
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+use core::panic::Location;
+
+/// Display adapter that shows the source location where it was created as
+/// `file:line:col`.
+///
+/// A precision from the format string (`{:.20}`) limits the chars of the file path: a
+/// longer path is cut from the left, keeping the file name, and starts with `…`.
+///
+/// ```
+/// use stackfmt::Here;
+///
+/// let mut buf = [0u8; 64];
+/// let (here, line) = (Here::new(), line!());
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:.8}", here));
+/// assert!(s.starts_with("\u{2026}"));
+/// assert_eq!(s.split(':').nth(1).unwrap().parse(), Ok(line));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Here {
+    location: &'static Location<'static>,
+}
+
+impl Here {
+    /// Creates adapter for the location of the caller.
+    #[track_caller]
+    pub fn new() -> Self {
+        Here {
+            location: Location::caller(),
+        }
+    }
+
+    /// Creates adapter for the given location.
+    pub fn at(location: &'static Location<'static>) -> Self {
+        Here { location }
+    }
+}
+
+impl Default for Here {
+    #[track_caller]
+    fn default() -> Self {
+        Here::new()
+    }
+}
+
+impl fmt::Display for Here {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(max) => write_tail(f, self.location.file(), max)?,
+            None => f.write_str(self.location.file())?,
+        }
+        write!(f, ":{}:{}", self.location.line(), self.location.column())
+    }
+}
+
+// Writes at most `max` chars of `text`, a longer one is cut from the left and starts with `…`
+fn write_tail<W: fmt::Write>(w: &mut W, text: &str, max: usize) -> fmt::Result {
+    let chars = text.chars().count();
+    if chars <= max {
+        return w.write_str(text);
+    }
+    if max > 0 {
+        let skip = chars - (max - 1);
+        let start = text.char_indices().nth(skip).map_or(text.len(), |(i, _)| i);
+        w.write_char('\u{2026}')?;
+        w.write_str(&text[start..])?;
+    }
+    Ok(())
+}
+
+/// Writes `args` prefixed with the caller location as `file:line:col: ` into the buffer.
+///
+/// The file path takes at most half of the buffer, a longer one is cut from the left as in
+/// [Here]. The message is truncated if it does not fit.
+///
+/// ```
+/// let mut buf = [0u8; 128];
+/// let s = stackfmt::fmt_here(&mut buf, format_args!("bad state {}", 3));
+/// assert!(s.ends_with(": bad state 3"));
+/// ```
+#[track_caller]
+pub fn fmt_here<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let here = Here::new();
+    let max_path = buffer.len() / 2;
+    let mut w = WriteTo::new(buffer);
+    match write!(w, "{:.max_path$}: {}", here, args, max_path = max_path) {
+        Ok(_) => w.as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_here, write_tail, Here};
+    use crate::{fmt_truncate, WriteTo};
+
+    #[test]
+    fn here_points_at_caller() {
+        let mut buf = [0u8; 128];
+        let (here, line) = (Here::new(), line!());
+        let mut expected = [0u8; 128];
+        let expected = fmt_truncate(&mut expected, format_args!("{}:{}:", file!(), line));
+        let s = fmt_truncate(&mut buf, format_args!("{}", here));
+        assert!(s.starts_with(expected), "{}", s);
+
+        let (s, line) = (fmt_here(&mut buf, format_args!("x={}", 5)), line!());
+        let mut expected = [0u8; 128];
+        let expected = fmt_truncate(&mut expected, format_args!("{}:{}:", file!(), line));
+        assert!(s.starts_with(expected), "{}", s);
+        assert!(s.ends_with(": x=5"));
+    }
+
+    #[test]
+    fn here_cuts_path_from_left() {
+        let path = "firmware/drivers/bus/spi/\u{E9}cran.rs";
+        let mut buf = [0u8; 64];
+        let mut w = WriteTo::new(&mut buf);
+        write_tail(&mut w, path, 10).unwrap();
+        assert_eq!(w.as_str(), "\u{2026}/\u{E9}cran.rs");
+
+        let mut buf = [0u8; 64];
+        let mut w = WriteTo::new(&mut buf);
+        write_tail(&mut w, path, 1).unwrap();
+        write_tail(&mut w, path, 0).unwrap();
+        write_tail(&mut w, "a.rs", 4).unwrap();
+        assert_eq!(w.as_str(), "\u{2026}a.rs");
+
+        let mut buf = [0u8; 128];
+        let file = file!(); // src/here.rs
+        let s = fmt_truncate(&mut buf, format_args!("{:.5}", Here::new()));
+        assert!(s.starts_with("\u{2026}e.rs:"), "{}", s);
+        let s = fmt_truncate(&mut buf, format_args!("{:.100}", Here::new()));
+        assert!(s.starts_with(file), "{}", s);
+    }
+
+    #[test]
+    fn fmt_here_small_buffer() {
+        let mut buf = [0u8; 16];
+        let s = fmt_here(&mut buf, format_args!("overflow"));
+        // the path takes at most 8 chars
+        assert!(s.starts_with("\u{2026}here.rs:"), "{}", s);
+        assert_eq!(s.len(), 16);
+    }
+}
@@ -0,0 +1,184 @@
+//  ( /   @ @    ()  Canonical hex+ASCII dump of a memory region
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+// Number of bytes shown on a single line of the dump
+const BYTES_PER_LINE: usize = 16;
+
+// Enough for 16 hex digits of offset + hex columns + ASCII gutter
+const LINE_BUFFER_SIZE: usize = 96;
+
+// Writes a single `hexdump -C` line without a trailing newline. The `chunk` is at most
+// BYTES_PER_LINE long, a shorter chunk is padded so the ASCII gutter stays aligned.
+fn write_line(w: &mut impl fmt::Write, addr: usize, chunk: &[u8]) -> fmt::Result {
+    write!(w, "{:08x} ", addr)?;
+    for i in 0..BYTES_PER_LINE {
+        if i == BYTES_PER_LINE / 2 {
+            w.write_str(" ")?;
+        }
+        match chunk.get(i) {
+            Some(b) => write!(w, " {:02x}", b)?,
+            None => w.write_str("   ")?,
+        }
+    }
+    w.write_str("  |")?;
+    for &b in chunk {
+        let ch = if b.is_ascii_graphic() || b == b' ' {
+            b as char
+        } else {
+            '.'
+        };
+        fmt::Write::write_char(w, ch)?;
+    }
+    w.write_str("|")
+}
+
+/// Formats `bytes` as `hexdump -C` lines and passes each of them (without newline) to `emit`.
+///
+/// Only a line-sized buffer on the stack is used, so the dumped region can be of any size.
+/// Offsets start from `base_addr`. Nothing is emitted for an empty slice.
+///
+/// ```
+/// let mut lines = 0;
+/// stackfmt::hexdump_lines(b"Hello, world!\n", 0x100, |line| {
+///     assert_eq!(
+///         line,
+///         "00000100  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|"
+///     );
+///     lines += 1;
+/// });
+/// assert_eq!(lines, 1);
+/// ```
+pub fn hexdump_lines(bytes: &[u8], base_addr: usize, mut emit: impl FnMut(&str)) {
+    for (n, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let mut buf = [0u8; LINE_BUFFER_SIZE];
+        let mut w = WriteTo::new(&mut buf);
+        let addr = base_addr.wrapping_add(n * BYTES_PER_LINE);
+        // Cannot fail: WriteTo never returns errors and the buffer fits the longest line
+        let _ = write_line(&mut w, addr, chunk);
+        emit(w.as_str());
+    }
+}
+
+/// Display adapter that renders a byte slice as `hexdump -C` lines separated by `\n`.
+///
+/// Intended for small slices where the whole dump fits in the destination of one `write!`,
+/// use [hexdump_lines] for larger regions.
+///
+/// ```
+/// let mut buf = [0u8; 128];
+/// let dump = stackfmt::HexDump::new(&[0x00, 0x41, 0x7f], 0);
+/// let formatted = stackfmt::fmt_truncate(&mut buf, format_args!("{}", dump));
+/// assert_eq!(
+///     formatted,
+///     "00000000  00 41 7f                                          |.A.|"
+/// );
+/// ```
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    base_addr: usize,
+}
+
+impl<'a> HexDump<'a> {
+    /// Creates adapter for `bytes` with offsets starting from `base_addr`.
+    pub fn new(bytes: &'a [u8], base_addr: usize) -> Self {
+        HexDump { bytes, base_addr }
+    }
+}
+
+impl<'a> fmt::Display for HexDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, chunk) in self.bytes.chunks(BYTES_PER_LINE).enumerate() {
+            if n > 0 {
+                f.write_str("\n")?;
+            }
+            write_line(f, self.base_addr.wrapping_add(n * BYTES_PER_LINE), chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Collects the lines emitted by hexdump_lines() into a fixed buffer separated by '\n'
+    fn dump<'a>(buf: &'a mut [u8], bytes: &[u8], base_addr: usize) -> &'a str {
+        let mut w = crate::WriteTo::new(buf);
+        super::hexdump_lines(bytes, base_addr, |line| {
+            let _ = core::fmt::Write::write_str(&mut w, line);
+            let _ = core::fmt::Write::write_str(&mut w, "\n");
+        });
+        w.as_str()
+    }
+
+    #[test]
+    fn hexdump_empty() {
+        let mut buf = [0u8; 256];
+        assert_eq!(dump(&mut buf, &[], 0), "");
+    }
+
+    #[test]
+    fn hexdump_one_byte() {
+        let mut buf = [0u8; 256];
+        assert_eq!(
+            dump(&mut buf, b"A", 0),
+            "00000000  41                                                |A|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_15_bytes() {
+        let mut buf = [0u8; 256];
+        assert_eq!(
+            dump(&mut buf, b"0123456789abcde", 0),
+            "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65     |0123456789abcde|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_16_bytes() {
+        let mut buf = [0u8; 256];
+        let bytes = [
+            0x00u8, 0x01, 0x02, 0x03, 0x20, 0x7e, 0x7f, 0x80, 0xff, b'a', b'b', b'c', b'd', b'e',
+            b'f', b'g',
+        ];
+        assert_eq!(
+            dump(&mut buf, &bytes, 0),
+            "00000000  00 01 02 03 20 7e 7f 80  ff 61 62 63 64 65 66 67  |.... ~...abcdefg|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_17_bytes() {
+        let mut buf = [0u8; 256];
+        assert_eq!(
+            dump(&mut buf, b"0123456789abcdefX", 0),
+            "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\n\
+             00000010  58                                                |X|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_base_address() {
+        let mut buf = [0u8; 256];
+        assert_eq!(
+            dump(&mut buf, b"0123456789abcdefXY", 0x2000_fff0),
+            "2000fff0  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\n\
+             20010000  58 59                                             |XY|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_display() {
+        let mut buf = [0u8; 256];
+        let dump = super::HexDump::new(b"0123456789abcdefX", 0x10);
+        let formatted = crate::fmt_truncate(&mut buf, format_args!("{}", dump));
+        assert_eq!(
+            formatted,
+            "00000010  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\n\
+             00000020  58                                                |X|"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//  ( /   @ @    ()  HTTP/1.1 response status line and headers
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [HttpHeadWriter::finish].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HttpHeadError {
+    /// No status line, a second status line, or a header after finish
+    OutOfOrder,
+    /// Header name is empty or has chars that are not allowed in a token
+    InvalidName,
+    /// Reason phrase or header value has CR or LF
+    InvalidValue,
+    /// Content-Length was given twice
+    DuplicateContentLength,
+}
+
+impl fmt::Display for HttpHeadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            HttpHeadError::OutOfOrder => "status line or header out of order",
+            HttpHeadError::InvalidName => "invalid header name",
+            HttpHeadError::InvalidValue => "CR or LF in header value",
+            HttpHeadError::DuplicateContentLength => "duplicate Content-Length",
+        })
+    }
+}
+
+/// Writes the head of an HTTP/1.1 response into a [WriteTo]: the status line, headers in the
+/// given order and the empty line.
+///
+/// The first error is remembered and reported by [finish](HttpHeadWriter::finish), the
+/// header that caused it is not written. Header names are checked to be tokens, values and
+/// the reason phrase must not have CR or LF.
+///
+/// ```
+/// use stackfmt::{HttpHeadWriter, WriteTo};
+///
+/// let mut buf = [0u8; 128];
+/// let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+/// head.status(200, "OK")
+///     .header("Content-Type", format_args!("text/plain"))
+///     .content_length(5);
+/// assert_eq!(
+///     head.finish(),
+///     Ok(("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\n", false))
+/// );
+/// ```
+pub struct HttpHeadWriter<'a> {
+    out: WriteTo<'a>,
+    has_status: bool,
+    has_content_length: bool,
+    finished: bool,
+    error: Option<HttpHeadError>,
+}
+
+impl<'a> HttpHeadWriter<'a> {
+    /// Creates writer putting the head into `out`.
+    pub fn new(out: WriteTo<'a>) -> Self {
+        HttpHeadWriter {
+            out,
+            has_status: false,
+            has_content_length: false,
+            finished: false,
+            error: None,
+        }
+    }
+
+    /// Writes the status line, must be the first call.
+    pub fn status(&mut self, code: u16, reason: &str) -> &mut Self {
+        if self.has_status || self.finished {
+            return self.fail(HttpHeadError::OutOfOrder);
+        }
+        if reason.contains(['\r', '\n']) {
+            return self.fail(HttpHeadError::InvalidValue);
+        }
+        let _ = write!(self.out, "HTTP/1.1 {:03} {}\r\n", code, reason);
+        self.has_status = true;
+        self
+    }
+
+    /// Writes header `name: value`.
+    pub fn header(&mut self, name: &str, value: fmt::Arguments) -> &mut Self {
+        if !self.has_status || self.finished {
+            return self.fail(HttpHeadError::OutOfOrder);
+        }
+        if name.is_empty() || !name.bytes().all(is_token_char) {
+            return self.fail(HttpHeadError::InvalidName);
+        }
+        let content_length = name.eq_ignore_ascii_case("Content-Length");
+        if content_length && self.has_content_length {
+            return self.fail(HttpHeadError::DuplicateContentLength);
+        }
+
+        let line_start = self.out.written_bytes();
+        let _ = write!(self.out, "{}: ", name);
+        let mut checked = SingleLine(&mut self.out);
+        if checked.write_fmt(value).is_err() {
+            self.out.truncate(line_start);
+            return self.fail(HttpHeadError::InvalidValue);
+        }
+        let _ = self.out.write_str("\r\n");
+        self.has_content_length |= content_length;
+        self
+    }
+
+    /// Writes `Content-Length` header.
+    pub fn content_length(&mut self, len: usize) -> &mut Self {
+        self.header("Content-Length", format_args!("{}", len))
+    }
+
+    /// Ends the head with an empty line and returns it with the flag if it did not fit.
+    ///
+    /// The head is ended only once, further calls return the same result.
+    pub fn finish(&mut self) -> Result<(&str, bool), HttpHeadError> {
+        if !self.has_status {
+            self.fail(HttpHeadError::OutOfOrder);
+        }
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if !self.finished {
+            let _ = self.out.write_str("\r\n");
+            self.finished = true;
+        }
+        Ok((self.out.to_str(), self.out.is_truncated()))
+    }
+
+    // Keeps the first error
+    fn fail(&mut self, error: HttpHeadError) -> &mut Self {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        self
+    }
+}
+
+// tchar of RFC 9110
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+// Fails on CR or LF so a value can not start a new header
+struct SingleLine<'w, 'a>(&'w mut WriteTo<'a>);
+
+impl<'w, 'a> fmt::Write for SingleLine<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.contains(['\r', '\n']) {
+            return Err(fmt::Error);
+        }
+        self.0.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpHeadError, HttpHeadWriter};
+    use crate::WriteTo;
+
+    #[test]
+    fn http_head_typical() {
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        let etag = 0x1F2Eu32;
+        head.status(404, "Not Found")
+            .header("Server", format_args!("stackfmt"))
+            .header("ETag", format_args!("\"{:x}\"", etag))
+            .content_length(0)
+            .header("Connection", format_args!("close"));
+        assert_eq!(
+            head.finish(),
+            Ok((
+                "HTTP/1.1 404 Not Found\r\nServer: stackfmt\r\nETag: \"1f2e\"\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n",
+                false
+            ))
+        );
+    }
+
+    #[test]
+    fn http_head_rejects_newline() {
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        let evil = "x\r\nSet-Cookie: a=b";
+        head.status(200, "OK")
+            .header("X-Name", format_args!("{}", evil));
+        assert_eq!(head.finish(), Err(HttpHeadError::InvalidValue));
+
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        head.status(200, "OK\n");
+        assert_eq!(head.finish(), Err(HttpHeadError::InvalidValue));
+
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        head.status(200, "OK").header("Bad Name", format_args!("1"));
+        assert_eq!(head.finish(), Err(HttpHeadError::InvalidName));
+    }
+
+    #[test]
+    fn http_head_order_and_duplicates() {
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        head.header("Server", format_args!("x"));
+        assert_eq!(head.finish(), Err(HttpHeadError::OutOfOrder));
+
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        head.status(200, "OK")
+            .content_length(3)
+            .header("content-length", format_args!("4"));
+        assert_eq!(head.finish(), Err(HttpHeadError::DuplicateContentLength));
+
+        let mut buf = [0u8; 256];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        assert_eq!(head.finish(), Err(HttpHeadError::OutOfOrder));
+    }
+
+    #[test]
+    fn http_head_overflow() {
+        let mut buf = [0u8; 32];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        head.status(200, "OK")
+            .header("Content-Type", format_args!("application/json"));
+        assert_eq!(
+            head.finish(),
+            Ok(("HTTP/1.1 200 OK\r\nContent-Type: a", true))
+        );
+    }
+
+    #[test]
+    fn http_head_finish_idempotent() {
+        let mut buf = [0u8; 64];
+        let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+        head.status(204, "No Content");
+        assert_eq!(
+            head.finish(),
+            Ok(("HTTP/1.1 204 No Content\r\n\r\n", false))
+        );
+        assert_eq!(
+            head.finish(),
+            Ok(("HTTP/1.1 204 No Content\r\n\r\n", false))
+        );
+        head.header("Late", format_args!("1"));
+        assert_eq!(head.finish(), Err(HttpHeadError::OutOfOrder));
+    }
+}
@@ -0,0 +1,229 @@
+//  ( /   @ @    ()  Intel HEX records
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Most data bytes in a record of [IhexWriter].
+pub const IHEX_MAX_RECORD_LEN: u8 = 32;
+
+/// Error of [fmt_ihex_data] and other record functions.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IhexError {
+    /// More than 255 data bytes for one record
+    DataTooLong,
+    /// The record does not fit into the buffer
+    BufferTooSmall,
+}
+
+impl fmt::Display for IhexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            IhexError::DataTooLong => "more than 255 bytes in Intel HEX record",
+            IhexError::BufferTooSmall => "Intel HEX record does not fit",
+        })
+    }
+}
+
+// Writes `:LLAAAATT<data>CC`, the checksum is the two's complement of the sum of all bytes
+fn write_record<W: fmt::Write>(w: &mut W, rtype: u8, address: u16, data: &[u8]) -> fmt::Result {
+    let len = data.len() as u8;
+    let mut sum = len
+        .wrapping_add((address >> 8) as u8)
+        .wrapping_add(address as u8)
+        .wrapping_add(rtype);
+    write!(w, ":{:02X}{:04X}{:02X}", len, address, rtype)?;
+    for &b in data {
+        write!(w, "{:02X}", b)?;
+        sum = sum.wrapping_add(b);
+    }
+    write!(w, "{:02X}", sum.wrapping_neg())
+}
+
+fn fmt_record<'a>(
+    buffer: &'a mut [u8],
+    rtype: u8,
+    address: u16,
+    data: &[u8],
+) -> Result<&'a str, IhexError> {
+    if data.len() > 255 {
+        return Err(IhexError::DataTooLong);
+    }
+    let mut w = WriteTo::new(buffer);
+    let _ = write_record(&mut w, rtype, address, data);
+    if w.is_truncated() {
+        return Err(IhexError::BufferTooSmall);
+    }
+    Ok(w.as_str())
+}
+
+/// Writes data record `:LLAAAA00DD..CC` into the buffer, it needs `11 + 2 × data.len()`
+/// bytes.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let data = [0x21, 0x46, 0x01, 0x36, 0x01, 0x21, 0x47, 0x01, 0x36, 0x00, 0x7E, 0xFE, 0x09, 0xD2, 0x19, 0x01];
+/// assert_eq!(
+///     stackfmt::fmt_ihex_data(&mut buf, 0x0100, &data),
+///     Ok(":10010000214601360121470136007EFE09D2190140")
+/// );
+/// ```
+pub fn fmt_ihex_data<'a>(
+    buffer: &'a mut [u8],
+    address: u16,
+    data: &[u8],
+) -> Result<&'a str, IhexError> {
+    fmt_record(buffer, 0x00, address, data)
+}
+
+/// Writes the end of file record `:00000001FF` into the buffer.
+pub fn fmt_ihex_eof(buffer: &mut [u8]) -> Result<&str, IhexError> {
+    fmt_record(buffer, 0x01, 0, &[])
+}
+
+/// Writes the extended linear address record with the upper 16 bits of the address for the
+/// following data records.
+pub fn fmt_ihex_linear_address(buffer: &mut [u8], upper: u16) -> Result<&str, IhexError> {
+    fmt_record(buffer, 0x04, 0, &upper.to_be_bytes())
+}
+
+/// Splits data into Intel HEX records and passes every one to a callback.
+///
+/// A record has at most the given number of bytes and never crosses a 64 KiB boundary;
+/// extended linear address records are inserted when the upper 16 bits of the address
+/// change. The records are passed without line ending.
+///
+/// ```
+/// let mut lines = 0;
+/// let mut hex = stackfmt::IhexWriter::new(16, |record: &str| {
+///     assert!(record.starts_with(':'));
+///     lines += 1;
+/// });
+/// hex.write(0x0800_0000, &[0xFF; 40]);
+/// hex.finish();
+/// assert_eq!(lines, 5); // address, 3 data and end of file
+/// ```
+pub struct IhexWriter<F: FnMut(&str)> {
+    emit: F,
+    record_len: usize,
+    upper: u16,
+}
+
+impl<F: FnMut(&str)> IhexWriter<F> {
+    /// Creates writer with up to `record_len` bytes in a record, clamped to
+    /// 1..=[IHEX_MAX_RECORD_LEN].
+    pub fn new(record_len: u8, emit: F) -> Self {
+        IhexWriter {
+            emit,
+            record_len: record_len.clamp(1, IHEX_MAX_RECORD_LEN) as usize,
+            upper: 0,
+        }
+    }
+
+    /// Passes records for `data` starting at `address`.
+    pub fn write(&mut self, mut address: u32, mut data: &[u8]) {
+        while !data.is_empty() {
+            let upper = (address >> 16) as u16;
+            if upper != self.upper {
+                self.record(0x04, 0, &upper.to_be_bytes());
+                self.upper = upper;
+            }
+            let to_boundary = 0x1_0000 - (address & 0xFFFF) as usize;
+            let len = data.len().min(self.record_len).min(to_boundary);
+            self.record(0x00, address as u16, &data[..len]);
+            address = address.wrapping_add(len as u32);
+            data = &data[len..];
+        }
+    }
+
+    /// Passes the end of file record.
+    pub fn finish(mut self) {
+        self.record(0x01, 0, &[]);
+    }
+
+    fn record(&mut self, rtype: u8, address: u16, data: &[u8]) {
+        let mut buf = [0u8; 11 + 2 * IHEX_MAX_RECORD_LEN as usize];
+        let mut w = WriteTo::new(&mut buf);
+        let _ = write_record(&mut w, rtype, address, data);
+        (self.emit)(w.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_ihex_data, fmt_ihex_eof, fmt_ihex_linear_address, IhexError, IhexWriter};
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn ihex_reference_records() {
+        let mut buf = [0u8; 64];
+        let data = [
+            0x21, 0x46, 0x01, 0x7E, 0x17, 0xC2, 0x00, 0x01, 0xFF, 0x5F, 0x16, 0x00, 0x21, 0x48,
+            0x01, 0x19,
+        ];
+        assert_eq!(
+            fmt_ihex_data(&mut buf, 0x0110, &data),
+            Ok(":100110002146017E17C20001FF5F16002148011928")
+        );
+        assert_eq!(
+            fmt_ihex_linear_address(&mut buf, 0x0800),
+            Ok(":020000040800F2")
+        );
+    }
+
+    #[test]
+    fn ihex_empty_and_eof() {
+        let mut buf = [0u8; 16];
+        assert_eq!(fmt_ihex_data(&mut buf, 0x0120, &[]), Ok(":00012000DF"));
+        assert_eq!(fmt_ihex_eof(&mut buf), Ok(":00000001FF"));
+    }
+
+    #[test]
+    fn ihex_errors() {
+        let mut buf = [0u8; 10];
+        assert_eq!(fmt_ihex_eof(&mut buf), Err(IhexError::BufferTooSmall));
+        let mut buf = [0u8; 600];
+        assert_eq!(
+            fmt_ihex_data(&mut buf, 0, &[0u8; 256]),
+            Err(IhexError::DataTooLong)
+        );
+        assert_eq!(
+            fmt_ihex_data(&mut buf, 0, &[0u8; 255]).map(str::len),
+            Ok(521)
+        );
+    }
+
+    #[test]
+    fn ihex_writer_splits() {
+        let mut out = [0u8; 256];
+        let mut log = WriteTo::new(&mut out);
+        let data: [u8; 20] = core::array::from_fn(|i| i as u8);
+        let mut hex = IhexWriter::new(16, |record: &str| {
+            let _ = writeln!(log, "{}", record);
+        });
+        hex.write(0x1000, &data);
+        hex.finish();
+        assert_eq!(
+            log.as_str(),
+            ":10100000000102030405060708090A0B0C0D0E0F68\n:041010001011121396\n:00000001FF\n"
+        );
+    }
+
+    #[test]
+    fn ihex_writer_crosses_64k() {
+        let mut out = [0u8; 256];
+        let mut log = WriteTo::new(&mut out);
+        let data: [u8; 12] = core::array::from_fn(|i| i as u8);
+        let mut hex = IhexWriter::new(32, |record: &str| {
+            let _ = writeln!(log, "{}", record);
+        });
+        hex.write(0xFFF8, &data);
+        hex.finish();
+        assert_eq!(
+            log.as_str(),
+            ":08FFF8000001020304050607E5\n:020000040001F9\n:0400000008090A0BD6\n:00000001FF\n"
+        );
+    }
+}
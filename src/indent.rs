@@ -0,0 +1,165 @@
+//  ( /   @ @    ()  Indents the lines of nested structure dumps
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Impl of [fmt::Write] that indents every non-empty line by the current depth.
+///
+/// The indent string (two spaces by default) is repeated `depth` times at the start of
+/// the output and after every `\n` followed by non-empty line. Changing depth in the middle
+/// of a line takes effect at the next line start.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 32];
+/// let mut w = stackfmt::IndentWriter::new(stackfmt::WriteTo::new(&mut buf));
+/// write!(w, "root {{\n").unwrap();
+/// w.indent();
+/// write!(w, "child\n").unwrap();
+/// w.dedent();
+/// write!(w, "}}").unwrap();
+/// assert_eq!(w.into_inner().as_str(), "root {\n  child\n}");
+/// ```
+pub struct IndentWriter<'s, W: fmt::Write> {
+    inner: W,
+    indent: &'s str,
+    depth: usize,
+    line_start: bool, // If the next byte starts a new line
+}
+
+impl<'s, W: fmt::Write> IndentWriter<'s, W> {
+    /// Creates stream indenting with two spaces.
+    pub fn new(inner: W) -> Self {
+        Self::with_indent(inner, "  ")
+    }
+
+    /// Creates stream indenting with the given string.
+    pub fn with_indent(inner: W, indent: &'s str) -> Self {
+        IndentWriter {
+            inner,
+            indent,
+            depth: 0,
+            line_start: true,
+        }
+    }
+
+    /// Increases the depth by one level.
+    pub fn indent(&mut self) {
+        self.depth += 1;
+    }
+
+    /// Decreases the depth by one level, stays at 0 if already there.
+    pub fn dedent(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Current depth.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<'s, W: fmt::Write> fmt::Write for IndentWriter<'s, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            if self.line_start && b != b'\n' {
+                self.inner.write_str(&s[run_start..i])?;
+                run_start = i;
+                for _ in 0..self.depth {
+                    self.inner.write_str(self.indent)?;
+                }
+            }
+            self.line_start = b == b'\n';
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndentWriter;
+    use crate::{LinePrefixWriter, WriteTo};
+    use core::fmt::Write;
+
+    #[test]
+    fn indent_nested_blocks() {
+        let mut buf = [0u8; 128];
+        let mut w = IndentWriter::new(WriteTo::new(&mut buf));
+        w.write_str("a {\n").unwrap();
+        w.indent();
+        w.write_str("b {\n").unwrap();
+        w.indent();
+        write!(w, "c = {}\nd = {}\n", 1, 2).unwrap();
+        w.dedent();
+        w.write_str("}\n").unwrap();
+        w.dedent();
+        w.write_str("}").unwrap();
+        assert_eq!(
+            w.into_inner().as_str(),
+            "a {\n  b {\n    c = 1\n    d = 2\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn indent_blank_lines() {
+        let mut buf = [0u8; 64];
+        let mut w = IndentWriter::new(WriteTo::new(&mut buf));
+        w.indent();
+        w.write_str("a\n\n\nb\n").unwrap();
+        assert_eq!(w.into_inner().as_str(), "  a\n\n\n  b\n");
+    }
+
+    #[test]
+    fn indent_mid_line_change() {
+        let mut buf = [0u8; 64];
+        let mut w = IndentWriter::new(WriteTo::new(&mut buf));
+        w.write_str("a").unwrap();
+        w.indent();
+        w.write_str("b\nc").unwrap();
+        assert_eq!(w.into_inner().as_str(), "ab\n  c");
+    }
+
+    #[test]
+    fn indent_dedent_saturates() {
+        let mut buf = [0u8; 64];
+        let mut w = IndentWriter::new(WriteTo::new(&mut buf));
+        w.dedent();
+        w.dedent();
+        assert_eq!(w.depth(), 0);
+        w.indent();
+        w.write_str("x").unwrap();
+        assert_eq!(w.into_inner().as_str(), "  x");
+    }
+
+    #[test]
+    fn indent_multi_byte_string() {
+        let mut buf = [0u8; 64];
+        let mut w = IndentWriter::with_indent(WriteTo::new(&mut buf), "\u{2502} ");
+        w.indent();
+        w.indent();
+        w.write_str("x\ny").unwrap();
+        assert_eq!(
+            w.into_inner().as_str(),
+            "\u{2502} \u{2502} x\n\u{2502} \u{2502} y"
+        );
+    }
+
+    #[test]
+    fn indent_with_prefix_writer() {
+        let mut buf = [0u8; 64];
+        let prefixed = LinePrefixWriter::new(WriteTo::new(&mut buf), |w| w.write_str("# "));
+        let mut w = IndentWriter::new(prefixed);
+        w.write_str("a\n").unwrap();
+        w.indent();
+        w.write_str("b").unwrap();
+        assert_eq!(w.into_inner().into_inner().as_str(), "# a\n#   b");
+    }
+}
@@ -0,0 +1,330 @@
+//  ( /   @ @    ()  InfluxDB line protocol points
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [LineProtocolWriter::finish_line].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineProtocolError {
+    /// Measurement, tag or field name is empty, or a tag value is empty
+    InvalidName,
+    /// Float field is NaN or infinite
+    InvalidValue,
+    /// Calls in wrong order: no measurement, no field, or tag after a field
+    Incomplete,
+    /// The line does not fit into the buffer
+    Overflow,
+}
+
+impl fmt::Display for LineProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LineProtocolError::InvalidName => "empty name or tag value",
+            LineProtocolError::InvalidValue => "float field is not finite",
+            LineProtocolError::Incomplete => "incomplete line protocol point",
+            LineProtocolError::Overflow => "line protocol point does not fit",
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Part {
+    Start,
+    Tags,
+    Fields,
+    Timestamp,
+}
+
+// Chars escaped with '\' in measurement names and in tag and field keys and tag values
+const MEASUREMENT_ESCAPES: &[u8] = b", ";
+const KEY_ESCAPES: &[u8] = b",= ";
+const STRING_ESCAPES: &[u8] = b"\"\\";
+
+/// Writes points of the InfluxDB line protocol into a [WriteTo].
+///
+/// A point is built with [measurement](LineProtocolWriter::measurement), any number of
+/// [tag](LineProtocolWriter::tag), at least one field and an optional
+/// [timestamp](LineProtocolWriter::timestamp). Names and tag values get commas, spaces and
+/// `=` escaped, string fields get `"` and `\` escaped. Errors are remembered and reported by
+/// [finish_line](LineProtocolWriter::finish_line), which also removes a failed or truncated
+/// point, so the buffer holds only complete points.
+///
+/// ```
+/// use stackfmt::{LineProtocolWriter, WriteTo};
+///
+/// let mut buf = [0u8; 128];
+/// let mut p = LineProtocolWriter::new(WriteTo::new(&mut buf));
+/// p.measurement("weather")
+///     .tag("station", "s1")
+///     .field_float("temp", 21.5, 1)
+///     .field_int("hum", 40)
+///     .timestamp(1700000000000000000);
+/// p.finish_line().unwrap();
+/// assert_eq!(
+///     p.into_inner().as_str(),
+///     "weather,station=s1 temp=21.5,hum=40i 1700000000000000000\n"
+/// );
+/// ```
+pub struct LineProtocolWriter<'a> {
+    out: WriteTo<'a>,
+    line_start: usize,
+    part: Part,
+    fields: usize,
+    error: Option<LineProtocolError>,
+}
+
+impl<'a> LineProtocolWriter<'a> {
+    /// Creates writer appending points to `out`.
+    pub fn new(out: WriteTo<'a>) -> Self {
+        LineProtocolWriter {
+            out,
+            line_start: 0,
+            part: Part::Start,
+            fields: 0,
+            error: None,
+        }
+    }
+
+    /// Starts the point with the measurement name.
+    pub fn measurement(&mut self, name: &str) -> &mut Self {
+        if self.part != Part::Start {
+            self.fail(LineProtocolError::Incomplete);
+        } else if name.is_empty() {
+            self.fail(LineProtocolError::InvalidName);
+        } else {
+            let _ = Escape::new(&mut self.out, MEASUREMENT_ESCAPES).write_str(name);
+        }
+        self.part = Part::Tags;
+        self
+    }
+
+    /// Adds tag `key=value` with both escaped.
+    pub fn tag<T: fmt::Display>(&mut self, key: &str, value: T) -> &mut Self {
+        if self.part != Part::Tags {
+            return self.fail(LineProtocolError::Incomplete);
+        }
+        if key.is_empty() {
+            return self.fail(LineProtocolError::InvalidName);
+        }
+        let _ = self.out.write_char(',');
+        let _ = Escape::new(&mut self.out, KEY_ESCAPES).write_str(key);
+        let _ = self.out.write_char('=');
+        let value_start = self.out.written_bytes();
+        let _ = write!(Escape::new(&mut self.out, KEY_ESCAPES), "{}", value);
+        if self.out.written_bytes() == value_start && !self.out.is_truncated() {
+            self.fail(LineProtocolError::InvalidName);
+        }
+        self
+    }
+
+    /// Adds float field with `decimals` digits after the point.
+    pub fn field_float(&mut self, key: &str, value: f64, decimals: usize) -> &mut Self {
+        if !value.is_finite() {
+            return self.fail(LineProtocolError::InvalidValue);
+        }
+        self.field(key, format_args!("{:.*}", decimals, value))
+    }
+
+    /// Adds integer field, written with the `i` suffix.
+    pub fn field_int(&mut self, key: &str, value: i64) -> &mut Self {
+        self.field(key, format_args!("{}i", value))
+    }
+
+    /// Adds string field, quoted with `"` and `\` escaped.
+    pub fn field_str<T: fmt::Display>(&mut self, key: &str, value: T) -> &mut Self {
+        if !self.field_key(key) {
+            return self;
+        }
+        let _ = self.out.write_char('"');
+        let _ = write!(Escape::new(&mut self.out, STRING_ESCAPES), "{}", value);
+        let _ = self.out.write_char('"');
+        self
+    }
+
+    /// Adds boolean field as `true` or `false`.
+    pub fn field_bool(&mut self, key: &str, value: bool) -> &mut Self {
+        self.field(key, format_args!("{}", value))
+    }
+
+    /// Sets time of the point, nanoseconds since the Unix epoch unless the database is set up
+    /// for another precision.
+    pub fn timestamp(&mut self, time: i64) -> &mut Self {
+        if self.part != Part::Fields {
+            return self.fail(LineProtocolError::Incomplete);
+        }
+        let _ = write!(self.out, " {}", time);
+        self.part = Part::Timestamp;
+        self
+    }
+
+    /// Ends the point with `\n`, or removes it if it has an error or did not fit.
+    pub fn finish_line(&mut self) -> Result<(), LineProtocolError> {
+        if self.error.is_none() && self.fields == 0 {
+            self.error = Some(LineProtocolError::Incomplete);
+        }
+        let _ = self.out.write_char('\n');
+        if self.error.is_none() && self.out.is_truncated() {
+            self.error = Some(LineProtocolError::Overflow);
+        }
+
+        let result = match self.error.take() {
+            Some(error) => {
+                self.out.truncate(self.line_start);
+                Err(error)
+            }
+            None => Ok(()),
+        };
+        self.line_start = self.out.written_bytes();
+        self.part = Part::Start;
+        self.fields = 0;
+        result
+    }
+
+    /// Returns the underlying stream with the points written so far.
+    pub fn into_inner(self) -> WriteTo<'a> {
+        self.out
+    }
+
+    fn field(&mut self, key: &str, value: fmt::Arguments) -> &mut Self {
+        if self.field_key(key) {
+            let _ = self.out.write_fmt(value);
+        }
+        self
+    }
+
+    // Writes separator and `key=`, false if the field cannot be added
+    fn field_key(&mut self, key: &str) -> bool {
+        let separator = match self.part {
+            Part::Tags => ' ',
+            Part::Fields => ',',
+            _ => {
+                self.fail(LineProtocolError::Incomplete);
+                return false;
+            }
+        };
+        if key.is_empty() {
+            self.fail(LineProtocolError::InvalidName);
+            return false;
+        }
+        let _ = self.out.write_char(separator);
+        let _ = Escape::new(&mut self.out, KEY_ESCAPES).write_str(key);
+        let _ = self.out.write_char('=');
+        self.part = Part::Fields;
+        self.fields += 1;
+        true
+    }
+
+    // Keeps the first error of the point
+    fn fail(&mut self, error: LineProtocolError) -> &mut Self {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        self
+    }
+}
+
+// Writes `\` before each of `chars`
+struct Escape<'w, 'a> {
+    out: &'w mut WriteTo<'a>,
+    chars: &'static [u8],
+}
+
+impl<'w, 'a> Escape<'w, 'a> {
+    fn new(out: &'w mut WriteTo<'a>, chars: &'static [u8]) -> Self {
+        Escape { out, chars }
+    }
+}
+
+impl<'w, 'a> fmt::Write for Escape<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, b) in s.bytes().enumerate() {
+            if !self.chars.contains(&b) {
+                continue;
+            }
+            self.out.write_str(&s[run_start..i])?;
+            let escaped = [b'\\', b];
+            // a char of `chars` is ASCII
+            self.out
+                .write_whole(unsafe { core::str::from_utf8_unchecked(&escaped) })?;
+            run_start = i + 1;
+        }
+        self.out.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineProtocolError, LineProtocolWriter};
+    use crate::WriteTo;
+
+    #[test]
+    fn influx_escaping() {
+        let mut buf = [0u8; 128];
+        let mut p = LineProtocolWriter::new(WriteTo::new(&mut buf));
+        p.measurement("cpu load,avg")
+            .tag("host name", "a=b,c")
+            .tag("id", 7)
+            .field_bool("on=off", true)
+            .field_float("v", -0.125, 2);
+        assert_eq!(p.finish_line(), Ok(()));
+        assert_eq!(
+            p.into_inner().as_str(),
+            "cpu\\ load\\,avg,host\\ name=a\\=b\\,c,id=7 on\\=off=true,v=-0.12\n"
+        );
+    }
+
+    #[test]
+    fn influx_string_field() {
+        let mut buf = [0u8; 128];
+        let mut p = LineProtocolWriter::new(WriteTo::new(&mut buf));
+        p.measurement("log")
+            .field_str("msg", r#"say "hi" C:\tmp, a=b"#)
+            .field_int("n", -3);
+        assert_eq!(p.finish_line(), Ok(()));
+        assert_eq!(
+            p.into_inner().as_str(),
+            "log msg=\"say \\\"hi\\\" C:\\\\tmp, a=b\",n=-3i\n"
+        );
+    }
+
+    #[test]
+    fn influx_multiple_points_and_errors() {
+        let mut buf = [0u8; 128];
+        let mut p = LineProtocolWriter::new(WriteTo::new(&mut buf));
+        p.measurement("m")
+            .tag("t", "a")
+            .field_int("x", 1)
+            .timestamp(10);
+        assert_eq!(p.finish_line(), Ok(()));
+        p.measurement("m").tag("t", "a");
+        assert_eq!(p.finish_line(), Err(LineProtocolError::Incomplete));
+        p.measurement("m").field_int("x", 1).tag("late", "a");
+        assert_eq!(p.finish_line(), Err(LineProtocolError::Incomplete));
+        p.measurement("m").tag("t", "").field_int("x", 1);
+        assert_eq!(p.finish_line(), Err(LineProtocolError::InvalidName));
+        p.measurement("").field_int("x", 1);
+        assert_eq!(p.finish_line(), Err(LineProtocolError::InvalidName));
+        p.measurement("m").field_float("x", f64::NAN, 1);
+        assert_eq!(p.finish_line(), Err(LineProtocolError::InvalidValue));
+        p.measurement("m").field_bool("ok", false).timestamp(11);
+        assert_eq!(p.finish_line(), Ok(()));
+        assert_eq!(p.into_inner().as_str(), "m,t=a x=1i 10\nm ok=false 11\n");
+    }
+
+    #[test]
+    fn influx_overflow_keeps_complete_points() {
+        let mut buf = [0u8; 24];
+        let mut p = LineProtocolWriter::new(WriteTo::new(&mut buf));
+        p.measurement("a").field_int("x", 1);
+        assert_eq!(p.finish_line(), Ok(()));
+        p.measurement("b").field_str("s", "long string value");
+        assert_eq!(p.finish_line(), Err(LineProtocolError::Overflow));
+        p.measurement("c").field_int("y", 2);
+        assert_eq!(p.finish_line(), Ok(()));
+        assert_eq!(p.into_inner().as_str(), "a x=1i\nc y=2i\n");
+    }
+}
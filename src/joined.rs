@@ -0,0 +1,169 @@
+//  ( /   @ @    ()  Items of an iterator joined with a separator
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Display adapter that shows items of an iterator separated by `sep`, like `1, 2, 3`.
+///
+/// The iterator is cloned for each formatting, so pass something cheap to clone: a slice
+/// iterator, a range or a `&[T]`. Formatter flags (width, precision...) apply to each item,
+/// not to the whole text. With [fmt_truncate](crate::fmt_truncate) the text is cut at the
+/// end of the buffer like any other formatted text, possibly in the middle of an item.
+///
+/// ```
+/// use stackfmt::Joined;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("[{}]", Joined::new(&[1, 2, 3], ", ")));
+/// assert_eq!(s, "[1, 2, 3]");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:03}", Joined::new(&[7, 42], ":")));
+/// assert_eq!(s, "007:042");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Joined<'s, I> {
+    iter: I,
+    sep: &'s str,
+}
+
+impl<'s, I: IntoIterator + Clone> Joined<'s, I> {
+    /// Creates adapter for items of `iter` separated by `sep`.
+    pub fn new(iter: I, sep: &'s str) -> Self {
+        Joined { iter, sep }
+    }
+
+    /// Renders each item with `fmt` instead of its Display impl.
+    ///
+    /// ```
+    /// let mut buf = [0u8; 32];
+    /// let pairs = [("a", 1), ("b", 2)];
+    /// let j = stackfmt::Joined::new(&pairs, " ").map_fmt(|(k, v), f| write!(f, "{}={}", k, v));
+    /// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", j)), "a=1 b=2");
+    /// ```
+    pub fn map_fmt<F>(self, fmt: F) -> JoinedWith<'s, I, F>
+    where
+        F: Fn(I::Item, &mut fmt::Formatter) -> fmt::Result,
+    {
+        JoinedWith {
+            iter: self.iter,
+            sep: self.sep,
+            fmt,
+        }
+    }
+}
+
+impl<'s, I> fmt::Display for Joined<'s, I>
+where
+    I: IntoIterator + Clone,
+    I::Item: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_joined(f, self.iter.clone(), self.sep, |item, f| item.fmt(f))
+    }
+}
+
+/// Display adapter returned by [Joined::map_fmt].
+#[derive(Clone, Copy)]
+pub struct JoinedWith<'s, I, F> {
+    iter: I,
+    sep: &'s str,
+    fmt: F,
+}
+
+impl<'s, I, F> fmt::Display for JoinedWith<'s, I, F>
+where
+    I: IntoIterator + Clone,
+    F: Fn(I::Item, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_joined(f, self.iter.clone(), self.sep, &self.fmt)
+    }
+}
+
+fn write_joined<I, F>(f: &mut fmt::Formatter, iter: I, sep: &str, fmt: F) -> fmt::Result
+where
+    I: IntoIterator,
+    F: Fn(I::Item, &mut fmt::Formatter) -> fmt::Result,
+{
+    for (i, item) in iter.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        fmt(item, f)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Joined;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn joined_empty_and_single() {
+        let mut buf = [0u8; 32];
+        let empty: [u8; 0] = [];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Joined::new(&empty, ", "))),
+            "[]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Joined::new(&[7], ", "))),
+            "[7]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Joined::new(0..0, ", "))),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn joined_multi_byte_separator() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Joined::new(["a", "b", "c"], " \u{2192} ")),
+        );
+        assert_eq!(s, "a \u{2192} b \u{2192} c");
+    }
+
+    #[test]
+    fn joined_item_flags() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(&mut buf, format_args!("{:>3}", Joined::new(1..4, "|")));
+        assert_eq!(s, "  1|  2|  3");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{:.1}", Joined::new(&[0.25, 1.5], " ")),
+        );
+        assert_eq!(s, "0.2 1.5");
+    }
+
+    #[test]
+    fn joined_map_fmt() {
+        let mut buf = [0u8; 32];
+        let j = Joined::new(1..=3, ",").map_fmt(|n, f| write!(f, "#{}", n * 10));
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{}", j)), "#10,#20,#30");
+        // can be formatted more than once
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{}", j)), "#10,#20,#30");
+    }
+
+    #[test]
+    fn joined_truncates_mid_item() {
+        let mut buf = [0u8; 16];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Joined::new(1000..2000, ", ")));
+        assert_eq!(s, "1000, 1001, 1002");
+        let mut buf = [0u8; 14];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Joined::new(["\u{20AC}\u{20AC}"; 100], ";")),
+        );
+        assert_eq!(s, "\u{20AC}\u{20AC};\u{20AC}\u{20AC};");
+        let mut buf = [0u8; 10];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Joined::new(["\u{20AC}\u{20AC}"; 100], ";")),
+        );
+        assert_eq!(s, "\u{20AC}\u{20AC};\u{20AC}");
+    }
+}
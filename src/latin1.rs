@@ -0,0 +1,216 @@
+//  ( /   @ @    ()  Formatted output converted to Latin-1 or Windows-1252 bytes
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+// Chars of Windows-1252 bytes 0x80..=0x9F, 0 for the unused ones
+const CP1252_EXTRAS: [u16; 32] = [
+    0x20AC, 0, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0, 0x017D, 0, 0, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014, 0x02DC,
+    0x2122, 0x0161, 0x203A, 0x0153, 0, 0x017E, 0x0178,
+];
+
+/// Impl of [fmt::Write] that stores formatted text as Latin-1 (ISO 8859-1) bytes into the
+/// provided buffer.
+///
+/// Chars up to U+00FF become their single byte, other chars are replaced with the
+/// substitute byte (`?` by default) or, in strict mode, fail the formatting. With
+/// [Latin1Writer::windows_1252] the bytes 0x80..=0x9F are used for the Windows-1252 chars
+/// like `€` and `“` instead of the C1 control chars. The output is not UTF-8, so it is
+/// returned as `&[u8]`.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buffer = [0u8; 16];
+/// let mut w = stackfmt::Latin1Writer::new(&mut buffer);
+/// write!(w, "{}\u{B0}C Gr\u{FC}\u{DF}e", 21).unwrap();
+/// assert_eq!(w.as_bytes(), b"21\xB0C Gr\xFC\xDFe");
+/// ```
+pub struct Latin1Writer<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+    overflow: bool,
+    substitute: u8,
+    strict: bool,
+    windows_1252: bool,
+    unmappable: Option<char>,
+}
+
+impl<'a> Latin1Writer<'a> {
+    /// Creates stream writing into `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Latin1Writer {
+            buffer,
+            used: 0,
+            overflow: false,
+            substitute: b'?',
+            strict: false,
+            windows_1252: false,
+            unmappable: None,
+        }
+    }
+
+    /// Sets the byte written for chars that have no Latin-1 byte.
+    pub fn substitute(mut self, substitute: u8) -> Self {
+        self.substitute = substitute;
+        self
+    }
+
+    /// If `on` a char that has no byte fails the formatting instead of being substituted.
+    pub fn strict(mut self, on: bool) -> Self {
+        self.strict = on;
+        self
+    }
+
+    /// If `on` the Windows-1252 chars are written as bytes 0x80..=0x9F.
+    pub fn windows_1252(mut self, on: bool) -> Self {
+        self.windows_1252 = on;
+        self
+    }
+
+    /// The first char that had no byte, in strict mode the one that failed the formatting
+    pub fn unmappable(&self) -> Option<char> {
+        self.unmappable
+    }
+
+    /// Bytes written so far
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.used]
+    }
+
+    /// How many bytes were written into the buffer
+    pub fn written_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// True if some data did not fit into the buffer and was dropped
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    /// Returns the written bytes and the flag if some data was dropped.
+    pub fn finish(self) -> (&'a [u8], bool) {
+        (&self.buffer[..self.used], self.overflow)
+    }
+
+    fn byte_of(&self, c: char) -> Option<u8> {
+        let code = c as u32;
+        if self.windows_1252 {
+            if let Some(i) = CP1252_EXTRAS
+                .iter()
+                .position(|&x| x as u32 == code && x != 0)
+            {
+                return Some(0x80 + i as u8);
+            }
+            if (0x80..=0x9F).contains(&code) {
+                return None;
+            }
+        }
+        u8::try_from(code).ok()
+    }
+}
+
+impl<'a> fmt::Write for Latin1Writer<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.overflow {
+                return Ok(()); // skip further inputs
+            }
+            let byte = match self.byte_of(c) {
+                Some(byte) => byte,
+                None => {
+                    self.unmappable.get_or_insert(c);
+                    if self.strict {
+                        return Err(fmt::Error);
+                    }
+                    self.substitute
+                }
+            };
+            if self.used == self.buffer.len() {
+                self.overflow = true;
+                return Ok(());
+            }
+            self.buffer[self.used] = byte;
+            self.used += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Latin1Writer;
+    use core::fmt::Write;
+
+    #[test]
+    fn latin1_ascii() {
+        let mut buf = [0u8; 8];
+        let mut w = Latin1Writer::new(&mut buf);
+        write!(w, "id={}", 42).unwrap();
+        assert_eq!(w.unmappable(), None);
+        assert_eq!(w.finish(), (&b"id=42"[..], false));
+
+        let mut buf = [0u8; 4];
+        let mut w = Latin1Writer::new(&mut buf);
+        w.write_str("abcdef").unwrap();
+        assert_eq!(w.finish(), (&b"abcd"[..], true));
+    }
+
+    #[test]
+    fn latin1_accents_and_degree() {
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf);
+        w.write_str("\u{E4}\u{F6} 5\u{B0} \u{FF}").unwrap();
+        assert_eq!(w.as_bytes(), b"\xE4\xF6 5\xB0 \xFF");
+        // the same in Windows-1252
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf).windows_1252(true);
+        w.write_str("\u{E4}\u{F6} 5\u{B0} \u{FF}").unwrap();
+        assert_eq!(w.as_bytes(), b"\xE4\xF6 5\xB0 \xFF");
+    }
+
+    #[test]
+    fn latin1_euro() {
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf);
+        w.write_str("5\u{20AC}").unwrap();
+        assert_eq!(w.as_bytes(), b"5?");
+        assert_eq!(w.unmappable(), Some('\u{20AC}'));
+
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf).windows_1252(true);
+        w.write_str("5\u{20AC} \u{201C}x\u{201D} a\u{2013}b \u{178}")
+            .unwrap();
+        assert_eq!(w.as_bytes(), b"5\x80 \x93x\x94 a\x96b \x9F");
+        // C1 controls have no Windows-1252 byte, in Latin-1 they do
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf).windows_1252(true);
+        w.write_str("\u{85}").unwrap();
+        assert_eq!(w.as_bytes(), b"?");
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf);
+        w.write_str("\u{85}").unwrap();
+        assert_eq!(w.as_bytes(), b"\x85");
+    }
+
+    #[test]
+    fn latin1_cjk_substituted() {
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf).substitute(b'*');
+        w.write_str("a\u{6F22}\u{5B57}b").unwrap();
+        assert_eq!(w.as_bytes(), b"a**b");
+        assert_eq!(w.unmappable(), Some('\u{6F22}'));
+    }
+
+    #[test]
+    fn latin1_strict() {
+        let mut buf = [0u8; 16];
+        let mut w = Latin1Writer::new(&mut buf).strict(true);
+        let name = "\u{6F22}";
+        assert!(write!(w, "ok \u{E9} {}", name).is_err());
+        assert_eq!(w.as_bytes(), b"ok \xE9 ");
+        assert_eq!(w.unmappable(), Some('\u{6F22}'));
+    }
+}
@@ -0,0 +1,180 @@
+//  ( /   @ @    ()  Signal and battery level glyphs
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use core::fmt;
+
+/// Block glyphs from empty to full, the default of [LevelBars].
+pub const LEVEL_BLOCKS: [&str; 5] = ["\u{2581}", "\u{2582}", "\u{2584}", "\u{2586}", "\u{2588}"];
+
+/// ASCII glyphs for displays without Unicode, mostly for [LevelBars::cells] like `###--`.
+pub const LEVEL_ASCII: [&str; 2] = ["-", "#"];
+
+/// Display adapter that shows a level like RSSI or battery charge as a glyph, like `▆`, or
+/// as a bar of cells, like `###--`.
+///
+/// The value is clamped to `min..=max` and scaled to the glyphs from empty to full, rounded
+/// to the nearest. With [LevelBars::cells] the bar has `n` cells: full cells show the last
+/// glyph, empty cells the first one and one cell between them may show a partial glyph.
+/// Width and alignment apply to the whole text.
+///
+/// ```
+/// use stackfmt::{LevelBars, LEVEL_ASCII};
+///
+/// let mut buf = [0u8; 32];
+/// let rssi = LevelBars::new(-55, -100, -40);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", rssi)), "\u{2586}");
+/// let battery = LevelBars::new(62, 0, 100).glyphs(&LEVEL_ASCII).cells(5);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("[{}]", battery)), "[###--]");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LevelBars<'a> {
+    value: i32,
+    min: i32,
+    max: i32,
+    glyphs: &'a [&'a str],
+    cells: Option<usize>,
+}
+
+impl<'a> LevelBars<'a> {
+    /// Creates adapter for `value` between `min` and `max`, panics if `min > max`.
+    pub fn new(value: i32, min: i32, max: i32) -> Self {
+        assert!(min <= max, "min must not be greater than max");
+        LevelBars {
+            value,
+            min,
+            max,
+            glyphs: &LEVEL_BLOCKS,
+            cells: None,
+        }
+    }
+
+    /// Uses `glyphs` from empty to full, panics if `glyphs` is empty.
+    pub fn glyphs(mut self, glyphs: &'a [&'a str]) -> Self {
+        assert!(!glyphs.is_empty(), "glyphs must not be empty");
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Shows a bar of `cells` glyphs instead of a single glyph.
+    pub fn cells(mut self, cells: usize) -> Self {
+        self.cells = Some(cells);
+        self
+    }
+
+    // Glyph of the `cell` of the bar with `filled` steps of glyph levels filled
+    fn glyph(&self, cell: usize, filled: u64) -> &'a str {
+        let top = self.glyphs.len() as u64 - 1;
+        let level = filled.saturating_sub(cell as u64 * top).min(top);
+        self.glyphs[level as usize]
+    }
+}
+
+impl<'a> fmt::Display for LevelBars<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells = self.cells.unwrap_or(1);
+        let offset = (self.value.clamp(self.min, self.max) as i64 - self.min as i64) as u64;
+        let span = (self.max as i64 - self.min as i64) as u64;
+        let steps = cells as u64 * (self.glyphs.len() as u64 - 1);
+        let filled = match span {
+            0 => steps,
+            // rounded to nearest
+            _ => ((offset as u128 * steps as u128 * 2 + span as u128) / (span as u128 * 2)) as u64,
+        };
+
+        let len = (0..cells)
+            .map(|cell| self.glyph(cell, filled).chars().count())
+            .sum();
+        pad_with(f, len, fmt::Alignment::Left, |f| {
+            for cell in 0..cells {
+                f.write_str(self.glyph(cell, filled))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LevelBars, LEVEL_ASCII};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn level_min_and_max() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", LevelBars::new(-100, -100, -40)),
+        );
+        assert_eq!(s, "\u{2581}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", LevelBars::new(-120, -100, -40)),
+        );
+        assert_eq!(s, "\u{2581}");
+        let s = fmt_truncate(&mut buf, format_args!("{}", LevelBars::new(-20, -100, -40)));
+        assert_eq!(s, "\u{2588}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", LevelBars::new(5, 5, 5).cells(3)),
+        );
+        assert_eq!(s, "\u{2588}\u{2588}\u{2588}");
+        let bars = LevelBars::new(i32::MAX, i32::MIN, i32::MAX).cells(2);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", bars)),
+            "\u{2588}\u{2588}"
+        );
+    }
+
+    #[test]
+    fn level_midpoint_rounding() {
+        let mut buf = [0u8; 32];
+        // 4 steps over 0..=100: 12 is closer to 0, 13 to 25
+        let s = fmt_truncate(&mut buf, format_args!("{}", LevelBars::new(12, 0, 100)));
+        assert_eq!(s, "\u{2581}");
+        let s = fmt_truncate(&mut buf, format_args!("{}", LevelBars::new(13, 0, 100)));
+        assert_eq!(s, "\u{2582}");
+        let s = fmt_truncate(&mut buf, format_args!("{}", LevelBars::new(50, 0, 100)));
+        assert_eq!(s, "\u{2584}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", LevelBars::new(50, 0, 100).cells(3)),
+        );
+        assert_eq!(s, "\u{2588}\u{2584}\u{2581}");
+    }
+
+    #[test]
+    fn level_ascii_bar() {
+        let mut buf = [0u8; 32];
+        let bar = |value| LevelBars::new(value, 0, 100).glyphs(&LEVEL_ASCII).cells(5);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", bar(0))),
+            "[-----]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", bar(9))),
+            "[-----]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", bar(10))),
+            "[#----]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", bar(100))),
+            "[#####]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>7}]", bar(60))),
+            "[  ###--]"
+        );
+        let single = LevelBars::new(70, 0, 100).glyphs(&LEVEL_ASCII);
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{}", single)), "#");
+    }
+
+    #[test]
+    #[should_panic]
+    fn level_inverted_range() {
+        let _ = LevelBars::new(0, 10, -10);
+    }
+}
@@ -13,6 +13,205 @@
 //! Implemented based on this SO answer 
 //! [https://stackoverflow.com/a/50201632/601298](https://stackoverflow.com/a/50201632/601298)
 #![no_std]
+mod ansi;
+mod ascii_escape;
+#[cfg(feature = "async")]
+mod async_fmt;
+mod at_command;
+mod base32;
+mod batch;
+mod bit_names;
+mod bits;
+mod budget;
+mod byte_size;
+mod c_array;
+mod can;
+mod case;
+mod cell;
+mod clip;
+mod collapse;
+mod contains;
+mod coord;
+mod crlf;
+mod datetime;
+mod debug_bytes;
+mod dedup;
+mod display_fn;
+mod dual;
+mod duration;
+mod error_chain;
+mod escpos;
+mod first_line;
+mod fixed_point;
+mod fixed_template;
+mod frame_diff;
+mod from_end;
+mod gcode;
+mod grouped;
+mod gsm7;
+mod here;
+mod hexdump;
+mod http_head;
+mod ihex;
+mod indent;
+mod influx;
+mod joined;
+mod latin1;
+mod level_bars;
+mod line_prefix;
+mod lines;
+mod list;
+mod localized;
+mod mac_addr;
+mod map_char;
+mod marquee;
+mod max_lines;
+mod max_size;
+mod metrics;
+mod or_default;
+mod ordinal;
+mod overflow_hook;
+mod pad;
+mod percent;
+mod plural;
+#[cfg(target_has_atomic = "ptr")]
+mod pool;
+mod printable;
+mod priority_line;
+mod progress;
+mod radix;
+#[cfg(feature = "embedded-io")]
+mod reader;
+mod redact;
+mod registers;
+mod repeated;
+mod runtime;
+#[cfg(feature = "semihosting")]
+mod semihosting;
+mod serial;
+mod si_value;
+mod single_line;
+mod sparkline;
+mod spelled;
+mod stack_string;
 mod stackfmt;
+#[cfg(any(target_has_atomic = "8", feature = "critical-section"))]
+mod static_buffer;
+mod stats;
+mod struct_writer;
+mod style;
+mod syslog;
+mod table;
+mod tabs;
+mod template;
+mod ticks;
+mod timestamp;
+mod truncated;
+mod url;
+mod utf8_lossy;
+mod uuid;
+mod vt100;
+mod word_wrap;
+mod wrap;
+mod write_bytes;
 
+pub use crate::ansi::*;
+pub use crate::ascii_escape::*;
+#[cfg(feature = "async")]
+pub use crate::async_fmt::*;
+pub use crate::at_command::*;
+pub use crate::base32::*;
+pub use crate::batch::*;
+pub use crate::bit_names::*;
+pub use crate::bits::*;
+pub use crate::budget::*;
+pub use crate::byte_size::*;
+pub use crate::c_array::*;
+pub use crate::can::*;
+pub use crate::case::*;
+pub use crate::cell::*;
+pub use crate::clip::*;
+pub use crate::collapse::*;
+pub use crate::contains::*;
+pub use crate::coord::*;
+pub use crate::crlf::*;
+pub use crate::datetime::*;
+pub use crate::debug_bytes::*;
+pub use crate::dedup::*;
+pub use crate::display_fn::*;
+pub use crate::dual::*;
+pub use crate::duration::*;
+pub use crate::error_chain::*;
+pub use crate::escpos::*;
+pub use crate::first_line::*;
+pub use crate::fixed_point::*;
+pub use crate::fixed_template::*;
+pub use crate::frame_diff::*;
+pub use crate::from_end::*;
+pub use crate::gcode::*;
+pub use crate::grouped::*;
+pub use crate::gsm7::*;
+pub use crate::here::*;
+pub use crate::hexdump::*;
+pub use crate::http_head::*;
+pub use crate::ihex::*;
+pub use crate::indent::*;
+pub use crate::influx::*;
+pub use crate::joined::*;
+pub use crate::latin1::*;
+pub use crate::level_bars::*;
+pub use crate::line_prefix::*;
+pub use crate::lines::*;
+pub use crate::list::*;
+pub use crate::localized::*;
+pub use crate::mac_addr::*;
+pub use crate::map_char::*;
+pub use crate::marquee::*;
+pub use crate::max_lines::*;
+pub use crate::max_size::*;
+pub use crate::metrics::*;
+pub use crate::or_default::*;
+pub use crate::ordinal::*;
+pub use crate::overflow_hook::*;
+pub use crate::percent::*;
+pub use crate::plural::*;
+#[cfg(target_has_atomic = "ptr")]
+pub use crate::pool::*;
+pub use crate::printable::*;
+pub use crate::priority_line::*;
+pub use crate::progress::*;
+pub use crate::radix::*;
+#[cfg(feature = "embedded-io")]
+pub use crate::reader::*;
+pub use crate::redact::*;
+pub use crate::registers::*;
+pub use crate::repeated::*;
+pub use crate::runtime::*;
+#[cfg(feature = "semihosting")]
+pub use crate::semihosting::*;
+pub use crate::serial::*;
+pub use crate::si_value::*;
+pub use crate::single_line::*;
+pub use crate::sparkline::*;
+pub use crate::spelled::*;
+pub use crate::stack_string::*;
 pub use crate::stackfmt::*;
+#[cfg(any(target_has_atomic = "8", feature = "critical-section"))]
+pub use crate::static_buffer::*;
+pub use crate::stats::*;
+pub use crate::struct_writer::*;
+pub use crate::style::*;
+pub use crate::syslog::*;
+pub use crate::table::*;
+pub use crate::tabs::*;
+pub use crate::template::*;
+pub use crate::ticks::*;
+pub use crate::timestamp::*;
+pub use crate::truncated::*;
+pub use crate::url::*;
+pub use crate::utf8_lossy::*;
+pub use crate::uuid::*;
+pub use crate::vt100::*;
+pub use crate::word_wrap::*;
+pub use crate::wrap::*;
+pub use crate::write_bytes::*;
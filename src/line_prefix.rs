@@ -0,0 +1,120 @@
+//  ( /   @ @    ()  Prefixes every line of formatted output
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Impl of [fmt::Write] that calls `prefix` at the start of every line before forwarding it.
+///
+/// The prefix is written at the start of the output and after every `\n` that is followed by
+/// more content, so output ending with `\n` gets no prefix for the trailing empty line and
+/// an empty output gets no prefix at all. The callback receives the inner stream and can use
+/// `write!` on it.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 32];
+/// let mut w = stackfmt::LinePrefixWriter::new(stackfmt::WriteTo::new(&mut buf), |w| {
+///     w.write_str("> ")
+/// });
+/// write!(w, "a\nb\n").unwrap();
+/// assert_eq!(w.into_inner().as_str(), "> a\n> b\n");
+/// ```
+pub struct LinePrefixWriter<W: fmt::Write, F: FnMut(&mut W) -> fmt::Result> {
+    inner: W,
+    prefix: F,
+    line_start: bool, // If the next content byte starts a new line
+}
+
+impl<W: fmt::Write, F: FnMut(&mut W) -> fmt::Result> LinePrefixWriter<W, F> {
+    /// Creates stream that writes the `prefix` into `inner` at every line start.
+    pub fn new(inner: W, prefix: F) -> Self {
+        LinePrefixWriter {
+            inner,
+            prefix,
+            line_start: true,
+        }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write, F: FnMut(&mut W) -> fmt::Result> fmt::Write for LinePrefixWriter<W, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            if self.line_start {
+                self.inner.write_str(&s[run_start..i])?;
+                run_start = i;
+                (self.prefix)(&mut self.inner)?;
+                self.line_start = false;
+            }
+            if b == b'\n' {
+                self.line_start = true;
+            }
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinePrefixWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn prefix_single_line() {
+        let mut buf = [0u8; 64];
+        let mut w = LinePrefixWriter::new(WriteTo::new(&mut buf), |w| w.write_str("# "));
+        write!(w, "hello {}", 42).unwrap();
+        assert_eq!(w.into_inner().as_str(), "# hello 42");
+    }
+
+    #[test]
+    fn prefix_multi_line() {
+        let mut buf = [0u8; 128];
+        let mut ms = 120;
+        let mut w = LinePrefixWriter::new(WriteTo::new(&mut buf), |w| {
+            ms += 3;
+            write!(w, "[boot {:05}ms] ", ms)
+        });
+        write!(w, "one\ntwo\n\nthree").unwrap();
+        assert_eq!(
+            w.into_inner().as_str(),
+            "[boot 00123ms] one\n[boot 00126ms] two\n[boot 00129ms] \n[boot 00132ms] three"
+        );
+    }
+
+    #[test]
+    fn prefix_trailing_newline() {
+        let mut buf = [0u8; 64];
+        let mut w = LinePrefixWriter::new(WriteTo::new(&mut buf), |w| w.write_str("> "));
+        w.write_str("a\n").unwrap();
+        assert_eq!(w.into_inner().as_str(), "> a\n");
+    }
+
+    #[test]
+    fn prefix_empty_write() {
+        let mut buf = [0u8; 64];
+        let mut w = LinePrefixWriter::new(WriteTo::new(&mut buf), |w| w.write_str("> "));
+        w.write_str("").unwrap();
+        assert_eq!(w.into_inner().as_str(), "");
+    }
+
+    #[test]
+    fn prefix_multi_byte() {
+        let mut buf = [0u8; 64];
+        let mut w = LinePrefixWriter::new(WriteTo::new(&mut buf), |w| w.write_str("\u{25B6} "));
+        w.write_str("\u{20AC}1\n").unwrap();
+        w.write_str("\u{20AC}2").unwrap();
+        assert_eq!(
+            w.into_inner().as_str(),
+            "\u{25B6} \u{20AC}1\n\u{25B6} \u{20AC}2"
+        );
+    }
+}
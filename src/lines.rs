@@ -0,0 +1,185 @@
+//  ( /   @ @    ()  Formatted output passed line by line through a small buffer
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Writer that collects output in the caller buffer and passes every line to a callback.
+///
+/// The callback gets each line without its `\n`. A line longer than the buffer is passed as
+/// several consecutive calls: every one but the last has as many whole chars as fit into
+/// the buffer, so the callback can treat them as continuation pieces. A single char that is
+/// longer than the whole buffer is passed on its own. The last line without `\n` is passed on
+/// [LinesWriter::finish].
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut scratch = [0u8; 16];
+/// let mut count = 0;
+/// let mut w = stackfmt::LinesWriter::new(&mut scratch, |line: &str| {
+///     assert!(line.starts_with("ch"));
+///     count += 1;
+/// });
+/// for ch in 0..3 {
+///     writeln!(w, "ch{}: ok", ch).unwrap();
+/// }
+/// w.finish();
+/// assert_eq!(count, 3);
+/// ```
+pub struct LinesWriter<'b, F: FnMut(&str)> {
+    buffer: &'b mut [u8],
+    emit: F,
+    len: usize, // bytes of the current line in the buffer
+}
+
+impl<'b, F: FnMut(&str)> LinesWriter<'b, F> {
+    /// Creates writer collecting lines in `buffer` and passing them to `emit`.
+    pub fn new(buffer: &'b mut [u8], emit: F) -> Self {
+        LinesWriter {
+            buffer,
+            emit,
+            len: 0,
+        }
+    }
+
+    /// Passes the last line to the callback if it is not empty.
+    pub fn finish(mut self) {
+        if self.len > 0 {
+            self.emit_line();
+        }
+    }
+
+    fn emit_line(&mut self) {
+        // the buffer holds whole chars of a str
+        (self.emit)(unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.len]) });
+        self.len = 0;
+    }
+
+    fn push(&mut self, mut text: &str) {
+        loop {
+            let room = self.buffer.len() - self.len;
+            if text.len() <= room {
+                self.buffer[self.len..self.len + text.len()].copy_from_slice(text.as_bytes());
+                self.len += text.len();
+                return;
+            }
+            let mut cut = room;
+            while !text.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            if cut == 0 && self.len == 0 {
+                // the char does not fit even into the empty buffer
+                cut = text.chars().next().map_or(0, char::len_utf8);
+                (self.emit)(&text[..cut]);
+            } else {
+                self.buffer[self.len..self.len + cut].copy_from_slice(&text.as_bytes()[..cut]);
+                self.len += cut;
+                self.emit_line();
+            }
+            text = &text[cut..];
+        }
+    }
+}
+
+impl<'b, F: FnMut(&str)> fmt::Write for LinesWriter<'b, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for piece in s.split_inclusive('\n') {
+            match piece.strip_suffix('\n') {
+                Some(line) => {
+                    self.push(line);
+                    self.emit_line();
+                }
+                None => self.push(piece),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats `args` passing every line to `emit`, `scratch` needs to hold only one line.
+///
+/// See [LinesWriter] for how the long lines are passed.
+///
+/// ```
+/// let mut scratch = [0u8; 8];
+/// let mut lines = 0;
+/// stackfmt::fmt_lines(&mut scratch, format_args!("a\nb\nc"), |_| lines += 1).unwrap();
+/// assert_eq!(lines, 3);
+/// ```
+pub fn fmt_lines<F: FnMut(&str)>(scratch: &mut [u8], args: fmt::Arguments, emit: F) -> fmt::Result {
+    let mut w = LinesWriter::new(scratch, emit);
+    fmt::write(&mut w, args)?;
+    w.finish();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fmt_lines;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    // Formats `args` with `N` bytes of scratch, puts every line into `out` in `<...>`
+    fn lines<'o, const N: usize>(out: &'o mut [u8], args: core::fmt::Arguments) -> &'o str {
+        let mut log = WriteTo::new(out);
+        let mut scratch = [0u8; N];
+        fmt_lines(&mut scratch, args, |line| {
+            let _ = write!(log, "<{}>", line);
+        })
+        .unwrap();
+        log.as_str()
+    }
+
+    #[test]
+    fn lines_short() {
+        let mut out = [0u8; 64];
+        let (a, b) = (1, "two");
+        assert_eq!(
+            lines::<8>(&mut out, format_args!("{}\n{}\nthree\n", a, b)),
+            "<1><two><three>"
+        );
+    }
+
+    #[test]
+    fn lines_longer_than_scratch() {
+        let mut out = [0u8; 64];
+        assert_eq!(
+            lines::<4>(&mut out, format_args!("abcdefghij\nxy\n")),
+            "<abcd><efgh><ij><xy>"
+        );
+        // split at char boundary
+        assert_eq!(
+            lines::<4>(&mut out, format_args!("ab\u{20AC}cd\n")),
+            "<ab><\u{20AC}c><d>"
+        );
+        // the exactly full line is not split
+        assert_eq!(lines::<4>(&mut out, format_args!("abcd\n")), "<abcd>");
+        // char longer than scratch
+        assert_eq!(
+            lines::<2>(&mut out, format_args!("a\u{10348}b")),
+            "<a><\u{10348}><b>"
+        );
+    }
+
+    #[test]
+    fn lines_unterminated_tail() {
+        let mut out = [0u8; 64];
+        let n = 42;
+        assert_eq!(
+            lines::<8>(&mut out, format_args!("one\nn={}", n)),
+            "<one><n=42>"
+        );
+        assert_eq!(lines::<8>(&mut out, format_args!("")), "");
+    }
+
+    #[test]
+    fn lines_empty() {
+        let mut out = [0u8; 64];
+        assert_eq!(
+            lines::<8>(&mut out, format_args!("a\n\n\nb\n")),
+            "<a><><><b>"
+        );
+        assert_eq!(lines::<8>(&mut out, format_args!("\n")), "<>");
+    }
+}
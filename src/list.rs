@@ -0,0 +1,176 @@
+//  ( /   @ @    ()  Items written one by one with separators
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+use core::fmt::Write;
+
+/// Writes items of a list into a [WriteTo] as they are found, putting the separator before
+/// every item except the first.
+///
+/// The optional prefix is written with the first item and the suffix by
+/// [ListWriter::finish], so nothing at all is written for an empty list. An item that does
+/// not fit is removed together with its separator (and the prefix if it was the first item)
+/// and the following items are dropped, so the list has only whole items; the suffix is
+/// written if it fits.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let mut out = stackfmt::WriteTo::new(&mut buf);
+/// let mut alarms = stackfmt::ListWriter::new(&mut out).prefix("alarms: ").separator("|");
+/// for (bit, name) in [(0, "OVP"), (2, "OTP")] {
+///     if 0b101 & (1 << bit) != 0 {
+///         alarms.item(format_args!("{}", name));
+///     }
+/// }
+/// assert!(alarms.finish());
+/// assert_eq!(out.as_str(), "alarms: OVP|OTP");
+/// ```
+pub struct ListWriter<'w, 'a> {
+    out: &'w mut WriteTo<'a>,
+    separator: &'w str,
+    prefix: &'w str,
+    suffix: &'w str,
+    items: usize,
+    truncated: bool,
+}
+
+impl<'w, 'a> ListWriter<'w, 'a> {
+    /// Creates list appended to `out` with `, ` as separator.
+    pub fn new(out: &'w mut WriteTo<'a>) -> Self {
+        ListWriter {
+            out,
+            separator: ", ",
+            prefix: "",
+            suffix: "",
+            items: 0,
+            truncated: false,
+        }
+    }
+
+    /// Sets the text between items.
+    pub fn separator(mut self, separator: &'w str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets the text written before the first item.
+    pub fn prefix(mut self, prefix: &'w str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Sets the text written after the items by [ListWriter::finish].
+    pub fn suffix(mut self, suffix: &'w str) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Writes the item with the separator or the prefix before it.
+    pub fn item(&mut self, args: fmt::Arguments) -> &mut Self {
+        if self.truncated {
+            return self;
+        }
+        let start = self.out.written_bytes();
+        let lead = if self.items == 0 {
+            self.prefix
+        } else {
+            self.separator
+        };
+        let _ = self.out.write_whole(lead);
+        let _ = self.out.write_fmt(args);
+        if self.out.is_truncated() {
+            self.out.truncate(start);
+            self.truncated = true;
+        } else {
+            self.items += 1;
+        }
+        self
+    }
+
+    /// True if no item was written.
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    /// How many items were written.
+    pub fn len(&self) -> usize {
+        self.items
+    }
+
+    /// True if an item did not fit, it and the following items were dropped.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Writes the suffix if there are items, returns true if all the items and the suffix
+    /// were written.
+    pub fn finish(self) -> bool {
+        if self.items > 0 {
+            let _ = self.out.write_whole(self.suffix);
+        }
+        !self.truncated && !self.out.is_truncated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ListWriter;
+    use crate::WriteTo;
+
+    #[test]
+    fn list_zero_items() {
+        let mut buf = [0u8; 16];
+        let mut out = WriteTo::new(&mut buf);
+        let list = ListWriter::new(&mut out).prefix("[").suffix("]");
+        assert!(list.is_empty());
+        assert!(list.finish());
+        assert_eq!(out.as_str(), "");
+    }
+
+    #[test]
+    fn list_one_item() {
+        let mut buf = [0u8; 16];
+        let mut out = WriteTo::new(&mut buf);
+        let mut list = ListWriter::new(&mut out).prefix("[").suffix("]");
+        list.item(format_args!("{}", 42));
+        assert_eq!((list.is_empty(), list.len()), (false, 1));
+        assert!(list.finish());
+        assert_eq!(out.as_str(), "[42]");
+    }
+
+    #[test]
+    fn list_several_items() {
+        let mut buf = [0u8; 32];
+        let mut out = WriteTo::new(&mut buf);
+        let mut list = ListWriter::new(&mut out);
+        list.item(format_args!("a={}", 1)).item(format_args!("b"));
+        list.item(format_args!("{:>3}", 7));
+        assert_eq!(list.len(), 3);
+        assert!(list.finish());
+        assert_eq!(out.as_str(), "a=1, b,   7");
+    }
+
+    #[test]
+    fn list_truncated_third_item() {
+        let mut buf = [0u8; 16];
+        let mut out = WriteTo::new(&mut buf);
+        let mut list = ListWriter::new(&mut out).prefix("[").suffix("]");
+        for name in ["alpha", "beta", "gamma", "d"] {
+            list.item(format_args!("{}", name));
+        }
+        assert!(list.is_truncated());
+        assert_eq!(list.len(), 2);
+        assert!(!list.finish());
+        assert_eq!(out.as_str(), "[alpha, beta]");
+
+        // the first item does not fit: nothing is left
+        let mut buf = [0u8; 4];
+        let mut out = WriteTo::new(&mut buf);
+        let mut list = ListWriter::new(&mut out).prefix("[").suffix("]");
+        list.item(format_args!("long"));
+        assert!(!list.finish());
+        assert_eq!(out.as_str(), "");
+    }
+}
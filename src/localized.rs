@@ -0,0 +1,183 @@
+//  ( /   @ @    ()  Numbers with locale specific separators
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::grouped::Grouped;
+use crate::pad::pad_with;
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows `value × 10^-decimals` with the digit group and decimal
+/// separators of a locale, like `1,234.56` or `1.234,56`.
+///
+/// The integer part is grouped by three digits as by [Grouped], the fraction is zero padded
+/// (`-5` with 2 decimals is `-0.05`). The separators are `,` and `.` by default and can be
+/// any chars, like the narrow no-break space `'\u{202F}'`. Width, fill, alignment and the
+/// `+` and `0` flags work as for integers, the width counts chars. Only integer math is
+/// used.
+///
+/// ```
+/// use stackfmt::LocalizedNumber;
+///
+/// let mut buf = [0u8; 24];
+/// let n = LocalizedNumber::new(123456, 2);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", n)), "1,234.56");
+/// let de = n.separators('.', ',');
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{} \u{20AC}", de)), "1.234,56 \u{20AC}");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LocalizedNumber {
+    value: i64,
+    decimals: u8,
+    group: char,
+    decimal: char,
+}
+
+impl LocalizedNumber {
+    /// Creates adapter for `value × 10^-decimals` with `,` and `.` separators.
+    pub fn new(value: i64, decimals: u8) -> Self {
+        LocalizedNumber {
+            value,
+            decimals,
+            group: ',',
+            decimal: '.',
+        }
+    }
+
+    /// Sets the char between groups of three digits and the char before the fraction.
+    pub fn separators(mut self, group: char, decimal: char) -> Self {
+        self.group = group;
+        self.decimal = decimal;
+        self
+    }
+}
+
+impl fmt::Display for LocalizedNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let magnitude = self.value.unsigned_abs() as u128;
+        let decimals = self.decimals as usize;
+        // i64 magnitude has at most 20 digits
+        let (int_part, frac) = if decimals > 20 {
+            (0, magnitude)
+        } else {
+            let pow = 10u128.pow(decimals as u32);
+            (magnitude / pow, magnitude % pow)
+        };
+
+        // 20 digits with 6 separators, the decimal separator and up to 255 decimals
+        let mut buf = [0u8; 20 + 6 * 4 + 4 + 255];
+        let mut w = WriteTo::new(&mut buf);
+        let _ = write!(
+            w,
+            "{}",
+            Grouped::new(int_part as i128).separator(self.group)
+        );
+        if decimals > 0 {
+            let _ = write!(w, "{}{:0w$}", self.decimal, frac, w = decimals);
+        }
+        let digits = w.as_str();
+        let sign = match (self.value < 0, f.sign_plus()) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+
+        let len = sign.len() + digits.chars().count();
+        if f.sign_aware_zero_pad() {
+            f.write_str(sign)?;
+            for _ in len..f.width().unwrap_or(0) {
+                f.write_char('0')?;
+            }
+            return f.write_str(digits);
+        }
+        pad_with(f, len, fmt::Alignment::Right, |f| {
+            f.write_str(sign)?;
+            f.write_str(digits)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalizedNumber;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn localized_classic() {
+        let mut buf = [0u8; 32];
+        let n = LocalizedNumber::new(123456789, 2);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", n)),
+            "1,234,567.89"
+        );
+        let de = n.separators('.', ',');
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", de)),
+            "1.234.567,89"
+        );
+        let n = LocalizedNumber::new(i64::MIN, 0);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", n)),
+            "-9,223,372,036,854,775,808"
+        );
+    }
+
+    #[test]
+    fn localized_space_group() {
+        let mut buf = [0u8; 32];
+        let n = LocalizedNumber::new(1234567, 3).separators('\u{202F}', ',');
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", n)),
+            "1\u{202F}234,567"
+        );
+        // width counts chars
+        let n = LocalizedNumber::new(12345, 0).separators(' ', ',');
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>8}]", n)),
+            "[  12 345]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:08}]", n)),
+            "[0012 345]"
+        );
+    }
+
+    #[test]
+    fn localized_zero_and_negative() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", LocalizedNumber::new(0, 0))),
+            "0"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", LocalizedNumber::new(0, 2))),
+            "0.00"
+        );
+        let n = LocalizedNumber::new(-5, 2).separators('.', ',');
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{}", n)), "-0,05");
+        let n = LocalizedNumber::new(-1234567, 0).separators('.', ',');
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{}", n)), "-1.234.567");
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:+}", LocalizedNumber::new(150, 1))),
+            "+15.0"
+        );
+    }
+
+    #[test]
+    fn localized_less_than_group() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", LocalizedNumber::new(999, 0))),
+            "999"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", LocalizedNumber::new(-12, 1))),
+            "-1.2"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", LocalizedNumber::new(7, 22))),
+            "0.0000000000000000000007"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//  ( /   @ @    ()  MAC address (EUI-48) and EUI-64 display
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter for a MAC address, `aa:bb:cc:dd:ee:ff` by default.
+///
+/// The alternate flag `{:#}` selects the dotted form `aabb.ccdd.eeff`. Use
+/// [MacAddr::separator] and [MacAddr::uppercase] for other styles.
+///
+/// ```
+/// let mac = [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6];
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("mac={}", stackfmt::MacAddr(&mac)));
+/// assert_eq!(s, "mac=00:1b:63:84:45:e6");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:#}", stackfmt::MacAddr(&mac)));
+/// assert_eq!(s, "001b.6384.45e6");
+/// ```
+#[derive(Clone, Copy)]
+pub struct MacAddr<'a>(pub &'a [u8; 6]);
+
+/// Display adapter for an EUI-64 identifier, `aa:bb:cc:dd:ee:ff:00:11` by default.
+///
+/// Same options as [MacAddr], the dotted form is `aabb.ccdd.eeff.0011`.
+#[derive(Clone, Copy)]
+pub struct Eui64<'a>(pub &'a [u8; 8]);
+
+/// Display adapter for a hardware address with the selected separator and case.
+///
+/// Created by the [MacAddr] and [Eui64] methods.
+#[derive(Clone, Copy)]
+pub struct HwAddr<'a> {
+    bytes: &'a [u8],
+    separator: char,
+    uppercase: bool,
+}
+
+impl<'a> MacAddr<'a> {
+    /// Separates bytes with `separator` (usually `:` or `-`).
+    pub fn separator(self, separator: char) -> HwAddr<'a> {
+        HwAddr::new(self.0).separator(separator)
+    }
+
+    /// Uses uppercase hex digits.
+    pub fn uppercase(self) -> HwAddr<'a> {
+        HwAddr::new(self.0).uppercase()
+    }
+}
+
+impl<'a> Eui64<'a> {
+    /// Separates bytes with `separator` (usually `:` or `-`).
+    pub fn separator(self, separator: char) -> HwAddr<'a> {
+        HwAddr::new(self.0).separator(separator)
+    }
+
+    /// Uses uppercase hex digits.
+    pub fn uppercase(self) -> HwAddr<'a> {
+        HwAddr::new(self.0).uppercase()
+    }
+}
+
+impl<'a> HwAddr<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        HwAddr {
+            bytes,
+            separator: ':',
+            uppercase: false,
+        }
+    }
+
+    /// Separates bytes with `separator` (usually `:` or `-`).
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Uses uppercase hex digits.
+    pub fn uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+
+    fn write_byte(&self, f: &mut fmt::Formatter, b: u8) -> fmt::Result {
+        if self.uppercase {
+            write!(f, "{:02X}", b)
+        } else {
+            write!(f, "{:02x}", b)
+        }
+    }
+}
+
+impl<'a> fmt::Display for HwAddr<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dotted = f.alternate();
+        for (n, &b) in self.bytes.iter().enumerate() {
+            if dotted && n > 0 && n % 2 == 0 {
+                f.write_char('.')?;
+            } else if !dotted && n > 0 {
+                f.write_char(self.separator)?;
+            }
+            self.write_byte(f, b)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for MacAddr<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&HwAddr::new(self.0), f)
+    }
+}
+
+impl<'a> fmt::Display for Eui64<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&HwAddr::new(self.0), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Eui64, MacAddr};
+
+    const MAC: [u8; 6] = [0xA4, 0xC1, 0x38, 0x0B, 0x5E, 0xF0];
+
+    #[test]
+    fn mac_separators() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", MacAddr(&MAC)));
+        assert_eq!(s, "a4:c1:38:0b:5e:f0");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", MacAddr(&MAC).separator('-')));
+        assert_eq!(s, "a4-c1-38-0b-5e-f0");
+    }
+
+    #[test]
+    fn mac_case() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", MacAddr(&MAC).uppercase()));
+        assert_eq!(s, "A4:C1:38:0B:5E:F0");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", MacAddr(&MAC).uppercase().separator('-')),
+        );
+        assert_eq!(s, "A4-C1-38-0B-5E-F0");
+    }
+
+    #[test]
+    fn mac_alternate_dotted() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:#}", MacAddr(&MAC)));
+        assert_eq!(s, "a4c1.380b.5ef0");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:#}", MacAddr(&MAC).uppercase()));
+        assert_eq!(s, "A4C1.380B.5EF0");
+    }
+
+    #[test]
+    fn mac_all_zero_all_ff() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", MacAddr(&[0; 6])));
+        assert_eq!(s, "00:00:00:00:00:00");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", MacAddr(&[0xFF; 6])));
+        assert_eq!(s, "ff:ff:ff:ff:ff:ff");
+    }
+
+    #[test]
+    fn eui64() {
+        let mut buf = [0u8; 64];
+        let eui = [0x00, 0x12, 0x4b, 0x00, 0x01, 0x02, 0x03, 0x04];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", Eui64(&eui)));
+        assert_eq!(s, "00:12:4b:00:01:02:03:04");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:#}", Eui64(&eui)));
+        assert_eq!(s, "0012.4b00.0102.0304");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", Eui64(&eui).separator('-').uppercase()),
+        );
+        assert_eq!(s, "00-12-4B-00-01-02-03-04");
+    }
+}
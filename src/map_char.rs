@@ -0,0 +1,147 @@
+//  ( /   @ @    ()  Per-char translation of formatted output for limited charsets
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteWhole;
+use core::fmt;
+
+/// What [MapCharWriter] outputs for a char.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MappedChar {
+    /// Forward the original char
+    Keep,
+    /// Output another char instead
+    Replace(char),
+    /// Output a string instead, the string is never cut by the truncation
+    ReplaceStr(&'static str),
+    /// Output nothing
+    Skip,
+}
+
+/// Mapper for [MapCharWriter] that keeps ASCII and replaces everything else with `?`.
+pub fn ascii_fallback(ch: char) -> MappedChar {
+    if ch.is_ascii() {
+        MappedChar::Keep
+    } else {
+        MappedChar::Replace('?')
+    }
+}
+
+/// Impl of [fmt::Write] that translates every char with the user mapping function.
+///
+/// `write_str` always receives whole chars, so a char is mapped the same way regardless of
+/// how the output is split into chunks. [MappedChar::ReplaceStr] replacements are written with
+/// [WriteWhole] so a longer replacement is either fully present or absent after truncation.
+///
+/// ```
+/// use core::fmt::Write;
+/// use stackfmt::MappedChar;
+///
+/// let mut buf = [0u8; 16];
+/// let mut w = stackfmt::MapCharWriter::new(stackfmt::WriteTo::new(&mut buf), |ch| match ch {
+///     '\u{B0}' => MappedChar::Replace('\u{DF}'), // the display shows degree at 0xDF
+///     '\u{E9}' => MappedChar::Replace('e'),
+///     _ => MappedChar::Keep,
+/// });
+/// write!(w, "caf\u{E9} 21\u{B0}C").unwrap();
+/// assert_eq!(w.into_inner().as_str(), "cafe 21\u{DF}C");
+/// ```
+pub struct MapCharWriter<W: WriteWhole, F: Fn(char) -> MappedChar> {
+    inner: W,
+    map: F,
+}
+
+impl<W: WriteWhole, F: Fn(char) -> MappedChar> MapCharWriter<W, F> {
+    /// Creates stream that maps chars with `map`.
+    pub fn new(inner: W, map: F) -> Self {
+        MapCharWriter { inner, map }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: WriteWhole, F: Fn(char) -> MappedChar> fmt::Write for MapCharWriter<W, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, ch) in s.char_indices() {
+            let mapped = (self.map)(ch);
+            if mapped == MappedChar::Keep {
+                continue;
+            }
+
+            self.inner.write_str(&s[run_start..i])?;
+            run_start = i + ch.len_utf8();
+            match mapped {
+                MappedChar::Replace(other) => self.inner.write_char(other)?,
+                MappedChar::ReplaceStr(other) => self.inner.write_whole(other)?,
+                MappedChar::Keep | MappedChar::Skip => {}
+            }
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+impl<W: WriteWhole, F: Fn(char) -> MappedChar> WriteWhole for MapCharWriter<W, F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{MapCharWriter, MappedChar};
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    fn lcd_map(ch: char) -> MappedChar {
+        match ch {
+            '\u{B0}' => MappedChar::Replace('\u{DF}'),
+            '\u{AD}' => MappedChar::Skip, // soft hyphen
+            '\u{2026}' => MappedChar::ReplaceStr("..."),
+            '\u{2264}' => MappedChar::ReplaceStr("<="),
+            _ => super::ascii_fallback(ch),
+        }
+    }
+
+    #[test]
+    fn map_degree_sign() {
+        let mut buf = [0u8; 64];
+        let mut w = MapCharWriter::new(WriteTo::new(&mut buf), lcd_map);
+        write!(w, "T={}\u{B0}C", 21).unwrap();
+        assert_eq!(w.into_inner().as_str(), "T=21\u{DF}C");
+    }
+
+    #[test]
+    fn map_skip_and_fallback() {
+        let mut buf = [0u8; 64];
+        let mut w = MapCharWriter::new(WriteTo::new(&mut buf), lcd_map);
+        write!(w, "con\u{AD}fig \u{4E2D}").unwrap();
+        assert_eq!(w.into_inner().as_str(), "config ?");
+    }
+
+    #[test]
+    fn map_one_to_many() {
+        let mut buf = [0u8; 64];
+        let mut w = MapCharWriter::new(WriteTo::new(&mut buf), lcd_map);
+        write!(w, "x\u{2264}5\u{2026}").unwrap();
+        assert_eq!(w.into_inner().as_str(), "x<=5...");
+    }
+
+    #[test]
+    fn map_replacement_truncation() {
+        let mut buf = [0u8; 4];
+        let mut w = MapCharWriter::new(WriteTo::new(&mut buf), lcd_map);
+        write!(w, "ab\u{2026}cd").unwrap();
+        assert_eq!(w.into_inner().as_str(), "ab");
+    }
+
+    #[test]
+    fn map_chunk_boundaries() {
+        let mut buf = [0u8; 64];
+        let mut w = MapCharWriter::new(WriteTo::new(&mut buf), lcd_map);
+        w.write_str("\u{B0}").unwrap();
+        w.write_str("a\u{B0}").unwrap();
+        w.write_str("\u{2026}").unwrap();
+        w.write_str("\u{10348}b").unwrap();
+        assert_eq!(w.into_inner().as_str(), "\u{DF}a\u{DF}...?b");
+    }
+}
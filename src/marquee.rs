@@ -0,0 +1,267 @@
+//  ( /   @ @    ()  Scrolling window over a text
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use core::fmt::Write;
+
+/// Returns up to `width_chars` chars of `text` starting from char `start_char`.
+///
+/// The window never splits a char and is clamped at the end of the text, a start beyond the
+/// end gives an empty str.
+///
+/// ```
+/// assert_eq!(stackfmt::char_window("h\u{E9}llo", 1, 3), "\u{E9}ll");
+/// assert_eq!(stackfmt::char_window("h\u{E9}llo", 3, 10), "lo");
+/// ```
+pub fn char_window(text: &str, start_char: usize, width_chars: usize) -> &str {
+    let byte_at = |n: usize| {
+        text.char_indices()
+            .nth(n)
+            .map_or(text.len(), |(offset, _)| offset)
+    };
+    let start = byte_at(start_char);
+    let rest = &text[start..];
+    let end = rest
+        .char_indices()
+        .nth(width_chars)
+        .map_or(rest.len(), |(offset, _)| offset);
+    &rest[..end]
+}
+
+/// Iterator over successive `width` chars windows of a text, for scrolling on a small
+/// display.
+///
+/// Each `next()` moves the window by one char. A text that fits into the width is not
+/// scrolled. Without [MarqueeIter::wrap] the iterator ends when the window reaches the end
+/// of the text; with it the text followed by the gap repeats endlessly.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// let mut marquee = stackfmt::MarqueeIter::new("Hello", 3).wrap(" ");
+/// let frames = [
+///     "Hel", "ell", "llo", "lo ", "o H", " He", "Hel",
+/// ];
+/// for frame in frames {
+///     let shown = stackfmt::fmt_truncate(&mut buf, format_args!("{}", marquee.next().unwrap()));
+///     assert_eq!(shown, frame);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MarqueeIter<'a> {
+    text: &'a str,
+    gap: Option<&'a str>,
+    width: usize,
+    position: usize,
+    chars: usize,
+}
+
+impl<'a> MarqueeIter<'a> {
+    /// Creates iterator over `width_chars` wide windows of `text`.
+    pub fn new(text: &'a str, width_chars: usize) -> Self {
+        MarqueeIter {
+            text,
+            gap: None,
+            width: width_chars,
+            position: 0,
+            chars: text.chars().count(),
+        }
+    }
+
+    /// Scrolls endlessly showing `gap` between the end and the start of the text.
+    pub fn wrap(mut self, gap: &'a str) -> Self {
+        self.gap = Some(gap);
+        self
+    }
+}
+
+impl<'a> Iterator for MarqueeIter<'a> {
+    type Item = MarqueeFrame<'a>;
+
+    fn next(&mut self) -> Option<MarqueeFrame<'a>> {
+        let start = self.position;
+        match self.gap {
+            _ if self.chars <= self.width => {
+                // static text, shown once or forever
+                if start > 0 && self.gap.is_none() {
+                    return None;
+                }
+                self.position = 1;
+                Some(MarqueeFrame::new(self.text, None, 0, self.width))
+            }
+            None => {
+                if start + self.width > self.chars {
+                    return None;
+                }
+                self.position += 1;
+                Some(MarqueeFrame::new(self.text, None, start, self.width))
+            }
+            Some(gap) => {
+                let cycle = self.chars + gap.chars().count();
+                self.position = (start + 1) % cycle;
+                Some(MarqueeFrame::new(self.text, Some(gap), start, self.width))
+            }
+        }
+    }
+}
+
+/// Display adapter for one window of [MarqueeIter].
+#[derive(Clone, Copy, Debug)]
+pub struct MarqueeFrame<'a> {
+    text: &'a str,
+    gap: Option<&'a str>, // None if the window does not wrap
+    start: usize,
+    width: usize,
+}
+
+impl<'a> MarqueeFrame<'a> {
+    fn new(text: &'a str, gap: Option<&'a str>, start: usize, width: usize) -> Self {
+        MarqueeFrame {
+            text,
+            gap,
+            start,
+            width,
+        }
+    }
+}
+
+impl<'a> fmt::Display for MarqueeFrame<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let head = char_window(self.text, self.start, self.width);
+        f.write_str(head)?;
+        let gap = match self.gap {
+            Some(gap) => gap,
+            None => return Ok(()),
+        };
+        // the window reached the end, continue with the gap and the text again
+        let mut left = self.width - head.chars().count();
+        let skip = self.start.saturating_sub(self.text.chars().count());
+        for c in gap.chars().skip(skip).chain(self.text.chars()) {
+            if left == 0 {
+                break;
+            }
+            f.write_char(c)?;
+            left -= 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{char_window, MarqueeIter};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn window_clamps() {
+        assert_eq!(char_window("abc", 0, 5), "abc");
+        assert_eq!(char_window("abc", 0, 3), "abc");
+        assert_eq!(char_window("abc", 2, 3), "c");
+        assert_eq!(char_window("abc", 3, 3), "");
+        assert_eq!(char_window("abc", 9, 3), "");
+        assert_eq!(char_window("abc", 1, 0), "");
+        assert_eq!(char_window("", 0, 4), "");
+    }
+
+    #[test]
+    fn window_multi_byte_edges() {
+        let s = "\u{20AC}a\u{1F600}b\u{E9}";
+        assert_eq!(char_window(s, 0, 1), "\u{20AC}");
+        assert_eq!(char_window(s, 1, 2), "a\u{1F600}");
+        assert_eq!(char_window(s, 2, 3), "\u{1F600}b\u{E9}");
+        assert_eq!(char_window(s, 4, 1), "\u{E9}");
+    }
+
+    #[test]
+    fn window_over_write_to() {
+        let mut buf = [0u8; 32];
+        let mut w = crate::WriteTo::new(&mut buf);
+        let _ = core::fmt::write(&mut w, format_args!("SSID \u{20AC}{}", 42));
+        assert_eq!(w.window(5, 2), "\u{20AC}4");
+        assert_eq!(w.to_str(), "SSID \u{20AC}42");
+    }
+
+    #[test]
+    fn marquee_shorter_than_window() {
+        let mut buf = [0u8; 16];
+        let mut m = MarqueeIter::new("ab", 4);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+            "ab"
+        );
+        assert!(m.next().is_none());
+        let mut m = MarqueeIter::new("ab", 4).wrap(" - ");
+        for _ in 0..3 {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+                "ab"
+            );
+        }
+    }
+
+    #[test]
+    fn marquee_exact_fit_and_scroll() {
+        let mut buf = [0u8; 16];
+        let mut m = MarqueeIter::new("abcd", 4);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+            "abcd"
+        );
+        assert!(m.next().is_none());
+        let mut m = MarqueeIter::new("abcde", 3);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+            "abc"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+            "bcd"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+            "cde"
+        );
+        assert!(m.next().is_none());
+    }
+
+    #[test]
+    fn marquee_wrap_around() {
+        let mut buf = [0u8; 16];
+        let mut m = MarqueeIter::new("abc", 2).wrap("--");
+        let frames = ["ab", "bc", "c-", "--", "-a", "ab", "bc"];
+        for frame in frames {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+                frame
+            );
+        }
+        // no gap
+        let mut m = MarqueeIter::new("abc", 2).wrap("");
+        let frames = ["ab", "bc", "ca", "ab"];
+        for frame in frames {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+                frame
+            );
+        }
+    }
+
+    #[test]
+    fn marquee_multi_byte() {
+        let mut buf = [0u8; 16];
+        let mut m = MarqueeIter::new("\u{20AC}\u{E9}\u{1F600}", 2).wrap("\u{B7}");
+        let frames = [
+            "\u{20AC}\u{E9}",
+            "\u{E9}\u{1F600}",
+            "\u{1F600}\u{B7}",
+            "\u{B7}\u{20AC}",
+            "\u{20AC}\u{E9}",
+        ];
+        for frame in frames {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", m.next().unwrap())),
+                frame
+            );
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//  ( /   @ @    ()  Output limited to a number of lines
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::plural::Plural;
+use core::fmt;
+
+/// Impl of [fmt::Write] that forwards text until the given number of line breaks and drops
+/// the rest.
+///
+/// Lines end with `\n`, so `\r\n` is one line ending, also when split between writes. The
+/// line break of the last kept line is forwarded and the lines can be of any length. The
+/// line breaks in the dropped text are counted and, if [MaxLinesWriter::summary] is on,
+/// [MaxLinesWriter::finish] adds `… (+K more lines)` line with the count.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 64];
+/// let mut w = stackfmt::MaxLinesWriter::new(stackfmt::WriteTo::new(&mut buf), 2).summary(true);
+/// for i in 0..5 {
+///     writeln!(w, "frame #{}", i).unwrap();
+/// }
+/// assert_eq!(w.finish().unwrap().as_str(), "frame #0\nframe #1\n\u{2026} (+3 more lines)\n");
+/// ```
+pub struct MaxLinesWriter<W: fmt::Write> {
+    inner: W,
+    remaining: usize,
+    dropped: usize,
+    summary: bool,
+}
+
+impl<W: fmt::Write> MaxLinesWriter<W> {
+    /// Creates stream forwarding up to `max_lines` lines into `inner`.
+    pub fn new(inner: W, max_lines: usize) -> Self {
+        MaxLinesWriter {
+            inner,
+            remaining: max_lines,
+            dropped: 0,
+            summary: false,
+        }
+    }
+
+    /// If `on` [MaxLinesWriter::finish] writes the count of dropped lines.
+    pub fn summary(mut self, on: bool) -> Self {
+        self.summary = on;
+        self
+    }
+
+    /// How many line breaks were dropped so far.
+    pub fn dropped_lines(&self) -> usize {
+        self.dropped
+    }
+
+    /// Writes the summary if enabled and some lines were dropped, returns the inner stream.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        if self.summary && self.dropped > 0 {
+            let lines = Plural::new(self.dropped as u64, "more line", "more lines");
+            writeln!(self.inner, "\u{2026} (+{})", lines)?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for MaxLinesWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.remaining == 0 {
+            self.dropped += s.bytes().filter(|&b| b == b'\n').count();
+            return Ok(());
+        }
+        match s.match_indices('\n').nth(self.remaining - 1) {
+            Some((end, _)) => {
+                self.remaining = 0;
+                self.dropped += s[end + 1..].bytes().filter(|&b| b == b'\n').count();
+                self.inner.write_str(&s[..end + 1])
+            }
+            None => {
+                self.remaining -= s.bytes().filter(|&b| b == b'\n').count();
+                self.inner.write_str(s)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MaxLinesWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    // Writes each chunk separately
+    fn max_lines<'a>(buf: &'a mut [u8], n: usize, chunks: &[&str]) -> (&'a str, usize) {
+        let mut w = MaxLinesWriter::new(WriteTo::new(buf), n).summary(true);
+        for chunk in chunks {
+            w.write_str(chunk).unwrap();
+        }
+        let dropped = w.dropped_lines();
+        (w.finish().unwrap().as_str(), dropped)
+    }
+
+    #[test]
+    fn max_lines_exact() {
+        let mut buf = [0u8; 64];
+        assert_eq!(max_lines(&mut buf, 3, &["a\nb\nc\n"]), ("a\nb\nc\n", 0));
+        assert_eq!(
+            max_lines(&mut buf, 3, &["a", "\nb\n", "c", "\n"]),
+            ("a\nb\nc\n", 0)
+        );
+        // text after the last line break is not a line
+        assert_eq!(max_lines(&mut buf, 3, &["a\nb\nc\nd"]), ("a\nb\nc\n", 0));
+        assert_eq!(
+            max_lines(&mut buf, 0, &["a\n"]),
+            ("\u{2026} (+1 more line)\n", 1)
+        );
+    }
+
+    #[test]
+    fn max_lines_summary() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            max_lines(&mut buf, 2, &["1\n2\n3\n", "4\n", "5\n"]),
+            ("1\n2\n\u{2026} (+3 more lines)\n", 3)
+        );
+        let mut w = MaxLinesWriter::new(WriteTo::new(&mut buf), 2);
+        w.write_str("1\n2\n3\n4\n5\n").unwrap();
+        assert_eq!(w.dropped_lines(), 3);
+        assert_eq!(w.finish().unwrap().as_str(), "1\n2\n");
+    }
+
+    #[test]
+    fn max_lines_long_line() {
+        let mut buf = [0u8; 256];
+        let line = "0123456789abcdef0123456789abcdef";
+        let (s, dropped) = max_lines(&mut buf, 2, &[line, line, line, line, line]);
+        assert_eq!(s.len(), 5 * line.len());
+        assert_eq!(dropped, 0);
+    }
+
+    #[test]
+    fn max_lines_crlf() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            max_lines(&mut buf, 2, &["a\r\nb\r", "\nc\r\n"]),
+            ("a\r\nb\r\n\u{2026} (+1 more line)\n", 1)
+        );
+    }
+}
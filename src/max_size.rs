@@ -0,0 +1,188 @@
+//  ( /   @ @    ()  Buffer sizes computed at compile time
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+
+// Count of decimal digits of `value`
+const fn decimal_digits(mut value: u128) -> usize {
+    let mut digits = 1;
+    while value >= 10 {
+        value /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// Max bytes of a `u8` shown with `{}`.
+pub const U8_MAX_WIDTH: usize = decimal_digits(u8::MAX as u128);
+/// Max bytes of a `i8` shown with `{}`, including the minus sign.
+pub const I8_MAX_WIDTH: usize = decimal_digits(i8::MIN.unsigned_abs() as u128) + 1;
+/// Max bytes of a `u16` shown with `{}`.
+pub const U16_MAX_WIDTH: usize = decimal_digits(u16::MAX as u128);
+/// Max bytes of a `i16` shown with `{}`, including the minus sign.
+pub const I16_MAX_WIDTH: usize = decimal_digits(i16::MIN.unsigned_abs() as u128) + 1;
+/// Max bytes of a `u32` shown with `{}`.
+pub const U32_MAX_WIDTH: usize = decimal_digits(u32::MAX as u128);
+/// Max bytes of a `i32` shown with `{}`, including the minus sign.
+pub const I32_MAX_WIDTH: usize = decimal_digits(i32::MIN.unsigned_abs() as u128) + 1;
+/// Max bytes of a `u64` shown with `{}`.
+pub const U64_MAX_WIDTH: usize = decimal_digits(u64::MAX as u128);
+/// Max bytes of a `i64` shown with `{}`, including the minus sign.
+pub const I64_MAX_WIDTH: usize = decimal_digits(i64::MIN.unsigned_abs() as u128) + 1;
+/// Max bytes of a `u128` shown with `{}`.
+pub const U128_MAX_WIDTH: usize = decimal_digits(u128::MAX);
+/// Max bytes of a `i128` shown with `{}`, including the minus sign.
+pub const I128_MAX_WIDTH: usize = decimal_digits(i128::MIN.unsigned_abs()) + 1;
+/// Max bytes of a `usize` shown with `{}`.
+pub const USIZE_MAX_WIDTH: usize = decimal_digits(usize::MAX as u128);
+/// Max bytes of a `isize` shown with `{}`, including the minus sign.
+pub const ISIZE_MAX_WIDTH: usize = decimal_digits(isize::MIN.unsigned_abs() as u128) + 1;
+
+// Returns (bytes of the text, count of placeholders) of a format string
+const fn parse_format(fmt: &str) -> (usize, usize) {
+    let bytes = fmt.as_bytes();
+    let (mut text, mut placeholders, mut i) = (0, 0, 0);
+    while i < bytes.len() {
+        let escaped = i + 1 < bytes.len() && bytes[i + 1] == bytes[i];
+        if (bytes[i] == b'{' || bytes[i] == b'}') && escaped {
+            text += 1;
+            i += 2;
+        } else if bytes[i] == b'{' {
+            while i < bytes.len() && bytes[i] != b'}' {
+                i += 1;
+            }
+            placeholders += 1;
+            i += 1;
+        } else {
+            text += 1;
+            i += 1;
+        }
+    }
+    (text, placeholders)
+}
+
+/// Returns bytes of the literal text of a format string, `{{` and `}}` count as one byte
+/// and placeholders as none.
+///
+/// ```
+/// assert_eq!(stackfmt::fmt_text_len("t={}C {{raw}}"), 9);
+/// ```
+pub const fn fmt_text_len(fmt: &str) -> usize {
+    parse_format(fmt).0
+}
+
+/// Returns count of the placeholders of a format string, a placeholder used twice like
+/// `{0} {0}` is counted twice.
+///
+/// ```
+/// assert_eq!(stackfmt::fmt_placeholders("{0}-{0} {name:>4} {{}}"), 3);
+/// ```
+pub const fn fmt_placeholders(fmt: &str) -> usize {
+    parse_format(fmt).1
+}
+
+/// Computes at compile time the max bytes a format string can produce given the max width
+/// of each placeholder, to size a buffer that is never truncated.
+///
+/// One width is given for each placeholder in order, the count is checked at compile time.
+/// The widths must include padding and precision set in the placeholders, as the format
+/// specs are not parsed. The `*_MAX_WIDTH` constants have the widths of integers shown with
+/// `{}`.
+///
+/// ```
+/// use stackfmt::{fmt_max_size, I16_MAX_WIDTH, U8_MAX_WIDTH};
+///
+/// let mut buf = [0u8; fmt_max_size!("t={}C v={}mV", U8_MAX_WIDTH, I16_MAX_WIDTH)];
+/// assert_eq!(buf.len(), 17);
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("t={}C v={}mV", 255u8, i16::MIN));
+/// assert_eq!(s, "t=255C v=-32768mV");
+/// ```
+///
+/// A missing width is a compile error:
+///
+/// ```compile_fail
+/// let buf = [0u8; stackfmt::fmt_max_size!("{}:{}", stackfmt::U8_MAX_WIDTH)];
+/// ```
+#[macro_export]
+macro_rules! fmt_max_size {
+    ($fmt:literal $(, $width:expr)* $(,)?) => {{
+        const WIDTHS: &[usize] = &[$($width),*];
+        const _: () = assert!(
+            $crate::fmt_placeholders($fmt) == WIDTHS.len(),
+            "fmt_max_size! needs one width for each placeholder"
+        );
+        $crate::fmt_text_len($fmt) $(+ $width)*
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn max_size_integer_widths() {
+        assert_eq!((U8_MAX_WIDTH, I8_MAX_WIDTH), (3, 4));
+        assert_eq!((U16_MAX_WIDTH, I16_MAX_WIDTH), (5, 6));
+        assert_eq!((U32_MAX_WIDTH, I32_MAX_WIDTH), (10, 11));
+        assert_eq!((U64_MAX_WIDTH, I64_MAX_WIDTH), (20, 20));
+        assert_eq!((U128_MAX_WIDTH, I128_MAX_WIDTH), (39, 40));
+        assert_eq!((decimal_digits(0), decimal_digits(10)), (1, 2));
+    }
+
+    #[test]
+    fn max_size_escaped_braces() {
+        assert_eq!(fmt_max_size!("{{}}"), 2);
+        assert_eq!(fmt_max_size!("{{{}}}", U8_MAX_WIDTH), 5);
+        assert_eq!(fmt_max_size!("a}}{:>8}", 8), 10);
+        assert_eq!(fmt_max_size!(""), 0);
+        let mut buf = [0u8; fmt_max_size!("{{{}}}", U8_MAX_WIDTH)];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{{{}}}", 200u8));
+        assert_eq!(s, "{200}");
+    }
+
+    #[test]
+    fn max_size_small_types_exhaustive() {
+        const SIZE: usize = fmt_max_size!("[{}|{}]", U8_MAX_WIDTH, I8_MAX_WIDTH);
+        let mut longest = 0;
+        for a in 0..=u8::MAX {
+            for b in i8::MIN..=i8::MAX {
+                let mut buf = [0u8; SIZE];
+                let mut w = WriteTo::new(&mut buf);
+                write!(w, "[{}|{}]", a, b).unwrap();
+                assert!(!w.is_truncated());
+                longest = longest.max(w.written_bytes());
+            }
+        }
+        assert_eq!(longest, SIZE);
+    }
+
+    #[test]
+    fn max_size_boundary_values() {
+        const SIZE: usize = fmt_max_size!(
+            "{} {} {} {}",
+            I16_MAX_WIDTH,
+            U32_MAX_WIDTH,
+            I64_MAX_WIDTH,
+            U64_MAX_WIDTH
+        );
+        let values = [
+            (i16::MIN, u32::MAX, i64::MIN, u64::MAX),
+            (i16::MAX, 0, i64::MAX, 0),
+            (-1, 1_000_000_000, -1, 10_000_000_000_000_000_000),
+        ];
+        for (a, b, c, d) in values {
+            let mut buf = [0u8; SIZE];
+            let mut w = WriteTo::new(&mut buf);
+            write!(w, "{} {} {} {}", a, b, c, d).unwrap();
+            assert!(!w.is_truncated());
+        }
+        let mut buf = [0u8; SIZE];
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{} {} {} {}", i16::MIN, u32::MAX, i64::MIN, u64::MAX),
+        );
+        assert_eq!(s.len(), SIZE);
+    }
+}
@@ -0,0 +1,280 @@
+//  ( /   @ @    ()  Prometheus text exposition format lines
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::fixed_point::FixedPoint;
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [MetricLineWriter::finish_line].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MetricError {
+    /// Metric name is empty or has chars other than `[a-zA-Z0-9_:]` or starts with a digit
+    InvalidName,
+    /// Label name is empty or has chars other than `[a-zA-Z0-9_]` or starts with a digit
+    InvalidLabel,
+    /// Calls in wrong order: no metric name, no value, or label after the value
+    Incomplete,
+    /// The line does not fit into the buffer
+    Overflow,
+}
+
+impl fmt::Display for MetricError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            MetricError::InvalidName => "invalid metric name",
+            MetricError::InvalidLabel => "invalid label name",
+            MetricError::Incomplete => "incomplete metric line",
+            MetricError::Overflow => "metric line does not fit",
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Part {
+    Start,
+    Name,
+    Labels,
+    Value,
+    Timestamp,
+}
+
+/// Writes lines of the Prometheus text exposition format into a [WriteTo].
+///
+/// A line is built with [metric](MetricLineWriter::metric), any number of
+/// [label](MetricLineWriter::label), one of the value methods and an optional
+/// [timestamp](MetricLineWriter::timestamp) in milliseconds. Errors are remembered and
+/// reported by [finish_line](MetricLineWriter::finish_line), which also removes a failed or
+/// truncated line, so the buffer holds only complete lines. Label values get `\`, `"` and
+/// newlines escaped.
+///
+/// ```
+/// use stackfmt::{MetricLineWriter, WriteTo};
+///
+/// let mut buf = [0u8; 128];
+/// let mut m = MetricLineWriter::new(WriteTo::new(&mut buf));
+/// m.metric("temp_celsius").label("sensor", "a0").value_fixed(235, 1).timestamp(1700000000000);
+/// m.finish_line().unwrap();
+/// m.metric("uptime_seconds").value_int(3600);
+/// m.finish_line().unwrap();
+/// assert_eq!(
+///     m.into_inner().as_str(),
+///     "temp_celsius{sensor=\"a0\"} 23.5 1700000000000\nuptime_seconds 3600\n"
+/// );
+/// ```
+pub struct MetricLineWriter<'a> {
+    out: WriteTo<'a>,
+    line_start: usize,
+    part: Part,
+    error: Option<MetricError>,
+}
+
+impl<'a> MetricLineWriter<'a> {
+    /// Creates writer appending lines to `out`.
+    pub fn new(out: WriteTo<'a>) -> Self {
+        MetricLineWriter {
+            out,
+            line_start: 0,
+            part: Part::Start,
+            error: None,
+        }
+    }
+
+    /// Starts the line with the metric name.
+    pub fn metric(&mut self, name: &str) -> &mut Self {
+        if self.part != Part::Start {
+            self.fail(MetricError::Incomplete);
+        } else if !is_valid_name(name, true) {
+            self.fail(MetricError::InvalidName);
+        } else {
+            let _ = self.out.write_str(name);
+        }
+        self.part = Part::Name;
+        self
+    }
+
+    /// Adds label `key="value"` with the value escaped.
+    pub fn label<T: fmt::Display>(&mut self, key: &str, value: T) -> &mut Self {
+        let opening = match self.part {
+            Part::Name => '{',
+            Part::Labels => ',',
+            _ => return self.fail(MetricError::Incomplete),
+        };
+        if !is_valid_name(key, false) {
+            return self.fail(MetricError::InvalidLabel);
+        }
+        let _ = write!(self.out, "{}{}=\"", opening, key);
+        let _ = write!(LabelEscape(&mut self.out), "{}", value);
+        let _ = self.out.write_char('"');
+        self.part = Part::Labels;
+        self
+    }
+
+    /// Sets integer value.
+    pub fn value_int(&mut self, value: i64) -> &mut Self {
+        self.value(format_args!("{}", value))
+    }
+
+    /// Sets value `value × 10^-decimals`, like 235 with 1 decimal for 23.5.
+    pub fn value_fixed(&mut self, value: i64, decimals: u8) -> &mut Self {
+        self.value(format_args!("{}", FixedPoint::new(value, decimals)))
+    }
+
+    /// Sets time of the sample in milliseconds since the Unix epoch.
+    pub fn timestamp(&mut self, millis: i64) -> &mut Self {
+        if self.part != Part::Value {
+            return self.fail(MetricError::Incomplete);
+        }
+        let _ = write!(self.out, " {}", millis);
+        self.part = Part::Timestamp;
+        self
+    }
+
+    /// Ends the line with `\n`, or removes it if it has an error or did not fit.
+    pub fn finish_line(&mut self) -> Result<(), MetricError> {
+        if self.error.is_none() && !matches!(self.part, Part::Value | Part::Timestamp) {
+            self.error = Some(MetricError::Incomplete);
+        }
+        let _ = self.out.write_char('\n');
+        if self.error.is_none() && self.out.is_truncated() {
+            self.error = Some(MetricError::Overflow);
+        }
+
+        let result = match self.error.take() {
+            Some(error) => {
+                self.out.truncate(self.line_start);
+                Err(error)
+            }
+            None => Ok(()),
+        };
+        self.line_start = self.out.written_bytes();
+        self.part = Part::Start;
+        result
+    }
+
+    /// Returns the underlying stream with the lines written so far.
+    pub fn into_inner(self) -> WriteTo<'a> {
+        self.out
+    }
+
+    fn value(&mut self, value: fmt::Arguments) -> &mut Self {
+        match self.part {
+            Part::Name => {}
+            Part::Labels => {
+                let _ = self.out.write_char('}');
+            }
+            _ => return self.fail(MetricError::Incomplete),
+        }
+        let _ = write!(self.out, " {}", value);
+        self.part = Part::Value;
+        self
+    }
+
+    // Keeps the first error of the line
+    fn fail(&mut self, error: MetricError) -> &mut Self {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        self
+    }
+}
+
+// Metric names may also have ':'
+fn is_valid_name(name: &str, metric: bool) -> bool {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || (metric && c == ':');
+    match name.chars().next() {
+        Some(first) => !first.is_ascii_digit() && name.chars().all(valid),
+        None => false,
+    }
+}
+
+// Escapes '\', '"' and '\n' in label values
+struct LabelEscape<'w, 'a>(&'w mut WriteTo<'a>);
+
+impl<'w, 'a> fmt::Write for LabelEscape<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, b) in s.bytes().enumerate() {
+            let escape = match b {
+                b'\\' => "\\\\",
+                b'"' => "\\\"",
+                b'\n' => "\\n",
+                _ => continue,
+            };
+            self.0.write_str(&s[run_start..i])?;
+            self.0.write_whole(escape)?;
+            run_start = i + 1;
+        }
+        self.0.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MetricError, MetricLineWriter};
+    use crate::WriteTo;
+
+    #[test]
+    fn metrics_no_labels() {
+        let mut buf = [0u8; 64];
+        let mut m = MetricLineWriter::new(WriteTo::new(&mut buf));
+        m.metric("http_requests_total").value_int(1027);
+        assert_eq!(m.finish_line(), Ok(()));
+        m.metric("node:cpu:ratio").value_fixed(-5, 3);
+        assert_eq!(m.finish_line(), Ok(()));
+        assert_eq!(
+            m.into_inner().as_str(),
+            "http_requests_total 1027\nnode:cpu:ratio -0.005\n"
+        );
+    }
+
+    #[test]
+    fn metrics_labels_escaping() {
+        let mut buf = [0u8; 128];
+        let mut m = MetricLineWriter::new(WriteTo::new(&mut buf));
+        m.metric("msg")
+            .label("path", r"C:\tmp")
+            .label("text", "say \"hi\"\nbye")
+            .label("id", 7)
+            .value_int(1)
+            .timestamp(-1);
+        assert_eq!(m.finish_line(), Ok(()));
+        assert_eq!(
+            m.into_inner().as_str(),
+            "msg{path=\"C:\\\\tmp\",text=\"say \\\"hi\\\"\\nbye\",id=\"7\"} 1 -1\n"
+        );
+    }
+
+    #[test]
+    fn metrics_invalid_names() {
+        let mut buf = [0u8; 64];
+        let mut m = MetricLineWriter::new(WriteTo::new(&mut buf));
+        m.metric("1st").value_int(1);
+        assert_eq!(m.finish_line(), Err(MetricError::InvalidName));
+        m.metric("temp-c").value_int(1);
+        assert_eq!(m.finish_line(), Err(MetricError::InvalidName));
+        m.metric("temp").label("a:b", "x").value_int(1);
+        assert_eq!(m.finish_line(), Err(MetricError::InvalidLabel));
+        m.metric("temp");
+        assert_eq!(m.finish_line(), Err(MetricError::Incomplete));
+        m.metric("temp").value_int(1).label("late", "x");
+        assert_eq!(m.finish_line(), Err(MetricError::Incomplete));
+        m.metric("ok").value_int(2);
+        assert_eq!(m.finish_line(), Ok(()));
+        assert_eq!(m.into_inner().as_str(), "ok 2\n");
+    }
+
+    #[test]
+    fn metrics_overflow_keeps_complete_lines() {
+        let mut buf = [0u8; 24];
+        let mut m = MetricLineWriter::new(WriteTo::new(&mut buf));
+        m.metric("a").label("k", "v").value_int(1);
+        assert_eq!(m.finish_line(), Ok(()));
+        m.metric("b").label("k", "long value").value_int(2);
+        assert_eq!(m.finish_line(), Err(MetricError::Overflow));
+        m.metric("c").value_int(3);
+        assert_eq!(m.finish_line(), Ok(()));
+        assert_eq!(m.into_inner().as_str(), "a{k=\"v\"} 1\nc 3\n");
+    }
+}
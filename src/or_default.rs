@@ -0,0 +1,135 @@
+//  ( /   @ @    ()  Optional values with fallback text
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Display adapter that shows the value of an `Option` or a fallback text for `None`.
+///
+/// Formatter flags (width, precision, alignment...) are forwarded to the value when present
+/// and applied to the fallback otherwise.
+///
+/// ```
+/// use stackfmt::OrDefault;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("t={:>5.1}", OrDefault::new(Some(21.55), "n/a")));
+/// assert_eq!(s, "t= 21.6");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("t={:>5}", OrDefault::new(None::<f32>, "n/a")));
+/// assert_eq!(s, "t=  n/a");
+/// ```
+#[derive(Clone, Copy)]
+pub struct OrDefault<'a, T> {
+    value: Option<T>,
+    fallback: &'a str,
+}
+
+impl<'a, T: fmt::Display> OrDefault<'a, T> {
+    /// Creates adapter for `value` showing `fallback` for `None`.
+    pub fn new(value: Option<T>, fallback: &'a str) -> Self {
+        OrDefault { value, fallback }
+    }
+}
+
+impl<'a, T: fmt::Display> fmt::Display for OrDefault<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.value {
+            Some(value) => value.fmt(f),
+            None => f.pad(self.fallback),
+        }
+    }
+}
+
+/// Display adapter that shows the value of an `Option` and nothing for `None`.
+///
+/// Useful for optional suffixes. The formatter flags are forwarded to the value, for `None`
+/// the width is still filled so columns stay aligned.
+///
+/// ```
+/// use stackfmt::MaybeDisplay;
+///
+/// let mut buf = [0u8; 32];
+/// let unit: Option<&str> = None;
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("42{}", MaybeDisplay(unit)));
+/// assert_eq!(s, "42");
+/// ```
+#[derive(Clone, Copy)]
+pub struct MaybeDisplay<T>(pub Option<T>);
+
+impl<T: fmt::Display> fmt::Display for MaybeDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.pad(""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MaybeDisplay, OrDefault};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn or_default_some_with_padding() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:>4}]", OrDefault::new(Some(7), "-")),
+        );
+        assert_eq!(s, "[   7]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:04}]", OrDefault::new(Some(7), "-")),
+        );
+        assert_eq!(s, "[0007]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:.2}]", OrDefault::new(Some(1.0 / 3.0), "-")),
+        );
+        assert_eq!(s, "[0.33]");
+    }
+
+    #[test]
+    fn or_default_none_with_padding() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:>4}]", OrDefault::new(None::<u8>, "-")),
+        );
+        assert_eq!(s, "[   -]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:<5.2}]", OrDefault::new(None::<u8>, "none")),
+        );
+        assert_eq!(s, "[no   ]");
+    }
+
+    #[test]
+    fn or_default_empty_fallback() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", OrDefault::new(None::<u8>, "")),
+        );
+        assert_eq!(s, "[]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:3}]", OrDefault::new(None::<u8>, "")),
+        );
+        assert_eq!(s, "[   ]");
+    }
+
+    #[test]
+    fn maybe_display() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(&mut buf, format_args!("42{}", MaybeDisplay(Some(" mV"))));
+        assert_eq!(s, "42 mV");
+        let s = fmt_truncate(&mut buf, format_args!("42{}", MaybeDisplay(None::<&str>)));
+        assert_eq!(s, "42");
+        let s = fmt_truncate(&mut buf, format_args!("[{:>3}]", MaybeDisplay(None::<u8>)));
+        assert_eq!(s, "[   ]");
+        let s = fmt_truncate(&mut buf, format_args!("[{:>3}]", MaybeDisplay(Some(5))));
+        assert_eq!(s, "[  5]");
+    }
+}
@@ -0,0 +1,150 @@
+//  ( /   @ @    ()  English ordinal numbers
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::fmt_truncate;
+use core::fmt;
+
+/// Display adapter that shows a number with its English ordinal suffix, like `3rd retry` or
+/// `21st sample`.
+///
+/// Numbers ending with 11, 12 and 13 get `th` (`111th`), others get `st`, `nd` or `rd` by
+/// the last digit. Width and alignment apply to the whole text.
+///
+/// ```
+/// use stackfmt::Ordinal;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{} retry", Ordinal(3)));
+/// assert_eq!(s, "3rd retry");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{} sample", Ordinal(112)));
+/// assert_eq!(s, "112th sample");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ordinal(pub u64);
+
+impl Ordinal {
+    /// Shows only the suffix without the number, same as [OrdinalSuffix].
+    pub fn suffix_only(self) -> OrdinalSuffix {
+        OrdinalSuffix(self.0)
+    }
+
+    /// Returns the suffix for the number: `st`, `nd`, `rd` or `th`.
+    pub fn suffix(&self) -> &'static str {
+        match (self.0 % 10, self.0 % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        }
+    }
+}
+
+impl fmt::Display for Ordinal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.width().is_some() {
+            let mut buf = [0u8; 24];
+            f.pad(fmt_truncate(
+                &mut buf,
+                format_args!("{}{}", self.0, self.suffix()),
+            ))
+        } else {
+            write!(f, "{}{}", self.0, self.suffix())
+        }
+    }
+}
+
+/// Display adapter that shows only the ordinal suffix for a number, for numbers formatted
+/// separately.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:>4}{}", 22, stackfmt::OrdinalSuffix(22)));
+/// assert_eq!(s, "  22nd");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OrdinalSuffix(pub u64);
+
+impl fmt::Display for OrdinalSuffix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(Ordinal(self.0).suffix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ordinal, OrdinalSuffix};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn ordinal_small() {
+        let expected = [
+            "0th", "1st", "2nd", "3rd", "4th", "5th", "6th", "7th", "8th", "9th", "10th", "11th",
+            "12th", "13th", "14th", "15th", "16th", "17th", "18th", "19th", "20th", "21st", "22nd",
+            "23rd", "24th", "25th",
+        ];
+        let mut buf = [0u8; 32];
+        for (n, text) in expected.iter().enumerate() {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", Ordinal(n as u64))),
+                *text
+            );
+        }
+    }
+
+    #[test]
+    fn ordinal_hundreds() {
+        let expected = [
+            "100th", "101st", "102nd", "103rd", "104th", "105th", "106th", "107th", "108th",
+            "109th", "110th", "111th", "112th", "113th", "114th", "115th",
+        ];
+        let mut buf = [0u8; 32];
+        for (n, text) in expected.iter().enumerate() {
+            let ordinal = Ordinal(100 + n as u64);
+            assert_eq!(fmt_truncate(&mut buf, format_args!("{}", ordinal)), *text);
+        }
+        let s = fmt_truncate(&mut buf, format_args!("{}", Ordinal(1000000001)));
+        assert_eq!(s, "1000000001st");
+        let s = fmt_truncate(&mut buf, format_args!("{}", Ordinal(u64::MAX)));
+        assert_eq!(s, "18446744073709551615th");
+    }
+
+    #[test]
+    fn ordinal_suffix_only() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ordinal(1).suffix_only())),
+            "st"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", OrdinalSuffix(13))),
+            "th"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", OrdinalSuffix(1012))),
+            "th"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", OrdinalSuffix(1022))),
+            "nd"
+        );
+    }
+
+    #[test]
+    fn ordinal_width() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>6}]", Ordinal(3))),
+            "[   3rd]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:<6}]", Ordinal(21))),
+            "[21st  ]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:^4}]", OrdinalSuffix(2))),
+            "[ nd ]"
+        );
+    }
+}
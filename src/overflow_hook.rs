@@ -0,0 +1,135 @@
+//  ( /   @ @    ()  Callback invoked when the formatted output first gets truncated
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+
+/// What [OverflowHook] passes to its callback.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OverflowInfo {
+    /// Bytes in the buffer before the chunk that did not fit
+    pub written: usize,
+    /// Length of the chunk that did not fit
+    pub chunk_len: usize,
+}
+
+/// [WriteTo] wrapper that calls `hook` once, when some output does not fit into the buffer.
+///
+/// The hook gets a copy of the numbers and not the stream, so it can count or record the
+/// truncation but can not write into the same buffer.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut truncated = 0;
+/// let mut buffer = [0u8; 8];
+/// let mut w = stackfmt::OverflowHook::new(stackfmt::WriteTo::new(&mut buffer), |_| truncated += 1);
+/// write!(w, "pressure {} hPa", 1013).unwrap();
+/// assert_eq!(w.into_inner().as_str(), "pressure");
+/// assert_eq!(truncated, 1);
+/// ```
+pub struct OverflowHook<'a, F: FnMut(&OverflowInfo)> {
+    inner: WriteTo<'a>,
+    hook: F,
+}
+
+impl<'a, F: FnMut(&OverflowInfo)> OverflowHook<'a, F> {
+    /// Creates stream writing into `inner` and calling `hook` on the first truncation.
+    pub fn new(inner: WriteTo<'a>, hook: F) -> Self {
+        OverflowHook { inner, hook }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> WriteTo<'a> {
+        self.inner
+    }
+
+    // Calls the hook if the write of `chunk_len` bytes started the truncation
+    fn check(&mut self, truncated: bool, written: usize, chunk_len: usize) {
+        if !truncated && self.inner.is_truncated() {
+            (self.hook)(&OverflowInfo { written, chunk_len });
+        }
+    }
+}
+
+impl<'a, F: FnMut(&OverflowInfo)> fmt::Write for OverflowHook<'a, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let (truncated, written) = (self.inner.is_truncated(), self.inner.written_bytes());
+        self.inner.write_str(s)?;
+        self.check(truncated, written, s.len());
+        Ok(())
+    }
+}
+
+impl<'a, F: FnMut(&OverflowInfo)> WriteWhole for OverflowHook<'a, F> {
+    fn write_whole(&mut self, s: &str) -> fmt::Result {
+        let (truncated, written) = (self.inner.is_truncated(), self.inner.written_bytes());
+        self.inner.write_whole(s)?;
+        self.check(truncated, written, s.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OverflowHook, OverflowInfo};
+    use crate::{WriteTo, WriteWhole};
+    use core::fmt::Write;
+
+    #[test]
+    fn hook_fires_once() {
+        let mut calls = 0;
+        let mut buf = [0u8; 4];
+        let mut w = OverflowHook::new(WriteTo::new(&mut buf), |_| calls += 1);
+        w.write_str("abc").unwrap();
+        w.write_str("def").unwrap();
+        w.write_str("ghi").unwrap();
+        w.write_whole("jkl").unwrap();
+        assert_eq!(w.into_inner().as_str(), "abcd");
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn hook_not_fired_on_exact_fit() {
+        let mut calls = 0;
+        let mut buf = [0u8; 6];
+        let mut w = OverflowHook::new(WriteTo::new(&mut buf), |_| calls += 1);
+        w.write_str("abc").unwrap();
+        w.write_str("def").unwrap();
+        w.write_str("").unwrap();
+        assert_eq!(w.into_inner().as_str(), "abcdef");
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn hook_gets_counts() {
+        let mut info = None;
+        let mut buf = [0u8; 8];
+        let mut w = OverflowHook::new(WriteTo::new(&mut buf), |i: &OverflowInfo| info = Some(*i));
+        let (a, b) = ("hello", 123456);
+        write!(w, "{} {}", a, b).unwrap();
+        let _ = w.into_inner();
+        assert_eq!(
+            info,
+            Some(OverflowInfo {
+                written: 6,
+                chunk_len: 6
+            })
+        );
+
+        // whole write that does not fit
+        let mut buf = [0u8; 8];
+        let mut w = OverflowHook::new(WriteTo::new(&mut buf), |i: &OverflowInfo| info = Some(*i));
+        w.write_str("abcde").unwrap();
+        w.write_whole("\u{1b}[0m").unwrap();
+        assert_eq!(w.into_inner().as_str(), "abcde");
+        assert_eq!(
+            info,
+            Some(OverflowInfo {
+                written: 5,
+                chunk_len: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//  ( /   @ @    ()  Width and alignment for text written in pieces
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+// Writes fill chars around `body` so that `len` chars it writes occupy the formatter width.
+// Used instead of Formatter::pad() when the text is not available as a single &str.
+pub(crate) fn pad_with<F>(
+    f: &mut fmt::Formatter,
+    len: usize,
+    default: fmt::Alignment,
+    body: F,
+) -> fmt::Result
+where
+    F: FnOnce(&mut fmt::Formatter) -> fmt::Result,
+{
+    let pad = f.width().unwrap_or(0).saturating_sub(len);
+    let (fill, align) = (f.fill(), f.align().unwrap_or(default));
+    write_padded(f, pad, fill, align, body)
+}
+
+// Writes `pad` fill chars before and/or after `body` according to the alignment
+pub(crate) fn write_padded<W, F>(
+    w: &mut W,
+    pad: usize,
+    fill: char,
+    align: fmt::Alignment,
+    body: F,
+) -> fmt::Result
+where
+    W: fmt::Write,
+    F: FnOnce(&mut W) -> fmt::Result,
+{
+    let (before, after) = match align {
+        fmt::Alignment::Left => (0, pad),
+        fmt::Alignment::Center => (pad / 2, pad - pad / 2),
+        fmt::Alignment::Right => (pad, 0),
+    };
+    for _ in 0..before {
+        w.write_char(fill)?;
+    }
+    body(w)?;
+    for _ in 0..after {
+        w.write_char(fill)?;
+    }
+    Ok(())
+}
+
+// Counts chars written, stops counting after `limit`
+pub(crate) struct CharCounter {
+    pub(crate) count: usize,
+    pub(crate) limit: usize,
+}
+
+impl fmt::Write for CharCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.count < self.limit {
+            self.count = (self.count + s.chars().count()).min(self.limit);
+        }
+        Ok(())
+    }
+}
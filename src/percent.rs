@@ -0,0 +1,282 @@
+//  ( /   @ @    ()  Percentage of two integers
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::{pad_with, CharCounter};
+use core::fmt;
+
+// Decimals of the percentage that are still computed exactly in u128
+const MAX_DECIMALS: u8 = 12;
+
+/// Display adapter that shows `numerator / denominator` as percentage, like `42.8%`.
+///
+/// The last decimal is rounded half up, only integer math is used. A zero denominator shows
+/// the placeholder (`--` by default) instead of the number. The value is not limited unless
+/// [Percent::max] is set, so 3 of 2 is `150%`. Width, fill and alignment (right by default)
+/// apply to the whole text.
+///
+/// ```
+/// use stackfmt::Percent;
+///
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Percent::new(428, 1000).decimals(1)));
+/// assert_eq!(s, "42.8%");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("[{:>5}]", Percent::new(1, 0)));
+/// assert_eq!(s, "[  --%]");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Percent<'p> {
+    numerator: u64,
+    denominator: u64,
+    decimals: u8,
+    placeholder: &'p str,
+    max: Option<u64>,
+}
+
+impl<'p> Percent<'p> {
+    /// Creates adapter for `numerator / denominator` shown without decimals.
+    pub fn new(numerator: u64, denominator: u64) -> Self {
+        Percent {
+            numerator,
+            denominator,
+            decimals: 0,
+            placeholder: "--",
+            max: None,
+        }
+    }
+
+    /// Sets the number of decimals, at most 12.
+    pub fn decimals(mut self, decimals: u8) -> Self {
+        self.decimals = decimals.min(MAX_DECIMALS);
+        self
+    }
+
+    /// Sets the text shown before `%` when the denominator is zero.
+    pub fn placeholder(mut self, placeholder: &'p str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Limits the shown percentage to `max`, like 100 for a progress that may overshoot.
+    pub fn max(mut self, max: u64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        if self.denominator == 0 {
+            return write!(w, "{}%", self.placeholder);
+        }
+        let scale = 10u128.pow(self.decimals as u32);
+        let (num, den) = (self.numerator as u128, self.denominator as u128);
+        let mut scaled = (num * 100 * scale * 2 + den) / (den * 2);
+        if let Some(max) = self.max {
+            scaled = scaled.min(max as u128 * scale);
+        }
+        write!(w, "{}", scaled / scale)?;
+        if self.decimals > 0 {
+            let width = self.decimals as usize;
+            write!(w, ".{:0width$}", scaled % scale, width = width)?;
+        }
+        w.write_char('%')
+    }
+}
+
+impl<'p> fmt::Display for Percent<'p> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut counter = CharCounter {
+            count: 0,
+            limit: usize::MAX,
+        };
+        self.write_to(&mut counter)?;
+        pad_with(f, counter.count, fmt::Alignment::Right, |f| {
+            self.write_to(f)
+        })
+    }
+}
+
+/// Display adapter that shows progress as `done/total (42.8%)`.
+///
+/// The percentage is a [Percent] and is configured the same way. Width, fill and alignment
+/// (right by default) apply to the whole text.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let ratio = stackfmt::Ratio::new(428, 1000).decimals(1);
+/// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", ratio)), "428/1000 (42.8%)");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ratio<'p> {
+    percent: Percent<'p>,
+}
+
+impl<'p> Ratio<'p> {
+    /// Creates adapter for `done` of `total`.
+    pub fn new(done: u64, total: u64) -> Self {
+        Ratio {
+            percent: Percent::new(done, total),
+        }
+    }
+
+    /// Sets the number of decimals of the percentage, see [Percent::decimals].
+    pub fn decimals(mut self, decimals: u8) -> Self {
+        self.percent = self.percent.decimals(decimals);
+        self
+    }
+
+    /// Sets the percentage shown for zero total, see [Percent::placeholder].
+    pub fn placeholder(mut self, placeholder: &'p str) -> Self {
+        self.percent = self.percent.placeholder(placeholder);
+        self
+    }
+
+    /// Limits the percentage, see [Percent::max].
+    pub fn max(mut self, max: u64) -> Self {
+        self.percent = self.percent.max(max);
+        self
+    }
+
+    fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write!(
+            w,
+            "{}/{} (",
+            self.percent.numerator, self.percent.denominator
+        )?;
+        self.percent.write_to(w)?;
+        w.write_char(')')
+    }
+}
+
+impl<'p> fmt::Display for Ratio<'p> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut counter = CharCounter {
+            count: 0,
+            limit: usize::MAX,
+        };
+        self.write_to(&mut counter)?;
+        pad_with(f, counter.count, fmt::Alignment::Right, |f| {
+            self.write_to(f)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Percent, Ratio};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn percent_zero_and_full() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(0, 7))),
+            "0%"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(0, 7).decimals(2))),
+            "0.00%"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(7, 7).decimals(1))),
+            "100.0%"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Percent::new(u64::MAX, u64::MAX))
+            ),
+            "100%"
+        );
+    }
+
+    #[test]
+    fn percent_rounding_half_up() {
+        let mut buf = [0u8; 32];
+        // 1/8 = 12.5%
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(1, 8))),
+            "13%"
+        );
+        // 1/16 = 6.25%
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Percent::new(1, 16).decimals(1))
+            ),
+            "6.3%"
+        );
+        // 1/3
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(1, 3).decimals(3))),
+            "33.333%"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(2, 3).decimals(3))),
+            "66.667%"
+        );
+        // 99.95% rounds up into the integer part
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Percent::new(9995, 10000).decimals(1))
+            ),
+            "100.0%"
+        );
+    }
+
+    #[test]
+    fn percent_zero_denominator() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(5, 0))),
+            "--%"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Percent::new(5, 0).placeholder("n/a "))
+            ),
+            "n/a %"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ratio::new(0, 0))),
+            "0/0 (--%)"
+        );
+    }
+
+    #[test]
+    fn percent_over_hundred() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(3, 2))),
+            "150%"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Percent::new(3, 2).max(100))),
+            "100%"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Percent::new(u64::MAX, 1).decimals(12))
+            ),
+            "1844674407370955161500.000000000000%"
+        );
+    }
+
+    #[test]
+    fn ratio_padding() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>14}]", Ratio::new(3, 4))),
+            "[     3/4 (75%)]"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("[{:<12}]", Ratio::new(5, 4).decimals(1).max(100))
+            ),
+            "[5/4 (100.0%)]"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//  ( /   @ @    ()  Count with singular or plural word
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::{pad_with, CharCounter};
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows a count with the matching word, like `1 retry` or `3 retries`.
+///
+/// The English rule is used: singular for exactly one, plural otherwise (including zero).
+/// For other languages use [Plural::with] and a closure selecting the word. Width and
+/// alignment apply to the whole text.
+///
+/// ```
+/// use stackfmt::Plural;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{} remaining", Plural::new(1, "retry", "retries")));
+/// assert_eq!(s, "1 retry remaining");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{} remaining", Plural::new(3, "retry", "retries")));
+/// assert_eq!(s, "3 retries remaining");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Plural<'a> {
+    count: u64,
+    singular: &'a str,
+    plural: &'a str,
+    omit_count: bool,
+}
+
+impl<'a> Plural<'a> {
+    /// Creates adapter for `count` with English plural rule.
+    pub fn new(count: u64, singular: &'a str, plural: &'a str) -> Self {
+        Plural {
+            count,
+            singular,
+            plural,
+            omit_count: false,
+        }
+    }
+
+    /// Creates adapter for `count` with a word selected by `select`.
+    ///
+    /// ```
+    /// // Polish: 1 plik, 2-4 pliki, 5-21 plików, 22-24 pliki...
+    /// let select = |n: u64| match (n % 10, n % 100) {
+    ///     _ if n == 1 => "plik",
+    ///     (2..=4, d) if !(12..=14).contains(&d) => "pliki",
+    ///     _ => "plik\u{F3}w",
+    /// };
+    /// let mut buf = [0u8; 32];
+    /// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", stackfmt::Plural::with(22, select)));
+    /// assert_eq!(s, "22 pliki");
+    /// ```
+    pub fn with<F: Fn(u64) -> &'a str>(count: u64, select: F) -> PluralWith<F> {
+        PluralWith {
+            count,
+            select,
+            omit_count: false,
+        }
+    }
+
+    /// Shows only the word without the count, same as [PluralWord].
+    pub fn omit_count(mut self) -> Self {
+        self.omit_count = true;
+        self
+    }
+}
+
+impl<'a> fmt::Display for Plural<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let word = if self.count == 1 {
+            self.singular
+        } else {
+            self.plural
+        };
+        write_count_word(f, self.count, word, self.omit_count)
+    }
+}
+
+/// Display adapter that shows only the singular or plural word for a count.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let files = stackfmt::PluralWord::new(2, "file", "files");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{} {} copied", 2, files));
+/// assert_eq!(s, "2 files copied");
+/// ```
+#[derive(Clone, Copy)]
+pub struct PluralWord<'a>(Plural<'a>);
+
+impl<'a> PluralWord<'a> {
+    /// Creates adapter for the word for `count` with English plural rule.
+    pub fn new(count: u64, singular: &'a str, plural: &'a str) -> Self {
+        PluralWord(Plural::new(count, singular, plural).omit_count())
+    }
+}
+
+impl<'a> fmt::Display for PluralWord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Display adapter returned by [Plural::with].
+#[derive(Clone, Copy)]
+pub struct PluralWith<F> {
+    count: u64,
+    select: F,
+    omit_count: bool,
+}
+
+impl<F> PluralWith<F> {
+    /// Shows only the word without the count.
+    pub fn omit_count(mut self) -> Self {
+        self.omit_count = true;
+        self
+    }
+}
+
+impl<'a, F: Fn(u64) -> &'a str> fmt::Display for PluralWith<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_count_word(f, self.count, (self.select)(self.count), self.omit_count)
+    }
+}
+
+fn write_count_word(
+    f: &mut fmt::Formatter,
+    count: u64,
+    word: &str,
+    omit_count: bool,
+) -> fmt::Result {
+    if omit_count {
+        f.pad(word)
+    } else {
+        let mut digits = CharCounter {
+            count: 0,
+            limit: usize::MAX,
+        };
+        let _ = write!(digits, "{}", count);
+        let len = digits.count + 1 + word.chars().count();
+        pad_with(f, len, fmt::Alignment::Left, |f| {
+            write!(f, "{} {}", count, word)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Plural, PluralWord};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn plural_english() {
+        let mut buf = [0u8; 32];
+        let r = |n| Plural::new(n, "retry", "retries");
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", r(0))),
+            "0 retries"
+        );
+        assert_eq!(fmt_truncate(&mut buf, format_args!("{}", r(1))), "1 retry");
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", r(2))),
+            "2 retries"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", r(1).omit_count())),
+            "retry"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", r(2).omit_count())),
+            "retries"
+        );
+    }
+
+    #[test]
+    fn plural_word() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", PluralWord::new(0, "item", "items"))
+            ),
+            "items"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", PluralWord::new(1, "item", "items"))
+            ),
+            "item"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("[{:>6}]", PluralWord::new(1, "item", "items"))
+            ),
+            "[  item]"
+        );
+    }
+
+    #[test]
+    fn plural_selector() {
+        // Russian: 1 файл, 2 файла, 5 файлов, 21 файл
+        let select = |n: u64| match (n % 10, n % 100) {
+            (1, d) if d != 11 => "\u{444}\u{430}\u{439}\u{43B}",
+            (2..=4, d) if !(12..=14).contains(&d) => "\u{444}\u{430}\u{439}\u{43B}\u{430}",
+            _ => "\u{444}\u{430}\u{439}\u{43B}\u{43E}\u{432}",
+        };
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Plural::with(1, select)));
+        assert_eq!(s, "1 \u{444}\u{430}\u{439}\u{43B}");
+        let s = fmt_truncate(&mut buf, format_args!("{}", Plural::with(21, select)));
+        assert_eq!(s, "21 \u{444}\u{430}\u{439}\u{43B}");
+        let s = fmt_truncate(&mut buf, format_args!("{}", Plural::with(3, select)));
+        assert_eq!(s, "3 \u{444}\u{430}\u{439}\u{43B}\u{430}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Plural::with(11, select).omit_count()),
+        );
+        assert_eq!(s, "\u{444}\u{430}\u{439}\u{43B}\u{43E}\u{432}");
+    }
+
+    #[test]
+    fn plural_width() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:<10}]", Plural::new(2, "hit", "hits")),
+        );
+        assert_eq!(s, "[2 hits    ]");
+
+        // long words are not cut by the padding
+        let mut buf = [0u8; 128];
+        let word = "measurements-of-the-outdoor-temperature-sensor";
+        let p = Plural::new(12, "x", word);
+        let s = fmt_truncate(&mut buf, format_args!("[{:>52}]", p));
+        assert_eq!(s, "[   12 measurements-of-the-outdoor-temperature-sensor]");
+    }
+}
@@ -0,0 +1,213 @@
+//  ( /   @ @    ()  Fixed slots of formatted messages kept for sending later
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Pool of `SLOTS` buffers of `SLOT` bytes for messages formatted now and sent later, like
+/// formatted in an interrupt handler and written to UART from the main loop.
+///
+/// [FormatPool::try_format] formats into a free slot (truncating if needed) and returns
+/// [PoolMsg] with the text, the slot is free again when the message is dropped. The free
+/// slots are kept in an atomic bitmap, so taking and releasing a slot is lock-free and
+/// works from interrupts; `SLOTS` is limited to the bits of `usize`. When all the slots are
+/// in use the message is dropped and counted, see [FormatPool::dropped]. The pool is not
+/// built for targets without atomic compare and swap.
+///
+/// ```
+/// use stackfmt::FormatPool;
+///
+/// static POOL: FormatPool<32, 4> = FormatPool::new();
+///
+/// // in the interrupt handler
+/// let msg = POOL.try_format(format_args!("overrun at {}", 1200)).unwrap();
+/// // in the main loop
+/// assert_eq!(msg.as_str(), "overrun at 1200");
+/// drop(msg); // the slot is free again
+/// assert_eq!(POOL.free_slots(), 4);
+/// ```
+pub struct FormatPool<const SLOT: usize, const SLOTS: usize> {
+    used: AtomicUsize,
+    dropped: AtomicUsize,
+    slots: [UnsafeCell<[u8; SLOT]>; SLOTS],
+}
+
+// A slot is accessed only by the one that set its bit in `used`
+unsafe impl<const SLOT: usize, const SLOTS: usize> Sync for FormatPool<SLOT, SLOTS> {}
+
+impl<const SLOT: usize, const SLOTS: usize> FormatPool<SLOT, SLOTS> {
+    const ALL: usize = if SLOTS == usize::BITS as usize {
+        usize::MAX
+    } else {
+        (1 << SLOTS) - 1
+    };
+
+    /// Creates pool with all the slots free.
+    pub const fn new() -> Self {
+        assert!(SLOTS <= usize::BITS as usize, "too many slots");
+        FormatPool {
+            used: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+            slots: [const { UnsafeCell::new([0u8; SLOT]) }; SLOTS],
+        }
+    }
+
+    /// Formats into a free slot, returns `None` and counts the message as dropped if there
+    /// is no free slot.
+    pub fn try_format(&self, args: fmt::Arguments) -> Option<PoolMsg<'_>> {
+        let mut used = self.used.load(Ordering::Acquire);
+        let bit = loop {
+            let free = !used & Self::ALL;
+            if free == 0 {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            let bit = free & free.wrapping_neg(); // the lowest free slot
+            match self.used.compare_exchange_weak(
+                used,
+                used | bit,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break bit,
+                Err(current) => used = current,
+            }
+        };
+
+        let slot = &self.slots[bit.trailing_zeros() as usize];
+        // the bit is ours, so nobody else uses the slot until it is cleared
+        let mut w = WriteTo::new(unsafe { &mut *slot.get() });
+        let _ = fmt::write(&mut w, args);
+        let truncated = w.is_truncated();
+        Some(PoolMsg {
+            text: w.as_str(),
+            truncated,
+            used: &self.used,
+            bit,
+        })
+    }
+
+    /// How many messages were dropped because all the slots were in use.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// How many slots are free now.
+    pub fn free_slots(&self) -> usize {
+        SLOTS - self.used.load(Ordering::Acquire).count_ones() as usize
+    }
+}
+
+impl<const SLOT: usize, const SLOTS: usize> Default for FormatPool<SLOT, SLOTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Message formatted into a slot of [FormatPool], the slot is released on drop.
+pub struct PoolMsg<'p> {
+    text: &'p str,
+    truncated: bool,
+    used: &'p AtomicUsize,
+    bit: usize,
+}
+
+impl<'p> PoolMsg<'p> {
+    /// Formatted text of the message
+    pub fn as_str(&self) -> &str {
+        self.text
+    }
+
+    /// True if the message did not fit into the slot and was truncated
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl<'p> Drop for PoolMsg<'p> {
+    fn drop(&mut self) {
+        self.used.fetch_and(!self.bit, Ordering::Release);
+    }
+}
+
+impl<'p> fmt::Display for PoolMsg<'p> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.text)
+    }
+}
+
+impl<'p> fmt::Debug for PoolMsg<'p> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.text, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FormatPool;
+
+    #[test]
+    fn pool_fill_all_slots() {
+        let pool: FormatPool<16, 3> = FormatPool::new();
+        let a = pool.try_format(format_args!("msg {}", 1)).unwrap();
+        let b = pool.try_format(format_args!("msg {}", 2)).unwrap();
+        let c = pool.try_format(format_args!("msg {}", 3)).unwrap();
+        assert_eq!(pool.free_slots(), 0);
+        assert!(pool.try_format(format_args!("msg {}", 4)).is_none());
+        assert!(pool.try_format(format_args!("msg {}", 5)).is_none());
+        assert_eq!(pool.dropped(), 2);
+        assert_eq!(
+            (a.as_str(), b.as_str(), c.as_str()),
+            ("msg 1", "msg 2", "msg 3")
+        );
+    }
+
+    #[test]
+    fn pool_drop_and_reuse() {
+        let pool: FormatPool<16, 2> = FormatPool::new();
+        let a = pool.try_format(format_args!("first")).unwrap();
+        let b = pool.try_format(format_args!("second")).unwrap();
+        drop(a);
+        assert_eq!(pool.free_slots(), 1);
+        let c = pool.try_format(format_args!("third")).unwrap();
+        assert_eq!((b.as_str(), c.as_str()), ("second", "third"));
+        drop(b);
+        drop(c);
+        assert_eq!(pool.free_slots(), 2);
+        assert_eq!(pool.dropped(), 0);
+    }
+
+    #[test]
+    fn pool_interleaving() {
+        let pool: FormatPool<8, 2> = FormatPool::new();
+        // each message is taken before the previous one is released
+        let mut prev = pool.try_format(format_args!("0")).unwrap();
+        for i in 1..10 {
+            let next = pool.try_format(format_args!("{}", i)).unwrap();
+            assert!(pool.try_format(format_args!("x")).is_none());
+            drop(prev);
+            assert_eq!(next.as_str().parse::<i32>(), Ok(i));
+            prev = next;
+        }
+        assert_eq!(pool.dropped(), 9);
+        assert_eq!(pool.free_slots(), 1);
+    }
+
+    #[test]
+    fn pool_truncation() {
+        let pool: FormatPool<8, 1> = FormatPool::new();
+        let msg = pool.try_format(format_args!("{}", "0123456789")).unwrap();
+        assert_eq!(msg.as_str(), "01234567");
+        assert!(msg.is_truncated());
+        drop(msg);
+        let msg = pool
+            .try_format(format_args!("\u{20AC}\u{20AC}\u{20AC}"))
+            .unwrap();
+        assert_eq!(msg.as_str(), "\u{20AC}\u{20AC}");
+        let full: FormatPool<4, { usize::BITS as usize }> = FormatPool::new();
+        assert_eq!(full.free_slots(), usize::BITS as usize);
+    }
+}
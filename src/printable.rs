@@ -0,0 +1,259 @@
+//  ( /   @ @    ()  Formatted output limited to printable ASCII
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// The first char of the formatted text that is not printable ASCII, see
+/// [PrintableAsciiWriter].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NonPrintable {
+    /// Byte offset of the char in the formatted text
+    pub offset: usize,
+    /// The char
+    pub value: char,
+}
+
+impl fmt::Display for NonPrintable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "non-printable char U+{:04X} at {}",
+            self.value as u32, self.offset
+        )
+    }
+}
+
+/// Impl of [fmt::Write] that stores formatted text into the provided buffer only if it is
+/// printable ASCII (`0x20..=0x7E`) or one of the allowed bytes like `\r`.
+///
+/// By default the first other char fails the write and all the following writes. In the
+/// lenient mode set by [PrintableAsciiWriter::substitute] such chars are replaced with the
+/// given ASCII char. Either way the first of them is kept for [PrintableAsciiWriter::non_printable]
+/// with its offset in the formatted text. Text that does not fit is dropped as by
+/// [WriteTo](crate::WriteTo), the chars are checked also after that.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buffer = [0u8; 32];
+/// let mut w = stackfmt::PrintableAsciiWriter::new(&mut buffer).allow(b"\r");
+/// write!(w, "VOLT {}\r", 12).unwrap();
+/// assert_eq!(w.as_str(), "VOLT 12\r");
+/// assert!(write!(w, "\t").is_err());
+/// ```
+pub struct PrintableAsciiWriter<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+    overflow: bool,
+    allowed: &'a [u8],
+    substitute: Option<u8>,
+    offset: usize, // bytes of the formatted text seen so far
+    non_printable: Option<NonPrintable>,
+}
+
+impl<'a> PrintableAsciiWriter<'a> {
+    /// Creates stream writing into `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        PrintableAsciiWriter {
+            buffer,
+            used: 0,
+            overflow: false,
+            allowed: &[],
+            substitute: None,
+            offset: 0,
+            non_printable: None,
+        }
+    }
+
+    /// Accepts also these bytes, like `b"\r"`; only ASCII bytes are taken into account.
+    pub fn allow(mut self, allowed: &'a [u8]) -> Self {
+        self.allowed = allowed;
+        self
+    }
+
+    /// Replaces not accepted chars with `c` instead of failing, panics if `c` is not ASCII.
+    pub fn substitute(mut self, c: char) -> Self {
+        assert!(c.is_ascii(), "substitute must be an ASCII char");
+        self.substitute = Some(c as u8);
+        self
+    }
+
+    /// The first not accepted char, if any
+    pub fn non_printable(&self) -> Option<NonPrintable> {
+        self.non_printable
+    }
+
+    /// Returns the data written so far as &str
+    pub fn as_str(&self) -> &str {
+        // only ASCII bytes are written
+        unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.used]) }
+    }
+
+    /// How many bytes were written into the buffer
+    pub fn written_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// True if some data did not fit into the buffer and was dropped
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    /// Returns the written text and the flag if some data was dropped.
+    pub fn finish(self) -> (&'a str, bool) {
+        let s = unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.used]) };
+        (s, self.overflow)
+    }
+
+    fn is_accepted(&self, c: char) -> bool {
+        matches!(c, ' '..='~') || (c.is_ascii() && self.allowed.contains(&(c as u8)))
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.used == self.buffer.len() {
+            self.overflow = true;
+        } else if !self.overflow {
+            self.buffer[self.used] = byte;
+            self.used += 1;
+        }
+    }
+}
+
+impl<'a> fmt::Write for PrintableAsciiWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.non_printable.is_some() && self.substitute.is_none() {
+            return Err(fmt::Error);
+        }
+        for (i, c) in s.char_indices() {
+            let byte = if self.is_accepted(c) {
+                c as u8
+            } else {
+                self.non_printable.get_or_insert(NonPrintable {
+                    offset: self.offset + i,
+                    value: c,
+                });
+                match self.substitute {
+                    Some(byte) => byte,
+                    None => return Err(fmt::Error),
+                }
+            };
+            self.push(byte);
+        }
+        self.offset += s.len();
+        Ok(())
+    }
+}
+
+/// Writes formatted text into the buffer truncating if needed, fails with the first char
+/// that is not printable ASCII.
+///
+/// ```
+/// let mut buf = [0u8; 16];
+/// assert_eq!(stackfmt::fmt_printable(&mut buf, format_args!("T={}", -5)), Ok("T=-5"));
+/// let err = stackfmt::fmt_printable(&mut buf, format_args!("T={}\n", -5)).unwrap_err();
+/// assert_eq!((err.offset, err.value), (4, '\n'));
+/// ```
+pub fn fmt_printable<'a>(
+    buffer: &'a mut [u8],
+    args: fmt::Arguments,
+) -> Result<&'a str, NonPrintable> {
+    let mut w = PrintableAsciiWriter::new(buffer);
+    let _ = fmt::write(&mut w, args);
+    match w.non_printable() {
+        Some(e) => Err(e),
+        None => Ok(w.finish().0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_printable, NonPrintable, PrintableAsciiWriter};
+    use core::fmt::Write;
+
+    #[test]
+    fn printable_clean() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_printable(&mut buf, format_args!("MEAS:VOLT? {} ~{}", 1, "x")),
+            Ok("MEAS:VOLT? 1 ~x")
+        );
+        let mut buf = [0u8; 4];
+        let mut w = PrintableAsciiWriter::new(&mut buf);
+        w.write_str("*IDN?").unwrap();
+        assert_eq!(w.finish(), ("*IDN", true));
+    }
+
+    #[test]
+    fn printable_tab_rejected() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_printable(&mut buf, format_args!("a\tb")),
+            Err(NonPrintable {
+                offset: 1,
+                value: '\t'
+            })
+        );
+        // allowed bytes
+        let mut w = PrintableAsciiWriter::new(&mut buf).allow(b"\r\n");
+        w.write_str("OK\r\n").unwrap();
+        assert!(w.write_str("\t").is_err());
+        // later writes fail too
+        assert!(w.write_str("more").is_err());
+        assert_eq!(w.as_str(), "OK\r\n");
+        assert_eq!(
+            w.non_printable(),
+            Some(NonPrintable {
+                offset: 4,
+                value: '\t'
+            })
+        );
+    }
+
+    #[test]
+    fn printable_multi_byte_offset() {
+        let mut buf = [0u8; 32];
+        let mut w = PrintableAsciiWriter::new(&mut buf);
+        w.write_str("T=").unwrap();
+        let (t, unit) = (21, '\u{B0}');
+        let err = write!(w, "{}{}C", t, unit);
+        assert!(err.is_err());
+        assert_eq!(w.as_str(), "T=21");
+        assert_eq!(
+            w.non_printable(),
+            Some(NonPrintable {
+                offset: 4,
+                value: '\u{B0}'
+            })
+        );
+        // the offset is in bytes of the text and also counts after the end of the buffer
+        let mut buf = [0u8; 2];
+        let e = fmt_printable(&mut buf, format_args!("\u{E9}t\u{E9}\u{20AC}\x7F")).unwrap_err();
+        assert_eq!((e.offset, e.value), (0, '\u{E9}'));
+        let e = fmt_printable(&mut buf, format_args!("abc\x7F")).unwrap_err();
+        assert_eq!((e.offset, e.value), (3, '\x7F'));
+    }
+
+    #[test]
+    fn printable_lenient() {
+        let mut buf = [0u8; 32];
+        let mut w = PrintableAsciiWriter::new(&mut buf).substitute('.');
+        let (price, face) = ("\u{20AC}5", "\u{1F600}\n");
+        write!(w, "{}\t|{}", price, face).unwrap();
+        assert_eq!(w.as_str(), ".5.|..");
+        assert_eq!(
+            w.non_printable(),
+            Some(NonPrintable {
+                offset: 0,
+                value: '\u{20AC}'
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn printable_non_ascii_substitute_rejected() {
+        let mut buf = [0u8; 8];
+        let _ = PrintableAsciiWriter::new(&mut buf).substitute('\u{FF}');
+    }
+}
@@ -0,0 +1,329 @@
+//  ( /   @ @    ()  Line of fields where the low priority ones are dropped to make room
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Max fields of a [PriorityLine], the following ones are dropped.
+pub const PRIORITY_LINE_MAX_FIELDS: usize = 16;
+
+#[derive(Clone, Copy, Default)]
+struct Field {
+    priority: u8,
+    start: usize, // offset in the buffer
+    len: usize,
+    chars: usize,
+}
+
+// Counts bytes and chars
+struct Measure {
+    len: usize,
+    chars: usize,
+}
+
+impl fmt::Write for Measure {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.len += s.len();
+        self.chars += s.chars().count();
+        Ok(())
+    }
+}
+
+/// Writes a line of fields into a [WriteTo], dropping whole fields of low priority when
+/// all of them do not fit, like `SSID IP RSSI UPTIME FW` on a narrow status display.
+///
+/// Every [PriorityLine::field] is measured and written into the buffer right away, so its
+/// Display impls run twice. [PriorityLine::finish] picks the kept fields from scratch:
+/// highest priority first, of equal priorities the earlier declared, skipping a field that
+/// does not fit with the ones picked before. The room is the rest of the buffer and, if
+/// set, [PriorityLine::max_width] chars, separators included. The kept fields stay in their
+/// order. When the buffer has no room for the text of another field, the fields that would
+/// not be kept are discarded, the last declared of the lowest priority first.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let mut out = stackfmt::WriteTo::new(&mut buf);
+/// let mut line = stackfmt::PriorityLine::new(&mut out).separator("  ").max_width(20);
+/// line.field(9, format_args!("{}", "homenet"))
+///     .field(8, format_args!("{}", "192.168.1.20"))
+///     .field(5, format_args!("{}dBm", -61))
+///     .field(1, format_args!("fw{}", "1.4"));
+/// assert_eq!(line.finish(), 2);
+/// assert_eq!(out.as_str(), "homenet  -61dBm");
+/// ```
+pub struct PriorityLine<'w, 'a> {
+    out: &'w mut WriteTo<'a>,
+    separator: &'w str,
+    capacity: usize, // bytes from the start of the line to the end of the buffer
+    max_width: usize,
+    fields: [Field; PRIORITY_LINE_MAX_FIELDS],
+    count: usize,
+    dropped: usize,
+}
+
+impl<'w, 'a> PriorityLine<'w, 'a> {
+    /// Creates line appended to `out` with a space as separator.
+    pub fn new(out: &'w mut WriteTo<'a>) -> Self {
+        let truncated = out.is_truncated();
+        let (buffer, used) = out.raw_parts();
+        let capacity = if truncated { 0 } else { buffer.len() - *used };
+        PriorityLine {
+            out,
+            separator: " ",
+            capacity,
+            max_width: usize::MAX,
+            fields: [Field::default(); PRIORITY_LINE_MAX_FIELDS],
+            count: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Sets the text between fields.
+    pub fn separator(mut self, separator: &'w str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Limits the line to `chars` chars, like the width of a display.
+    pub fn max_width(mut self, chars: usize) -> Self {
+        self.max_width = chars;
+        self
+    }
+
+    /// Adds the field, higher `priority` fields are kept first.
+    pub fn field(&mut self, priority: u8, args: fmt::Arguments) -> &mut Self {
+        let mut measure = Measure { len: 0, chars: 0 };
+        let _ = measure.write_fmt(args);
+        if self.count == PRIORITY_LINE_MAX_FIELDS {
+            self.dropped += 1;
+            return self;
+        }
+        if measure.len > self.room() {
+            // make room only from the fields not kept along with this one
+            self.fields[self.count] = Field {
+                priority,
+                start: 0,
+                len: measure.len,
+                chars: measure.chars,
+            };
+            let kept = self.select(self.count + 1);
+            while measure.len > self.room() {
+                let lowest = (0..self.count)
+                    .filter(|&k| kept & (1 << k) == 0)
+                    .min_by_key(|&k| (self.fields[k].priority, usize::MAX - k));
+                match lowest {
+                    Some(k) => self.remove(k),
+                    None => {
+                        self.dropped += 1;
+                        return self;
+                    }
+                }
+            }
+        }
+
+        let start = self.out.written_bytes();
+        let _ = self.out.write_fmt(args);
+        if self.out.is_truncated() {
+            // the value is shown longer than measured
+            self.out.truncate(start);
+            self.dropped += 1;
+            return self;
+        }
+        self.fields[self.count] = Field {
+            priority,
+            start,
+            len: self.out.written_bytes() - start,
+            chars: measure.chars,
+        };
+        self.count += 1;
+        self
+    }
+
+    /// Puts the separators between the kept fields, returns how many fields were dropped.
+    pub fn finish(mut self) -> usize {
+        let kept = self.select(self.count);
+        for k in (0..self.count).rev() {
+            if kept & (1 << k) == 0 {
+                self.remove(k);
+            }
+        }
+        let sep = self.separator.as_bytes();
+        let fields = &self.fields[..self.count];
+        let (buffer, used) = self.out.raw_parts();
+        // move the fields right starting from the last one, the room was checked
+        let mut end = *used + sep.len() * self.count.saturating_sub(1);
+        *used = end;
+        for (i, field) in fields.iter().enumerate().rev() {
+            let dest = end - field.len;
+            buffer.copy_within(field.start..field.start + field.len, dest);
+            if i > 0 {
+                buffer[dest - sep.len()..dest].copy_from_slice(sep);
+                end = dest - sep.len();
+            }
+        }
+        self.dropped
+    }
+
+    // Bytes left for the text of fields
+    fn room(&self) -> usize {
+        let used = self.fields[..self.count]
+            .iter()
+            .map(|f| f.len)
+            .sum::<usize>();
+        self.capacity - used
+    }
+
+    // Bit mask of the first `count` fields to keep, picked by priority then by order
+    fn select(&self, count: usize) -> u32 {
+        let mut order = [0usize; PRIORITY_LINE_MAX_FIELDS];
+        for k in 0..count {
+            // insertion sort, a stable one keeps the declaration order of ties
+            let mut i = k;
+            while i > 0 && self.fields[order[i - 1]].priority < self.fields[k].priority {
+                order[i] = order[i - 1];
+                i -= 1;
+            }
+            order[i] = k;
+        }
+        let sep_chars = self.separator.chars().count();
+        let (mut kept, mut len, mut chars) = (0u32, 0, 0);
+        for &k in &order[..count] {
+            let f = &self.fields[k];
+            let sep = if kept == 0 { 0 } else { 1 };
+            let new_len = len + f.len + sep * self.separator.len();
+            let new_chars = chars + f.chars + sep * sep_chars;
+            if new_len <= self.capacity && new_chars <= self.max_width {
+                kept |= 1 << k;
+                len = new_len;
+                chars = new_chars;
+            }
+        }
+        kept
+    }
+
+    // Removes the text of a field and moves the following ones left
+    fn remove(&mut self, k: usize) {
+        let removed = self.fields[k];
+        let (buffer, used) = self.out.raw_parts();
+        buffer.copy_within(removed.start + removed.len..*used, removed.start);
+        *used -= removed.len;
+        for f in &mut self.fields[k + 1..self.count] {
+            f.start -= removed.len;
+        }
+        self.fields.copy_within(k + 1..self.count, k);
+        self.count -= 1;
+        self.dropped += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PriorityLine;
+    use crate::WriteTo;
+
+    #[test]
+    fn priority_all_fit() {
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out).separator(" | ");
+        line.field(3, format_args!("a"))
+            .field(1, format_args!("{}", 22))
+            .field(2, format_args!("c"));
+        assert_eq!(line.finish(), 0);
+        assert_eq!(out.as_str(), "a | 22 | c");
+    }
+
+    #[test]
+    fn priority_lowest_dropped() {
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out).max_width(14);
+        line.field(5, format_args!("ssid"))
+            .field(1, format_args!("fw1.2"))
+            .field(4, format_args!("up{}h", 12));
+        assert_eq!(line.finish(), 1);
+        assert_eq!(out.as_str(), "ssid up12h");
+
+        // the buffer limits too, text before the line stays
+        let mut buf = [0u8; 15];
+        let mut out = WriteTo::new(&mut buf);
+        core::fmt::Write::write_str(&mut out, "> ").unwrap();
+        let mut line = PriorityLine::new(&mut out);
+        line.field(1, format_args!("low"))
+            .field(9, format_args!("high"))
+            .field(5, format_args!("mid"))
+            .field(2, format_args!("x"));
+        assert_eq!(line.finish(), 1);
+        assert_eq!(out.as_str(), "> high mid x");
+    }
+
+    #[test]
+    fn priority_only_top_fits() {
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out).separator("  ").max_width(10);
+        line.field(2, format_args!("192.168.0.1"))
+            .field(7, format_args!("ALARM"))
+            .field(3, format_args!("-70dBm"));
+        assert_eq!(line.finish(), 2);
+        assert_eq!(out.as_str(), "ALARM");
+
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out).max_width(3);
+        line.field(1, format_args!("long"));
+        assert_eq!(line.finish(), 1);
+        assert_eq!(out.as_str(), "");
+    }
+
+    #[test]
+    fn priority_ties_by_order() {
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out)
+            .separator("\u{2502}")
+            .max_width(9);
+        line.field(1, format_args!("one"))
+            .field(1, format_args!("two"))
+            .field(1, format_args!("six"));
+        assert_eq!(line.finish(), 1);
+        assert_eq!(out.as_str(), "one\u{2502}two");
+
+        // the later field of the lowest priority is removed first
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out).max_width(9);
+        line.field(1, format_args!("aa"))
+            .field(1, format_args!("bb"))
+            .field(5, format_args!("cccc"));
+        assert_eq!(line.finish(), 1);
+        assert_eq!(out.as_str(), "aa cccc");
+    }
+
+    #[test]
+    fn priority_rebuilt_from_scratch() {
+        // a higher field removed below does not take the room of a lower one
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out).max_width(8);
+        line.field(1, format_args!("x"))
+            .field(2, format_args!("yyyyyy"))
+            .field(9, format_args!("zzzzz"));
+        assert_eq!(line.finish(), 1);
+        assert_eq!(out.as_str(), "x zzzzz");
+
+        // the buffer only holds some of the texts
+        let mut buf = [0u8; 10];
+        let mut out = WriteTo::new(&mut buf);
+        let mut line = PriorityLine::new(&mut out);
+        line.field(1, format_args!("aaaa"))
+            .field(3, format_args!("bbb"))
+            .field(2, format_args!("cc"))
+            .field(5, format_args!("dddd"))
+            .field(4, format_args!("e"));
+        assert_eq!(line.finish(), 2);
+        assert_eq!(out.as_str(), "bbb dddd e");
+    }
+}
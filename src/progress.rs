@@ -0,0 +1,221 @@
+//  ( /   @ @    ()  Progress bar as text
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::repeated::RepeatedChar;
+use core::fmt;
+
+/// Display adapter that shows a progress bar like `[#####-----] 50% (512/1024)`.
+///
+/// The bar is 20 chars wide by default. Both the bar and the percentage are rounded down
+/// with integer math, so they show complete only when `current` reaches `total`. A
+/// `current` above `total` is clamped, a zero `total` is shown as complete. The percentage
+/// and the counts are added with [ProgressBar::percent] and [ProgressBar::counts].
+///
+/// ```
+/// use stackfmt::ProgressBar;
+///
+/// let mut buf = [0u8; 48];
+/// let bar = ProgressBar::new(512, 1024).width(10).percent(true).counts(true);
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("\r{} KiB", bar));
+/// assert_eq!(s, "\r[#####-----] 50% (512/1024) KiB");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProgressBar {
+    current: u64,
+    total: u64,
+    width: usize,
+    filled: char,
+    empty: char,
+    percent: bool,
+    counts: bool,
+}
+
+impl ProgressBar {
+    /// Creates adapter for `current` of `total` done.
+    pub fn new(current: u64, total: u64) -> Self {
+        ProgressBar {
+            current: current.min(total),
+            total,
+            width: 20,
+            filled: '#',
+            empty: '-',
+            percent: false,
+            counts: false,
+        }
+    }
+
+    /// Sets the number of chars between the brackets.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the char for the done part of the bar.
+    pub fn filled(mut self, filled: char) -> Self {
+        self.filled = filled;
+        self
+    }
+
+    /// Sets the char for the remaining part of the bar.
+    pub fn empty(mut self, empty: char) -> Self {
+        self.empty = empty;
+        self
+    }
+
+    /// Appends the percentage done, like ` 50%`.
+    pub fn percent(mut self, percent: bool) -> Self {
+        self.percent = percent;
+        self
+    }
+
+    /// Appends current and total, like ` (512/1024)`.
+    pub fn counts(mut self, counts: bool) -> Self {
+        self.counts = counts;
+        self
+    }
+
+    // current × scale / total rounded down, complete for zero total
+    fn part(&self, scale: u128) -> u128 {
+        if self.total == 0 {
+            scale
+        } else {
+            self.current as u128 * scale / self.total as u128
+        }
+    }
+}
+
+impl fmt::Display for ProgressBar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let filled = self.part(self.width as u128) as usize;
+        write!(
+            f,
+            "[{}{}]",
+            RepeatedChar(self.filled, filled),
+            RepeatedChar(self.empty, self.width - filled)
+        )?;
+        if self.percent {
+            write!(f, " {}%", self.part(100))?;
+        }
+        if self.counts {
+            write!(f, " ({}/{})", self.current, self.total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressBar;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn progress_ends() {
+        let mut buf = [0u8; 64];
+        let bar = |c, t| ProgressBar::new(c, t).width(10).percent(true);
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", bar(0, 50))),
+            "[----------] 0%"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", bar(50, 50))),
+            "[##########] 100%"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", bar(49, 50))),
+            "[#########-] 98%"
+        );
+        // clamped
+        let s = fmt_truncate(&mut buf, format_args!("{}", bar(70, 50).counts(true)));
+        assert_eq!(s, "[##########] 100% (50/50)");
+    }
+
+    #[test]
+    fn progress_rounding() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", ProgressBar::new(1, 3).width(10).percent(true)),
+        );
+        assert_eq!(s, "[###-------] 33%");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", ProgressBar::new(2, 3).width(10).percent(true)),
+        );
+        assert_eq!(s, "[######----] 66%");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}",
+                ProgressBar::new(u64::MAX - 1, u64::MAX)
+                    .width(4)
+                    .percent(true)
+            ),
+        );
+        assert_eq!(s, "[###-] 99%");
+    }
+
+    #[test]
+    fn progress_zero_total() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}",
+                ProgressBar::new(0, 0).width(4).percent(true).counts(true)
+            ),
+        );
+        assert_eq!(s, "[####] 100% (0/0)");
+    }
+
+    #[test]
+    fn progress_width() {
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", ProgressBar::new(5, 10).width(1))
+            ),
+            "[-]"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", ProgressBar::new(10, 10).width(1))
+            ),
+            "[#]"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", ProgressBar::new(10, 10).width(0))
+            ),
+            "[]"
+        );
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}",
+                ProgressBar::new(3, 4)
+                    .width(4)
+                    .filled('\u{2588}')
+                    .empty('\u{B7}')
+            ),
+        );
+        assert_eq!(s, "[\u{2588}\u{2588}\u{2588}\u{B7}]");
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", ProgressBar::new(1, 2))),
+            "[##########----------]"
+        );
+    }
+
+    #[test]
+    fn progress_small_buffer() {
+        let mut buf = [0u8; 8];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", ProgressBar::new(1, 2).width(10).percent(true)),
+        );
+        assert_eq!(s, "[#####--");
+    }
+}
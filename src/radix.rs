@@ -0,0 +1,206 @@
+//  ( /   @ @    ()  Integers in any base from 2 to 36
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+// Digits of u64::MAX in base 2
+const MAX_DIGITS: usize = 64;
+
+/// Display adapter that shows an integer in a base from 2 to 36 with digits `0-9a-z`, like
+/// base 36 short codes.
+///
+/// [Radix::uppercase] uses `A-Z`, [Radix::min_digits] adds leading zeros. Width, fill,
+/// alignment and the `+` and `0` flags work as for integers.
+///
+/// ```
+/// use stackfmt::Radix;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Radix::new(1295, 36)));
+/// assert_eq!(s, "zz");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Radix::signed(-7, 5).min_digits(4)));
+/// assert_eq!(s, "-0012");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Radix {
+    magnitude: u64,
+    negative: bool,
+    base: u8,
+    uppercase: bool,
+    min_digits: u8,
+}
+
+impl Radix {
+    /// Creates adapter for `value` in `base`, panics if `base` is not in `2..=36`.
+    pub fn new(value: u64, base: u8) -> Self {
+        assert!((2..=36).contains(&base), "base must be in 2..=36");
+        Radix {
+            magnitude: value,
+            negative: false,
+            base,
+            uppercase: false,
+            min_digits: 0,
+        }
+    }
+
+    /// Creates adapter for signed `value` in `base`, panics if `base` is not in `2..=36`.
+    pub fn signed(value: i64, base: u8) -> Self {
+        let mut radix = Self::new(value.unsigned_abs(), base);
+        radix.negative = value < 0;
+        radix
+    }
+
+    /// Uses `A-Z` for digits above 9.
+    pub fn uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+
+    /// Adds leading zeros up to `digits` digits, at most 64.
+    pub fn min_digits(mut self, digits: u8) -> Self {
+        self.min_digits = digits;
+        self
+    }
+}
+
+impl fmt::Display for Radix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letters = if self.uppercase { b'A' } else { b'a' };
+        let mut digits = [b'0'; MAX_DIGITS];
+        let (mut value, base) = (self.magnitude, self.base as u64);
+        let mut start = digits.len();
+        loop {
+            start -= 1;
+            let digit = (value % base) as u8;
+            digits[start] = match digit {
+                0..=9 => b'0' + digit,
+                _ => letters + digit - 10,
+            };
+            value /= base;
+            if value == 0 {
+                break;
+            }
+        }
+        start = start.min(MAX_DIGITS.saturating_sub(self.min_digits as usize));
+        // only ASCII digits are written
+        let text = unsafe { core::str::from_utf8_unchecked(&digits[start..]) };
+        f.pad_integral(!self.negative, "", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Radix;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn radix_base36() {
+        let mut buf = [0u8; 80];
+        let cases = [
+            (0, "0"),
+            (35, "z"),
+            (36, "10"),
+            (1296, "100"),
+            (46655, "zzz"),
+        ];
+        for (value, text) in cases {
+            assert_eq!(
+                fmt_truncate(&mut buf, format_args!("{}", Radix::new(value, 36))),
+                text
+            );
+        }
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Radix::new(1_000_000, 36).uppercase()),
+        );
+        assert_eq!(s, "LFLS");
+    }
+
+    #[test]
+    fn radix_base2_matches_std() {
+        let mut buf = [0u8; 80];
+        let mut expected = [0u8; 80];
+        for value in [0u64, 1, 2, 5, 255, 1 << 40, u64::MAX] {
+            let s = fmt_truncate(&mut expected, format_args!("{:b}", value));
+            let r = fmt_truncate(&mut buf, format_args!("{}", Radix::new(value, 2)));
+            assert_eq!(r, s);
+        }
+    }
+
+    #[test]
+    fn radix_base5_and_signed() {
+        let mut buf = [0u8; 80];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Radix::new(124, 5))),
+            "444"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Radix::new(125, 5))),
+            "1000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Radix::signed(-125, 5))),
+            "-1000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Radix::signed(i64::MIN, 16))),
+            "-8000000000000000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:+}", Radix::signed(6, 5))),
+            "+11"
+        );
+    }
+
+    #[test]
+    fn radix_width_padding() {
+        let mut buf = [0u8; 80];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Radix::new(5, 2).min_digits(8))),
+            "00000101"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Radix::new(1000, 2).min_digits(4))
+            ),
+            "1111101000"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Radix::new(1, 2).min_digits(200))
+            )
+            .len(),
+            64
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:>5}]", Radix::new(35, 36))),
+            "[    z]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:<4}]", Radix::new(7, 3))),
+            "[21  ]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:05}]", Radix::signed(-7, 3))),
+            "[-0021]"
+        );
+    }
+
+    #[test]
+    fn radix_u64_max_base3() {
+        let mut buf = [0u8; 80];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Radix::new(u64::MAX, 3))),
+            "11112220022122120101211020120210210211220"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn radix_invalid_base() {
+        let _ = Radix::new(1, 37);
+    }
+}
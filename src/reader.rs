@@ -0,0 +1,208 @@
+//  ( /   @ @    ()  UTF-8 text read from a byte source into WriteTo
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{Storage, WriteTo};
+use core::fmt;
+use embedded_io::Read;
+
+// Bytes read by a single call of the source
+const READ_CHUNK: usize = 32;
+
+/// Error of [WriteTo::extend_from_reader].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExtendError<E> {
+    /// The source failed
+    Io(E),
+    /// The data is not valid UTF-8 or ends in the middle of a char
+    InvalidUtf8,
+}
+
+impl<E: fmt::Display> fmt::Display for ExtendError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtendError::Io(e) => write!(f, "read failed: {}", e),
+            ExtendError::InvalidUtf8 => f.write_str("invalid UTF-8"),
+        }
+    }
+}
+
+pub(crate) fn extend_from_reader<S: Storage, R: Read>(
+    w: &mut WriteTo<S>,
+    reader: &mut R,
+    max: usize,
+) -> Result<usize, ExtendError<R::Error>> {
+    let mut chunk = [0u8; READ_CHUNK];
+    let (mut carry, mut total) = (0, 0); // bytes of an incomplete char at the chunk start
+    loop {
+        let room = max.saturating_sub(total).min(w.remaining_capacity());
+        // never read more than can be stored, a char cut by the limit stays unread or is
+        // dropped
+        let want = (chunk.len() - carry).min(room.saturating_sub(carry));
+        if want == 0 {
+            return Ok(total);
+        }
+        let n = reader
+            .read(&mut chunk[carry..carry + want])
+            .map_err(ExtendError::Io)?;
+        if n == 0 {
+            return match carry {
+                0 => Ok(total),
+                _ => Err(ExtendError::InvalidUtf8),
+            };
+        }
+        let len = carry + n;
+        let (valid, rest) = match core::str::from_utf8(&chunk[..len]) {
+            Ok(s) => (s, 0),
+            Err(e) if e.error_len().is_some() => {
+                let valid = e.valid_up_to();
+                // the chunk starts with whole chars, so the prefix is valid
+                let _ = fmt::Write::write_str(w, unsafe {
+                    core::str::from_utf8_unchecked(&chunk[..valid])
+                });
+                return Err(ExtendError::InvalidUtf8);
+            }
+            Err(e) => {
+                let valid = e.valid_up_to();
+                let s = unsafe { core::str::from_utf8_unchecked(&chunk[..valid]) };
+                (s, len - valid)
+            }
+        };
+        let _ = fmt::Write::write_str(w, valid);
+        total += valid.len();
+        chunk.copy_within(len - rest..len, 0);
+        carry = rest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExtendError;
+    use crate::WriteTo;
+    use core::fmt::Write;
+    use embedded_io::{ErrorKind, ErrorType, Read};
+
+    // Gives `data` in pieces of the sizes from `sizes`, repeating the last one
+    struct Chunked<'d> {
+        data: &'d [u8],
+        sizes: &'d [usize],
+        calls: usize,
+        fail_at_end: bool,
+    }
+
+    impl<'d> Chunked<'d> {
+        fn new(data: &'d [u8], sizes: &'d [usize]) -> Self {
+            Chunked {
+                data,
+                sizes,
+                calls: 0,
+                fail_at_end: false,
+            }
+        }
+    }
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct LinkDown;
+
+    impl embedded_io::Error for LinkDown {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::BrokenPipe
+        }
+    }
+
+    impl<'d> ErrorType for Chunked<'d> {
+        type Error = LinkDown;
+    }
+
+    impl<'d> Read for Chunked<'d> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, LinkDown> {
+            if self.data.is_empty() && self.fail_at_end {
+                return Err(LinkDown);
+            }
+            let size = self.sizes[self.calls.min(self.sizes.len() - 1)];
+            self.calls += 1;
+            let n = size.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn reader_awkward_chunks() {
+        let text = "body: 25\u{B0}C \u{20AC}5 \u{1F600} end";
+        for size in 1..8 {
+            let mut buf = [0u8; 64];
+            let mut w = WriteTo::new(&mut buf);
+            write!(w, "req ").unwrap();
+            let sizes = [size, size + 2, 1];
+            let mut r = Chunked::new(text.as_bytes(), &sizes);
+            assert_eq!(w.extend_from_reader(&mut r, 100), Ok(text.len()));
+            w.write_str("!").unwrap();
+            assert_eq!(
+                w.to_str().strip_prefix("req "),
+                Some("body: 25\u{B0}C \u{20AC}5 \u{1F600} end!")
+            );
+        }
+    }
+
+    #[test]
+    fn reader_invalid_utf8() {
+        let mut buf = [0u8; 64];
+        let mut w = WriteTo::new(&mut buf);
+        let mut r = Chunked::new(b"ok \xE2\x82 bad", &[4]);
+        assert_eq!(
+            w.extend_from_reader(&mut r, 100),
+            Err(ExtendError::InvalidUtf8)
+        );
+        assert_eq!(w.as_str(), "ok ");
+        // ends in the middle of a char
+        let mut buf = [0u8; 64];
+        let mut w = WriteTo::new(&mut buf);
+        let mut r = Chunked::new(b"ok \xE2\x82", &[2]);
+        assert_eq!(
+            w.extend_from_reader(&mut r, 100),
+            Err(ExtendError::InvalidUtf8)
+        );
+        assert_eq!(w.as_str(), "ok ");
+        // failed source
+        let mut buf = [0u8; 64];
+        let mut w = WriteTo::new(&mut buf);
+        let mut r = Chunked::new(b"abc", &[2]);
+        r.fail_at_end = true;
+        assert_eq!(
+            w.extend_from_reader(&mut r, 100),
+            Err(ExtendError::Io(LinkDown))
+        );
+        assert_eq!(w.as_str(), "abc");
+    }
+
+    #[test]
+    fn reader_exact_capacity() {
+        let mut buf = [0u8; 8];
+        let mut w = WriteTo::new(&mut buf);
+        w.write_str("id=").unwrap();
+        let mut r = Chunked::new(b"12345678", &[3]);
+        assert_eq!(w.extend_from_reader(&mut r, 100), Ok(5));
+        assert_eq!(r.data, b"678"); // nothing read beyond the capacity
+        assert_eq!(w.extend_from_reader(&mut r, 100), Ok(0));
+        assert_eq!(w.as_str(), "id=12345");
+
+        // the euro sign does not fit and is dropped
+        let mut buf = [0u8; 8];
+        let mut w = WriteTo::new(&mut buf);
+        let mut r = Chunked::new("abcdef\u{20AC}".as_bytes(), &[5]);
+        assert_eq!(w.extend_from_reader(&mut r, 100), Ok(6));
+        assert_eq!(w.as_str(), "abcdef");
+    }
+
+    #[test]
+    fn reader_max() {
+        let mut buf = [0u8; 32];
+        let mut w = WriteTo::new(&mut buf);
+        let mut r = Chunked::new("\u{E4}\u{E4}\u{E4}\u{E4}".as_bytes(), &[7]);
+        assert_eq!(w.extend_from_reader(&mut r, 5), Ok(4));
+        assert_eq!(w.extend_from_reader(&mut r, 0), Ok(0));
+        assert_eq!(w.as_str(), "\u{E4}\u{E4}");
+    }
+}
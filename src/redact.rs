@@ -0,0 +1,154 @@
+//  ( /   @ @    ()  Masks digits in formatted output except the last few of each number
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::str::from_utf8_unchecked;
+
+/// The most digits [RedactDigitsWriter] can keep at the end of a run
+pub const REDACT_MAX_KEEP: usize = 32;
+
+/// Impl of [fmt::Write] that replaces ASCII digits with `*` except the last `keep` digits of
+/// every run of consecutive digits.
+///
+/// A run split across several `write_str` calls is still one run. Only the last `keep`
+/// digits are held back (at most [REDACT_MAX_KEEP]), every earlier digit is masked as soon as
+/// it is known not to be among them, so runs of any length are handled in fixed memory.
+/// The held digits of a run at the end of the output are written by
+/// [RedactDigitsWriter::finish].
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_redact_digits(&mut buf, format_args!("card {}", 4111222233334444u64));
+/// assert_eq!(s, "card ************4444");
+/// ```
+pub struct RedactDigitsWriter<W: fmt::Write> {
+    inner: W,
+    keep: usize,
+    held: [u8; REDACT_MAX_KEEP], // The last digits of the current run
+    held_len: usize,
+}
+
+impl<W: fmt::Write> RedactDigitsWriter<W> {
+    /// Creates stream that keeps the last 4 digits of every run.
+    pub fn new(inner: W) -> Self {
+        Self::with_keep(inner, 4)
+    }
+
+    /// Creates stream that keeps the last `keep` digits of every run.
+    ///
+    /// Panics if `keep` is greater than [REDACT_MAX_KEEP].
+    pub fn with_keep(inner: W, keep: usize) -> Self {
+        assert!(
+            keep <= REDACT_MAX_KEEP,
+            "RedactDigitsWriter keeps too many digits"
+        );
+        RedactDigitsWriter {
+            inner,
+            keep,
+            held: [0u8; REDACT_MAX_KEEP],
+            held_len: 0,
+        }
+    }
+
+    /// Writes the digits held at the end of the output and returns the inner stream.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        self.release_held()?;
+        Ok(self.inner)
+    }
+
+    fn release_held(&mut self) -> fmt::Result {
+        // only ASCII digits are held
+        let digits = unsafe { from_utf8_unchecked(&self.held[..self.held_len]) };
+        self.held_len = 0;
+        self.inner.write_str(digits)
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for RedactDigitsWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0; // Start of the text (non-digit) run to forward
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            if !b.is_ascii_digit() {
+                if self.held_len > 0 {
+                    self.release_held()?;
+                }
+                continue;
+            }
+
+            self.inner.write_str(&s[run_start..i])?;
+            run_start = i + 1;
+            if self.keep == 0 {
+                self.inner.write_str("*")?;
+            } else {
+                if self.held_len == self.keep {
+                    // the oldest held digit is not among the last ones
+                    self.inner.write_str("*")?;
+                    self.held.copy_within(1..self.held_len, 0);
+                    self.held_len -= 1;
+                }
+                self.held[self.held_len] = b;
+                self.held_len += 1;
+            }
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+/// Writes formatted string with all but the last 4 digits of every number masked into the
+/// buffer truncating if needed.
+pub fn fmt_redact_digits<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = RedactDigitsWriter::new(WriteTo::new(buffer));
+    match fmt::write(&mut w, args) {
+        Ok(_) => match w.finish() {
+            Ok(w) => w.as_str(),
+            Err(_) => "",
+        },
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RedactDigitsWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn redact_16_digits() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_redact_digits(&mut buf, format_args!("{}", "5500000000000004"));
+        assert_eq!(s, "************0004");
+    }
+
+    #[test]
+    fn redact_short_runs() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_redact_digits(&mut buf, format_args!("id 12, 1234, 12345"));
+        assert_eq!(s, "id 12, 1234, *2345");
+    }
+
+    #[test]
+    fn redact_split_runs() {
+        let mut buf = [0u8; 64];
+        let mut w = RedactDigitsWriter::new(WriteTo::new(&mut buf));
+        w.write_str("+1 55").unwrap();
+        w.write_str("5").unwrap();
+        w.write_str("0123").unwrap();
+        w.write_str("45x").unwrap();
+        assert_eq!(w.finish().unwrap().as_str(), "+1 *****2345x");
+    }
+
+    #[test]
+    fn redact_interleaved_text() {
+        let mut buf = [0u8; 64];
+        let mut w = RedactDigitsWriter::with_keep(WriteTo::new(&mut buf), 2);
+        write!(w, "tel:{}-{} \u{20AC}{}", 555, 1234567, 99).unwrap();
+        assert_eq!(w.finish().unwrap().as_str(), "tel:*55-*****67 \u{20AC}99");
+
+        let mut w = RedactDigitsWriter::with_keep(WriteTo::new(&mut buf), 0);
+        write!(w, "pin {}", 1234).unwrap();
+        assert_eq!(w.finish().unwrap().as_str(), "pin ****");
+    }
+}
@@ -0,0 +1,188 @@
+//  ( /   @ @    ()  Register values listed with aligned names
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::bit_names::BitNames;
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Register name, value and a `(mask, name)` table of its flags for [fmt_registers_with_bits].
+pub type DecodedRegister<'a> = (&'a str, u32, &'a [(u32, &'a str)]);
+
+// Enough for a long name, the value and a few decoded flags
+const LINE_BUFFER_SIZE: usize = 160;
+
+// Writes `NAME = 0x0000_2001` with the name padded to `width`, then the set flags if a
+// table is given
+fn write_register<W: fmt::Write>(
+    w: &mut W,
+    width: usize,
+    name: &str,
+    value: u32,
+    bits: &[(u32, &str)],
+) -> fmt::Result {
+    write!(
+        w,
+        "{:<width$} = 0x{:04X}_{:04X}",
+        name,
+        value >> 16,
+        value & 0xFFFF,
+        width = width
+    )?;
+    if !bits.is_empty() {
+        write!(w, " [{}]", BitNames(value, bits))?;
+    }
+    Ok(())
+}
+
+// Chars of the longest name
+fn name_width<'r, I: Iterator<Item = &'r str>>(names: I) -> usize {
+    names.map(|name| name.chars().count()).max().unwrap_or(0)
+}
+
+// Writes lines ending with `\n` into the buffer, a line that does not fit is dropped with
+// all the following ones
+fn fmt_lines<'a, F>(buffer: &'a mut [u8], count: usize, mut write_line: F) -> &'a str
+where
+    F: FnMut(&mut WriteTo<'a>, usize) -> fmt::Result,
+{
+    let mut w = WriteTo::new(buffer);
+    for i in 0..count {
+        let start = w.written_bytes();
+        let _ = write_line(&mut w, i);
+        let _ = w.write_char('\n');
+        if w.is_truncated() {
+            w.truncate(start);
+            break;
+        }
+    }
+    w.as_str()
+}
+
+/// Writes registers as `NAME = 0x0000_2001` lines, each ending with `\n`, into the buffer.
+///
+/// Names are padded to the longest one so the values line up, values are shown as 8 hex
+/// digits with `_` in the middle. A line that does not fit is dropped with all the following
+/// ones, so the output never ends with half of a value.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let s = stackfmt::fmt_registers(&mut buf, &[("CR1", 0x2001), ("ISR", 0xC0), ("BRR", 0x45)]);
+/// assert_eq!(s, "CR1 = 0x0000_2001\nISR = 0x0000_00C0\nBRR = 0x0000_0045\n");
+/// ```
+pub fn fmt_registers<'a>(buffer: &'a mut [u8], regs: &[(&str, u32)]) -> &'a str {
+    let width = name_width(regs.iter().map(|r| r.0));
+    fmt_lines(buffer, regs.len(), |w, i| {
+        write_register(w, width, regs[i].0, regs[i].1, &[])
+    })
+}
+
+/// Same as [fmt_registers] but every register has a `(mask, name)` table of its flags, the
+/// set ones are appended as `[RXNE|TC]` (see [BitNames]). An empty table appends nothing.
+///
+/// ```
+/// const ISR: &[(u32, &str)] = &[(0x20, "RXNE"), (0x40, "TC"), (0x80, "TXE")];
+///
+/// let mut buf = [0u8; 64];
+/// let s = stackfmt::fmt_registers_with_bits(&mut buf, &[("CR1", 0x2001, &[]), ("ISR", 0xC0, ISR)]);
+/// assert_eq!(s, "CR1 = 0x0000_2001\nISR = 0x0000_00C0 [TC|TXE]\n");
+/// ```
+pub fn fmt_registers_with_bits<'a>(buffer: &'a mut [u8], regs: &[DecodedRegister]) -> &'a str {
+    let width = name_width(regs.iter().map(|r| r.0));
+    fmt_lines(buffer, regs.len(), |w, i| {
+        write_register(w, width, regs[i].0, regs[i].1, regs[i].2)
+    })
+}
+
+/// Passes registers as lines (without newline) of [fmt_registers] to `emit`, for lists that
+/// do not fit into a buffer.
+///
+/// Only a line-sized buffer is used, a line longer than 160 bytes is truncated.
+pub fn register_lines<F: FnMut(&str)>(regs: &[(&str, u32)], mut emit: F) {
+    let width = name_width(regs.iter().map(|r| r.0));
+    for &(name, value) in regs {
+        let mut buf = [0u8; LINE_BUFFER_SIZE];
+        let mut w = WriteTo::new(&mut buf);
+        let _ = write_register(&mut w, width, name, value, &[]);
+        emit(w.as_str());
+    }
+}
+
+/// Passes registers as lines of [fmt_registers_with_bits] to `emit`, see [register_lines].
+pub fn register_lines_with_bits<F: FnMut(&str)>(regs: &[DecodedRegister], mut emit: F) {
+    let width = name_width(regs.iter().map(|r| r.0));
+    for &(name, value, bits) in regs {
+        let mut buf = [0u8; LINE_BUFFER_SIZE];
+        let mut w = WriteTo::new(&mut buf);
+        let _ = write_register(&mut w, width, name, value, bits);
+        emit(w.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_registers, fmt_registers_with_bits, register_lines, register_lines_with_bits};
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    const SR: &[(u32, &str)] = &[(0x01, "BSY"), (0x02, "ERR")];
+
+    #[test]
+    fn registers_name_lengths() {
+        let mut buf = [0u8; 128];
+        assert_eq!(
+            fmt_registers(
+                &mut buf,
+                &[("A", 1), ("CTRL", 0xFFFF_0000), ("DR", 0xDEAD_BEEF)]
+            ),
+            "A    = 0x0000_0001\nCTRL = 0xFFFF_0000\nDR   = 0xDEAD_BEEF\n"
+        );
+
+        let mut out = [0u8; 128];
+        let mut log = WriteTo::new(&mut out);
+        register_lines(&[("TIMx_ARR", 999), ("PSC", 7)], |line| {
+            let _ = writeln!(log, "<{}>", line);
+        });
+        assert_eq!(
+            log.as_str(),
+            "<TIMx_ARR = 0x0000_03E7>\n<PSC      = 0x0000_0007>\n"
+        );
+    }
+
+    #[test]
+    fn registers_empty() {
+        let mut buf = [0u8; 16];
+        assert_eq!(fmt_registers(&mut buf, &[]), "");
+        let mut lines = 0;
+        register_lines(&[], |_| lines += 1);
+        assert_eq!(lines, 0);
+    }
+
+    #[test]
+    fn registers_drop_whole_lines() {
+        let mut buf = [0u8; 30];
+        assert_eq!(
+            fmt_registers(&mut buf, &[("CR1", 0x2001), ("ISR", 0xC0)]),
+            "CR1 = 0x0000_2001\n"
+        );
+        let mut buf = [0u8; 17];
+        assert_eq!(fmt_registers(&mut buf, &[("CR1", 0x2001)]), "");
+    }
+
+    #[test]
+    fn registers_bit_decode() {
+        let mut buf = [0u8; 128];
+        assert_eq!(
+            fmt_registers_with_bits(&mut buf, &[("SR", 3, SR), ("STATUS", 0x104, SR), ("X", 0, SR)]),
+            "SR     = 0x0000_0003 [BSY|ERR]\nSTATUS = 0x0000_0104 [0x104]\nX      = 0x0000_0000 [none]\n"
+        );
+
+        let mut out = [0u8; 128];
+        let mut log = WriteTo::new(&mut out);
+        register_lines_with_bits(&[("SR", 2, SR), ("DR", 0x41, &[])], |line| {
+            let _ = writeln!(log, "{}", line);
+        });
+        assert_eq!(log.as_str(), "SR = 0x0000_0002 [ERR]\nDR = 0x0000_0041\n");
+    }
+}
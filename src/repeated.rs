@@ -0,0 +1,166 @@
+//  ( /   @ @    ()  A piece of text repeated a number of times
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use core::fmt;
+
+// Size of the stack chunk filled with copies of the piece
+const CHUNK_SIZE: usize = 64;
+
+/// Display adapter that writes `piece` `n` times, like a `----------` rule.
+///
+/// Copies of the piece are collected into a 64 byte chunk on the stack and written chunk by
+/// chunk, so a long repetition costs few `write_str()` calls. Width and alignment apply to
+/// the whole repetition. When the destination buffer of [fmt_truncate](crate::fmt_truncate)
+/// overflows the text is cut on a char boundary, which may be in the middle of a piece.
+///
+/// ```
+/// use stackfmt::Repeated;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("+{}+", Repeated::new("-", 8)));
+/// assert_eq!(s, "+--------+");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("[{:^8}]", Repeated::new("<>", 2)));
+/// assert_eq!(s, "[  <><>  ]");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Repeated<'a> {
+    piece: &'a str,
+    n: usize,
+}
+
+impl<'a> Repeated<'a> {
+    /// Creates adapter writing `piece` `n` times.
+    pub fn new(piece: &'a str, n: usize) -> Self {
+        Repeated { piece, n }
+    }
+}
+
+impl<'a> fmt::Display for Repeated<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let len = self.piece.chars().count().saturating_mul(self.n);
+        pad_with(f, len, fmt::Alignment::Left, |f| {
+            write_repeated(f, self.piece, self.n)
+        })
+    }
+}
+
+/// Display adapter that writes the char `.0` `.1` times.
+///
+/// Same as [Repeated] for a single char piece.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", stackfmt::RepeatedChar('\u{2550}', 3)));
+/// assert_eq!(s, "\u{2550}\u{2550}\u{2550}");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RepeatedChar(pub char, pub usize);
+
+impl fmt::Display for RepeatedChar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut utf8 = [0u8; 4];
+        fmt::Display::fmt(&Repeated::new(self.0.encode_utf8(&mut utf8), self.1), f)
+    }
+}
+
+fn write_repeated(f: &mut fmt::Formatter, piece: &str, n: usize) -> fmt::Result {
+    if piece.is_empty() || n == 0 {
+        return Ok(());
+    }
+    let per_chunk = CHUNK_SIZE / piece.len();
+    if per_chunk <= 1 {
+        for _ in 0..n {
+            f.write_str(piece)?;
+        }
+        return Ok(());
+    }
+
+    let mut chunk = [0u8; CHUNK_SIZE];
+    let chunk_len = per_chunk * piece.len();
+    for copy in chunk[..chunk_len].chunks_exact_mut(piece.len()) {
+        copy.copy_from_slice(piece.as_bytes());
+    }
+    // whole copies of a valid str are valid str
+    let chunk = match core::str::from_utf8(&chunk[..chunk_len]) {
+        Ok(chunk) => chunk,
+        Err(_) => return Err(fmt::Error),
+    };
+    for _ in 0..n / per_chunk {
+        f.write_str(chunk)?;
+    }
+    f.write_str(&chunk[..n % per_chunk * piece.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Repeated, RepeatedChar};
+    use crate::fmt_truncate;
+
+    #[test]
+    fn repeated_zero() {
+        let mut buf = [0u8; 8];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Repeated::new("ab", 0))),
+            "[]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", RepeatedChar('x', 0))),
+            "[]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Repeated::new("", 5))),
+            "[]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:3}]", Repeated::new("ab", 0))),
+            "[   ]"
+        );
+    }
+
+    #[test]
+    fn repeated_counts() {
+        let mut buf = [0u8; 200];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Repeated::new("abc", 50)));
+        assert_eq!(s.len(), 150);
+        assert!(s.as_bytes().chunks(3).all(|piece| piece == b"abc"));
+        // 64 bytes exactly, one chunk
+        let s = fmt_truncate(&mut buf, format_args!("{}", RepeatedChar('-', 64)));
+        assert_eq!(s.len(), 64);
+        assert!(s.bytes().all(|b| b == b'-'));
+        // piece longer than a chunk
+        let long = "0123456789012345678901234567890123456789012345678901234567890123456789";
+        let s = fmt_truncate(&mut buf, format_args!("{}", Repeated::new(long, 2)));
+        assert_eq!(&s[..70], long);
+        assert_eq!(&s[70..], long);
+    }
+
+    #[test]
+    fn repeated_multi_byte() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Repeated::new("\u{20AC}-", 3)));
+        assert_eq!(s, "\u{20AC}-\u{20AC}-\u{20AC}-");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:>5}]", Repeated::new("\u{20AC}", 3)),
+        );
+        assert_eq!(s, "[  \u{20AC}\u{20AC}\u{20AC}]");
+    }
+
+    #[test]
+    fn repeated_truncates() {
+        // cut on a piece boundary
+        let mut buf = [0u8; 10];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Repeated::new("ab", 1000)));
+        assert_eq!(s, "ababababab");
+        // cut in the middle of a piece
+        let mut buf = [0u8; 7];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Repeated::new("abc", 1000)));
+        assert_eq!(s, "abcabca");
+        // never in the middle of a char
+        let mut buf = [0u8; 8];
+        let s = fmt_truncate(&mut buf, format_args!("{}", RepeatedChar('\u{20AC}', 1000)));
+        assert_eq!(s, "\u{20AC}\u{20AC}");
+    }
+}
@@ -0,0 +1,312 @@
+//  ( /   @ @    ()  Format strings interpreted at runtime
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::{write_padded, CharCounter};
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [fmt_runtime], `pos` is the byte offset in the template.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RuntimeFmtError {
+    /// `{` without matching `}`
+    UnclosedBrace { pos: usize },
+    /// `}` that is not part of `}}` or a placeholder
+    UnmatchedClose { pos: usize },
+    /// Placeholder with index or spec that can not be parsed
+    BadPlaceholder { pos: usize },
+    /// Placeholder refers to an argument that was not given
+    MissingArgument { pos: usize, index: usize },
+    /// An argument returned an error when formatted
+    Format,
+}
+
+impl fmt::Display for RuntimeFmtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuntimeFmtError::UnclosedBrace { pos } => write!(f, "unclosed '{{' at {}", pos),
+            RuntimeFmtError::UnmatchedClose { pos } => write!(f, "unmatched '}}' at {}", pos),
+            RuntimeFmtError::BadPlaceholder { pos } => write!(f, "bad placeholder at {}", pos),
+            RuntimeFmtError::MissingArgument { pos, index } => {
+                write!(
+                    f,
+                    "placeholder at {} refers to missing argument {}",
+                    pos, index
+                )
+            }
+            RuntimeFmtError::Format => f.write_str("argument formatting failed"),
+        }
+    }
+}
+
+// Parsed `:[[fill]align][0][width][.precision]`
+#[derive(Default)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<fmt::Alignment>,
+    zero: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+}
+
+fn parse_align(c: char) -> Option<fmt::Alignment> {
+    match c {
+        '<' => Some(fmt::Alignment::Left),
+        '^' => Some(fmt::Alignment::Center),
+        '>' => Some(fmt::Alignment::Right),
+        _ => None,
+    }
+}
+
+// Splits leading decimal digits, None if there are none
+fn parse_number(s: &str) -> Option<(usize, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    match s[..end].parse() {
+        Ok(n) => Some((n, &s[end..])),
+        Err(_) => None,
+    }
+}
+
+fn parse_spec(mut s: &str) -> Option<Spec> {
+    let mut spec = Spec::default();
+    let mut chars = s.chars();
+    if let (Some(fill), Some(align)) = (chars.next(), chars.next().and_then(parse_align)) {
+        spec.fill = Some(fill);
+        spec.align = Some(align);
+        s = chars.as_str();
+    } else if let Some(align) = s.chars().next().and_then(parse_align) {
+        spec.align = Some(align);
+        s = &s[1..];
+    }
+    if let Some(rest) = s.strip_prefix('0') {
+        spec.zero = true;
+        s = rest;
+    }
+    if let Some((width, rest)) = parse_number(s) {
+        spec.width = Some(width);
+        s = rest;
+    }
+    if let Some(rest) = s.strip_prefix('.') {
+        let (precision, rest) = parse_number(rest)?;
+        spec.precision = Some(precision);
+        s = rest;
+    }
+    if s.is_empty() {
+        Some(spec)
+    } else {
+        None
+    }
+}
+
+// Argument with optional precision; width, fill and alignment are applied by the caller
+struct WithPrecision<'a>(&'a dyn fmt::Display, Option<usize>);
+
+impl<'a> fmt::Display for WithPrecision<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.1 {
+            Some(precision) => write!(f, "{:.*}", precision, self.0),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+// Argument formatted with the runtime spec
+struct WithSpec<'a>(&'a dyn fmt::Display, &'a Spec);
+
+impl<'a> fmt::Display for WithSpec<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let spec = self.1;
+        let arg = WithPrecision(self.0, spec.precision);
+        let width = match spec.width {
+            Some(width) => width,
+            None => return arg.fmt(f),
+        };
+        if spec.align.is_none() {
+            // the argument picks its default alignment as with std: numbers go right
+            return match (spec.zero, spec.precision) {
+                (true, Some(p)) => write!(f, "{:0w$.p$}", self.0, w = width, p = p),
+                (true, None) => write!(f, "{:0w$}", self.0, w = width),
+                (false, Some(p)) => write!(f, "{:w$.p$}", self.0, w = width, p = p),
+                (false, None) => write!(f, "{:w$}", self.0, w = width),
+            };
+        }
+        let mut counter = CharCounter {
+            count: 0,
+            limit: width,
+        };
+        write!(counter, "{}", arg)?;
+        let (fill, align) = (
+            spec.fill.unwrap_or(' '),
+            spec.align.unwrap_or(fmt::Alignment::Left),
+        );
+        write_padded(f, width - counter.count, fill, align, |f| arg.fmt(f))
+    }
+}
+
+/// Formats `args` with a format string known only at runtime, like a translated UI text.
+///
+/// Supported placeholders are `{}` (next argument), `{N}` (argument by index) and both with
+/// a spec `{:[[fill]align][0][width][.precision]}`, e.g. `{0:>8.2}` or `{:*^10}`. Use `{{`
+/// and `}}` for braces. Without alignment the width is passed to the argument, so as with std
+/// numbers are aligned right and strings left. An argument with width and alignment is
+/// formatted twice: to measure and to write. The text is
+/// truncated to the buffer as by [fmt_truncate](crate::fmt_truncate).
+///
+/// ```
+/// let args: [&dyn core::fmt::Display; 2] = [&"Alice", &3];
+/// let mut buf = [0u8; 48];
+/// let template = "{1} neue Nachrichten f\u{FC}r {0}";
+/// let s = stackfmt::fmt_runtime(&mut buf, template, &args).unwrap();
+/// assert_eq!(s, "3 neue Nachrichten f\u{FC}r Alice");
+/// ```
+pub fn fmt_runtime<'a>(
+    buffer: &'a mut [u8],
+    template: &str,
+    args: &[&dyn fmt::Display],
+) -> Result<&'a str, RuntimeFmtError> {
+    let mut w = WriteTo::new(buffer);
+    let mut next_arg = 0;
+    let mut rest = template;
+    while let Some(brace) = rest.find(['{', '}']) {
+        let pos = template.len() - rest.len() + brace;
+        w.write_str(&rest[..brace])
+            .map_err(|_| RuntimeFmtError::Format)?;
+        let after = &rest[brace + 1..];
+        if rest[brace..].starts_with("{{") || rest[brace..].starts_with("}}") {
+            w.write_str(&rest[brace..brace + 1])
+                .map_err(|_| RuntimeFmtError::Format)?;
+            rest = &after[1..];
+            continue;
+        }
+        if rest.as_bytes()[brace] == b'}' {
+            return Err(RuntimeFmtError::UnmatchedClose { pos });
+        }
+
+        let close = match after.find('}') {
+            Some(close) => close,
+            None => return Err(RuntimeFmtError::UnclosedBrace { pos }),
+        };
+        let placeholder = &after[..close];
+        let (index, spec) = match placeholder.find(':') {
+            Some(colon) => (&placeholder[..colon], &placeholder[colon + 1..]),
+            None => (placeholder, ""),
+        };
+        let index = if index.is_empty() {
+            next_arg += 1;
+            next_arg - 1
+        } else {
+            match parse_number(index) {
+                Some((index, "")) => index,
+                _ => return Err(RuntimeFmtError::BadPlaceholder { pos }),
+            }
+        };
+        let spec = parse_spec(spec).ok_or(RuntimeFmtError::BadPlaceholder { pos })?;
+        let arg = match args.get(index) {
+            Some(arg) => *arg,
+            None => return Err(RuntimeFmtError::MissingArgument { pos, index }),
+        };
+        write!(w, "{}", WithSpec(arg, &spec)).map_err(|_| RuntimeFmtError::Format)?;
+        rest = &after[close + 1..];
+    }
+    w.write_str(rest).map_err(|_| RuntimeFmtError::Format)?;
+    Ok(w.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_runtime, RuntimeFmtError};
+    use core::fmt::Display;
+
+    #[test]
+    fn runtime_positional() {
+        let mut buf = [0u8; 64];
+        let args: [&dyn Display; 3] = [&"file.txt", &"/tmp", &42];
+        assert_eq!(
+            fmt_runtime(&mut buf, "{} copied to {} ({} bytes)", &args),
+            Ok("file.txt copied to /tmp (42 bytes)")
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "{2} Bytes: {1} \u{2190} {0}", &args),
+            Ok("42 Bytes: /tmp \u{2190} file.txt")
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "{0}{0}{}", &args),
+            Ok("file.txtfile.txtfile.txt")
+        );
+        assert_eq!(fmt_runtime(&mut buf, "no args", &[]), Ok("no args"));
+    }
+
+    #[test]
+    fn runtime_escaped_braces() {
+        let mut buf = [0u8; 64];
+        let args: [&dyn Display; 1] = [&7];
+        assert_eq!(fmt_runtime(&mut buf, "{{{}}}", &args), Ok("{7}"));
+        assert_eq!(fmt_runtime(&mut buf, "}}{{", &args), Ok("}{"));
+    }
+
+    #[test]
+    fn runtime_spec() {
+        let mut buf = [0u8; 64];
+        let args: [&dyn Display; 3] = [&1.23456, &"abc", &-42];
+        assert_eq!(fmt_runtime(&mut buf, "[{0:.2}]", &args), Ok("[1.23]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{0:>8.3}]", &args), Ok("[   1.235]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{1:5}]", &args), Ok("[abc  ]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{1:*^7}]", &args), Ok("[**abc**]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{1:.2}]", &args), Ok("[ab]"));
+        assert_eq!(
+            fmt_runtime(&mut buf, "[{1:\u{B7}>4.1}]", &args),
+            Ok("[\u{B7}\u{B7}\u{B7}a]")
+        );
+        assert_eq!(fmt_runtime(&mut buf, "[{2:06}]", &args), Ok("[-00042]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{2:2}]", &args), Ok("[-42]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{2:5}]", &args), Ok("[  -42]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{0:7.1}]", &args), Ok("[    1.2]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{2:<5}]", &args), Ok("[-42  ]"));
+        assert_eq!(fmt_runtime(&mut buf, "[{0:07.2}]", &args), Ok("[0001.23]"));
+    }
+
+    #[test]
+    fn runtime_malformed() {
+        let mut buf = [0u8; 64];
+        let args: [&dyn Display; 1] = [&1];
+        assert_eq!(
+            fmt_runtime(&mut buf, "ab{", &args),
+            Err(RuntimeFmtError::UnclosedBrace { pos: 2 })
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "a}b", &args),
+            Err(RuntimeFmtError::UnmatchedClose { pos: 1 })
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "{x}", &args),
+            Err(RuntimeFmtError::BadPlaceholder { pos: 0 })
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "{:q}", &args),
+            Err(RuntimeFmtError::BadPlaceholder { pos: 0 })
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "{:5.}", &args),
+            Err(RuntimeFmtError::BadPlaceholder { pos: 0 })
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "{} {}", &args),
+            Err(RuntimeFmtError::MissingArgument { pos: 3, index: 1 })
+        );
+        assert_eq!(
+            fmt_runtime(&mut buf, "\u{20AC}{7}", &args),
+            Err(RuntimeFmtError::MissingArgument { pos: 3, index: 7 })
+        );
+    }
+
+    #[test]
+    fn runtime_truncates() {
+        let mut buf = [0u8; 8];
+        let args: [&dyn Display; 1] = [&"\u{20AC}\u{20AC}\u{20AC}"];
+        assert_eq!(
+            fmt_runtime(&mut buf, "ab{}cd", &args),
+            Ok("ab\u{20AC}\u{20AC}")
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//  ( /   @ @    ()  Output to the debugger host with ARM semihosting
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+// SYS_WRITE0: writes a NUL terminated string to the host console
+#[cfg(all(target_arch = "arm", target_os = "none"))]
+const SYS_WRITE0: usize = 0x04;
+
+// Passes NUL terminated `text` to the host in one semihosting call, `bkpt 0xAB` is the
+// Cortex-M (M-profile) form of the call
+#[cfg(all(target_arch = "arm", target_os = "none"))]
+fn host_write0(text: &[u8]) {
+    // the host reads the string at r1 while the core is halted by the breakpoint
+    unsafe {
+        core::arch::asm!(
+            "bkpt #0xAB",
+            inout("r0") SYS_WRITE0 => _,
+            in("r1") text.as_ptr(),
+            options(nostack, preserves_flags),
+        );
+    }
+}
+
+// There is no semihosting host for other targets
+#[cfg(not(all(target_arch = "arm", target_os = "none")))]
+fn host_write0(_text: &[u8]) {}
+
+// Collects text in `scratch` and passes it to `emit` NUL terminated, a chunk at a time
+struct Chunked<'s, F: FnMut(&[u8])> {
+    scratch: &'s mut [u8],
+    used: usize,
+    emit: F,
+}
+
+impl<'s, F: FnMut(&[u8])> Chunked<'s, F> {
+    fn flush(&mut self) {
+        if self.used > 0 {
+            self.scratch[self.used] = 0;
+            (self.emit)(&self.scratch[..self.used + 1]);
+            self.used = 0;
+        }
+    }
+}
+
+impl<'s, F: FnMut(&[u8])> fmt::Write for Chunked<'s, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // the last byte is kept for the NUL
+        let chunk = self.scratch.len().saturating_sub(1);
+        if chunk == 0 {
+            return Ok(());
+        }
+        let mut bytes = s.as_bytes();
+        while !bytes.is_empty() {
+            let n = bytes.len().min(chunk - self.used);
+            self.scratch[self.used..self.used + n].copy_from_slice(&bytes[..n]);
+            self.used += n;
+            bytes = &bytes[n..];
+            if self.used == chunk {
+                self.flush();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Impl of [fmt::Write] that sends text to the debugger console with the semihosting
+/// `SYS_WRITE0` call, available with the `semihosting` feature.
+///
+/// Text is collected in the scratch buffer and sent when the buffer is full, on
+/// [HostWriter::flush] and on drop, so long output is sent in chunks and nothing is lost.
+/// The last byte of the scratch is used for the NUL terminator, so a scratch of at least 2
+/// bytes is needed. `SYS_WRITE0` takes a NUL terminated string: a NUL in the text ends the
+/// chunk early, the host does not show the rest of it. The core halts on a breakpoint for
+/// every chunk, without a debugger attached it stays in the fault handler.
+///
+/// Only Cortex-M is supported: the call is made with `bkpt 0xAB` on bare metal ARM
+/// (`target_os = "none"`), which is wrong for the A and R profile cores that use `svc` or
+/// `hlt` there. On other targets the writer does nothing.
+///
+/// ```no_run
+/// use core::fmt::Write;
+///
+/// let mut scratch = [0u8; 64];
+/// let mut host = stackfmt::HostWriter::new(&mut scratch);
+/// writeln!(host, "clock at {} MHz", 72).unwrap();
+/// host.flush();
+/// ```
+pub struct HostWriter<'s> {
+    inner: Chunked<'s, fn(&[u8])>,
+}
+
+impl<'s> HostWriter<'s> {
+    /// Creates writer collecting text in `scratch`.
+    pub fn new(scratch: &'s mut [u8]) -> Self {
+        HostWriter {
+            inner: Chunked {
+                scratch,
+                used: 0,
+                emit: host_write0,
+            },
+        }
+    }
+
+    /// Sends the collected text to the host.
+    pub fn flush(&mut self) {
+        self.inner.flush();
+    }
+}
+
+impl<'s> fmt::Write for HostWriter<'s> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)
+    }
+}
+
+impl<'s> Drop for HostWriter<'s> {
+    fn drop(&mut self) {
+        self.inner.flush();
+    }
+}
+
+/// Formats into `scratch` and sends the text to the debugger console with semihosting,
+/// in chunks of the scratch size if it is longer, see [HostWriter].
+///
+/// With QEMU the host is QEMU itself, for example for an LM3S6965 firmware:
+///
+/// ```text
+/// qemu-system-arm -cpu cortex-m3 -machine lm3s6965evb -nographic \
+///     -semihosting-config enable=on,target=native -kernel firmware.elf
+/// ```
+///
+/// ```no_run
+/// let mut scratch = [0u8; 64];
+/// stackfmt::fmt_to_host(&mut scratch, format_args!("boot stage {}\n", 2));
+/// ```
+pub fn fmt_to_host(scratch: &mut [u8], args: fmt::Arguments) {
+    let mut host = HostWriter::new(scratch);
+    let _ = fmt::write(&mut host, args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Chunked;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    // Sends `text` through Chunked with scratch of `N` bytes, logs chunks separated by '|'
+    fn chunks<'o, const N: usize>(out: &'o mut [u8], text: &str) -> &'o str {
+        let mut log = WriteTo::new(out);
+        let mut scratch = [0xFFu8; N];
+        {
+            let mut w = Chunked {
+                scratch: &mut scratch,
+                used: 0,
+                emit: |chunk: &[u8]| {
+                    let (last, text) = chunk.split_last().unwrap();
+                    assert_eq!(*last, 0);
+                    let _ = log.write_str(core::str::from_utf8(text).unwrap());
+                    let _ = log.write_char('|');
+                },
+            };
+            for piece in text.split_inclusive(' ') {
+                w.write_str(piece).unwrap();
+            }
+            w.flush();
+        }
+        log.as_str()
+    }
+
+    #[test]
+    fn semihosting_short_text_one_chunk() {
+        let mut out = [0u8; 64];
+        assert_eq!(chunks::<16>(&mut out, "boot ok"), "boot ok|");
+        assert_eq!(chunks::<16>(&mut out, ""), "");
+    }
+
+    #[test]
+    fn semihosting_long_text_chunked() {
+        let mut out = [0u8; 64];
+        assert_eq!(
+            chunks::<5>(&mut out, "clock at 72 MHz"),
+            "cloc|k at| 72 |MHz|"
+        );
+        // exactly full chunk is sent at once
+        assert_eq!(chunks::<4>(&mut out, "abc def"), "abc| de|f|");
+    }
+
+    #[test]
+    fn semihosting_tiny_scratch() {
+        let mut out = [0u8; 64];
+        assert_eq!(chunks::<2>(&mut out, "ab"), "a|b|");
+        assert_eq!(chunks::<1>(&mut out, "ab"), "");
+    }
+}
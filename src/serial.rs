@@ -0,0 +1,359 @@
+//  ( /   @ @    ()  Formatted output into a byte-at-a-time serial port
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Error of a non-blocking serial operation, the same as `nb::Error`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NbError<E> {
+    /// The operation can not complete now and should be retried
+    WouldBlock,
+    /// The operation failed
+    Other(E),
+}
+
+/// Serial port that accepts one byte at a time, the same shape as
+/// `embedded_hal::serial::Write<u8>`.
+///
+/// With the `ehal` feature every `embedded_hal::serial::Write<u8>` port is a [SerialWrite],
+/// otherwise a port implements the two methods, returning [NbError::WouldBlock] while busy.
+pub trait SerialWrite {
+    /// Error reported by the port
+    type Error;
+
+    /// Writes a single byte.
+    fn write(&mut self, byte: u8) -> Result<(), NbError<Self::Error>>;
+
+    /// Waits until all written bytes are sent.
+    fn flush(&mut self) -> Result<(), NbError<Self::Error>>;
+}
+
+#[cfg(feature = "ehal")]
+impl<E> From<nb::Error<E>> for NbError<E> {
+    fn from(e: nb::Error<E>) -> Self {
+        match e {
+            nb::Error::WouldBlock => NbError::WouldBlock,
+            nb::Error::Other(e) => NbError::Other(e),
+        }
+    }
+}
+
+#[cfg(feature = "ehal")]
+impl<T: embedded_hal::serial::Write<u8>> SerialWrite for T {
+    type Error = T::Error;
+
+    fn write(&mut self, byte: u8) -> Result<(), NbError<T::Error>> {
+        embedded_hal::serial::Write::write(self, byte).map_err(NbError::from)
+    }
+
+    fn flush(&mut self) -> Result<(), NbError<T::Error>> {
+        embedded_hal::serial::Write::flush(self).map_err(NbError::from)
+    }
+}
+
+// Retries `op` while it would block, like `nb::block!`
+fn block<E, F: FnMut() -> Result<(), NbError<E>>>(mut op: F) -> Result<(), E> {
+    loop {
+        match op() {
+            Ok(()) => return Ok(()),
+            Err(NbError::WouldBlock) => continue,
+            Err(NbError::Other(e)) => return Err(e),
+        }
+    }
+}
+
+/// Impl of [fmt::Write] that sends every byte to a [SerialWrite] port, waiting while the port
+/// would block.
+///
+/// Nothing is truncated: the output is limited only by the time it takes to send. As
+/// [fmt::Error] can not carry the port error it is kept for [SerialFmt::last_error].
+///
+/// ```
+/// use core::fmt::Write;
+/// use stackfmt::{NbError, SerialWrite};
+///
+/// struct Port(usize);
+/// impl SerialWrite for Port {
+///     type Error = ();
+///     fn write(&mut self, _: u8) -> Result<(), NbError<()>> {
+///         self.0 += 1;
+///         Ok(())
+///     }
+///     fn flush(&mut self) -> Result<(), NbError<()>> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut serial = stackfmt::SerialFmt::new(Port(0));
+/// write!(serial, "t={}\r\n", 25).unwrap();
+/// assert_eq!(serial.into_inner().0, 6);
+/// ```
+pub struct SerialFmt<W: SerialWrite> {
+    port: W,
+    error: Option<W::Error>,
+}
+
+impl<W: SerialWrite> SerialFmt<W> {
+    /// Creates stream sending bytes to `port`.
+    pub fn new(port: W) -> Self {
+        SerialFmt { port, error: None }
+    }
+
+    /// The error of the last failed write, if any
+    pub fn last_error(&self) -> Option<&W::Error> {
+        self.error.as_ref()
+    }
+
+    /// Waits until the port sent all bytes.
+    pub fn flush(&mut self) -> fmt::Result {
+        let port = &mut self.port;
+        block(|| port.flush()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+
+    /// Returns the port.
+    pub fn into_inner(self) -> W {
+        self.port
+    }
+}
+
+impl<W: SerialWrite> fmt::Write for SerialFmt<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            let port = &mut self.port;
+            if let Err(e) = block(|| port.write(byte)) {
+                self.error = Some(e);
+                return Err(fmt::Error);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Impl of [fmt::Write] that stages the output in the caller buffer and sends it to a
+/// [SerialWrite] port when the buffer is full or on [StagedSerialFmt::flush].
+///
+/// Formatting of a message is not interleaved with waiting for the port, which helps when the
+/// arguments are read from hardware registers. The data still in the buffer is lost if the
+/// stream is dropped without [StagedSerialFmt::flush].
+pub struct StagedSerialFmt<'b, W: SerialWrite> {
+    port: W,
+    buffer: &'b mut [u8],
+    len: usize, // bytes staged in the buffer
+    error: Option<W::Error>,
+}
+
+impl<'b, W: SerialWrite> StagedSerialFmt<'b, W> {
+    /// Creates stream staging data in `buffer` before sending it to `port`.
+    pub fn new(port: W, buffer: &'b mut [u8]) -> Self {
+        StagedSerialFmt {
+            port,
+            buffer,
+            len: 0,
+            error: None,
+        }
+    }
+
+    /// The error of the last failed write, if any
+    pub fn last_error(&self) -> Option<&W::Error> {
+        self.error.as_ref()
+    }
+
+    /// Sends the staged bytes and waits until the port sent them.
+    pub fn flush(&mut self) -> fmt::Result {
+        self.send()?;
+        let port = &mut self.port;
+        block(|| port.flush()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+
+    /// Returns the port, the staged bytes which were not flushed are dropped.
+    pub fn into_inner(self) -> W {
+        self.port
+    }
+
+    // Sends the staged bytes, on error the unsent ones stay staged
+    fn send(&mut self) -> fmt::Result {
+        for i in 0..self.len {
+            let (port, byte) = (&mut self.port, self.buffer[i]);
+            if let Err(e) = block(|| port.write(byte)) {
+                self.buffer.copy_within(i..self.len, 0);
+                self.len -= i;
+                self.error = Some(e);
+                return Err(fmt::Error);
+            }
+        }
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl<'b, W: SerialWrite> fmt::Write for StagedSerialFmt<'b, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut bytes = s.as_bytes();
+        while !bytes.is_empty() {
+            if self.len == self.buffer.len() {
+                self.send()?;
+                if self.buffer.is_empty() {
+                    // nowhere to stage, send directly
+                    let port = &mut self.port;
+                    for &byte in bytes {
+                        block(|| port.write(byte)).map_err(|e| {
+                            self.error = Some(e);
+                            fmt::Error
+                        })?;
+                    }
+                    return Ok(());
+                }
+            }
+            let n = bytes.len().min(self.buffer.len() - self.len);
+            self.buffer[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+            self.len += n;
+            bytes = &bytes[n..];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NbError, SerialFmt, SerialWrite, StagedSerialFmt};
+    use core::fmt::Write;
+
+    // Port that would block before every other byte and fails on the byte `fail_at`
+    struct MockPort {
+        sent: [u8; 64],
+        len: usize,
+        busy: bool,
+        retries: usize,
+        fail_at: Option<usize>,
+        flushes: usize,
+    }
+
+    impl MockPort {
+        fn new(fail_at: Option<usize>) -> Self {
+            MockPort {
+                sent: [0u8; 64],
+                len: 0,
+                busy: false,
+                retries: 0,
+                fail_at,
+                flushes: 0,
+            }
+        }
+
+        fn sent(&self) -> &str {
+            core::str::from_utf8(&self.sent[..self.len]).unwrap()
+        }
+    }
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct Overrun(usize);
+
+    impl SerialWrite for MockPort {
+        type Error = Overrun;
+
+        fn write(&mut self, byte: u8) -> Result<(), NbError<Overrun>> {
+            self.busy = !self.busy;
+            if self.busy {
+                self.retries += 1;
+                return Err(NbError::WouldBlock);
+            }
+            if self.fail_at == Some(self.len) {
+                return Err(NbError::Other(Overrun(self.len)));
+            }
+            self.sent[self.len] = byte;
+            self.len += 1;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), NbError<Overrun>> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn serial_retries_would_block() {
+        let mut serial = SerialFmt::new(MockPort::new(None));
+        let id = 7;
+        write!(serial, "id={};", id).unwrap();
+        serial.flush().unwrap();
+        assert_eq!(serial.last_error(), None);
+        let port = serial.into_inner();
+        assert_eq!(port.sent(), "id=7;");
+        assert_eq!(port.retries, 5);
+        assert_eq!(port.flushes, 1);
+    }
+
+    #[test]
+    fn serial_error_mid_string() {
+        let mut serial = SerialFmt::new(MockPort::new(Some(3)));
+        assert!(serial.write_str("hello").is_err());
+        assert_eq!(serial.last_error(), Some(&Overrun(3)));
+        assert_eq!(serial.into_inner().sent(), "hel");
+    }
+
+    #[test]
+    fn staged_sends_when_full_and_on_flush() {
+        let mut buf = [0u8; 4];
+        let mut serial = StagedSerialFmt::new(MockPort::new(None), &mut buf);
+        serial.write_str("abcdef").unwrap();
+        serial.write_str("g").unwrap();
+        serial.flush().unwrap();
+        let port = serial.into_inner();
+        assert_eq!(port.sent(), "abcdefg");
+        assert_eq!(port.flushes, 1);
+
+        // zero-size staging sends directly
+        let mut serial = StagedSerialFmt::new(MockPort::new(None), &mut []);
+        serial.write_str("xyz").unwrap();
+        assert_eq!(serial.into_inner().sent(), "xyz");
+    }
+
+    #[test]
+    fn staged_error_keeps_unsent() {
+        let mut buf = [0u8; 8];
+        let mut serial = StagedSerialFmt::new(MockPort::new(Some(2)), &mut buf);
+        serial.write_str("hello").unwrap();
+        assert!(serial.flush().is_err());
+        assert_eq!(serial.last_error(), Some(&Overrun(2)));
+        assert_eq!(&serial.buffer[..serial.len], b"llo");
+    }
+
+    #[cfg(feature = "ehal")]
+    #[test]
+    fn serial_ehal_port() {
+        // embedded-hal port busy before every other byte
+        struct HalPort(MockPort);
+        impl embedded_hal::serial::Write<u8> for HalPort {
+            type Error = Overrun;
+            fn write(&mut self, byte: u8) -> nb::Result<(), Overrun> {
+                SerialWrite::write(&mut self.0, byte).map_err(|e| match e {
+                    NbError::WouldBlock => nb::Error::WouldBlock,
+                    NbError::Other(e) => nb::Error::Other(e),
+                })
+            }
+            fn flush(&mut self) -> nb::Result<(), Overrun> {
+                self.0.flushes += 1;
+                Ok(())
+            }
+        }
+
+        let mut serial = SerialFmt::new(HalPort(MockPort::new(None)));
+        write!(serial, "v={}", 12).unwrap();
+        serial.flush().unwrap();
+        let port = serial.into_inner().0;
+        assert_eq!(port.sent(), "v=12");
+        assert_eq!((port.retries, port.flushes), (4, 1));
+
+        let mut serial = SerialFmt::new(HalPort(MockPort::new(Some(1))));
+        assert!(serial.write_str("ab").is_err());
+        assert_eq!(serial.last_error(), Some(&Overrun(1)));
+    }
+}
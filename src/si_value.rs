@@ -0,0 +1,244 @@
+//  ( /   @ @    ()  Engineering notation with SI prefixes without floating point
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use core::fmt;
+
+// Prefixes from 10^-12 to 10^9 in steps of 10^3, the micro sign is at index 2
+const PREFIXES: [&str; 8] = ["p", "n", "\u{B5}", "m", "", "k", "M", "G"];
+const MIN_EXP: i32 = -12;
+const MAX_EXP: i32 = 9;
+
+/// Display adapter that shows `raw × 10^exponent` with an SI prefix, like `3.3 mA` or `47 kΩ`.
+///
+/// The prefix is chosen so the number has 1 to 3 integer digits, it is shown with up to 3
+/// significant digits rounded half away from zero (trailing fraction zeros are removed).
+/// Only integer math is used. A value that rounds up to 1000 of a prefix is shown with the
+/// next prefix. Values beyond the prefix range are shown with `G` (more integer digits) or
+/// `p` (at most 2 decimals). Width and alignment apply to the whole text.
+///
+/// ```
+/// use stackfmt::SiValue;
+///
+/// let mut buf = [0u8; 16];
+/// // 3300 µA
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", SiValue::new(3300, -6, "A")));
+/// assert_eq!(s, "3.3 mA");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", SiValue::new(47000, 0, "\u{3A9}")));
+/// assert_eq!(s, "47 k\u{3A9}");
+/// ```
+#[derive(Clone, Copy)]
+pub struct SiValue<'a> {
+    raw: i64,
+    exponent: i8,
+    unit: &'a str,
+    ascii_micro: bool,
+}
+
+impl<'a> SiValue<'a> {
+    /// Creates adapter for `raw` in units of `10^exponent` of `unit`.
+    pub fn new(raw: i64, exponent: i8, unit: &'a str) -> Self {
+        SiValue {
+            raw,
+            exponent,
+            unit,
+            ascii_micro: false,
+        }
+    }
+
+    /// Uses `u` instead of `µ` for micro.
+    pub fn ascii_micro(mut self, ascii: bool) -> Self {
+        self.ascii_micro = ascii;
+        self
+    }
+}
+
+// Number of decimal digits of a non-zero value
+fn digits(mut value: u128) -> i32 {
+    let mut n = 0;
+    while value > 0 {
+        value /= 10;
+        n += 1;
+    }
+    n
+}
+
+// value × 10^shift rounded half up, as digits and a count of zeros to append to them when
+// the product does not fit
+fn shift_round(value: u128, shift: i32) -> (u128, u32) {
+    if shift >= 0 {
+        match 10u128
+            .checked_pow(shift as u32)
+            .and_then(|m| m.checked_mul(value))
+        {
+            Some(scaled) => (scaled, 0),
+            None => (value, shift as u32),
+        }
+    } else {
+        match 10u128.checked_pow((-shift) as u32) {
+            Some(divisor) => ((value + divisor / 2) / divisor, 0),
+            None => (0, 0),
+        }
+    }
+}
+
+impl<'a> fmt::Display for SiValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit_len = self.unit.chars().count();
+        let magnitude = self.raw.unsigned_abs() as u128;
+        if magnitude == 0 {
+            let sep = if self.unit.is_empty() { "" } else { " " };
+            return pad_with(f, 1 + sep.len() + unit_len, fmt::Alignment::Left, |f| {
+                write!(f, "0{}{}", sep, self.unit)
+            });
+        }
+
+        let exponent = self.exponent as i32;
+        // decimal exponent of the leading digit
+        let mut lead = exponent + digits(magnitude) - 1;
+        let (prefix_exp, decimals, scaled, zeros) = loop {
+            let prefix_exp = (lead.div_euclid(3) * 3).clamp(MIN_EXP, MAX_EXP);
+            let int_digits = lead - prefix_exp + 1;
+            let decimals = (3 - int_digits).clamp(0, 2);
+            // zeros are only appended with the largest prefix and no decimals
+            let (scaled, zeros) = shift_round(magnitude, exponent - prefix_exp + decimals);
+            if prefix_exp < MAX_EXP && digits(scaled) > int_digits.max(1) + decimals {
+                lead += 1; // rounded up to the next power of 10
+                continue;
+            }
+            break (prefix_exp, decimals as u32, scaled, zeros);
+        };
+
+        let pow = 10u128.pow(decimals);
+        let (int_part, mut frac, mut frac_digits) = (scaled / pow, scaled % pow, decimals);
+        while frac_digits > 0 && frac % 10 == 0 {
+            frac /= 10;
+            frac_digits -= 1;
+        }
+
+        let mut prefix = PREFIXES[((prefix_exp - MIN_EXP) / 3) as usize];
+        if prefix == "\u{B5}" && self.ascii_micro {
+            prefix = "u";
+        }
+        let sign = if self.raw < 0 && scaled != 0 { "-" } else { "" };
+        let sep = if prefix.is_empty() && self.unit.is_empty() {
+            ""
+        } else {
+            " "
+        };
+        let int_len = digits(int_part).max(1) as usize + zeros as usize;
+        let frac_len = if frac_digits > 0 {
+            1 + frac_digits as usize
+        } else {
+            0
+        };
+        let len = sign.len() + int_len + frac_len + sep.len() + prefix.chars().count() + unit_len;
+        pad_with(f, len, fmt::Alignment::Left, |f| {
+            write!(f, "{}{}", sign, int_part)?;
+            for _ in 0..zeros {
+                f.write_str("0")?;
+            }
+            if frac_digits > 0 {
+                write!(f, ".{:0w$}", frac, w = frac_digits as usize)?;
+            }
+            write!(f, "{}{}{}", sep, prefix, self.unit)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SiValue;
+
+    fn si<'a>(buf: &'a mut [u8], value: SiValue) -> &'a str {
+        crate::fmt_truncate(buf, format_args!("{}", value))
+    }
+
+    #[test]
+    fn si_decades() {
+        let mut buf = [0u8; 32];
+        let table: &[(i64, i8, &str)] = &[
+            (1, -12, "1 pA"),
+            (12, -10, "1.2 nA"),
+            (470, -9, "470 nA"),
+            (3300, -6, "3.3 mA"),
+            (1, 0, "1 A"),
+            (999, 0, "999 A"),
+            (1000, 0, "1 kA"),
+            (1234, 0, "1.23 kA"),
+            (12345, 0, "12.3 kA"),
+            (123456, 0, "123 kA"),
+            (4_700_000, 3, "4.7 GA"),
+            (15, -7, "1.5 \u{B5}A"),
+        ];
+        for (raw, exp, expected) in table {
+            assert_eq!(si(&mut buf, SiValue::new(*raw, *exp, "A")), *expected);
+        }
+    }
+
+    #[test]
+    fn si_rounding_into_next_prefix() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            si(&mut buf, SiValue::new(999_950, 0, "\u{3A9}")),
+            "1 M\u{3A9}"
+        );
+        assert_eq!(
+            si(&mut buf, SiValue::new(999_499, 0, "\u{3A9}")),
+            "999 k\u{3A9}"
+        );
+        assert_eq!(si(&mut buf, SiValue::new(9_995, 0, "V")), "10 kV");
+        assert_eq!(si(&mut buf, SiValue::new(99_949, -3, "V")), "99.9 V");
+        assert_eq!(si(&mut buf, SiValue::new(99_950, -3, "V")), "100 V");
+    }
+
+    #[test]
+    fn si_negative_and_zero() {
+        let mut buf = [0u8; 32];
+        assert_eq!(si(&mut buf, SiValue::new(-3300, -6, "A")), "-3.3 mA");
+        assert_eq!(si(&mut buf, SiValue::new(0, -6, "A")), "0 A");
+        assert_eq!(si(&mut buf, SiValue::new(0, 0, "")), "0");
+        assert_eq!(si(&mut buf, SiValue::new(i64::MIN, 0, "")), "-9223372037 G");
+    }
+
+    #[test]
+    fn si_out_of_range() {
+        let mut buf = [0u8; 32];
+        assert_eq!(si(&mut buf, SiValue::new(5, -15, "F")), "0.01 pF");
+        assert_eq!(si(&mut buf, SiValue::new(4, -15, "F")), "0 pF");
+        assert_eq!(si(&mut buf, SiValue::new(-4, -15, "F")), "0 pF");
+        assert_eq!(si(&mut buf, SiValue::new(i64::MAX, -128, "F")), "0 pF");
+        assert_eq!(si(&mut buf, SiValue::new(12, 12, "Hz")), "12000 GHz");
+    }
+
+    #[test]
+    fn si_ascii_micro_and_width() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            si(&mut buf, SiValue::new(220, -6, "F").ascii_micro(true)),
+            "220 uF"
+        );
+        let s = crate::fmt_truncate(&mut buf, format_args!("[{:>8}]", SiValue::new(47, 3, "")));
+        assert_eq!(s, "[    47 k]");
+    }
+
+    #[test]
+    fn si_big_exponents() {
+        let mut buf = [0u8; 192];
+        let s = si(&mut buf, SiValue::new(1, 50, "Hz"));
+        assert_eq!(s.len(), 1 + 41 + " GHz".len());
+        assert!(s.starts_with("10000000000") && s.ends_with("00 GHz"));
+        let s = si(&mut buf, SiValue::new(-25, 40, "W"));
+        assert_eq!(s.len(), "-25".len() + 31 + " GW".len());
+        assert!(s.starts_with("-25000"));
+        let s = si(&mut buf, SiValue::new(i64::MAX, 127, ""));
+        assert!(s.starts_with("9223372036854775807000"));
+        assert_eq!(s.len(), 19 + 118 + " G".len());
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("[{:>47}]", SiValue::new(1, 50, "Hz")),
+        );
+        assert!(s.starts_with("[ 1000"));
+    }
+}
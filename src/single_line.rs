@@ -0,0 +1,161 @@
+//  ( /   @ @    ()  Keeps formatted output on a single line by escaping control chars
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+use core::str::from_utf8_unchecked;
+
+/// What [SingleLineWriter] does with C0 control chars other than `\n`, `\r` and `\t`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ControlChars {
+    /// Replace with `\xHH`
+    Escape,
+    /// Remove from the output
+    Strip,
+}
+
+/// Impl of [fmt::Write] that rewrites line breaks and control chars so the output stays on
+/// a single line.
+///
+/// `\n` becomes `\` `n`, `\r` becomes `\` `r` and `\t` becomes `\` `t` unless tabs are passed
+/// through. Other C0 control chars and DEL are escaped as `\xHH` or stripped. Everything else
+/// is forwarded untouched. Escape sequences are written with [WriteWhole] so the truncation
+/// never leaves a lone backslash of an escape introduced by this writer.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_single_line(&mut buf, format_args!("a\r\nb{}", '\n'));
+/// assert_eq!(s, r"a\r\nb\n");
+/// ```
+pub struct SingleLineWriter<W: WriteWhole> {
+    inner: W,
+    pass_tabs: bool,
+    controls: ControlChars,
+}
+
+impl<W: WriteWhole> SingleLineWriter<W> {
+    /// Creates stream that escapes tabs and other control chars.
+    pub fn new(inner: W) -> Self {
+        SingleLineWriter {
+            inner,
+            pass_tabs: false,
+            controls: ControlChars::Escape,
+        }
+    }
+
+    /// Forwards `\t` as is when `pass` is true.
+    pub fn pass_tabs(mut self, pass: bool) -> Self {
+        self.pass_tabs = pass;
+        self
+    }
+
+    /// Selects how to output control chars that have no short escape.
+    pub fn controls(mut self, mode: ControlChars) -> Self {
+        self.controls = mode;
+        self
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: WriteWhole> fmt::Write for SingleLineWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            let is_control = b < 0x20 || b == 0x7F;
+            if !is_control || (b == b'\t' && self.pass_tabs) {
+                continue;
+            }
+
+            self.inner.write_str(&s[run_start..i])?;
+            run_start = i + 1;
+
+            let hex = b"0123456789abcdef";
+            let escape = match b {
+                b'\n' => [b'\\', b'n', 0, 0],
+                b'\r' => [b'\\', b'r', 0, 0],
+                b'\t' => [b'\\', b't', 0, 0],
+                _ if self.controls == ControlChars::Strip => continue,
+                _ => [b'\\', b'x', hex[(b >> 4) as usize], hex[(b & 0xF) as usize]],
+            };
+            let len = if escape[2] == 0 { 2 } else { 4 };
+            // escape is made of ASCII chars
+            self.inner
+                .write_whole(unsafe { from_utf8_unchecked(&escape[..len]) })?;
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+impl<W: WriteWhole> WriteWhole for SingleLineWriter<W> {}
+
+/// Writes formatted string with line breaks and control chars escaped into the buffer
+/// truncating if needed.
+pub fn fmt_single_line<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
+    let mut w = SingleLineWriter::new(WriteTo::new(buffer));
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ControlChars, SingleLineWriter};
+    use crate::WriteTo;
+
+    #[test]
+    fn single_line_crlf() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_single_line(&mut buf, format_args!("one\r\ntwo\r\n{}", 3));
+        assert_eq!(s, r"one\r\ntwo\r\n3");
+    }
+
+    #[test]
+    fn single_line_only_newlines() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_single_line(&mut buf, format_args!("\n\n\n"));
+        assert_eq!(s, r"\n\n\n");
+    }
+
+    #[test]
+    fn single_line_tabs() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_single_line(&mut buf, format_args!("a\tb"));
+        assert_eq!(s, r"a\tb");
+
+        let mut w = SingleLineWriter::new(WriteTo::new(&mut buf)).pass_tabs(true);
+        core::fmt::write(&mut w, format_args!("a\tb\n")).unwrap();
+        assert_eq!(w.into_inner().as_str(), "a\tb\\n");
+    }
+
+    #[test]
+    fn single_line_other_controls() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_single_line(&mut buf, format_args!("a\x00b\x1b[0m\x7f\u{20AC}"));
+        assert_eq!(s, "a\\x00b\\x1b[0m\\x7f\u{20AC}");
+
+        let mut w = SingleLineWriter::new(WriteTo::new(&mut buf)).controls(ControlChars::Strip);
+        core::fmt::write(&mut w, format_args!("a\x00b\x07\n")).unwrap();
+        assert_eq!(w.into_inner().as_str(), r"ab\n");
+    }
+
+    #[test]
+    fn single_line_truncate_at_escape() {
+        let mut buf = [0u8; 3];
+        let s = super::fmt_single_line(&mut buf, format_args!("ab\ncd"));
+        assert_eq!(s, "ab");
+
+        let mut buf = [0u8; 4];
+        let s = super::fmt_single_line(&mut buf, format_args!("ab\ncd"));
+        assert_eq!(s, r"ab\n");
+
+        let mut buf = [0u8; 5];
+        let s = super::fmt_single_line(&mut buf, format_args!("ab\x01cd"));
+        assert_eq!(s, "ab");
+    }
+}
@@ -0,0 +1,174 @@
+//  ( /   @ @    ()  Samples as a line of block glyphs
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use core::fmt;
+
+const BLOCKS: [char; 8] = [
+    '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+];
+const ASCII: [char; 9] = ['.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Display adapter that shows samples as a sparkline, like `▁▂▄▇█▆▃`.
+///
+/// Each sample is one char: the minimum to maximum of the samples (or the range set with
+/// [Sparkline::range]) is scaled to 8 block glyphs, or to the 9 chars `.:-=+*#%@` with
+/// [Sparkline::ascii]. Samples outside the range are clamped. When all samples are equal
+/// the line is flat at a middle level. Width and alignment apply to the whole line.
+///
+/// ```
+/// use stackfmt::Sparkline;
+///
+/// let mut buf = [0u8; 32];
+/// let rtt = [10, 12, 17, 26, 28, 22, 14];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Sparkline::new(&rtt)));
+/// assert_eq!(s, "\u{2581}\u{2582}\u{2584}\u{2587}\u{2588}\u{2586}\u{2583}");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", Sparkline::new(&rtt).ascii(true)));
+/// assert_eq!(s, ".:=%@*-");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Sparkline<'a> {
+    samples: &'a [i32],
+    range: Option<(i32, i32)>,
+    ascii: bool,
+}
+
+impl<'a> Sparkline<'a> {
+    /// Creates adapter for `samples` scaled between their min and max.
+    pub fn new(samples: &'a [i32]) -> Self {
+        Sparkline {
+            samples,
+            range: None,
+            ascii: false,
+        }
+    }
+
+    /// Uses a fixed scale from `min` to `max` instead of the range of the samples.
+    pub fn range(mut self, min: i32, max: i32) -> Self {
+        self.range = Some((min.min(max), min.max(max)));
+        self
+    }
+
+    /// Uses ASCII chars instead of Unicode block glyphs.
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+}
+
+impl<'a> fmt::Display for Sparkline<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let glyphs: &[char] = if self.ascii { &ASCII } else { &BLOCKS };
+        let top = glyphs.len() as i64 - 1;
+        let (min, max) = match self.range {
+            Some(range) => range,
+            None => {
+                let min = self.samples.iter().copied().min().unwrap_or(0);
+                let max = self.samples.iter().copied().max().unwrap_or(0);
+                (min, max)
+            }
+        };
+        let (min, span) = (min as i64, max as i64 - min as i64);
+
+        pad_with(f, self.samples.len(), fmt::Alignment::Left, |f| {
+            for sample in self.samples {
+                let offset = (*sample as i64 - min).clamp(0, span);
+                let level = if span == 0 {
+                    top / 2
+                } else {
+                    (offset * top * 2 + span) / (span * 2) // rounded to nearest
+                };
+                fmt::Write::write_char(f, glyphs[level as usize])?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sparkline;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn sparkline_empty_and_single() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Sparkline::new(&[]))),
+            "[]"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{}]", Sparkline::new(&[42]))),
+            "[\u{2584}]"
+        );
+    }
+
+    #[test]
+    fn sparkline_constant() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Sparkline::new(&[5, 5, 5])));
+        assert_eq!(s, "\u{2584}\u{2584}\u{2584}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Sparkline::new(&[5, 5, 5]).ascii(true)),
+        );
+        assert_eq!(s, "+++");
+    }
+
+    #[test]
+    fn sparkline_scaling() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Sparkline::new(&[0, 1, 2, 3, 4, 5, 6, 7])),
+        );
+        assert_eq!(
+            s,
+            "\u{2581}\u{2582}\u{2583}\u{2584}\u{2585}\u{2586}\u{2587}\u{2588}"
+        );
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Sparkline::new(&[-100, 0, 100])),
+        );
+        assert_eq!(s, "\u{2581}\u{2585}\u{2588}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Sparkline::new(&[i32::MIN, 0, i32::MAX])),
+        );
+        assert_eq!(s, "\u{2581}\u{2585}\u{2588}");
+    }
+
+    #[test]
+    fn sparkline_fixed_range() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Sparkline::new(&[-5, 0, 50, 100, 200]).range(0, 100)),
+        );
+        assert_eq!(s, "\u{2581}\u{2581}\u{2585}\u{2588}\u{2588}");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Sparkline::new(&[3, 7]).range(10, 0).ascii(true)),
+        );
+        assert_eq!(s, "-#");
+    }
+
+    #[test]
+    fn sparkline_ascii() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}",
+                Sparkline::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8]).ascii(true)
+            ),
+        );
+        assert_eq!(s, ".:-=+*#%@");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{:>5}]", Sparkline::new(&[1, 2]).ascii(true)),
+        );
+        assert_eq!(s, "[   .@]");
+    }
+}
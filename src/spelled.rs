@@ -0,0 +1,277 @@
+//  ( /   @ @    ()  Numbers spelled out in English words
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::{pad_with, CharCounter};
+use core::fmt;
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+// u64::MAX is about 18 quintillion
+const SCALES: [&str; 7] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+];
+
+/// Display adapter that shows a number in English words, like `one hundred twenty-three`.
+///
+/// The whole `u64` range is supported with the short scale (billion is 10^9). With
+/// [SpelledOut::and] the British form is used, `one hundred and five`. Width and alignment
+/// apply to the whole text.
+///
+/// ```
+/// use stackfmt::SpelledOut;
+///
+/// let mut buf = [0u8; 64];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", SpelledOut::new(123)));
+/// assert_eq!(s, "one hundred twenty-three");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", SpelledOut::new(2005).and(true)));
+/// assert_eq!(s, "two thousand and five");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SpelledOut {
+    value: u64,
+    and: bool,
+}
+
+impl SpelledOut {
+    /// Creates adapter for `value`.
+    pub fn new(value: u64) -> Self {
+        SpelledOut { value, and: false }
+    }
+
+    /// Puts `and` before the tens and ones, like `one hundred and five`.
+    pub fn and(mut self, and: bool) -> Self {
+        self.and = and;
+        self
+    }
+}
+
+impl fmt::Display for SpelledOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_padded_words(f, |w| write_number(w, self.value, self.and))
+    }
+}
+
+/// Display adapter that spells an amount of money given in cents (or other hundredths),
+/// like `twelve euros and five cents`.
+///
+/// Words are given as singular and plural pairs for the main and the fractional unit. The
+/// fractional part is omitted when it is zero. Width and alignment apply to the whole text.
+///
+/// ```
+/// use stackfmt::SpelledAmount;
+///
+/// let mut buf = [0u8; 64];
+/// let amount = SpelledAmount::new(1205, ("euro", "euros"), ("cent", "cents"));
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", amount));
+/// assert_eq!(s, "twelve euros and five cents");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SpelledAmount<'a> {
+    cents: u64,
+    major: (&'a str, &'a str),
+    minor: (&'a str, &'a str),
+    and: bool,
+}
+
+impl<'a> SpelledAmount<'a> {
+    /// Creates adapter for `cents` hundredths of the `major` unit.
+    pub fn new(cents: u64, major: (&'a str, &'a str), minor: (&'a str, &'a str)) -> Self {
+        SpelledAmount {
+            cents,
+            major,
+            minor,
+            and: false,
+        }
+    }
+
+    /// Spells the numbers with British `and`, see [SpelledOut::and].
+    pub fn and(mut self, and: bool) -> Self {
+        self.and = and;
+        self
+    }
+}
+
+impl<'a> fmt::Display for SpelledAmount<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (major, minor) = (self.cents / 100, self.cents % 100);
+        let word = |n: u64, (singular, plural): (&'a str, &'a str)| match n {
+            1 => singular,
+            _ => plural,
+        };
+        write_padded_words(f, |w| {
+            write_number(w, major, self.and)?;
+            write!(w, " {}", word(major, self.major))?;
+            if minor > 0 {
+                w.write_str(" and ")?;
+                write_number(w, minor, self.and)?;
+                write!(w, " {}", word(minor, self.minor))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+// Pads the words written by `body`: they are counted first if width is set
+fn write_padded_words<F>(f: &mut fmt::Formatter, body: F) -> fmt::Result
+where
+    F: Fn(&mut dyn fmt::Write) -> fmt::Result,
+{
+    match f.width() {
+        Some(width) => {
+            let mut counter = CharCounter {
+                count: 0,
+                limit: width,
+            };
+            body(&mut counter)?;
+            pad_with(f, counter.count, fmt::Alignment::Left, |f| body(f))
+        }
+        None => body(f),
+    }
+}
+
+// Writes words for 1..=999
+fn write_hundreds(w: &mut dyn fmt::Write, n: u64, and: bool) -> fmt::Result {
+    let (hundreds, rest) = (n / 100, n % 100);
+    if hundreds > 0 {
+        write!(w, "{} hundred", ONES[hundreds as usize])?;
+        if rest == 0 {
+            return Ok(());
+        }
+        w.write_str(if and { " and " } else { " " })?;
+    }
+    match rest {
+        0..=19 => w.write_str(ONES[rest as usize]),
+        _ if rest % 10 == 0 => w.write_str(TENS[rest as usize / 10]),
+        _ => write!(
+            w,
+            "{}-{}",
+            TENS[rest as usize / 10],
+            ONES[rest as usize % 10]
+        ),
+    }
+}
+
+fn write_number(w: &mut dyn fmt::Write, value: u64, and: bool) -> fmt::Result {
+    if value == 0 {
+        return w.write_str(ONES[0]);
+    }
+    let mut groups = [0u64; SCALES.len()];
+    let mut rest = value;
+    for group in groups.iter_mut() {
+        *group = rest % 1000;
+        rest /= 1000;
+    }
+
+    let mut first = true;
+    for (scale, group) in groups.iter().enumerate().rev() {
+        if *group == 0 {
+            continue;
+        }
+        if !first {
+            // British "one thousand and five"
+            let last_small = scale == 0 && *group < 100;
+            w.write_str(if and && last_small { " and " } else { " " })?;
+        }
+        write_hundreds(w, *group, and)?;
+        if scale > 0 {
+            write!(w, " {}", SCALES[scale])?;
+        }
+        first = false;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpelledAmount, SpelledOut};
+    use crate::fmt_truncate;
+
+    fn words(buf: &mut [u8], value: u64, and: bool) -> &str {
+        fmt_truncate(buf, format_args!("{}", SpelledOut::new(value).and(and)))
+    }
+
+    #[test]
+    fn spelled_small() {
+        let mut buf = [0u8; 256];
+        assert_eq!(words(&mut buf, 0, false), "zero");
+        assert_eq!(words(&mut buf, 7, false), "seven");
+        assert_eq!(words(&mut buf, 13, false), "thirteen");
+        assert_eq!(words(&mut buf, 21, false), "twenty-one");
+        assert_eq!(words(&mut buf, 40, false), "forty");
+        assert_eq!(words(&mut buf, 100, false), "one hundred");
+        assert_eq!(words(&mut buf, 105, false), "one hundred five");
+        assert_eq!(words(&mut buf, 105, true), "one hundred and five");
+        assert_eq!(words(&mut buf, 100, true), "one hundred");
+    }
+
+    #[test]
+    fn spelled_large() {
+        let mut buf = [0u8; 256];
+        assert_eq!(
+            words(&mut buf, 999_999_999, false),
+            "nine hundred ninety-nine million nine hundred ninety-nine thousand \
+             nine hundred ninety-nine"
+        );
+        assert_eq!(words(&mut buf, 1_000_000_001, false), "one billion one");
+        assert_eq!(words(&mut buf, 1_000_000_001, true), "one billion and one");
+        assert_eq!(
+            words(&mut buf, 2_000_310, true),
+            "two million three hundred and ten"
+        );
+        assert!(words(&mut buf, u64::MAX, false).starts_with("eighteen quintillion four"));
+        assert!(words(&mut buf, u64::MAX, false).ends_with("six hundred fifteen"));
+    }
+
+    #[test]
+    fn spelled_currency() {
+        let mut buf = [0u8; 128];
+        let euro = |cents| SpelledAmount::new(cents, ("euro", "euros"), ("cent", "cents"));
+        let s = fmt_truncate(&mut buf, format_args!("{}", euro(1205)));
+        assert_eq!(s, "twelve euros and five cents");
+        let s = fmt_truncate(&mut buf, format_args!("{}", euro(100)));
+        assert_eq!(s, "one euro");
+        let s = fmt_truncate(&mut buf, format_args!("{}", euro(1)));
+        assert_eq!(s, "zero euros and one cent");
+        let s = fmt_truncate(&mut buf, format_args!("{}", euro(10521).and(true)));
+        assert_eq!(s, "one hundred and five euros and twenty-one cents");
+    }
+
+    #[test]
+    fn spelled_width() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(&mut buf, format_args!("[{:>8}]", SpelledOut::new(21)));
+        assert_eq!(s, "[twenty-one]");
+        let s = fmt_truncate(&mut buf, format_args!("[{:^7}]", SpelledOut::new(3)));
+        assert_eq!(s, "[ three ]");
+    }
+}
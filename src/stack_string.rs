@@ -0,0 +1,231 @@
+//  ( /   @ @    ()  Owned string in a fixed size array
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use core::ops::Deref;
+
+/// Capacity of [StackString] made by [format_stack!] when the size is not given.
+pub const FORMAT_STACK_CAPACITY: usize = 128;
+
+/// String of up to `N` bytes stored in the value itself, so it can be returned from a
+/// function or kept in a struct, usually made by [format_stack!].
+///
+/// It implements [fmt::Write] and truncates like [WriteTo](crate::WriteTo): a write that
+/// does not fit is cut at a char boundary and the later writes are dropped.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut s = stackfmt::StackString::<8>::new();
+/// write!(s, "{}+{}", 20, 22).unwrap();
+/// assert_eq!(s.as_str(), "20+22");
+/// assert_eq!(s.len(), 5);
+/// ```
+#[derive(Clone, Copy)]
+pub struct StackString<const N: usize> {
+    buffer: [u8; N],
+    used: usize,
+    overflow: bool,
+}
+
+impl<const N: usize> StackString<N> {
+    /// Creates empty string.
+    pub const fn new() -> Self {
+        StackString {
+            buffer: [0u8; N],
+            used: 0,
+            overflow: false,
+        }
+    }
+
+    /// Creates string with formatted text truncating if needed.
+    pub fn from_fmt(args: fmt::Arguments) -> Self {
+        let mut s = Self::new();
+        let _ = fmt::write(&mut s, args);
+        s
+    }
+
+    /// The text
+    pub fn as_str(&self) -> &str {
+        // the buffer holds whole chars of a str
+        unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.used]) }
+    }
+
+    /// Max length in bytes
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// True if some text did not fit and was dropped
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    /// Removes the text and the truncated flag.
+    pub fn clear(&mut self) {
+        self.used = 0;
+        self.overflow = false;
+    }
+}
+
+impl<const N: usize> fmt::Write for StackString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.overflow {
+            return Ok(()); // skip further inputs
+        }
+        let mut n = s.len();
+        if n > N - self.used {
+            self.overflow = true;
+            n = N - self.used;
+            while !s.is_char_boundary(n) {
+                n -= 1;
+            }
+        }
+        self.buffer[self.used..self.used + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.used += n;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for StackString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for StackString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for StackString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq for StackString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for StackString<N> {}
+
+impl<const N: usize> PartialEq<str> for StackString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for StackString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> fmt::Display for StackString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for StackString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Formats into a [StackString] returned by value, truncating if needed.
+///
+/// The capacity can be given before the format string and a `;`, any const expression
+/// works; without it the capacity is [FORMAT_STACK_CAPACITY].
+///
+/// ```
+/// use stackfmt::{format_stack, StackString};
+///
+/// fn describe(code: u16) -> StackString<16> {
+///     format_stack!(16; "error E{:04}", code)
+/// }
+/// assert_eq!(describe(42), "error E0042");
+///
+/// let s = format_stack!("a={}", 5);
+/// assert_eq!(s, "a=5");
+/// assert_eq!(s.capacity(), stackfmt::FORMAT_STACK_CAPACITY);
+/// ```
+#[macro_export]
+macro_rules! format_stack {
+    ($size:expr; $($args:tt)+) => {
+        $crate::StackString::<{ $size }>::from_fmt(format_args!($($args)+))
+    };
+    ($($args:tt)+) => {
+        $crate::StackString::<{ $crate::FORMAT_STACK_CAPACITY }>::from_fmt(format_args!($($args)+))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StackString, FORMAT_STACK_CAPACITY};
+    use core::fmt::Write;
+
+    const LABEL_LEN: usize = 12;
+
+    fn make_label(id: u32) -> StackString<LABEL_LEN> {
+        format_stack!(LABEL_LEN; "sensor-{}", id)
+    }
+
+    struct Message {
+        level: u8,
+        text: StackString<{ 4 * 8 }>,
+    }
+
+    #[test]
+    fn stack_string_returned() {
+        let label = make_label(7);
+        assert_eq!(label, "sensor-7");
+        assert!(!label.is_truncated());
+        let label = make_label(123456);
+        assert_eq!(label, "sensor-12345");
+        assert!(label.is_truncated());
+        // default capacity
+        let id = 5;
+        let s = format_stack!("id={}", id);
+        assert_eq!((s.as_str(), s.capacity()), ("id=5", FORMAT_STACK_CAPACITY));
+        assert_eq!(format_stack!("plain"), "plain");
+        assert_eq!(format_stack!("{}-{}", 1, 2), "1-2");
+    }
+
+    #[test]
+    fn stack_string_in_struct() {
+        let temp = 21;
+        let msg = Message {
+            level: 2,
+            text: format_stack!(4 * 8; "temp={}C", temp),
+        };
+        let copy = msg;
+        assert_eq!(copy.level, 2);
+        assert_eq!(copy.text, "temp=21C");
+        assert_eq!(copy.text.len(), 8);
+        assert_eq!(copy.text.capacity(), 32);
+    }
+
+    #[test]
+    fn stack_string_truncated_at_char() {
+        let s = format_stack!(5; "{}", "ab\u{20AC}");
+        assert_eq!(s, "ab\u{20AC}");
+        let s = format_stack!(4; "{}", "ab\u{20AC}");
+        assert_eq!(s, "ab");
+        assert!(s.is_truncated());
+        // later writes are dropped
+        let mut s = s;
+        s.write_str("c").unwrap();
+        assert_eq!(s, "ab");
+        s.clear();
+        s.write_str("c").unwrap();
+        assert_eq!(s, "c");
+    }
+}
@@ -2,7 +2,10 @@
 //   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
 //    -/   "   \-    based on Stefan SO answer: https://stackoverflow.com/a/50201632/601298
 //
+#[cfg(feature = "stats")]
+use crate::stats::FmtStats;
 use core::fmt;
+use core::marker::PhantomData;
 use core::str::from_utf8_unchecked;
 
 /// Impl of [core::fmt::Write] stream that writes formatted string into provided u8 buffer.
@@ -18,31 +21,335 @@ use core::str::from_utf8_unchecked;
 /// };
 /// assert_eq!(buffer, "The answer is 42".as_bytes());
 /// ```
-pub struct WriteTo<'a> {
-    buffer: &'a mut [u8],
-    used: usize,    // Position inside buffer where the written string ends
-    overflow: bool, // If formatted data was truncated
+///
+/// The storage is a borrowed `&mut [u8]` by default. It can also be owned, like `[u8; 64]`
+/// of [OwnedWriteTo], so the stream is kept in a struct without a lifetime, moved to
+/// another task and reused after [WriteTo::clear].
+pub struct WriteTo<'a, S = &'a mut [u8]> {
+    buffer: S,
+    used: usize,              // Position inside buffer where the written string ends
+    overflow: bool,           // If formatted data was truncated
+    atomic_crlf: bool,        // If truncation must not split `\r\n`
+    newline_terminated: bool, // If as_str() ensures the trailing `\n`
+    #[cfg(feature = "stats")]
+    stats: FmtStats,
+    lifetime: PhantomData<&'a ()>,
+}
+
+/// [WriteTo] that owns an array of `N` bytes.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// struct Sensor {
+///     label: stackfmt::OwnedWriteTo<16>,
+/// }
+///
+/// let mut sensor = Sensor { label: stackfmt::WriteTo::new_owned() };
+/// write!(sensor.label, "adc{}", 3).unwrap();
+/// assert_eq!(sensor.label.as_str(), "adc3");
+/// ```
+pub type OwnedWriteTo<const N: usize> = WriteTo<'static, [u8; N]>;
+
+/// Storage of a [WriteTo]: a borrowed `&mut [u8]` or an owned `[u8; N]`.
+///
+/// The stream relies on `as_ref()` and `as_mut()` giving the same bytes every time, so the
+/// trait is sealed and other types can not be used:
+///
+/// ```compile_fail
+/// struct Bytes([u8; 8]);
+/// impl AsRef<[u8]> for Bytes {
+///     fn as_ref(&self) -> &[u8] { &self.0 }
+/// }
+/// impl AsMut<[u8]> for Bytes {
+///     fn as_mut(&mut self) -> &mut [u8] { &mut self.0 }
+/// }
+/// let w = stackfmt::WriteTo::with_storage(Bytes([0u8; 8]));
+/// ```
+pub trait Storage: AsRef<[u8]> + AsMut<[u8]> + sealed::Sealed {}
+
+impl Storage for &mut [u8] {}
+impl<const N: usize> Storage for [u8; N] {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for &mut [u8] {}
+    impl<const N: usize> Sealed for [u8; N] {}
 }
 
-// Construction and string access
+// Construction and string access of the borrowed buffer
 impl<'a> WriteTo<'a> {
     /// Creates new stream.
     pub fn new(buffer: &'a mut [u8]) -> Self {
+        WriteTo::with_storage(buffer)
+    }
+
+    /// Returns buffer view as &str
+    pub fn as_str(mut self) -> &'a str {
+        if self.newline_terminated {
+            self.ensure_newline();
+        }
+        unsafe { from_utf8_unchecked(&self.buffer[..self.used]) }
+    }
+}
+
+// Construction and string access of the owned array
+impl<'a, const N: usize> WriteTo<'a, [u8; N]> {
+    /// Creates stream owning an array of zeros.
+    pub fn new_owned() -> Self {
+        WriteTo::with_storage([0u8; N])
+    }
+
+    /// Returns view of the data written so far as &str, the same as [WriteTo::to_str]. The
+    /// stream is not consumed, so [WriteTo::newline_terminated] is not applied: call
+    /// [WriteTo::ensure_newline] before.
+    pub fn as_str(&self) -> &str {
+        self.to_str()
+    }
+}
+
+impl<'a, S: Storage> WriteTo<'a, S> {
+    /// Creates new stream writing into `storage`.
+    pub fn with_storage(storage: S) -> Self {
         WriteTo {
-            buffer,
+            buffer: storage,
             used: 0,
             overflow: false,
+            atomic_crlf: false,
+            newline_terminated: false,
+            #[cfg(feature = "stats")]
+            stats: FmtStats::default(),
+            lifetime: PhantomData,
         }
     }
 
+    /// If `on` the truncation never splits a `\r\n` pair: when the `\n` does not fit, the
+    /// preceding `\r` is dropped too, even if it was written by a previous call.
+    ///
+    /// ```
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0u8; 6];
+    /// let mut w = stackfmt::WriteTo::new(&mut buffer).atomic_crlf(true);
+    /// write!(w, "ok\r\nerr\r\n").unwrap();
+    /// assert_eq!(w.as_str(), "ok\r\ner");
+    /// ```
+    pub fn atomic_crlf(mut self, on: bool) -> Self {
+        self.atomic_crlf = on;
+        self
+    }
+
+    /// If `on` [WriteTo::as_str] calls [WriteTo::ensure_newline] before returning the string.
+    pub fn newline_terminated(mut self, on: bool) -> Self {
+        self.newline_terminated = on;
+        self
+    }
+
+    /// Makes the written data end with `\n`: appends it if missing and, if the buffer is full,
+    /// puts it in place of the last char.
+    ///
+    /// Nothing changes if the data already ends with `\n` or the buffer is empty.
+    ///
+    /// ```
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0u8; 8];
+    /// let mut w = stackfmt::WriteTo::new(&mut buffer);
+    /// write!(w, "disk full: /var").unwrap();
+    /// w.ensure_newline();
+    /// assert_eq!(w.as_str(), "disk fu\n");
+    /// ```
+    pub fn ensure_newline(&mut self) {
+        let buffer = self.buffer.as_mut();
+        if self.used > 0 && buffer[self.used - 1] == b'\n' {
+            return;
+        }
+        if self.used == buffer.len() {
+            if self.used == 0 {
+                return;
+            }
+            self.used = find_closest_boundary(&buffer[..self.used], self.used - 1);
+        }
+        buffer[self.used] = b'\n';
+        self.used += 1;
+    }
+
+    /// Drops all written data and accepts new data again.
+    pub fn clear(&mut self) {
+        self.used = 0;
+        self.overflow = false;
+    }
+
+    /// Stats of write calls since creation or the last reset, kept across
+    /// [WriteTo::clear]. Requires the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> &FmtStats {
+        &self.stats
+    }
+
+    /// Starts collecting stats from zero. Requires the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats = FmtStats::default();
+    }
+
     /// How many bytes were written into the buffer
     pub fn written_bytes(&self) -> usize {
         self.used
     }
 
-    /// Returns buffer view as &str
-    pub fn as_str(self) -> &'a str {
-        unsafe { from_utf8_unchecked(&self.buffer[..self.used]) }
+    /// Returns view of the data written so far as &str without consuming the stream
+    pub fn to_str(&self) -> &str {
+        unsafe { from_utf8_unchecked(&self.buffer.as_ref()[..self.used]) }
+    }
+
+    /// Returns up to `width_chars` chars of the written data from char `start_char`, see
+    /// [char_window](crate::char_window).
+    pub fn window(&self, start_char: usize, width_chars: usize) -> &str {
+        crate::marquee::char_window(self.to_str(), start_char, width_chars)
+    }
+
+    /// True if some data did not fit into the buffer and was dropped
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    // The whole buffer and the written length for in-place edits by the writers of this
+    // crate, they keep the written part valid UTF-8
+    pub(crate) fn raw_parts(&mut self) -> (&mut [u8], &mut usize) {
+        (self.buffer.as_mut(), &mut self.used)
+    }
+
+    // Bytes that can still be written
+    pub(crate) fn remaining_capacity(&self) -> usize {
+        if self.overflow {
+            0
+        } else {
+            self.buffer.as_ref().len() - self.used
+        }
+    }
+
+    /// Drops the data written after the first `len` bytes and accepts new data again.
+    ///
+    /// Used to roll back a partially written record: remember [WriteTo::written_bytes] before
+    /// writing it and truncate back if [WriteTo::is_truncated] is set afterwards. If `len`
+    /// is in the middle of a char the whole char is dropped.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.used {
+            self.used = find_closest_boundary(&self.buffer.as_ref()[..self.used], len);
+        }
+        self.overflow = false;
+    }
+
+    /// Keeps only the written chars for which `pred` returns true, compacting them in place.
+    ///
+    /// If some chars were removed the stream accepts new data again.
+    ///
+    /// ```
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0u8; 16];
+    /// let mut w = stackfmt::WriteTo::new(&mut buffer);
+    /// write!(w, "+1 (555) 010-99").unwrap();
+    /// w.retain(|c| c.is_ascii_digit());
+    /// assert_eq!(w.as_str(), "155501099");
+    /// ```
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut pred: F) {
+        let buffer = self.buffer.as_mut();
+        let (mut read, mut write) = (0, 0);
+        while read < self.used {
+            let rest = unsafe { from_utf8_unchecked(&buffer[read..self.used]) };
+            let c = match rest.chars().next() {
+                Some(c) => c,
+                None => break,
+            };
+            let len = c.len_utf8();
+            if pred(c) {
+                buffer.copy_within(read..read + len, write);
+                write += len;
+            }
+            read += len;
+        }
+        if write < self.used {
+            self.used = write;
+            self.overflow = false;
+        }
+    }
+
+    /// Replaces occurrences of `from` in the written data with `to`, returns how many.
+    ///
+    /// Matches are found left to right without overlapping. When `to` is longer than `from`
+    /// the replacing stops at the first match that does not fit into the buffer. Nothing is
+    /// replaced for an empty `from`. If the data became shorter the stream accepts new data
+    /// again.
+    ///
+    /// ```
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0u8; 32];
+    /// let mut w = stackfmt::WriteTo::new(&mut buffer);
+    /// write!(w, "dev <ID>: ok\nbat low").unwrap();
+    /// assert_eq!(w.replace_all("<ID>", "42"), 1);
+    /// assert_eq!(w.replace_all("\n", "; "), 1);
+    /// assert_eq!(w.as_str(), "dev 42: ok; bat low");
+    /// ```
+    pub fn replace_all(&mut self, from: &str, to: &str) -> usize {
+        if from.is_empty() {
+            return 0;
+        }
+        let buffer = self.buffer.as_mut();
+        let initial_used = self.used;
+        let (mut count, mut pos) = (0, 0);
+        loop {
+            let rest = unsafe { from_utf8_unchecked(&buffer[pos..self.used]) };
+            let start = match rest.find(from) {
+                Some(offset) => pos + offset,
+                None => break,
+            };
+            let new_used = self.used - from.len() + to.len();
+            if new_used > buffer.len() {
+                break;
+            }
+            // move the tail after the match and put `to` in place of `from`
+            let tail_start = start + from.len();
+            buffer.copy_within(tail_start..self.used, start + to.len());
+            buffer[start..start + to.len()].copy_from_slice(to.as_bytes());
+            self.used = new_used;
+            pos = start + to.len();
+            count += 1;
+        }
+        if self.used < initial_used {
+            self.overflow = false;
+        }
+        count
+    }
+
+    /// Appends UTF-8 text read from `reader`, at most `max` bytes and what fits into the
+    /// buffer, returns how many bytes were appended.
+    ///
+    /// The text is read in small chunks and validated as it comes, a char split between
+    /// reads is kept until its last byte arrives. Nothing beyond the limit is read, except
+    /// the bytes of a char that crosses it: they are dropped. On an error the valid text
+    /// before it stays appended. Needs the `embedded-io` feature.
+    ///
+    /// ```
+    /// use core::fmt::Write;
+    ///
+    /// let mut body: &[u8] = "t=21\u{B0}C".as_bytes();
+    /// let mut buffer = [0u8; 32];
+    /// let mut w = stackfmt::WriteTo::new(&mut buffer);
+    /// write!(w, "POST /temp ").unwrap();
+    /// assert_eq!(w.extend_from_reader(&mut body, 64), Ok(7));
+    /// assert_eq!(w.as_str(), "POST /temp t=21\u{B0}C");
+    /// ```
+    #[cfg(feature = "embedded-io")]
+    pub fn extend_from_reader<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+        max: usize,
+    ) -> Result<usize, crate::ExtendError<R::Error>> {
+        crate::reader::extend_from_reader(self, reader, max)
     }
 }
 
@@ -74,31 +381,144 @@ fn find_closest_boundary(raw_string: &[u8], max_len: usize) -> usize {
 }
 
 // Makes the WriteTo<'a> target for core::fmt::write() method.
-impl<'a> fmt::Write for WriteTo<'a> {
+impl<'a, S: Storage> fmt::Write for WriteTo<'a, S> {
     // Write that data fmt::write() feeds into a buffer and truncate if needed.
     fn write_str(&mut self, s: &str) -> fmt::Result {
         if self.overflow {
+            #[cfg(feature = "stats")]
+            self.stats.record(s.len(), 0);
             return Ok(()); // skip further inputs
         }
 
-        let remaining_buf = &mut self.buffer[self.used..];
+        // SAFETY: `used` never exceeds the buffer length, every method that changes it keeps
+        // it within the buffer.
+        let remaining_buf = unsafe { self.buffer.as_mut().get_unchecked_mut(self.used..) };
+        let raw_s = s.as_bytes();
+
+        // The only length comparison: does the whole input string fit into the buffer?
+        let mut n = raw_s.len();
+        if n > remaining_buf.len() {
+            // It does not, take what fits and back up to the closest char boundary
+            self.overflow = true;
+            n = remaining_buf.len();
+            while !s.is_char_boundary(n) {
+                n -= 1;
+            }
+            if self.atomic_crlf {
+                n = self.split_crlf_back(raw_s, n);
+            }
+        }
+        // SAFETY: `used` is still within the buffer (backing up over `\r` only decreases it)
+        // and `n` is at most the length of both slices: it is either the input length that
+        // fits or the remaining length that was only decreased.
+        unsafe {
+            self.buffer
+                .as_mut()
+                .get_unchecked_mut(self.used..)
+                .get_unchecked_mut(..n)
+                .copy_from_slice(raw_s.get_unchecked(..n));
+        }
+        self.used += n;
+        #[cfg(feature = "stats")]
+        self.stats.record(s.len(), n);
+        Ok(())
+    }
+}
+
+impl<'a, S: Storage> WriteTo<'a, S> {
+    // Truncation keeps `n` bytes of `raw_s`: backs up so `\r` is not kept without the `\n`
+    // following it. Returns the new `n`, a `\r` from a previous call is dropped from `used`.
+    #[cold]
+    fn split_crlf_back(&mut self, raw_s: &[u8], n: usize) -> usize {
+        if raw_s.get(n) != Some(&b'\n') {
+            n
+        } else if n > 0 {
+            if raw_s[n - 1] == b'\r' {
+                n - 1
+            } else {
+                n
+            }
+        } else {
+            if self.used > 0 && self.buffer.as_ref()[self.used - 1] == b'\r' {
+                self.used -= 1;
+            }
+            0
+        }
+    }
+}
+
+// The write_str() before the bounds checks were removed, used to verify it is unchanged
+#[cfg(test)]
+fn write_str_reference(w: &mut WriteTo, s: &str) {
+    if w.overflow {
+        return; // skip further inputs
+    }
+
+    let remaining_buf = &mut w.buffer[w.used..];
+    let raw_s = s.as_bytes();
+
+    if remaining_buf.len() >= raw_s.len() {
+        // The whole input string fits into the buffer, just copy it
+        remaining_buf[..raw_s.len()].copy_from_slice(raw_s);
+        w.used += raw_s.len();
+    } else {
+        // The whole input string does not fit into the buffer.
+        w.overflow = true;
+        let boundary_size = find_closest_boundary(raw_s, remaining_buf.len());
+        remaining_buf[..boundary_size].copy_from_slice(&raw_s[..boundary_size]);
+        w.used += boundary_size;
+    }
+}
+
+/// [fmt::Write] stream that can accept a piece of text only as a whole.
+///
+/// Writers that produce multi-char units (escape sequences, groups of symbols) use it so the
+/// truncation never cuts such unit in the middle. The default implementation just calls
+/// `write_str()` which is right for streams that never truncate; other streams can opt in
+/// with an empty `impl WriteWhole for MyStream {}`.
+pub trait WriteWhole: fmt::Write {
+    /// Writes the whole `s` or, if it does not fit, nothing and stops accepting further data.
+    fn write_whole(&mut self, s: &str) -> fmt::Result {
+        self.write_str(s)
+    }
+}
+
+impl<'a, S: Storage> WriteWhole for WriteTo<'a, S> {
+    fn write_whole(&mut self, s: &str) -> fmt::Result {
+        if self.overflow {
+            #[cfg(feature = "stats")]
+            self.stats.record(s.len(), 0);
+            return Ok(()); // skip further inputs
+        }
+
+        let remaining_buf = &mut self.buffer.as_mut()[self.used..];
         let raw_s = s.as_bytes();
 
         if remaining_buf.len() >= raw_s.len() {
-            // The whole input string fits into the buffer, just copy it
             remaining_buf[..raw_s.len()].copy_from_slice(raw_s);
             self.used += raw_s.len();
+            #[cfg(feature = "stats")]
+            self.stats.record(s.len(), s.len());
         } else {
-            // The whole input string does not fit into the buffer.
             self.overflow = true;
-            let boundary_size = find_closest_boundary(raw_s, remaining_buf.len());
-            remaining_buf[..boundary_size].copy_from_slice(&raw_s[..boundary_size]);
-            self.used += boundary_size;
+            if self.atomic_crlf {
+                self.split_crlf_back(raw_s, 0);
+            }
+            #[cfg(feature = "stats")]
+            self.stats.record(s.len(), 0);
         }
         Ok(())
     }
 }
 
+impl<'a> WriteWhole for fmt::Formatter<'a> {}
+
+impl<W: WriteWhole + ?Sized> WriteWhole for &mut W {
+    fn write_whole(&mut self, s: &str) -> fmt::Result {
+        (**self).write_whole(s)
+    }
+}
+
 /// Writes formatted string into the buffer truncating if needed making the result valid utf8.
 ///
 /// Example:
@@ -115,8 +535,191 @@ pub fn fmt_truncate<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> &'a str {
     }
 }
 
+/// Writes `value` with `{}` into the buffer truncating if needed, for generic code that has
+/// a value rather than [fmt::Arguments].
+///
+/// ```rust
+/// fn label<'a, T: core::fmt::Display>(buf: &'a mut [u8], value: &T) -> &'a str {
+///     stackfmt::fmt_display(buf, value)
+/// }
+/// let mut buf = [0u8; 8];
+/// assert_eq!(label(&mut buf, &3.5), "3.5");
+/// ```
+pub fn fmt_display<'a, T: fmt::Display + ?Sized>(buffer: &'a mut [u8], value: &T) -> &'a str {
+    fmt_truncate(buffer, format_args!("{}", value))
+}
+
+/// Writes `value` with `{:#}` into the buffer truncating if needed.
+pub fn fmt_display_alt<'a, T: fmt::Display + ?Sized>(buffer: &'a mut [u8], value: &T) -> &'a str {
+    fmt_truncate(buffer, format_args!("{:#}", value))
+}
+
+/// Writes `value` with `{:?}` into the buffer truncating if needed.
+///
+/// ```rust
+/// let mut buf = [0u8; 16];
+/// assert_eq!(stackfmt::fmt_debug(&mut buf, "a\tb"), "\"a\\tb\"");
+/// ```
+pub fn fmt_debug<'a, T: fmt::Debug + ?Sized>(buffer: &'a mut [u8], value: &T) -> &'a str {
+    fmt_truncate(buffer, format_args!("{:?}", value))
+}
+
+/// Writes `value` with `{:#?}` into the buffer truncating if needed.
+pub fn fmt_debug_alt<'a, T: fmt::Debug + ?Sized>(buffer: &'a mut [u8], value: &T) -> &'a str {
+    fmt_truncate(buffer, format_args!("{:#?}", value))
+}
+
 #[cfg(test)]
 pub mod tests {
+    use core::fmt::Write;
+
+    #[test]
+    fn write_str_matches_reference() {
+        // chunks with 1 to 4 byte chars to cut at every position
+        const PIECES: [&str; 8] = [
+            "a",
+            "bc",
+            "\u{E9}",
+            "\u{20AC}",
+            "\u{1F600}",
+            "",
+            "xyz\u{E9}",
+            "\u{20AC}\u{1F600}q",
+        ];
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for _ in 0..2000 {
+            let len = (next() % 24) as usize;
+            let (mut buf, mut reference_buf) = ([0u8; 24], [0u8; 24]);
+            let mut w = super::WriteTo::new(&mut buf[..len]);
+            let mut reference = super::WriteTo::new(&mut reference_buf[..len]);
+            for _ in 0..(next() % 10) {
+                let chunk = PIECES[(next() % PIECES.len() as u64) as usize];
+                let _ = w.write_str(chunk);
+                super::write_str_reference(&mut reference, chunk);
+                assert_eq!(w.written_bytes(), reference.written_bytes());
+                assert_eq!(w.is_truncated(), reference.is_truncated());
+            }
+            assert_eq!(w.as_str(), reference.as_str());
+        }
+    }
+
+    #[test]
+    fn replace_all_shrink_and_equal() {
+        let mut buf = [0u8; 32];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("a, b, c");
+        assert_eq!(w.replace_all(", ", ","), 2);
+        assert_eq!(w.to_str(), "a,b,c");
+        assert_eq!(w.replace_all(",", ";"), 2);
+        assert_eq!(w.to_str(), "a;b;c");
+        assert_eq!(w.replace_all("x", "yy"), 0);
+        assert_eq!(w.replace_all("", "yy"), 0);
+        assert_eq!(w.replace_all("a;b;c", ""), 1);
+        assert_eq!(w.to_str(), "");
+    }
+
+    #[test]
+    fn replace_all_non_overlapping() {
+        let mut buf = [0u8; 32];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("aaaaa");
+        assert_eq!(w.replace_all("aa", "b"), 2);
+        assert_eq!(w.to_str(), "bba");
+        // replaced text is not searched again
+        assert_eq!(w.replace_all("b", "bb"), 2);
+        assert_eq!(w.to_str(), "bbbba");
+    }
+
+    #[test]
+    fn replace_all_grow() {
+        let mut buf = [0u8; 10];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("a-b-c-d");
+        assert_eq!(w.replace_all("-", "--"), 3);
+        assert_eq!(w.to_str(), "a--b--c--d");
+        // beyond capacity: only the first fits
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("a-b-c-d");
+        assert_eq!(w.replace_all("-", " - "), 1);
+        assert_eq!(w.to_str(), "a - b-c-d");
+    }
+
+    #[test]
+    fn replace_all_multi_byte_neighbours() {
+        let mut buf = [0u8; 32];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("\u{20AC}x\u{20AC}\u{E9}x\u{1F600}");
+        assert_eq!(w.replace_all("x", "\u{2192}"), 2);
+        assert_eq!(
+            w.to_str(),
+            "\u{20AC}\u{2192}\u{20AC}\u{E9}\u{2192}\u{1F600}"
+        );
+        assert_eq!(w.replace_all("\u{20AC}", "E"), 2);
+        assert_eq!(w.to_str(), "E\u{2192}E\u{E9}\u{2192}\u{1F600}");
+        // a multi-byte char that does not fit is not replaced
+        let mut buf = [0u8; 4];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("ab");
+        assert_eq!(w.replace_all("b", "\u{1F600}"), 0);
+        assert_eq!(w.replace_all("b", "\u{20AC}"), 1);
+        assert_eq!(w.as_str(), "a\u{20AC}");
+    }
+
+    #[test]
+    fn retain_filters_in_place() {
+        let mut buf = [0u8; 32];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str(" a b\t\nc ");
+        w.retain(|c| !c.is_whitespace());
+        assert_eq!(w.to_str(), "abc");
+
+        w.retain(|_| true);
+        assert_eq!(w.to_str(), "abc");
+        w.retain(|_| false);
+        assert_eq!(w.to_str(), "");
+    }
+
+    #[test]
+    fn retain_moves_multi_byte_chars() {
+        let mut buf = [0u8; 12];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("x\u{20AC} y\u{1F600} \u{E9}z");
+        assert!(w.is_truncated());
+        w.retain(|c| c != ' ' && c != 'x');
+        assert!(!w.is_truncated());
+        assert_eq!(w.to_str(), "\u{20AC}y\u{1F600}");
+        let _ = w.write_str("!");
+        assert_eq!(w.as_str(), "\u{20AC}y\u{1F600}!");
+    }
+
+    #[test]
+    fn truncate_rolls_back_overflow() {
+        let mut buf = [0u8; 8];
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("ab\u{20AC}");
+        let mark = w.written_bytes();
+        let _ = w.write_str("cdefgh");
+        assert!(w.is_truncated());
+        w.truncate(mark);
+        assert!(!w.is_truncated());
+        let _ = w.write_str("xy");
+        assert_eq!(w.as_str(), "ab\u{20AC}xy");
+
+        // in the middle of a char
+        let mut w = super::WriteTo::new(&mut buf);
+        let _ = w.write_str("ab\u{20AC}");
+        w.truncate(3);
+        assert_eq!(w.written_bytes(), 2);
+        w.truncate(10);
+        assert_eq!(w.as_str(), "ab");
+    }
+
     #[test]
     /// Test for is_not_first_utf8(), e.g. if given byte is a non-start byte of UTF8
     fn is_not_first_utf8_test() {
@@ -188,6 +791,19 @@ pub mod tests {
         assert_eq!(formatted, "");
     }
 
+    #[test]
+    fn write_whole_fits_or_nothing() {
+        use super::WriteWhole;
+        use core::fmt::Write;
+
+        let mut buf = [0u8; 6];
+        let mut w = super::WriteTo::new(&mut buf);
+        w.write_whole("\\n").unwrap();
+        w.write_whole("abcde").unwrap(); // does not fit and stops the stream
+        w.write_str("x").unwrap();
+        assert_eq!(w.as_str(), "\\n");
+    }
+
     #[test]
     fn format_truncate_unicode() {
         let mut buf = [0u8; 4];
@@ -196,4 +812,210 @@ pub mod tests {
         let formatted: &str = super::fmt_truncate(&mut buf, format_args!("Add{}", "\u{20AC}"));
         assert_eq!(formatted, "Add");
     }
+
+    #[test]
+    fn atomic_crlf_pair_in_one_chunk() {
+        use core::fmt::Write;
+
+        let mut buf = [0u8; 5];
+        let mut w = super::WriteTo::new(&mut buf).atomic_crlf(true);
+        w.write_str("abcd\r\nef").unwrap();
+        assert!(w.is_truncated());
+        assert_eq!(w.as_str(), "abcd");
+    }
+
+    #[test]
+    fn atomic_crlf_pair_across_chunks() {
+        use core::fmt::Write;
+
+        let mut buf = [0u8; 5];
+        let mut w = super::WriteTo::new(&mut buf).atomic_crlf(true);
+        w.write_str("abcd\r").unwrap();
+        w.write_str("\nef").unwrap();
+        assert_eq!(w.as_str(), "abcd");
+
+        let mut buf = [0u8; 5];
+        let mut w = super::WriteTo::new(&mut buf).atomic_crlf(true);
+        w.write_str("abcd\r").unwrap();
+        super::WriteWhole::write_whole(&mut w, "\n").unwrap();
+        assert_eq!(w.as_str(), "abcd");
+    }
+
+    #[test]
+    fn atomic_crlf_lone_cr_is_kept() {
+        use core::fmt::Write;
+
+        let mut buf = [0u8; 5];
+        let mut w = super::WriteTo::new(&mut buf).atomic_crlf(true);
+        w.write_str("abcd\rxy").unwrap();
+        assert_eq!(w.as_str(), "abcd\r");
+
+        // Without the option the pair is split
+        let mut buf = [0u8; 5];
+        let mut w = super::WriteTo::new(&mut buf);
+        w.write_str("abcd\r\nef").unwrap();
+        assert_eq!(w.as_str(), "abcd\r");
+    }
+
+    #[test]
+    fn ensure_newline_with_space() {
+        let mut buf = [0u8; 8];
+        let mut w = super::WriteTo::new(&mut buf);
+        core::fmt::Write::write_str(&mut w, "abc").unwrap();
+        w.ensure_newline();
+        w.ensure_newline();
+        assert_eq!(w.as_str(), "abc\n");
+    }
+
+    #[test]
+    fn ensure_newline_full_ascii() {
+        let mut buf = [0u8; 4];
+        let mut w = super::WriteTo::new(&mut buf).newline_terminated(true);
+        core::fmt::Write::write_str(&mut w, "abcdef").unwrap();
+        assert_eq!(w.as_str(), "abc\n");
+    }
+
+    #[test]
+    fn ensure_newline_full_multibyte() {
+        let mut buf = [0u8; 6];
+        let mut w = super::WriteTo::new(&mut buf);
+        core::fmt::Write::write_str(&mut w, "ab\u{10348}").unwrap();
+        w.ensure_newline();
+        w.ensure_newline();
+        assert_eq!(w.as_str(), "ab\n");
+    }
+
+    #[test]
+    fn ensure_newline_already_terminated() {
+        let mut buf = [0u8; 4];
+        let mut w = super::WriteTo::new(&mut buf).newline_terminated(true);
+        core::fmt::Write::write_str(&mut w, "abc\n").unwrap();
+        w.ensure_newline();
+        assert_eq!(w.as_str(), "abc\n");
+
+        let mut buf = [0u8; 0];
+        let mut w = super::WriteTo::new(&mut buf);
+        w.ensure_newline();
+        assert_eq!(w.as_str(), "");
+    }
+
+    // Writes the same random chunks into a borrowed and an owned buffer of N bytes
+    fn owned_matches_borrowed<const N: usize>(seed: u64) {
+        const PIECES: [&str; 5] = ["a", "bc", "\u{E9}", "\u{20AC}", "\u{1F600}\r\n"];
+        let mut seed = seed;
+        let mut buf = [0u8; N];
+        let mut borrowed = super::WriteTo::new(&mut buf);
+        let mut owned = super::OwnedWriteTo::<N>::new_owned();
+        for _ in 0..12 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let chunk = PIECES[(seed >> 33) as usize % PIECES.len()];
+            borrowed.write_str(chunk).unwrap();
+            owned.write_str(chunk).unwrap();
+            assert_eq!(owned.is_truncated(), borrowed.is_truncated());
+        }
+        assert_eq!(owned.as_str(), borrowed.as_str());
+    }
+
+    #[test]
+    fn owned_truncation_same_as_borrowed() {
+        for seed in 0..50 {
+            owned_matches_borrowed::<0>(seed);
+            owned_matches_borrowed::<1>(seed);
+            owned_matches_borrowed::<5>(seed);
+            owned_matches_borrowed::<13>(seed);
+        }
+    }
+
+    struct Status {
+        code: u8,
+        text: super::OwnedWriteTo<8>,
+    }
+
+    fn make_status(code: u8) -> Status {
+        let mut text = super::WriteTo::new_owned();
+        write!(text, "E{} overheat", code).unwrap();
+        Status { code, text }
+    }
+
+    #[test]
+    fn owned_in_struct_and_moved() {
+        let status = make_status(42);
+        assert_eq!(status.code, 42);
+        assert_eq!(status.text.as_str(), "E42 over");
+        assert!(status.text.is_truncated());
+
+        fn send<T: Send + 'static>(value: T) -> T {
+            value
+        }
+        let mut status = send(status);
+        status.text.clear();
+        write!(status.text, "ok").unwrap();
+        assert_eq!(status.text.as_str(), "ok");
+        assert!(!status.text.is_truncated());
+    }
+
+    #[test]
+    fn other_storage() {
+        let mut w = super::WriteTo::with_storage([0u8; 4]).atomic_crlf(true);
+        write!(w, "abc\r\n").unwrap();
+        assert_eq!(w.to_str(), "abc");
+        w.truncate(1);
+        assert_eq!(w.replace_all("a", "xyz"), 1);
+        assert_eq!(w.as_str(), "xyz");
+
+        // a borrowed slice is the other storage
+        let mut buf = [0u8; 4];
+        let mut w = super::WriteTo::with_storage(&mut buf[..]);
+        write!(w, "{}", 12345).unwrap();
+        assert_eq!(w.as_str(), "1234");
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)] // fields are only read by Debug
+    struct Reading {
+        id: u8,
+        value: i16,
+    }
+
+    struct Celsius(i16);
+
+    impl core::fmt::Display for Celsius {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            if f.alternate() {
+                write!(f, "{} \u{B0}C", self.0)
+            } else {
+                write!(f, "{}C", self.0)
+            }
+        }
+    }
+
+    #[test]
+    fn fmt_single_display() {
+        let mut buf = [0u8; 16];
+        assert_eq!(super::fmt_display(&mut buf, &Celsius(-5)), "-5C");
+        assert_eq!(super::fmt_display_alt(&mut buf, &Celsius(21)), "21 \u{B0}C");
+        assert_eq!(super::fmt_display(&mut buf, "unsized str"), "unsized str");
+        let mut small = [0u8; 4];
+        assert_eq!(super::fmt_display(&mut small, &Celsius(-1234)), "-123");
+        assert_eq!(super::fmt_display_alt(&mut small, &Celsius(21)), "21 ");
+    }
+
+    #[test]
+    fn fmt_single_debug() {
+        let reading = Reading { id: 7, value: -3 };
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            super::fmt_debug(&mut buf, &reading),
+            "Reading { id: 7, value: -3 }"
+        );
+        assert_eq!(
+            super::fmt_debug_alt(&mut buf, &reading),
+            "Reading {\n    id: 7,\n    value: -3,\n}"
+        );
+        let mut small = [0u8; 12];
+        assert_eq!(super::fmt_debug(&mut small, &reading), "Reading { id");
+        assert_eq!(super::fmt_debug_alt(&mut small, &reading), "Reading {\n  ");
+    }
 }
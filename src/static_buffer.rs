@@ -0,0 +1,135 @@
+//  ( /   @ @    ()  Static scratch buffer handed out once
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Buffer for a `static` that gives `&'static mut` access to its bytes only once, usually
+/// declared with [static_fmt_buffer!].
+///
+/// [StaticFmtBuffer::take] returns the buffer on the first call and `None` after that, so
+/// the code that got it (like an interrupt handler) owns it without `static mut` and
+/// `unsafe`. The flag is an atomic swap, so the target needs atomic compare and swap. For
+/// targets like `thumbv6m` that have only atomic loads and stores the `critical-section`
+/// feature sets the flag in `critical_section::with`, the application provides the
+/// critical section implementation; without the feature the buffer is not built for them.
+pub struct StaticFmtBuffer<const N: usize> {
+    taken: AtomicBool,
+    buffer: UnsafeCell<[u8; N]>,
+}
+
+// The buffer is reachable only via the single successful take()
+unsafe impl<const N: usize> Sync for StaticFmtBuffer<N> {}
+
+impl<const N: usize> StaticFmtBuffer<N> {
+    /// Creates buffer of `N` zero bytes that was not taken yet.
+    pub const fn new() -> Self {
+        StaticFmtBuffer {
+            taken: AtomicBool::new(false),
+            buffer: UnsafeCell::new([0u8; N]),
+        }
+    }
+
+    /// Returns the buffer on the first call, `None` on the following calls.
+    #[allow(clippy::mut_from_ref)] // handed out once, see the flag
+    pub fn take(&'static self) -> Option<&'static mut [u8; N]> {
+        if self.set_taken() {
+            return None;
+        }
+        // the flag was not set before, so there is no other reference
+        Some(unsafe { &mut *self.buffer.get() })
+    }
+
+    // Sets the flag, returns true if it was already set
+    #[cfg(not(feature = "critical-section"))]
+    fn set_taken(&self) -> bool {
+        self.taken.swap(true, Ordering::AcqRel)
+    }
+
+    // Same without compare and swap, nothing runs between the load and the store
+    #[cfg(feature = "critical-section")]
+    fn set_taken(&self) -> bool {
+        critical_section::with(|_| {
+            let taken = self.taken.load(Ordering::Acquire);
+            self.taken.store(true, Ordering::Release);
+            taken
+        })
+    }
+
+    /// True if the buffer was handed out.
+    pub fn is_taken(&self) -> bool {
+        self.taken.load(Ordering::Acquire)
+    }
+}
+
+impl<const N: usize> Default for StaticFmtBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Declares a `static` [StaticFmtBuffer] of the given size, `take()` hands it out once.
+///
+/// An interrupt handler logging into its own buffer:
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// stackfmt::static_fmt_buffer!(ISR_LOG: 64);
+///
+/// struct IsrLogger {
+///     out: stackfmt::WriteTo<'static>,
+/// }
+///
+/// fn init_logger() -> Option<IsrLogger> {
+///     Some(IsrLogger { out: stackfmt::WriteTo::new(ISR_LOG.take()?) })
+/// }
+///
+/// fn on_adc_interrupt(logger: &mut IsrLogger, sample: u16) {
+///     logger.out.clear();
+///     let _ = write!(logger.out, "adc={}", sample);
+///     // send logger.out.as_str() to the UART here
+/// }
+///
+/// let mut logger = init_logger().unwrap();
+/// on_adc_interrupt(&mut logger, 512);
+/// assert_eq!(logger.out.as_str(), "adc=512");
+/// // the second init gets nothing
+/// assert!(init_logger().is_none());
+/// ```
+#[macro_export]
+macro_rules! static_fmt_buffer {
+    ($vis:vis $name:ident : $size:expr) => {
+        $vis static $name: $crate::StaticFmtBuffer<{ $size }> = $crate::StaticFmtBuffer::new();
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    static_fmt_buffer!(SCRATCH: 16);
+    static_fmt_buffer!(pub(crate) SHARED: 4 * 2);
+
+    #[test]
+    fn static_buffer_take_once() {
+        assert!(!SCRATCH.is_taken());
+        let buf = SCRATCH.take().unwrap();
+        assert_eq!(buf.len(), 16);
+        assert!(SCRATCH.take().is_none());
+        assert!(SCRATCH.is_taken());
+
+        let mut w = WriteTo::new(buf);
+        write!(w, "{}-{}", 1, 2).unwrap();
+        assert_eq!(w.as_str(), "1-2");
+    }
+
+    #[test]
+    fn static_buffer_size_expr() {
+        let buf = SHARED.take().unwrap();
+        assert_eq!(buf, &[0u8; 8]);
+        assert!(SHARED.take().is_none());
+    }
+}
@@ -0,0 +1,163 @@
+//  ( /   @ @    ()  Statistics of write calls for buffer tuning
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Numbers collected by [StatsWriter] (and [WriteTo](crate::WriteTo) with the `stats`
+/// feature).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FmtStats {
+    /// Number of `write_str()` calls
+    pub calls: usize,
+    /// Bytes passed to `write_str()`
+    pub offered: usize,
+    /// Bytes actually stored or forwarded
+    pub accepted: usize,
+    /// Length of the longest single `write_str()` input
+    pub largest_chunk: usize,
+}
+
+impl FmtStats {
+    pub(crate) fn record(&mut self, offered: usize, accepted: usize) {
+        self.calls += 1;
+        self.offered += offered;
+        self.accepted += accepted;
+        self.largest_chunk = self.largest_chunk.max(offered);
+    }
+}
+
+/// Impl of [fmt::Write] that forwards everything to the inner stream and collects
+/// [FmtStats] about the calls.
+///
+/// A chunk counts as accepted if the inner stream returned `Ok`. [WriteTo](crate::WriteTo)
+/// returns `Ok` also when it truncates, enable the `stats` feature to get the numbers from
+/// it directly.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 32];
+/// let mut w = stackfmt::StatsWriter::new(stackfmt::WriteTo::new(&mut buf));
+/// let (id, temp) = (7, 21);
+/// write!(w, "id={} temp={}", id, temp).unwrap();
+/// assert_eq!(w.stats().calls, 4);
+/// assert_eq!(w.stats().offered, 12);
+/// assert_eq!(w.stats().largest_chunk, 6);
+/// ```
+pub struct StatsWriter<W: fmt::Write> {
+    inner: W,
+    stats: FmtStats,
+}
+
+impl<W: fmt::Write> StatsWriter<W> {
+    /// Creates stream collecting stats about writes into `inner`.
+    pub fn new(inner: W) -> Self {
+        StatsWriter {
+            inner,
+            stats: FmtStats::default(),
+        }
+    }
+
+    /// Stats collected since creation or the last reset
+    pub fn stats(&self) -> &FmtStats {
+        &self.stats
+    }
+
+    /// Starts collecting from zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = FmtStats::default();
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for StatsWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let result = self.inner.write_str(s);
+        let accepted = if result.is_ok() { s.len() } else { 0 };
+        self.stats.record(s.len(), accepted);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FmtStats, StatsWriter};
+    use core::fmt;
+    use core::fmt::Write;
+
+    // Fails writes that exceed `room` bytes
+    struct Limited {
+        room: usize,
+    }
+
+    impl fmt::Write for Limited {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if s.len() > self.room {
+                return Err(fmt::Error);
+            }
+            self.room -= s.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stats_several_chunks() {
+        let mut w = StatsWriter::new(Limited { room: 100 });
+        let (name, id, tag) = ("ab", 12345, 'x');
+        write!(w, "{}:{}:{}", name, id, tag).unwrap();
+        assert_eq!(
+            *w.stats(),
+            FmtStats {
+                calls: 5,
+                offered: 10,
+                accepted: 10,
+                largest_chunk: 5
+            }
+        );
+    }
+
+    #[test]
+    fn stats_offered_more_than_accepted() {
+        let mut w = StatsWriter::new(Limited { room: 4 });
+        let _ = w.write_str("abc");
+        let _ = w.write_str("def");
+        let _ = w.write_str("g");
+        assert_eq!(w.stats().offered, 7);
+        assert_eq!(w.stats().accepted, 4);
+        assert_eq!(w.stats().calls, 3);
+    }
+
+    #[test]
+    fn stats_reset() {
+        let mut w = StatsWriter::new(Limited { room: 100 });
+        write!(w, "{}", 1).unwrap();
+        w.reset_stats();
+        assert_eq!(*w.stats(), FmtStats::default());
+        write!(w, "ab").unwrap();
+        assert_eq!(w.stats().calls, 1);
+        assert_eq!(w.into_inner().room, 97);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn stats_of_write_to_survive_clear() {
+        let mut buf = [0u8; 4];
+        let mut w = crate::WriteTo::new(&mut buf);
+        let _ = w.write_str("abc");
+        let _ = w.write_str("def");
+        assert_eq!(w.stats().offered, 6);
+        assert_eq!(w.stats().accepted, 4);
+        w.clear();
+        let _ = w.write_str("xy");
+        assert_eq!(w.stats().calls, 3);
+        assert_eq!(w.stats().accepted, 6);
+        w.reset_stats();
+        assert_eq!(*w.stats(), FmtStats::default());
+        assert_eq!(w.as_str(), "xy");
+    }
+}
@@ -0,0 +1,305 @@
+//  ( /   @ @    ()  Debug struct style output from plain code
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+const INDENT: &str = "    ";
+
+// State of one struct level, kept apart so a nested writer can update its parent
+struct Level {
+    depth: usize,
+    pretty: bool,
+    outer: usize,   // bytes reserved for closing the enclosing structs
+    reserve: usize, // bytes reserved for closing this and the enclosing structs
+    fields: usize,
+    truncated: bool,
+}
+
+/// Writes `Name { field: value, .. }` like [fmt::Formatter::debug_struct] does, but from
+/// plain code into a [WriteTo].
+///
+/// The name is written with the first field or by [StructWriter::finish], a struct without
+/// fields is shown as just the name. [StructWriter::pretty] selects the multi-line form of
+/// `{:#?}` and [StructWriter::field_struct] starts a nested struct, which must be finished
+/// before the parent is used again. Space for the closing braces is kept while fields are
+/// written: a field that does not fit is removed and the following fields are dropped, and
+/// `finish` still closes the braces, so the output stays well formed.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let mut out = stackfmt::WriteTo::new(&mut buf);
+/// let mut s = stackfmt::StructWriter::new(&mut out).name("Motor");
+/// s.field("rpm", format_args!("{}", 1200)).field_display("temp", &71.5);
+/// s.field("state", format_args!("{:?}", core::cmp::Ordering::Less));
+/// assert!(s.finish());
+/// assert_eq!(out.as_str(), "Motor { rpm: 1200, temp: 71.5, state: Less }");
+/// ```
+pub struct StructWriter<'w, 'a> {
+    out: &'w mut WriteTo<'a>,
+    name: &'w str,
+    started: bool,
+    level: Level,
+    parent: Option<&'w mut Level>,
+}
+
+impl<'w, 'a> StructWriter<'w, 'a> {
+    /// Creates writer appending a struct to `out`.
+    pub fn new(out: &'w mut WriteTo<'a>) -> Self {
+        StructWriter {
+            out,
+            name: "",
+            started: false,
+            level: Level {
+                depth: 0,
+                pretty: false,
+                outer: 0,
+                reserve: 0,
+                fields: 0,
+                truncated: false,
+            },
+            parent: None,
+        }
+    }
+
+    /// Sets the name of the struct.
+    pub fn name(mut self, name: &'w str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Writes each field on its own line indented by 4 spaces, like `{:#?}`.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.level.pretty = pretty;
+        self
+    }
+
+    /// Writes field `name: value`.
+    pub fn field(&mut self, name: &str, value: fmt::Arguments) -> &mut Self {
+        if let Some(start) = self.begin_field(name) {
+            let _ = self.out.write_fmt(value);
+            if self.level.pretty {
+                let _ = self.out.write_str(",\n");
+            }
+            self.end_field(start, self.level.reserve);
+        }
+        self
+    }
+
+    /// Writes field `name: value` with the value shown by its [fmt::Display].
+    pub fn field_display(&mut self, name: &str, value: &dyn fmt::Display) -> &mut Self {
+        self.field(name, format_args!("{}", value))
+    }
+
+    /// Starts field `name` with a nested struct named `struct_name`, the returned writer
+    /// must be finished before this one is used again.
+    pub fn field_struct<'c>(
+        &'c mut self,
+        name: &str,
+        struct_name: &'c str,
+    ) -> StructWriter<'c, 'a> {
+        let pretty = self.level.pretty;
+        let trailer = if pretty { 2 } else { 0 }; // ",\n" after the nested struct
+        let mut level = Level {
+            depth: self.level.depth + 1,
+            pretty,
+            outer: self.level.reserve + trailer,
+            reserve: 0,
+            fields: 0,
+            truncated: self.level.truncated,
+        };
+        level.reserve = level.outer + close_len(&level);
+        let mut started = false;
+        if let Some(start) = self.begin_field(name) {
+            let _ = self.out.write_str(struct_name);
+            started = self.end_field(start, level.reserve);
+        }
+        level.truncated |= !started;
+        StructWriter {
+            out: &mut *self.out,
+            name: struct_name,
+            started,
+            level,
+            parent: Some(&mut self.level),
+        }
+    }
+
+    /// True if a field did not fit, it and the following fields were dropped.
+    pub fn is_truncated(&self) -> bool {
+        self.level.truncated
+    }
+
+    /// Writes the closing brace, returns true if all the fields were written.
+    pub fn finish(mut self) -> bool {
+        if self.parent.is_none() {
+            self.start();
+        }
+        if self.started && self.level.fields > 0 {
+            if self.level.pretty {
+                self.write_indent(self.level.depth);
+                let _ = self.out.write_char('}');
+            } else {
+                let _ = self.out.write_str(" }");
+            }
+        }
+        let truncated = self.level.truncated || self.out.is_truncated();
+        if let Some(parent) = self.parent.take() {
+            if self.started && parent.pretty {
+                let _ = self.out.write_str(",\n");
+            }
+            parent.truncated |= truncated;
+        }
+        !truncated
+    }
+
+    // Writes the name of a top level struct
+    fn start(&mut self) {
+        if self.started || self.level.truncated {
+            return;
+        }
+        self.level.reserve = self.level.outer + close_len(&self.level);
+        let start = self.out.written_bytes();
+        let _ = self.out.write_str(self.name);
+        self.started = self.fits(start, self.level.reserve);
+    }
+
+    // Writes the separator and `name: `, returns where the field starts or None if dropped
+    fn begin_field(&mut self, name: &str) -> Option<usize> {
+        self.start();
+        if !self.started || self.level.truncated {
+            self.level.truncated = true;
+            return None;
+        }
+        let start = self.out.written_bytes();
+        match (self.level.pretty, self.level.fields) {
+            (true, 0) => {
+                let _ = self.out.write_str(" {\n");
+            }
+            (false, 0) => {
+                let _ = self.out.write_str(" { ");
+            }
+            (false, _) => {
+                let _ = self.out.write_str(", ");
+            }
+            (true, _) => {}
+        }
+        if self.level.pretty {
+            self.write_indent(self.level.depth + 1);
+        }
+        let _ = write!(self.out, "{}: ", name);
+        Some(start)
+    }
+
+    // Keeps the field if `reserve` bytes are left after it, or removes it
+    fn end_field(&mut self, start: usize, reserve: usize) -> bool {
+        let fits = self.fits(start, reserve);
+        if fits {
+            self.level.fields += 1;
+        }
+        fits
+    }
+
+    // Keeps the text after `start` if `reserve` bytes are left after it, or removes it
+    fn fits(&mut self, start: usize, reserve: usize) -> bool {
+        if self.out.is_truncated() || self.out.remaining_capacity() < reserve {
+            self.out.truncate(start);
+            self.level.truncated = true;
+            false
+        } else {
+            true
+        }
+    }
+
+    fn write_indent(&mut self, depth: usize) {
+        for _ in 0..depth {
+            let _ = self.out.write_str(INDENT);
+        }
+    }
+}
+
+// Bytes of the closing brace of a struct with fields
+fn close_len(level: &Level) -> usize {
+    if level.pretty {
+        INDENT.len() * level.depth + 1
+    } else {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StructWriter;
+    use crate::WriteTo;
+
+    #[test]
+    fn struct_flat() {
+        let mut buf = [0u8; 64];
+        let mut out = WriteTo::new(&mut buf);
+        let mut s = StructWriter::new(&mut out).name("Point");
+        s.field("x", format_args!("{}", -1))
+            .field_display("y", &"up");
+        assert!(!s.is_truncated());
+        assert!(s.finish());
+        assert_eq!(out.as_str(), "Point { x: -1, y: up }");
+    }
+
+    #[test]
+    fn struct_zero_fields() {
+        let mut buf = [0u8; 32];
+        let mut out = WriteTo::new(&mut buf);
+        assert!(StructWriter::new(&mut out).name("Idle").finish());
+        assert_eq!(out.as_str(), "Idle");
+
+        let mut out = WriteTo::new(&mut buf);
+        let mut s = StructWriter::new(&mut out).name("Outer");
+        assert!(s.field_struct("inner", "Unit").finish());
+        assert!(s.finish());
+        assert_eq!(out.as_str(), "Outer { inner: Unit }");
+    }
+
+    #[test]
+    fn struct_nested_pretty() {
+        let mut buf = [0u8; 128];
+        let mut out = WriteTo::new(&mut buf);
+        let mut s = StructWriter::new(&mut out).name("Motor").pretty(true);
+        s.field("rpm", format_args!("{}", 1200));
+        let mut pid = s.field_struct("pid", "Pid");
+        pid.field("kp", format_args!("{}", 0.5))
+            .field("ki", format_args!("{}", 0.1));
+        assert!(pid.finish());
+        s.field("state", format_args!("Running"));
+        assert!(s.finish());
+        assert_eq!(
+            out.as_str(),
+            "Motor {\n    rpm: 1200,\n    pid: Pid {\n        kp: 0.5,\n        ki: 0.1,\n    },\n    state: Running,\n}"
+        );
+    }
+
+    #[test]
+    fn struct_truncated_in_nested_field() {
+        let mut buf = [0u8; 32];
+        let mut out = WriteTo::new(&mut buf);
+        let mut s = StructWriter::new(&mut out).name("M");
+        s.field("a", format_args!("{}", 1));
+        let mut inner = s.field_struct("b", "B");
+        inner.field("c", format_args!("{}", 2));
+        inner.field("long", format_args!("{}", "does not fit"));
+        inner.field("d", format_args!("{}", 3));
+        assert!(inner.is_truncated());
+        assert!(!inner.finish());
+        assert!(s.is_truncated());
+        s.field("e", format_args!("{}", 4));
+        assert!(!s.finish());
+        assert_eq!(out.as_str(), "M { a: 1, b: B { c: 2 } }");
+
+        // a buffer that fits only the name
+        let mut buf = [0u8; 3];
+        let mut out = WriteTo::new(&mut buf);
+        let mut s = StructWriter::new(&mut out).name("M");
+        s.field("a", format_args!("{}", 1));
+        assert!(!s.finish());
+        assert_eq!(out.as_str(), "M");
+    }
+}
@@ -0,0 +1,395 @@
+//  ( /   @ @    ()  ANSI styling that can be switched off at runtime
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// Crate-level switch used by Styled values that were not given an explicit flag
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// SGR sequence that resets all the attributes
+const RESET: &str = "\x1b[0m";
+
+/// Enables or disables styling for all [Styled] values created with [Styled::new].
+///
+/// Styling is disabled by default so the output is plain text unless requested.
+pub fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns the current value of the flag set by [set_color_enabled].
+pub fn color_enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
+/// One of the basic 16 terminal colors.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl Color {
+    // SGR code of the color used as foreground, background code is +10
+    fn fg_code(self) -> u8 {
+        let n = self as u8;
+        if n < 8 {
+            30 + n
+        } else {
+            90 + n - 8
+        }
+    }
+}
+
+/// Text attribute applied by [Styled::style].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Style {
+    Bold,
+    Dim,
+    Underline,
+}
+
+/// Display adapter that surrounds the value with SGR escape sequences when styling is on.
+///
+/// When styling is off the value is formatted exactly as without the adapter. A styled value
+/// nested into another one restores the outer style after its own reset. While styling is on,
+/// width, precision, alignment and the `+`, `#` and `0` flags are forwarded to the value,
+/// but the fill is always a space.
+///
+/// ```
+/// use stackfmt::{Color, Styled};
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("{}", Styled::with_enabled("ERR", true).fg(Color::Red).bold()),
+/// );
+/// assert_eq!(s, "\x1b[1;31mERR\x1b[0m");
+///
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("{}", Styled::with_enabled("ERR", false).fg(Color::Red)),
+/// );
+/// assert_eq!(s, "ERR");
+/// ```
+pub struct Styled<D: fmt::Display> {
+    value: D,
+    enabled: Option<bool>, // None means the crate-level flag decides
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    underline: bool,
+}
+
+impl<D: fmt::Display> Styled<D> {
+    /// Creates unstyled adapter that follows the crate-level [set_color_enabled] flag.
+    pub fn new(value: D) -> Self {
+        Styled {
+            value,
+            enabled: None,
+            fg: None,
+            bg: None,
+            bold: false,
+            dim: false,
+            underline: false,
+        }
+    }
+
+    /// Creates unstyled adapter with styling explicitly turned on or off.
+    pub fn with_enabled(value: D, enabled: bool) -> Self {
+        let mut styled = Self::new(value);
+        styled.enabled = Some(enabled);
+        styled
+    }
+
+    /// Sets foreground color.
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Sets background color.
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Adds text attribute.
+    pub fn style(mut self, style: Style) -> Self {
+        match style {
+            Style::Bold => self.bold = true,
+            Style::Dim => self.dim = true,
+            Style::Underline => self.underline = true,
+        }
+        self
+    }
+
+    /// Same as `style(Style::Bold)`.
+    pub fn bold(self) -> Self {
+        self.style(Style::Bold)
+    }
+
+    /// Same as `style(Style::Dim)`.
+    pub fn dim(self) -> Self {
+        self.style(Style::Dim)
+    }
+
+    /// Same as `style(Style::Underline)`.
+    pub fn underline(self) -> Self {
+        self.style(Style::Underline)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or_else(color_enabled)
+    }
+
+    // Writes `ESC [ codes m` or nothing if no style is set
+    fn write_prefix(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        let flags = [(self.bold, 1u8), (self.dim, 2), (self.underline, 4)];
+        let codes = flags
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, code)| *code)
+            .chain(self.fg.map(Color::fg_code))
+            .chain(self.bg.map(|c| c.fg_code() + 10));
+        for (n, code) in codes.enumerate() {
+            w.write_str(if n == 0 { "\x1b[" } else { ";" })?;
+            write!(w, "{}", code)?;
+        }
+        if self.has_style() {
+            w.write_str("m")?;
+        }
+        Ok(())
+    }
+
+    fn has_style(&self) -> bool {
+        self.bold || self.dim || self.underline || self.fg.is_some() || self.bg.is_some()
+    }
+}
+
+// Forwards the value output, re-applying the outer style after every reset written by
+// nested Styled values
+struct Reapply<'s, 'f, 'b, D: fmt::Display> {
+    f: &'f mut fmt::Formatter<'b>,
+    styled: &'s Styled<D>,
+}
+
+impl<'s, 'f, 'b, D: fmt::Display> fmt::Write for Reapply<'s, 'f, 'b, D> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.f.write_str(s)?;
+        if s.ends_with(RESET) {
+            self.styled.write_prefix(self.f)?;
+        }
+        Ok(())
+    }
+}
+
+// Flags of the outer formatter, they are read before it is wrapped into Reapply
+struct Flags {
+    align: Option<fmt::Alignment>,
+    width: usize,
+    precision: Option<usize>,
+    plus: bool,
+    alternate: bool,
+    zero: bool,
+}
+
+impl Flags {
+    fn of(f: &fmt::Formatter) -> Self {
+        Flags {
+            align: f.align(),
+            width: f.width().unwrap_or(0),
+            precision: f.precision(),
+            plus: f.sign_plus(),
+            alternate: f.alternate(),
+            zero: f.sign_aware_zero_pad(),
+        }
+    }
+}
+
+// Writes value with the spec `{:<align><flags>width$[.p$]}`, format strings must be literals
+macro_rules! write_spec {
+    ($w:expr, $value:expr, $flags:expr, $align:literal, $sign:literal) => {
+        match $flags.precision {
+            Some(p) => write!(
+                $w,
+                concat!("{:", $align, $sign, "width$.p$}"),
+                $value,
+                width = $flags.width,
+                p = p
+            ),
+            None => write!(
+                $w,
+                concat!("{:", $align, $sign, "width$}"),
+                $value,
+                width = $flags.width
+            ),
+        }
+    };
+}
+
+// Formats value with the flags of the outer formatter, except the fill
+fn write_with_flags<D: fmt::Display>(
+    w: &mut impl fmt::Write,
+    value: &D,
+    flags: &Flags,
+) -> fmt::Result {
+    use fmt::Alignment::{Center, Left, Right};
+
+    macro_rules! with_align {
+        ($align:literal) => {
+            match (flags.plus, flags.alternate, flags.zero) {
+                (false, false, false) => write_spec!(w, value, flags, $align, ""),
+                (true, false, false) => write_spec!(w, value, flags, $align, "+"),
+                (false, true, false) => write_spec!(w, value, flags, $align, "#"),
+                (false, false, true) => write_spec!(w, value, flags, $align, "0"),
+                (true, true, false) => write_spec!(w, value, flags, $align, "+#"),
+                (true, false, true) => write_spec!(w, value, flags, $align, "+0"),
+                (false, true, true) => write_spec!(w, value, flags, $align, "#0"),
+                (true, true, true) => write_spec!(w, value, flags, $align, "+#0"),
+            }
+        };
+    }
+    match flags.align {
+        None => with_align!(""),
+        Some(Left) => with_align!("<"),
+        Some(Right) => with_align!(">"),
+        Some(Center) => with_align!("^"),
+    }
+}
+
+impl<D: fmt::Display> fmt::Display for Styled<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.is_enabled() || !self.has_style() {
+            return self.value.fmt(f);
+        }
+
+        let flags = Flags::of(f);
+        self.write_prefix(f)?;
+        write_with_flags(&mut Reapply { f, styled: self }, &self.value, &flags)?;
+        f.write_str(RESET)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Style, Styled};
+
+    #[test]
+    fn styled_on_and_off() {
+        let mut buf = [0u8; 64];
+        let on = Styled::with_enabled(42, true).fg(Color::Green);
+        let s = crate::fmt_truncate(&mut buf, format_args!("v={}!", on));
+        assert_eq!(s, "v=\x1b[32m42\x1b[0m!");
+
+        let off = Styled::with_enabled(42, false).fg(Color::Green);
+        let s = crate::fmt_truncate(&mut buf, format_args!("v={}!", off));
+        assert_eq!(s, "v=42!");
+    }
+
+    #[test]
+    fn styled_all_attributes() {
+        let mut buf = [0u8; 64];
+        let styled = Styled::with_enabled("x", true)
+            .fg(Color::BrightYellow)
+            .bg(Color::Blue)
+            .style(Style::Dim)
+            .underline();
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", styled));
+        assert_eq!(s, "\x1b[2;4;93;44mx\x1b[0m");
+    }
+
+    #[test]
+    fn styled_without_style_is_plain() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", Styled::with_enabled("x", true)),
+        );
+        assert_eq!(s, "x");
+    }
+
+    struct Alternate;
+
+    impl core::fmt::Display for Alternate {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.write_str(if f.alternate() { "alternate" } else { "plain" })
+        }
+    }
+
+    #[test]
+    fn styled_forwards_flags() {
+        let mut buf = [0u8; 64];
+        let on = Styled::with_enabled(1.2345, true).bold();
+        let s = crate::fmt_truncate(&mut buf, format_args!("[{:>7.2}]", on));
+        assert_eq!(s, "[\x1b[1m   1.23\x1b[0m]");
+
+        let off = Styled::with_enabled(1.2345, false).bold();
+        let s = crate::fmt_truncate(&mut buf, format_args!("[{:>7.2}]", off));
+        assert_eq!(s, "[   1.23]");
+
+        // sign, alternate and zero flags
+        let on = Styled::with_enabled(5, true).bold();
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:+}|{:+05}", on, on));
+        assert_eq!(s, "\x1b[1m+5\x1b[0m|\x1b[1m+0005\x1b[0m");
+        let alt = Styled::with_enabled(Alternate, true).bold();
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}|{:#}", alt, alt));
+        assert_eq!(s, "\x1b[1mplain\x1b[0m|\x1b[1malternate\x1b[0m");
+    }
+
+    #[test]
+    fn styled_nested() {
+        let mut buf = [0u8; 128];
+        let inner = Styled::with_enabled("B", true).fg(Color::Blue);
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}",
+                Styled::with_enabled(format_args!("A {} C", inner), true)
+                    .fg(Color::Red)
+                    .bold()
+            ),
+        );
+        assert_eq!(s, "\x1b[1;31mA \x1b[34mB\x1b[0m\x1b[1;31m C\x1b[0m");
+
+        // inner value with styling off does not disturb the outer one
+        let inner = Styled::with_enabled("B", false).fg(Color::Blue);
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}",
+                Styled::with_enabled(format_args!("A {} C", inner), true).fg(Color::Red)
+            ),
+        );
+        assert_eq!(s, "\x1b[31mA B C\x1b[0m");
+    }
+
+    #[test]
+    fn styled_global_flag() {
+        let mut buf = [0u8; 64];
+        // explicit flag always takes precedence, so only this test touches the global one
+        assert!(!super::color_enabled());
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", Styled::new("x").bold()));
+        assert_eq!(s, "x");
+        super::set_color_enabled(true);
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", Styled::new("x").bold()));
+        assert_eq!(s, "\x1b[1mx\x1b[0m");
+        super::set_color_enabled(false);
+    }
+}
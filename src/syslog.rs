@@ -0,0 +1,363 @@
+//  ( /   @ @    ()  RFC 5424 syslog messages
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use crate::timestamp::Timestamp;
+use core::fmt;
+use core::fmt::Write;
+
+// Field length limits of RFC 5424
+const HOSTNAME_MAX: usize = 255;
+const APP_NAME_MAX: usize = 48;
+const PROCID_MAX: usize = 128;
+const MSGID_MAX: usize = 32;
+const SD_NAME_MAX: usize = 32;
+
+/// Builds an RFC 5424 syslog message `<PRI>1 TIMESTAMP HOST APP PROCID MSGID SD MSG` into a
+/// [WriteTo].
+///
+/// Header fields are set first, they are written when the first structured data element or
+/// the message is added, or on [SyslogBuilder::finish]. Structured data elements go before
+/// the message: an element started after [SyslogBuilder::msg] is not written and marks the
+/// message as one that should not be sent. Header fields and SD names are
+/// clamped to the RFC length limits and chars outside of printable US-ASCII (and `=`, ` `,
+/// `]`, `"` in SD names) are replaced with `_`; absent or empty fields are shown as `-`.
+/// SD param values get `"`, `\` and `]` escaped. Facility is masked to 0..=23 and severity
+/// to 0..=7.
+///
+/// ```
+/// use stackfmt::{SyslogBuilder, WriteTo};
+///
+/// let mut buf = [0u8; 128];
+/// let mut msg = SyslogBuilder::new(WriteTo::new(&mut buf))
+///     .facility(16)
+///     .severity(4)
+///     .timestamp(1717250602)
+///     .hostname("gw-01")
+///     .app_name("sensord");
+/// msg.sd_element("meta").param("fw", "1.2").param("up", 3600);
+/// msg.msg(format_args!("temperature {} C", 71));
+/// let (s, truncated) = msg.finish();
+/// assert_eq!(
+///     s,
+///     r#"<132>1 2024-06-01T14:03:22Z gw-01 sensord - - [meta fw="1.2" up="3600"] temperature 71 C"#
+/// );
+/// assert!(!truncated);
+/// ```
+pub struct SyslogBuilder<'a> {
+    out: WriteTo<'a>,
+    facility: u8,
+    severity: u8,
+    timestamp: Option<Timestamp>,
+    hostname: &'a str,
+    app_name: &'a str,
+    procid: &'a str,
+    msgid: &'a str,
+    header_written: bool,
+    has_sd: bool,
+    has_msg: bool,
+    misordered: bool, // an SD element was started after the message
+}
+
+impl<'a> SyslogBuilder<'a> {
+    /// Creates builder with facility user (1) and severity notice (5).
+    pub fn new(out: WriteTo<'a>) -> Self {
+        SyslogBuilder {
+            out,
+            facility: 1,
+            severity: 5,
+            timestamp: None,
+            hostname: "",
+            app_name: "",
+            procid: "",
+            msgid: "",
+            header_written: false,
+            has_sd: false,
+            has_msg: false,
+            misordered: false,
+        }
+    }
+
+    /// Sets facility 0..=23, e.g. 16 for local0.
+    pub fn facility(mut self, facility: u8) -> Self {
+        self.facility = facility.min(23);
+        self
+    }
+
+    /// Sets severity 0 (emergency) ..= 7 (debug).
+    pub fn severity(mut self, severity: u8) -> Self {
+        self.severity = severity.min(7);
+        self
+    }
+
+    /// Sets time of the event in seconds since the Unix epoch.
+    pub fn timestamp(mut self, unix_secs: i64) -> Self {
+        self.timestamp = Some(Timestamp::new(unix_secs));
+        self
+    }
+
+    /// Sets time of the event with microseconds.
+    pub fn timestamp_micros(mut self, unix_secs: i64, micros: u32) -> Self {
+        let nanos = micros.min(999_999) * 1000;
+        self.timestamp = Some(Timestamp::with_nanos(unix_secs, nanos));
+        self
+    }
+
+    /// Sets host name, at most 255 chars.
+    pub fn hostname(mut self, hostname: &'a str) -> Self {
+        self.hostname = hostname;
+        self
+    }
+
+    /// Sets application name, at most 48 chars.
+    pub fn app_name(mut self, app_name: &'a str) -> Self {
+        self.app_name = app_name;
+        self
+    }
+
+    /// Sets process id, at most 128 chars.
+    pub fn procid(mut self, procid: &'a str) -> Self {
+        self.procid = procid;
+        self
+    }
+
+    /// Sets message type id, at most 32 chars.
+    pub fn msgid(mut self, msgid: &'a str) -> Self {
+        self.msgid = msgid;
+        self
+    }
+
+    /// Starts a structured data element `[id ...]`, params are added to the returned value.
+    /// After [SyslogBuilder::msg] the element and its params are dropped, see
+    /// [SyslogBuilder::finish].
+    pub fn sd_element(&mut self, id: &str) -> SdElement<'_, 'a> {
+        if self.has_msg {
+            self.misordered = true;
+            return SdElement {
+                builder: self,
+                skip: true,
+            };
+        }
+        self.write_header();
+        let _ = self.out.write_str(if self.has_sd { "[" } else { " [" });
+        write_name(&mut self.out, id, SD_NAME_MAX, true);
+        self.has_sd = true;
+        SdElement {
+            builder: self,
+            skip: false,
+        }
+    }
+
+    /// Writes the free-form message, only the first call has effect.
+    pub fn msg(&mut self, args: fmt::Arguments) {
+        if self.has_msg {
+            return;
+        }
+        self.write_header();
+        self.write_empty_sd();
+        let _ = self.out.write_char(' ');
+        let _ = fmt::write(&mut self.out, args);
+        self.has_msg = true;
+    }
+
+    /// Returns the message and true if it was truncated or an SD element came after the
+    /// message, so it should not be sent.
+    pub fn finish(mut self) -> (&'a str, bool) {
+        self.write_header();
+        self.write_empty_sd();
+        let failed = self.out.is_truncated() || self.misordered;
+        (self.out.as_str(), failed)
+    }
+
+    fn write_header(&mut self) {
+        if self.header_written {
+            return;
+        }
+        self.header_written = true;
+        let pri = self.facility as u32 * 8 + self.severity as u32;
+        let _ = write!(self.out, "<{}>1 ", pri);
+        let _ = match &self.timestamp {
+            Some(timestamp) => write!(self.out, "{}", timestamp),
+            None => self.out.write_char('-'),
+        };
+        let fields = [
+            (self.hostname, HOSTNAME_MAX),
+            (self.app_name, APP_NAME_MAX),
+            (self.procid, PROCID_MAX),
+            (self.msgid, MSGID_MAX),
+        ];
+        for (value, max) in fields {
+            let _ = self.out.write_char(' ');
+            write_name(&mut self.out, value, max, false);
+        }
+    }
+
+    fn write_empty_sd(&mut self) {
+        if !self.has_sd {
+            let _ = self.out.write_str(" -");
+            self.has_sd = true;
+        }
+    }
+}
+
+/// Structured data element of [SyslogBuilder], closed with `]` when dropped.
+pub struct SdElement<'b, 'a> {
+    builder: &'b mut SyslogBuilder<'a>,
+    skip: bool, // started after the message, nothing is written
+}
+
+impl<'b, 'a> SdElement<'b, 'a> {
+    /// Adds param `key="value"` with the value escaped.
+    pub fn param<T: fmt::Display>(self, key: &str, value: T) -> Self {
+        if self.skip {
+            return self;
+        }
+        let out = &mut self.builder.out;
+        let _ = out.write_char(' ');
+        write_name(out, key, SD_NAME_MAX, true);
+        let _ = out.write_str("=\"");
+        let _ = write!(SdValueEscape(&mut *out), "{}", value);
+        let _ = out.write_char('"');
+        self
+    }
+}
+
+impl<'b, 'a> Drop for SdElement<'b, 'a> {
+    fn drop(&mut self) {
+        if !self.skip {
+            let _ = self.builder.out.write_char(']');
+        }
+    }
+}
+
+// Writes up to `max` printable ASCII chars of `value`, `-` if it is empty
+fn write_name(out: &mut WriteTo, value: &str, max: usize, sd_name: bool) {
+    if value.is_empty() {
+        let _ = out.write_char('-');
+        return;
+    }
+    for c in value.chars().take(max) {
+        let forbidden = sd_name && matches!(c, '=' | ' ' | ']' | '"');
+        let printable = ('!'..='~').contains(&c);
+        let _ = out.write_char(if printable && !forbidden { c } else { '_' });
+    }
+}
+
+// Escapes '"', '\' and ']' in SD param values
+struct SdValueEscape<'w, 'a>(&'w mut WriteTo<'a>);
+
+impl<'w, 'a> fmt::Write for SdValueEscape<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, b) in s.bytes().enumerate() {
+            let escape = match b {
+                b'"' => "\\\"",
+                b'\\' => "\\\\",
+                b']' => "\\]",
+                _ => continue,
+            };
+            self.0.write_str(&s[run_start..i])?;
+            self.0.write_whole(escape)?;
+            run_start = i + 1;
+        }
+        self.0.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyslogBuilder;
+    use crate::WriteTo;
+
+    #[test]
+    fn syslog_full_message() {
+        let mut buf = [0u8; 256];
+        let mut b = SyslogBuilder::new(WriteTo::new(&mut buf))
+            .facility(4)
+            .severity(2)
+            .timestamp_micros(1717250602, 3)
+            .hostname("mymachine.example.com")
+            .app_name("su")
+            .procid("77")
+            .msgid("ID47");
+        b.sd_element("exampleSDID@32473")
+            .param("iut", 3)
+            .param("eventSource", "Application");
+        b.sd_element("origin").param("ip", "192.0.2.1");
+        b.msg(format_args!(
+            "'su root' failed for lonvick on /dev/pts/{}",
+            8
+        ));
+        let (s, truncated) = b.finish();
+        assert_eq!(
+            s,
+            "<34>1 2024-06-01T14:03:22.000003Z mymachine.example.com su 77 ID47 \
+             [exampleSDID@32473 iut=\"3\" eventSource=\"Application\"][origin ip=\"192.0.2.1\"] \
+             'su root' failed for lonvick on /dev/pts/8"
+        );
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn syslog_minimal() {
+        let mut buf = [0u8; 64];
+        let (s, truncated) = SyslogBuilder::new(WriteTo::new(&mut buf)).finish();
+        assert_eq!(s, "<13>1 - - - - - -");
+        assert!(!truncated);
+
+        let mut b = SyslogBuilder::new(WriteTo::new(&mut buf))
+            .facility(99)
+            .severity(99);
+        b.msg(format_args!("boot"));
+        b.msg(format_args!("ignored"));
+        assert_eq!(b.finish().0, "<191>1 - - - - - - boot");
+    }
+
+    #[test]
+    fn syslog_sd_escaping() {
+        let mut buf = [0u8; 128];
+        let mut b = SyslogBuilder::new(WriteTo::new(&mut buf));
+        b.sd_element("x y").param("k=v", r#"a"b\c]d"#);
+        let (s, _) = b.finish();
+        assert_eq!(s, r#"<13>1 - - - - - [x_y k_v="a\"b\\c\]d"]"#);
+    }
+
+    #[test]
+    fn syslog_field_clamping() {
+        let mut buf = [0u8; 512];
+        let long = "abcdefghijklmnopqrstuvwxyz0123456789abcdefghijklmnopqrstuvwxyz";
+        let b = SyslogBuilder::new(WriteTo::new(&mut buf))
+            .hostname("h\u{F6}st name")
+            .app_name(long)
+            .msgid(long);
+        let (s, _) = b.finish();
+        assert_eq!(
+            s,
+            "<13>1 - h_st_name abcdefghijklmnopqrstuvwxyz0123456789abcdefghijkl - \
+             abcdefghijklmnopqrstuvwxyz012345 -"
+        );
+    }
+
+    #[test]
+    fn syslog_truncation_flag() {
+        let mut buf = [0u8; 24];
+        let mut b = SyslogBuilder::new(WriteTo::new(&mut buf));
+        b.sd_element("a").param("b", "\"\"\"");
+        let (s, truncated) = b.finish();
+        assert_eq!(s, r#"<13>1 - - - - - [a b="\""#);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn syslog_sd_after_msg() {
+        let mut buf = [0u8; 64];
+        let mut b = SyslogBuilder::new(WriteTo::new(&mut buf));
+        b.sd_element("a").param("b", 1);
+        b.msg(format_args!("hello"));
+        b.sd_element("late").param("c", 2);
+        let (s, failed) = b.finish();
+        assert_eq!(s, r#"<13>1 - - - - - [a b="1"] hello"#);
+        assert!(failed);
+    }
+}
@@ -0,0 +1,156 @@
+//  ( /   @ @    ()  Rows of fixed width columns
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::cell::Cell;
+use crate::repeated::RepeatedChar;
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Writes rows of a table with `COLS` fixed width columns into a [WriteTo].
+///
+/// Each cell is padded or cut to the column width with the [Cell] semantics, columns are
+/// separated by a gap (one space by default) and each row ends with `\n`. A row that does
+/// not fit into the buffer is removed completely and `Err` is returned, so the buffer
+/// always holds whole rows.
+///
+/// ```
+/// use core::fmt::Alignment::{Left, Right};
+/// use stackfmt::{TableWriter, WriteTo};
+///
+/// let mut buf = [0u8; 80];
+/// let mut table = TableWriter::new(WriteTo::new(&mut buf), [(5, Left), (6, Right), (2, Left)]);
+/// table.header(&["name", "value", "u"]).unwrap();
+/// table.rule('-').unwrap();
+/// table.row(&[&"vbat", &3.31, &"V"]).unwrap();
+/// table.row(&[&"temperature", &-4, &"\u{B0}C"]).unwrap();
+/// assert_eq!(
+///     table.into_inner().as_str(),
+///     "name   value u \n---------------\nvbat    3.31 V \ntempe     -4 \u{B0}C\n"
+/// );
+/// ```
+pub struct TableWriter<'a, const COLS: usize> {
+    out: WriteTo<'a>,
+    columns: [(usize, fmt::Alignment); COLS],
+    gap: &'a str,
+}
+
+impl<'a, const COLS: usize> TableWriter<'a, COLS> {
+    /// Creates writer with (width, alignment) of each column.
+    pub fn new(out: WriteTo<'a>, columns: [(usize, fmt::Alignment); COLS]) -> Self {
+        TableWriter {
+            out,
+            columns,
+            gap: " ",
+        }
+    }
+
+    /// Sets the text between columns, like `" | "`.
+    pub fn gap(mut self, gap: &'a str) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Writes a row of column titles.
+    pub fn header(&mut self, titles: &[&str; COLS]) -> fmt::Result {
+        self.write_row(|cell| &titles[cell])
+    }
+
+    /// Writes a row with the values of each column.
+    pub fn row(&mut self, values: &[&dyn fmt::Display; COLS]) -> fmt::Result {
+        self.write_row(|cell| values[cell])
+    }
+
+    /// Writes the char `ch` across the whole width of the table.
+    pub fn rule(&mut self, ch: char) -> fmt::Result {
+        let gaps = self.gap.chars().count() * COLS.saturating_sub(1);
+        let width = self.columns.iter().map(|(width, _)| width).sum::<usize>() + gaps;
+        self.write_whole_row(|out| writeln!(out, "{}", RepeatedChar(ch, width)))
+    }
+
+    /// Returns the underlying stream with the rows written so far.
+    pub fn into_inner(self) -> WriteTo<'a> {
+        self.out
+    }
+
+    fn write_row<'v, F>(&mut self, value: F) -> fmt::Result
+    where
+        F: Fn(usize) -> &'v dyn fmt::Display,
+    {
+        let (columns, gap) = (self.columns, self.gap);
+        self.write_whole_row(|out| {
+            for (i, (width, align)) in columns.iter().enumerate() {
+                if i > 0 {
+                    out.write_str(gap)?;
+                }
+                write!(out, "{}", Cell::new(value(i), *width).align(*align))?;
+            }
+            out.write_char('\n')
+        })
+    }
+
+    // Removes the row if it did not fit
+    fn write_whole_row<F>(&mut self, write: F) -> fmt::Result
+    where
+        F: FnOnce(&mut WriteTo<'a>) -> fmt::Result,
+    {
+        let start = self.out.written_bytes();
+        let result = write(&mut self.out);
+        if result.is_err() || self.out.is_truncated() {
+            self.out.truncate(start);
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TableWriter;
+    use crate::WriteTo;
+    use core::fmt::Alignment::{Center, Left, Right};
+
+    #[test]
+    fn table_mixed_alignment() {
+        let mut buf = [0u8; 128];
+        let mut table =
+            TableWriter::new(WriteTo::new(&mut buf), [(4, Left), (5, Center), (4, Right)]).gap("|");
+        table.header(&["id", "state", "ms"]).unwrap();
+        table.rule('=').unwrap();
+        table.row(&[&1, &"ok", &12]).unwrap();
+        table.row(&[&22, &"error", &12345]).unwrap();
+        assert_eq!(
+            table.into_inner().as_str(),
+            "id  |state|  ms\n===============\n1   | ok  |  12\n22  |error|1234\n"
+        );
+    }
+
+    #[test]
+    fn table_multi_byte_cells() {
+        let mut buf = [0u8; 128];
+        let mut table = TableWriter::new(WriteTo::new(&mut buf), [(3, Left), (3, Right)]);
+        table
+            .row(&[&"\u{20AC}\u{20AC}\u{20AC}\u{20AC}", &"\u{B0}C"])
+            .unwrap();
+        table.rule('\u{2500}').unwrap();
+        assert_eq!(
+            table.into_inner().as_str(),
+            "\u{20AC}\u{20AC}\u{20AC}  \u{B0}C\n\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\n"
+        );
+    }
+
+    #[test]
+    fn table_overflow_keeps_complete_rows() {
+        // each row is 6 bytes: "ab cd\n"
+        let mut buf = [0u8; 15];
+        let mut table = TableWriter::new(WriteTo::new(&mut buf), [(2, Left), (2, Left)]);
+        assert!(table.row(&[&"ab", &"cd"]).is_ok());
+        assert!(table.row(&[&"ef", &"gh"]).is_ok());
+        assert!(table.row(&[&"ij", &"kl"]).is_err());
+        assert!(table.rule('-').is_err());
+        let out = table.into_inner();
+        assert!(!out.is_truncated());
+        assert_eq!(out.as_str(), "ab cd\nef gh\n");
+    }
+}
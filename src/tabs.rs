@@ -0,0 +1,152 @@
+//  ( /   @ @    ()  Expands tabs in formatted output into spaces
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+// Source of the spaces written instead of a tab
+const SPACES: &str = "                ";
+
+/// Default distance between tab stops used by [TabExpandWriter::new]
+pub const DEFAULT_TAB_WIDTH: usize = 8;
+
+/// Impl of [fmt::Write] that replaces every `\t` with spaces up to the next tab stop.
+///
+/// The current column is tracked across `write_str` calls and reset by `\n` and `\r`.
+/// Every char counts as one column, including multi-byte and wide ones. Tab width of 0
+/// removes tabs.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_expand_tabs(&mut buf, format_args!("a\tbc\td"), 4);
+/// assert_eq!(s, "a   bc  d");
+/// ```
+pub struct TabExpandWriter<W: fmt::Write> {
+    inner: W,
+    width: usize,
+    column: usize, // Column where the next char goes, 0-based
+}
+
+impl<W: fmt::Write> TabExpandWriter<W> {
+    /// Creates stream with tab stops every [DEFAULT_TAB_WIDTH] columns.
+    pub fn new(inner: W) -> Self {
+        Self::with_width(inner, DEFAULT_TAB_WIDTH)
+    }
+
+    /// Creates stream with tab stops every `width` columns.
+    pub fn with_width(inner: W, width: usize) -> Self {
+        TabExpandWriter {
+            inner,
+            width,
+            column: 0,
+        }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_spaces(&mut self, mut n: usize) -> fmt::Result {
+        while n > 0 {
+            let chunk = n.min(SPACES.len());
+            self.inner.write_str(&SPACES[..chunk])?;
+            n -= chunk;
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for TabExpandWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut run_start = 0;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            match b {
+                b'\t' => {
+                    self.inner.write_str(&s[run_start..i])?;
+                    run_start = i + 1;
+                    if self.width > 0 {
+                        let spaces = self.width - self.column % self.width;
+                        self.write_spaces(spaces)?;
+                        self.column += spaces;
+                    }
+                }
+                b'\n' | b'\r' => self.column = 0,
+                0x80..=0xBF => {} // continuation byte of the already counted char
+                _ => self.column += 1,
+            }
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+/// Writes formatted string with tabs expanded to `width` columns into the buffer truncating
+/// if needed.
+pub fn fmt_expand_tabs<'a>(buffer: &'a mut [u8], args: fmt::Arguments, width: usize) -> &'a str {
+    let mut w = TabExpandWriter::with_width(WriteTo::new(buffer), width);
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TabExpandWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn tab_at_column_zero() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("\tx"), 8);
+        assert_eq!(s, "        x");
+    }
+
+    #[test]
+    fn tab_before_stop() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("abc\tx"), 4);
+        assert_eq!(s, "abc x");
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("abcd\tx"), 4);
+        assert_eq!(s, "abcd    x");
+    }
+
+    #[test]
+    fn consecutive_tabs() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("a\t\tb"), 4);
+        assert_eq!(s, "a       b");
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("\t\t\t"), 20);
+        assert_eq!(s.len(), 60);
+    }
+
+    #[test]
+    fn tabs_after_newline() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("ab\n\tc\r\td"), 4);
+        assert_eq!(s, "ab\n    c\r    d");
+    }
+
+    #[test]
+    fn tabs_across_chunks() {
+        let mut buf = [0u8; 64];
+        let mut w = TabExpandWriter::with_width(WriteTo::new(&mut buf), 4);
+        w.write_str("\u{20AC}").unwrap();
+        w.write_str("\t").unwrap();
+        w.write_str("ab").unwrap();
+        w.write_str("\tc").unwrap();
+        assert_eq!(w.into_inner().as_str(), "\u{20AC}   ab  c");
+    }
+
+    #[test]
+    fn tabs_default_and_zero_width() {
+        let mut buf = [0u8; 64];
+        let mut w = TabExpandWriter::new(WriteTo::new(&mut buf));
+        w.write_str("a\tb").unwrap();
+        assert_eq!(w.into_inner().as_str(), "a       b");
+        let s = super::fmt_expand_tabs(&mut buf, format_args!("a\tb"), 0);
+        assert_eq!(s, "ab");
+    }
+}
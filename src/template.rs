@@ -0,0 +1,170 @@
+//  ( /   @ @    ()  ${key} placeholders replaced at runtime
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Error of [fmt_template].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TemplateError<'t> {
+    /// `${key}` with a key not in the table, `offset` is the byte offset of `$`
+    UnknownKey { key: &'t str, offset: usize },
+    /// `${` without the closing `}`
+    Unclosed { offset: usize },
+}
+
+impl<'t> fmt::Display for TemplateError<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::UnknownKey { key, offset } => {
+                write!(f, "unknown key '{}' at {}", key, offset)
+            }
+            TemplateError::Unclosed { offset } => write!(f, "unclosed placeholder at {}", offset),
+        }
+    }
+}
+
+// Writes `template` with placeholders replaced, unknown ones verbatim unless `strict`
+fn render<'t>(
+    w: &mut WriteTo,
+    template: &'t str,
+    vars: &[(&str, &dyn fmt::Display)],
+    strict: bool,
+) -> Result<(), TemplateError<'t>> {
+    let mut rest = template;
+    while let Some(pos) = rest.find('$') {
+        let offset = template.len() - rest.len() + pos;
+        let _ = w.write_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(after) = after.strip_prefix('$') {
+            let _ = w.write_char('$');
+            rest = after;
+        } else if let Some(inner) = after.strip_prefix('{') {
+            let end = match inner.find('}') {
+                Some(end) => end,
+                None if strict => return Err(TemplateError::Unclosed { offset }),
+                None => {
+                    let _ = w.write_str(&rest[pos..]);
+                    return Ok(());
+                }
+            };
+            let key = &inner[..end];
+            match vars.iter().find(|(name, _)| *name == key) {
+                Some((_, value)) => {
+                    let _ = write!(w, "{}", value);
+                }
+                None if strict => return Err(TemplateError::UnknownKey { key, offset }),
+                None => {
+                    let _ = w.write_str(&rest[pos..pos + end + 3]);
+                }
+            }
+            rest = &inner[end + 1..];
+        } else {
+            let _ = w.write_char('$');
+            rest = after;
+        }
+    }
+    let _ = w.write_str(rest);
+    Ok(())
+}
+
+/// Writes a runtime template into the buffer with `${key}` replaced by the value of `key`
+/// from `vars`, truncating if needed.
+///
+/// `$$` is a literal `$` and a `$` not followed by `{` is kept as is. A key not in `vars`
+/// or `${` without `}` is an error, see [fmt_template_lenient] to keep them verbatim.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let template = "Device ${name} battery at ${batt}%";
+/// let s = stackfmt::fmt_template(&mut buf, template, &[("name", &"pump"), ("batt", &87)]);
+/// assert_eq!(s, Ok("Device pump battery at 87%"));
+/// ```
+pub fn fmt_template<'a, 't>(
+    buffer: &'a mut [u8],
+    template: &'t str,
+    vars: &[(&str, &dyn fmt::Display)],
+) -> Result<&'a str, TemplateError<'t>> {
+    let mut w = WriteTo::new(buffer);
+    render(&mut w, template, vars, true)?;
+    Ok(w.as_str())
+}
+
+/// Same as [fmt_template] but keeps placeholders with unknown keys and an unclosed `${`
+/// verbatim.
+///
+/// ```
+/// let mut buf = [0u8; 64];
+/// let s = stackfmt::fmt_template_lenient(&mut buf, "${a}+${b}", &[("a", &1)]);
+/// assert_eq!(s, "1+${b}");
+/// ```
+pub fn fmt_template_lenient<'a>(
+    buffer: &'a mut [u8],
+    template: &str,
+    vars: &[(&str, &dyn fmt::Display)],
+) -> &'a str {
+    let mut w = WriteTo::new(buffer);
+    let _ = render(&mut w, template, vars, false);
+    w.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_template, fmt_template_lenient, TemplateError};
+
+    #[test]
+    fn template_substitutions() {
+        let mut buf = [0u8; 64];
+        let vars: [(&str, &dyn core::fmt::Display); 3] = [("t", &21.5), ("u", &"C"), ("id", &7)];
+        let s = fmt_template(&mut buf, "#${id}: ${t}${u}, again ${t}${u}", &vars);
+        assert_eq!(s, Ok("#7: 21.5C, again 21.5C"));
+        let s = fmt_template(&mut buf, "no placeholders", &vars);
+        assert_eq!(s, Ok("no placeholders"));
+        let mut small = [0u8; 6];
+        assert_eq!(
+            fmt_template(&mut small, "id=${id}${t}", &vars),
+            Ok("id=721")
+        );
+    }
+
+    #[test]
+    fn template_unknown_key() {
+        let mut buf = [0u8; 64];
+        let vars: [(&str, &dyn core::fmt::Display); 1] = [("a", &1)];
+        let err = fmt_template(&mut buf, "${a} ${bb} ${c}", &vars).unwrap_err();
+        assert_eq!(
+            err,
+            TemplateError::UnknownKey {
+                key: "bb",
+                offset: 5
+            }
+        );
+        let mut text = [0u8; 32];
+        let s = crate::fmt_truncate(&mut text, format_args!("{}", err));
+        assert_eq!(s, "unknown key 'bb' at 5");
+        let s = fmt_template_lenient(&mut buf, "${a} ${bb} ${}", &vars);
+        assert_eq!(s, "1 ${bb} ${}");
+    }
+
+    #[test]
+    fn template_dollar_escape() {
+        let mut buf = [0u8; 64];
+        let vars: [(&str, &dyn core::fmt::Display); 1] = [("price", &5)];
+        let s = fmt_template(&mut buf, "$$${price} $$$$ $x $", &vars);
+        assert_eq!(s, Ok("$5 $$ $x $"));
+        let s = fmt_template(&mut buf, "$${price}", &vars);
+        assert_eq!(s, Ok("${price}"));
+    }
+
+    #[test]
+    fn template_unclosed() {
+        let mut buf = [0u8; 64];
+        let vars: [(&str, &dyn core::fmt::Display); 1] = [("a", &1)];
+        let s = fmt_template(&mut buf, "${a} ${a", &vars);
+        assert_eq!(s, Err(TemplateError::Unclosed { offset: 5 }));
+        let s = fmt_template_lenient(&mut buf, "${a} ${a", &vars);
+        assert_eq!(s, "1 ${a");
+    }
+}
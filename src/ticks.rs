@@ -0,0 +1,240 @@
+//  ( /   @ @    ()  Tick counter values shown as seconds
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::pad::pad_with;
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows ticks of a counter running at `hz` as seconds, like `12.345`.
+///
+/// Three decimals are shown by default, a precision from the format string (`{:.6}`) selects
+/// another number; the value is rounded to the nearest last digit. Width, fill, alignment
+/// and the `0` flag work as for integers, so `[{:8}]` gives the usual `[  12.345]` log
+/// prefix. Only integer math is used. A zero `hz` is shown as `?`.
+///
+/// The result is exact only to one tick: with 32768 Hz a tick is about 30.5 µs, so the digits
+/// after the fourth are not meaningful even though they are computed.
+///
+/// ```
+/// use stackfmt::Ticks;
+///
+/// let mut buf = [0u8; 16];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("[{:8}]", Ticks::new(12_345_678, 1_000_000)));
+/// assert_eq!(s, "[  12.346]");
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{:.1}", Ticks::new(49152, 32768)));
+/// assert_eq!(s, "1.5");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ticks {
+    ticks: u64,
+    hz: u32,
+    max: Option<u32>,
+}
+
+impl Ticks {
+    /// Creates adapter for `ticks` of a counter running at `hz`.
+    pub fn new(ticks: u64, hz: u32) -> Self {
+        Ticks {
+            ticks,
+            hz,
+            max: None,
+        }
+    }
+
+    /// Treats the ticks as a difference of two readings (`end.wrapping_sub(start)`) of a counter
+    /// that runs from 0 to `max` and wraps: a difference over `max` means the counter wrapped
+    /// and gets `max + 1` added.
+    ///
+    /// ```
+    /// let (start, end) = (u32::MAX - 499, 500u32);
+    /// let delta = (end as u64).wrapping_sub(start as u64);
+    ///
+    /// let mut buf = [0u8; 16];
+    /// let ticks = stackfmt::Ticks::new(delta, 1000).wrapping(u32::MAX);
+    /// assert_eq!(stackfmt::fmt_truncate(&mut buf, format_args!("{}", ticks)), "1.000");
+    /// ```
+    pub fn wrapping(mut self, max: u32) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
+impl fmt::Display for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.hz == 0 {
+            return f.pad_integral(true, "", "?");
+        }
+        let mut ticks = self.ticks;
+        if let Some(max) = self.max {
+            if ticks > max as u64 {
+                ticks = ticks.wrapping_add(max as u64 + 1);
+            }
+        }
+
+        // the remainder is below u32 hz, times 10^9 it still fits into u64
+        let shown = f.precision().unwrap_or(3);
+        let digits = shown.min(9);
+        let hz = self.hz as u64;
+        let pow = 10u64.pow(digits as u32);
+        let mut secs = ticks / hz;
+        let mut frac = ((ticks % hz) * pow + hz / 2) / hz;
+        if frac == pow {
+            secs += 1;
+            frac = 0;
+        }
+
+        // the zeros after the 9th digit are streamed, a precision can be longer than any buffer
+        let mut scratch = [0u8; 32];
+        let mut w = WriteTo::new(&mut scratch);
+        let _ = write!(w, "{}", secs);
+        if shown > 0 {
+            let _ = write!(w, ".{:0width$}", frac, width = digits);
+        }
+        let text = w.as_str();
+        let sign = if f.sign_plus() { "+" } else { "" };
+        let len = sign.len() + text.len() + (shown - digits);
+        let body = |f: &mut fmt::Formatter| {
+            f.write_str(text)?;
+            for _ in digits..shown {
+                f.write_char('0')?;
+            }
+            Ok(())
+        };
+        if f.sign_aware_zero_pad() {
+            f.write_str(sign)?;
+            for _ in len..f.width().unwrap_or(0) {
+                f.write_char('0')?;
+            }
+            return body(f);
+        }
+        pad_with(f, len, fmt::Alignment::Right, |f| {
+            f.write_str(sign)?;
+            body(f)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ticks;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn ticks_zero() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ticks::new(0, 1000))),
+            "0.000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.0}", Ticks::new(0, 1000))),
+            "0"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ticks::new(5, 0))),
+            "?"
+        );
+    }
+
+    #[test]
+    fn ticks_sub_millisecond() {
+        let mut buf = [0u8; 32];
+        // 499 µs rounds down, 500 µs rounds up
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ticks::new(499, 1_000_000))),
+            "0.000"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ticks::new(500, 1_000_000))),
+            "0.001"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.6}", Ticks::new(7, 1_000_000))),
+            "0.000007"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.12}", Ticks::new(7, 1_000_000))),
+            "0.000007000000"
+        );
+    }
+
+    #[test]
+    fn ticks_odd_frequency() {
+        let mut buf = [0u8; 32];
+        // 1 tick of 32768 Hz is 30.517578125 µs
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{:.6}", Ticks::new(1, 32768))),
+            "0.000031"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("{}", Ticks::new(32767, 32768))),
+            "1.000"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Ticks::new(10 * 32768 + 100, 32768))
+            ),
+            "10.003"
+        );
+        assert_eq!(
+            fmt_truncate(&mut buf, format_args!("[{:08}]", Ticks::new(1, 3))),
+            "[0000.333]"
+        );
+    }
+
+    #[test]
+    fn ticks_near_wrap() {
+        let mut buf = [0u8; 32];
+        let hz = 1_000_000;
+        let (start, end) = (u32::MAX - 9, 5u32);
+        let delta = (end as u64).wrapping_sub(start as u64);
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:.6}", Ticks::new(delta, hz).wrapping(u32::MAX))
+            ),
+            "0.000015"
+        );
+        // difference computed on u32 is already in range
+        let delta = end.wrapping_sub(start) as u64;
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:.6}", Ticks::new(delta, hz).wrapping(u32::MAX))
+            ),
+            "0.000015"
+        );
+        // 24-bit counter
+        let delta = 3u64.wrapping_sub(0xFF_FFFE);
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{:.6}", Ticks::new(delta, hz).wrapping(0xFF_FFFF))
+            ),
+            "0.000005"
+        );
+        assert_eq!(
+            fmt_truncate(
+                &mut buf,
+                format_args!("{}", Ticks::new(u32::MAX as u64, hz))
+            ),
+            "4294.967"
+        );
+    }
+
+    #[test]
+    fn ticks_long_precision() {
+        let mut buf = [0u8; 128];
+        let s = fmt_truncate(&mut buf, format_args!("{:.60}", Ticks::new(1, 3)));
+        assert_eq!(s.len(), 62);
+        assert!(s.starts_with("0.333333333000"));
+        let s = fmt_truncate(&mut buf, format_args!("[{:>70.60}]", Ticks::new(5, 2)));
+        assert_eq!(s.len(), 72);
+        assert!(s.starts_with("[        2.5000"));
+        let s = fmt_truncate(&mut buf, format_args!("{:+070.60}", Ticks::new(5, 2)));
+        assert!(s.starts_with("+00000002.5000") && s.len() == 70);
+    }
+}
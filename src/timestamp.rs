@@ -0,0 +1,305 @@
+//  ( /   @ @    ()  Unix timestamps as ISO-8601 and HTTP dates
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Converts days since 1970-01-01 into (year, month 1..=12, day 1..=31) of the proleptic
+/// Gregorian calendar.
+///
+/// This is `civil_from_days` by Howard Hinnant, valid for the whole `i64` seconds range.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097); // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11], March based
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Display adapter that shows seconds since the Unix epoch as `2024-06-01T14:03:22Z`.
+///
+/// Dates are in the proleptic Gregorian calendar and are correct for the whole `i64`
+/// range, including negative (pre-1970) timestamps. Years before 1 are shown with a minus
+/// sign (year 0 is 1 BC) and years after 9999 with more digits. When created with
+/// [Timestamp::with_nanos] the fraction of a second is shown with 3, 6 or 9 digits, the
+/// shortest that is exact.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", stackfmt::Timestamp::new(1717250602)));
+/// assert_eq!(s, "2024-06-01T14:03:22Z");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Timestamp {
+    secs: i64,
+    nanos: Option<u32>,
+}
+
+impl Timestamp {
+    /// Creates adapter for `secs` since 1970-01-01T00:00:00Z.
+    pub fn new(secs: i64) -> Self {
+        Timestamp { secs, nanos: None }
+    }
+
+    /// Creates adapter showing fraction of a second, `nanos` above 999 999 999 are clamped.
+    pub fn with_nanos(secs: i64, nanos: u32) -> Self {
+        Timestamp {
+            secs,
+            nanos: Some(nanos.min(999_999_999)),
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.secs.div_euclid(86400));
+        let time = self.secs.rem_euclid(86400);
+        if year < 0 {
+            write!(f, "-{:04}", -year)?;
+        } else {
+            write!(f, "{:04}", year)?;
+        }
+        write!(
+            f,
+            "-{:02}-{:02}T{:02}:{:02}:{:02}",
+            month,
+            day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        )?;
+        match self.nanos {
+            Some(n) if n % 1_000_000 == 0 => write!(f, ".{:03}", n / 1_000_000)?,
+            Some(n) if n % 1_000 == 0 => write!(f, ".{:06}", n / 1_000)?,
+            Some(n) => write!(f, ".{:09}", n)?,
+            None => {}
+        }
+        f.write_str("Z")
+    }
+}
+
+/// Writes `unix_secs` as ISO-8601 UTC date and time into the buffer truncating if needed.
+///
+/// ```
+/// let mut buf = [0u8; 20];
+/// assert_eq!(stackfmt::fmt_iso8601(&mut buf, 951782400), "2000-02-29T00:00:00Z");
+/// ```
+pub fn fmt_iso8601(buffer: &mut [u8], unix_secs: i64) -> &str {
+    let mut w = WriteTo::new(buffer);
+    match fmt::write(&mut w, format_args!("{}", Timestamp::new(unix_secs))) {
+        Ok(_) => w.as_str(),
+        Err(_) => "",
+    }
+}
+
+// Seconds of 0001-01-01T00:00:00Z and 9999-12-31T23:59:59Z, the range of 4 digit years
+const HTTP_DATE_MIN: i64 = -62135596800;
+const HTTP_DATE_MAX: i64 = 253402300799;
+
+/// Display adapter that shows seconds since the Unix epoch as the HTTP-date of RFC 7231
+/// (IMF-fixdate) like `Tue, 04 Jun 2024 11:05:00 GMT`.
+///
+/// The text is always 29 chars: timestamps before year 1 or after year 9999 are clamped to
+/// `Mon, 01 Jan 0001 00:00:00 GMT` and `Fri, 31 Dec 9999 23:59:59 GMT`.
+///
+/// ```
+/// use stackfmt::{HttpDate, HttpHeadWriter, WriteTo};
+///
+/// let mut buf = [0u8; 96];
+/// let mut head = HttpHeadWriter::new(WriteTo::new(&mut buf));
+/// head.status(200, "OK").header("Date", format_args!("{}", HttpDate(1717499100)));
+/// assert_eq!(
+///     head.finish().unwrap().0,
+///     "HTTP/1.1 200 OK\r\nDate: Tue, 04 Jun 2024 11:05:00 GMT\r\n\r\n"
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HttpDate(pub i64);
+
+impl fmt::Display for HttpDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let secs = self.0.clamp(HTTP_DATE_MIN, HTTP_DATE_MAX);
+        let days = secs.div_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+        let time = secs.rem_euclid(86400);
+        write!(
+            f,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[days.rem_euclid(7) as usize], // 1970-01-01 is Thursday
+            day,
+            MONTHS[month as usize - 1],
+            year,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        )
+    }
+}
+
+/// Writes `unix_secs` as HTTP-date into the buffer truncating if needed, 29 bytes are
+/// always enough.
+///
+/// ```
+/// let mut buf = [0u8; 29];
+/// assert_eq!(stackfmt::fmt_http_date(&mut buf, 0), "Thu, 01 Jan 1970 00:00:00 GMT");
+/// ```
+pub fn fmt_http_date(buffer: &mut [u8], unix_secs: i64) -> &str {
+    let mut w = WriteTo::new(buffer);
+    match fmt::write(&mut w, format_args!("{}", HttpDate(unix_secs))) {
+        Ok(_) => w.as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+
+    #[test]
+    fn iso8601_known_timestamps() {
+        let table: &[(i64, &str)] = &[
+            (0, "1970-01-01T00:00:00Z"),
+            (1717250602, "2024-06-01T14:03:22Z"),
+            (946684799, "1999-12-31T23:59:59Z"),
+            (946684800, "2000-01-01T00:00:00Z"),
+            (4102444800, "2100-01-01T00:00:00Z"),
+            (253402300799, "9999-12-31T23:59:59Z"),
+        ];
+        let mut buf = [0u8; 32];
+        for (secs, expected) in table {
+            assert_eq!(super::fmt_iso8601(&mut buf, *secs), *expected);
+        }
+    }
+
+    #[test]
+    fn iso8601_leap_days() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, 951782400),
+            "2000-02-29T00:00:00Z"
+        );
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, 1709164800),
+            "2024-02-29T00:00:00Z"
+        );
+        // 2100 is not a leap year
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, 4107456000),
+            "2100-02-28T00:00:00Z"
+        );
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, 4107542400),
+            "2100-03-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn iso8601_negative() {
+        let mut buf = [0u8; 32];
+        assert_eq!(super::fmt_iso8601(&mut buf, -1), "1969-12-31T23:59:59Z");
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, -2208988800),
+            "1900-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, -62135596800),
+            "0001-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, -62135596801),
+            "0000-12-31T23:59:59Z"
+        );
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, -62198755200),
+            "-0001-01-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn iso8601_extremes_do_not_panic() {
+        let mut buf = [0u8; 40];
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, i64::MAX),
+            "292277026596-12-04T15:30:07Z"
+        );
+        assert_eq!(
+            super::fmt_iso8601(&mut buf, i64::MIN),
+            "-292277022657-01-27T08:29:52Z"
+        );
+    }
+
+    #[test]
+    fn iso8601_subseconds() {
+        let mut buf = [0u8; 40];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", Timestamp::with_nanos(0, 0)));
+        assert_eq!(s, "1970-01-01T00:00:00.000Z");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", Timestamp::with_nanos(-1, 250_000_000)),
+        );
+        assert_eq!(s, "1969-12-31T23:59:59.250Z");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", Timestamp::with_nanos(0, 1_000)),
+        );
+        assert_eq!(s, "1970-01-01T00:00:00.000001Z");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", Timestamp::with_nanos(0, 7)));
+        assert_eq!(s, "1970-01-01T00:00:00.000000007Z");
+    }
+
+    #[test]
+    fn http_date_known_timestamps() {
+        let table: &[(i64, &str)] = &[
+            (0, "Thu, 01 Jan 1970 00:00:00 GMT"),
+            (1717499100, "Tue, 04 Jun 2024 11:05:00 GMT"),
+            (784111777, "Sun, 06 Nov 1994 08:49:37 GMT"),
+            (-1, "Wed, 31 Dec 1969 23:59:59 GMT"),
+        ];
+        let mut buf = [0u8; 32];
+        for (secs, expected) in table {
+            assert_eq!(super::fmt_http_date(&mut buf, *secs), *expected);
+        }
+    }
+
+    #[test]
+    fn http_date_leap_day_and_year_boundary() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            super::fmt_http_date(&mut buf, 1709164800),
+            "Thu, 29 Feb 2024 00:00:00 GMT"
+        );
+        assert_eq!(
+            super::fmt_http_date(&mut buf, 951782400),
+            "Tue, 29 Feb 2000 00:00:00 GMT"
+        );
+        assert_eq!(
+            super::fmt_http_date(&mut buf, 1704067199),
+            "Sun, 31 Dec 2023 23:59:59 GMT"
+        );
+        assert_eq!(
+            super::fmt_http_date(&mut buf, 1704067200),
+            "Mon, 01 Jan 2024 00:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn http_date_clamped() {
+        let mut buf = [0u8; 32];
+        let s = super::fmt_http_date(&mut buf, i64::MAX);
+        assert_eq!(s, "Fri, 31 Dec 9999 23:59:59 GMT");
+        assert_eq!(s.len(), 29);
+        let s = super::fmt_http_date(&mut buf, i64::MIN);
+        assert_eq!(s, "Mon, 01 Jan 0001 00:00:00 GMT");
+        assert_eq!(s.len(), 29);
+    }
+}
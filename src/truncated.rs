@@ -0,0 +1,278 @@
+//  ( /   @ @    ()  Length limit for one value inside a larger format
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Max length in bytes of the marker shown by [Truncated] for a cut value.
+pub const TRUNCATED_MAX_MARKER: usize = 16;
+
+/// Display adapter that shows at most `max` bytes or chars of a value, like a long SSID in a
+/// log line.
+///
+/// The value is streamed through a limiter, nothing is stored except the last few chars
+/// needed to decide whether the marker (see [Truncated::marker]) has to replace them. The
+/// cut is always on a char boundary and the text together with the marker fits within the
+/// limit. A marker that does not fit the limit itself or is longer than
+/// [TRUNCATED_MAX_MARKER] bytes is not shown. Formatter flags are not passed to the value.
+///
+/// ```
+/// use stackfmt::Truncated;
+///
+/// let mut buf = [0u8; 64];
+/// let ssid = "MyVeryLongNetworkNameThatNobodyWillRead";
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("ssid={} rssi={}", Truncated::chars(ssid, 12).marker("\u{2026}"), -61),
+/// );
+/// assert_eq!(s, "ssid=MyVeryLongN\u{2026} rssi=-61");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Truncated<'m, T> {
+    value: T,
+    max: usize,
+    chars: bool,
+    marker: &'m str,
+}
+
+impl<T: fmt::Display> Truncated<'static, T> {
+    /// Creates adapter showing at most `max_bytes` bytes of `value`.
+    pub fn new(value: T, max_bytes: usize) -> Self {
+        Truncated {
+            value,
+            max: max_bytes,
+            chars: false,
+            marker: "",
+        }
+    }
+
+    /// Creates adapter showing at most `max_chars` chars of `value`.
+    pub fn chars(value: T, max_chars: usize) -> Self {
+        Truncated {
+            value,
+            max: max_chars,
+            chars: true,
+            marker: "",
+        }
+    }
+}
+
+impl<'m, T: fmt::Display> Truncated<'m, T> {
+    /// Sets the text that ends a cut value, like `…` or `...`.
+    pub fn marker<'n>(self, marker: &'n str) -> Truncated<'n, T> {
+        Truncated {
+            value: self.value,
+            max: self.max,
+            chars: self.chars,
+            marker,
+        }
+    }
+}
+
+impl<'m, T: fmt::Display> fmt::Display for Truncated<'m, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limiter = Limiter::new(f, self.max, self.chars, self.marker);
+        fmt::write(&mut limiter, format_args!("{}", self.value))?;
+        limiter.finish().map(|_| ())
+    }
+}
+
+// Writer passing at most `max` bytes (or chars) to the inner writer. The text after
+// `max - marker` is held back until it is known whether it fits or the marker replaces it.
+pub(crate) struct Limiter<'m, W> {
+    inner: W,
+    chars: bool,
+    marker: &'m str,
+    direct: usize,  // units that still can be passed without holding
+    reserve: usize, // units reserved for the marker
+    holding: bool,
+    held: [u8; TRUNCATED_MAX_MARKER * 4],
+    held_len: usize,
+    held_units: usize,
+    overflow: bool,
+}
+
+impl<'m, W: fmt::Write> Limiter<'m, W> {
+    pub(crate) fn new(inner: W, max: usize, chars: bool, marker: &'m str) -> Self {
+        let marker_units = if chars {
+            marker.chars().count()
+        } else {
+            marker.len()
+        };
+        let marker = if marker.len() <= TRUNCATED_MAX_MARKER && marker_units <= max {
+            marker
+        } else {
+            ""
+        };
+        let reserve = if marker.is_empty() { 0 } else { marker_units };
+        Limiter {
+            inner,
+            chars,
+            marker,
+            direct: max - reserve,
+            reserve,
+            holding: false,
+            held: [0u8; TRUNCATED_MAX_MARKER * 4],
+            held_len: 0,
+            held_units: 0,
+            overflow: false,
+        }
+    }
+
+    // Writes the held text if the value turned out to fit and returns the inner writer
+    pub(crate) fn finish(mut self) -> Result<W, fmt::Error> {
+        if !self.overflow {
+            let held = unsafe { core::str::from_utf8_unchecked(&self.held[..self.held_len]) };
+            self.inner.write_str(held)?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<'m, W: fmt::Write> fmt::Write for Limiter<'m, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.overflow {
+            return Ok(()); // skip the rest of the value
+        }
+        for (i, c) in s.char_indices() {
+            let units = if self.chars { 1 } else { c.len_utf8() };
+            if !self.holding {
+                if units <= self.direct {
+                    self.direct -= units;
+                    continue;
+                }
+                self.inner.write_str(&s[..i])?;
+                self.holding = true;
+                self.reserve += self.direct;
+            }
+            if self.held_units + units <= self.reserve {
+                c.encode_utf8(&mut self.held[self.held_len..]);
+                self.held_len += c.len_utf8();
+                self.held_units += units;
+            } else {
+                self.overflow = true;
+                return self.inner.write_str(self.marker);
+            }
+        }
+        if !self.holding {
+            self.inner.write_str(s)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Truncated;
+    use crate::fmt_truncate;
+
+    #[test]
+    fn truncated_under_cap() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("hello", 5).marker("...")),
+        );
+        assert_eq!(s, "[hello]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::chars("\u{20AC}\u{20AC}", 2).marker("~")),
+        );
+        assert_eq!(s, "[\u{20AC}\u{20AC}]");
+        let s = fmt_truncate(&mut buf, format_args!("[{}]", Truncated::new(12345, 8)));
+        assert_eq!(s, "[12345]");
+    }
+
+    #[test]
+    fn truncated_over_cap() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("hello world", 8).marker("...")),
+        );
+        assert_eq!(s, "[hello...]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("hello world", 5)),
+        );
+        assert_eq!(s, "[hello]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "[{}]",
+                Truncated::chars("hello world", 6).marker("\u{2026}")
+            ),
+        );
+        assert_eq!(s, "[hello\u{2026}]");
+    }
+
+    #[test]
+    fn truncated_unicode_at_edge() {
+        let mut buf = [0u8; 32];
+        // 'a' + '€' need 4 bytes, the cut can not be after the 1st byte of '€'
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("a\u{20AC}b", 2)),
+        );
+        assert_eq!(s, "[a]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("a\u{20AC}b", 4)),
+        );
+        assert_eq!(s, "[a\u{20AC}]");
+        // fits exactly while the marker is held back
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("a\u{20AC}b", 5).marker("\u{2026}")),
+        );
+        assert_eq!(s, "[a\u{20AC}b]");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("a\u{20AC}bc", 5).marker("\u{2026}")),
+        );
+        assert_eq!(s, "[a\u{2026}]");
+    }
+
+    #[test]
+    fn truncated_zero_budget() {
+        let mut buf = [0u8; 32];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("hello", 0).marker("...")),
+        );
+        assert_eq!(s, "[]");
+        let s = fmt_truncate(&mut buf, format_args!("[{}]", Truncated::chars("", 0)));
+        assert_eq!(s, "[]");
+        // marker longer than the limit is omitted
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("[{}]", Truncated::new("hello", 2).marker("...")),
+        );
+        assert_eq!(s, "[he]");
+    }
+
+    #[test]
+    fn truncated_nested() {
+        let mut buf = [0u8; 64];
+        let inner = Truncated::chars("abcdefgh", 6).marker("~");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{} {}",
+                Truncated::new("first field", 5),
+                Truncated::chars(inner, 4).marker("!")
+            ),
+        );
+        assert_eq!(s, "first abc!");
+        // value written in several pieces
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}|{}",
+                Truncated::new(format_args!("{}-{}", 123, 456), 6),
+                Truncated::new(7, 1)
+            ),
+        );
+        assert_eq!(s, "123-45|7");
+    }
+}
@@ -0,0 +1,190 @@
+//  ( /   @ @    ()  URLs with percent-encoded path segments and query parameters
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::{WriteTo, WriteWhole};
+use core::fmt;
+use core::fmt::Write;
+
+/// Builds a URL in a [WriteTo] from a base, path segments and query parameters.
+///
+/// Segments are always percent-encoded, including `/` and `%`, so an already encoded value
+/// gets encoded again. Exactly one `/` is put before each segment and an empty segment gives
+/// an empty path element (`a//b`). Query keys and values are form-encoded: a space becomes
+/// `+`. All chars except ASCII letters, digits and `-._~` are encoded as UTF-8 bytes, an
+/// encoded char is never cut by the truncation. Segments must come before the query
+/// parameters, a segment after a query parameter is ignored.
+///
+/// ```
+/// use stackfmt::{UrlBuilder, WriteTo};
+///
+/// let mut buf = [0u8; 96];
+/// let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+/// url.base("https://example.com/api/v1/")
+///     .push_segment("devices")
+///     .push_segment("dev 7/a")
+///     .query("from", 1700000000)
+///     .query("tag", "a&b c");
+/// assert_eq!(
+///     url.finish(),
+///     ("https://example.com/api/v1/devices/dev%207%2Fa?from=1700000000&tag=a%26b+c", false)
+/// );
+/// ```
+pub struct UrlBuilder<'a> {
+    out: WriteTo<'a>,
+    has_segments: bool,
+    has_query: bool,
+}
+
+impl<'a> UrlBuilder<'a> {
+    /// Creates builder writing the URL into `out`.
+    pub fn new(out: WriteTo<'a>) -> Self {
+        UrlBuilder {
+            out,
+            has_segments: false,
+            has_query: false,
+        }
+    }
+
+    /// Writes the start of the URL as is, like `https://host/path`.
+    pub fn base(&mut self, base: &str) -> &mut Self {
+        let _ = self.out.write_str(base);
+        self
+    }
+
+    /// Adds `/` and the encoded segment, the `/` is not repeated if the base ends with it.
+    pub fn push_segment<T: fmt::Display>(&mut self, segment: T) -> &mut Self {
+        if self.has_query {
+            return self;
+        }
+        if self.has_segments || !self.out.to_str().ends_with('/') {
+            let _ = self.out.write_char('/');
+        }
+        self.has_segments = true;
+        let _ = write!(UrlEncode::new(&mut self.out, false), "{}", segment);
+        self
+    }
+
+    /// Adds query parameter `key=value` after `?` or `&`, both form-encoded.
+    pub fn query<K: fmt::Display, V: fmt::Display>(&mut self, key: K, value: V) -> &mut Self {
+        let _ = self.out.write_char(if self.has_query { '&' } else { '?' });
+        self.has_query = true;
+        let _ = write!(UrlEncode::new(&mut self.out, true), "{}", key);
+        let _ = self.out.write_char('=');
+        let _ = write!(UrlEncode::new(&mut self.out, true), "{}", value);
+        self
+    }
+
+    /// Returns the URL and the flag if it did not fit.
+    pub fn finish(&self) -> (&str, bool) {
+        (self.out.to_str(), self.out.is_truncated())
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> WriteTo<'a> {
+        self.out
+    }
+}
+
+// Percent-encodes all but unreserved chars, with `form` a space is written as `+`
+struct UrlEncode<'w, 'a> {
+    out: &'w mut WriteTo<'a>,
+    form: bool,
+}
+
+impl<'w, 'a> UrlEncode<'w, 'a> {
+    fn new(out: &'w mut WriteTo<'a>, form: bool) -> Self {
+        UrlEncode { out, form }
+    }
+}
+
+impl<'w, 'a> fmt::Write for UrlEncode<'w, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+        let mut run_start = 0;
+        for (i, c) in s.char_indices() {
+            if c.is_ascii_alphanumeric() || "-._~".contains(c) {
+                continue;
+            }
+            self.out.write_str(&s[run_start..i])?;
+            if self.form && c == ' ' {
+                self.out.write_whole("+")?;
+            } else {
+                let mut escape = [0u8; 12];
+                let mut len = 0;
+                for &b in &s.as_bytes()[i..i + c.len_utf8()] {
+                    escape[len] = b'%';
+                    escape[len + 1] = HEX[(b >> 4) as usize];
+                    escape[len + 2] = HEX[(b & 0xF) as usize];
+                    len += 3;
+                }
+                // only ASCII was put into the escape
+                self.out
+                    .write_whole(unsafe { core::str::from_utf8_unchecked(&escape[..len]) })?;
+            }
+            run_start = i + c.len_utf8();
+        }
+        self.out.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UrlBuilder;
+    use crate::WriteTo;
+
+    #[test]
+    fn url_segments_reserved_chars() {
+        let mut buf = [0u8; 128];
+        let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+        url.base("http://h")
+            .push_segment("a/b")
+            .push_segment("50%")
+            .push_segment("")
+            .push_segment("x?y#z")
+            .push_segment(42);
+        assert_eq!(url.finish(), ("http://h/a%2Fb/50%25//x%3Fy%23z/42", false));
+
+        let mut buf = [0u8; 128];
+        let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+        url.push_segment("a~b_c.d-e").push_segment("sp ace");
+        assert_eq!(url.finish(), ("/a~b_c.d-e/sp%20ace", false));
+    }
+
+    #[test]
+    fn url_unicode_query() {
+        let mut buf = [0u8; 128];
+        let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+        url.base("/search")
+            .query("q", "caf\u{E9} cr\u{E8}me")
+            .query("\u{20AC}", 5)
+            .push_segment("ignored");
+        assert_eq!(
+            url.finish(),
+            ("/search?q=caf%C3%A9+cr%C3%A8me&%E2%82%AC=5", false)
+        );
+    }
+
+    #[test]
+    fn url_no_query() {
+        let mut buf = [0u8; 64];
+        let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+        url.base("/api/").push_segment("v1");
+        assert_eq!(url.finish(), ("/api/v1", false));
+        assert_eq!(url.into_inner().as_str(), "/api/v1");
+    }
+
+    #[test]
+    fn url_overflow_mid_parameter() {
+        let mut buf = [0u8; 18];
+        let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+        url.base("/d").query("name", "\u{E9}t\u{E9}");
+        // the encoded char is not cut
+        assert_eq!(url.finish(), ("/d?name=%C3%A9t", true));
+
+        let mut buf = [0u8; 13];
+        let mut url = UrlBuilder::new(WriteTo::new(&mut buf));
+        url.base("/d").query("name", "\u{E9}t\u{E9}").query("x", 1);
+        assert_eq!(url.finish(), ("/d?name=", true));
+    }
+}
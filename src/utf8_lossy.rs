@@ -0,0 +1,114 @@
+//  ( /   @ @    ()  Displays bytes that are probably text like String::from_utf8_lossy
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+use core::fmt::Write;
+
+/// Display adapter that shows a byte slice as UTF-8 text replacing invalid sequences with
+/// U+FFFD.
+///
+/// Valid runs are written as is. Every maximal invalid subpart (WHATWG / Unicode "substitution
+/// of maximal subparts" rule, same as `String::from_utf8_lossy`) becomes one U+FFFD,
+/// including a multi-byte sequence cut at the end of the slice. `Debug` output is quoted
+/// and escaped like `str` and shows invalid bytes as `\xhh`.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("payload: {}", stackfmt::Utf8Lossy(b"ok\xFF!")),
+/// );
+/// assert_eq!(s, "payload: ok\u{FFFD}!");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Utf8Lossy<'a>(pub &'a [u8]);
+
+impl<'a> fmt::Display for Utf8Lossy<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in self.0.utf8_chunks() {
+            f.write_str(chunk.valid())?;
+            if !chunk.invalid().is_empty() {
+                f.write_char(char::REPLACEMENT_CHARACTER)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for Utf8Lossy<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        for chunk in self.0.utf8_chunks() {
+            for ch in chunk.valid().chars() {
+                if ch == '\'' {
+                    f.write_char(ch)?; // no need to escape single quote in a string
+                } else {
+                    write!(f, "{}", ch.escape_debug())?;
+                }
+            }
+            for b in chunk.invalid() {
+                write!(f, "\\x{:02x}", b)?;
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Utf8Lossy;
+
+    fn lossy<'a>(buf: &'a mut [u8], bytes: &[u8]) -> &'a str {
+        crate::fmt_truncate(buf, format_args!("{}", Utf8Lossy(bytes)))
+    }
+
+    #[test]
+    fn lossy_ascii() {
+        let mut buf = [0u8; 64];
+        assert_eq!(lossy(&mut buf, b"Hello 42"), "Hello 42");
+        assert_eq!(lossy(&mut buf, b""), "");
+    }
+
+    #[test]
+    fn lossy_valid_multi_byte() {
+        let mut buf = [0u8; 64];
+        let text = "\u{20AC}\u{10348}\u{E9}";
+        assert_eq!(lossy(&mut buf, text.as_bytes()), text);
+    }
+
+    #[test]
+    fn lossy_lone_continuation() {
+        let mut buf = [0u8; 64];
+        assert_eq!(lossy(&mut buf, b"a\x80\xBFb"), "a\u{FFFD}\u{FFFD}b");
+    }
+
+    #[test]
+    fn lossy_overlong() {
+        let mut buf = [0u8; 64];
+        // every byte of an overlong encoding is a separate maximal subpart
+        assert_eq!(lossy(&mut buf, b"\xC0\x80"), "\u{FFFD}\u{FFFD}");
+        assert_eq!(
+            lossy(&mut buf, b"\xE0\x80\x80x"),
+            "\u{FFFD}\u{FFFD}\u{FFFD}x"
+        );
+        // surrogate half
+        assert_eq!(lossy(&mut buf, b"\xED\xA0\x80"), "\u{FFFD}\u{FFFD}\u{FFFD}");
+    }
+
+    #[test]
+    fn lossy_cut_at_end() {
+        let mut buf = [0u8; 64];
+        assert_eq!(lossy(&mut buf, b"ab\xE2\x82"), "ab\u{FFFD}");
+        assert_eq!(lossy(&mut buf, b"\xF0\x90\x8D"), "\u{FFFD}");
+        // truncated sequence in the middle followed by valid char
+        assert_eq!(lossy(&mut buf, b"\xE2\x82x"), "\u{FFFD}x");
+    }
+
+    #[test]
+    fn lossy_debug() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:?}", Utf8Lossy(b"a\"\n'\xFF")));
+        assert_eq!(s, "\"a\\\"\\n'\\xff\"");
+    }
+}
@@ -0,0 +1,186 @@
+//  ( /   @ @    ()  UUID display from raw bytes, including short BLE forms
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use core::fmt;
+
+/// Display adapter for a UUID given as 16 bytes in RFC 4122 (big endian) order.
+///
+/// Shown as `8-4-4-4-12` lowercase hex digits, the alternate flag `{:#}` adds braces.
+/// `Debug` output is the same as `Display`.
+///
+/// ```
+/// let id = [
+///     0xf8, 0x1d, 0x4f, 0xae, 0x7d, 0xec, 0x11, 0xd0, 0xa7, 0x65, 0x00, 0xa0, 0xc9, 0x1e, 0x6b,
+///     0xf6,
+/// ];
+/// let mut buf = [0u8; 48];
+/// let s = stackfmt::fmt_truncate(&mut buf, format_args!("{}", stackfmt::UuidBytes(&id)));
+/// assert_eq!(s, "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+/// ```
+#[derive(Clone, Copy)]
+pub struct UuidBytes<'a>(pub &'a [u8; 16]);
+
+/// Display adapter for a UUID with uppercase hex digits, created by [UuidBytes::uppercase].
+#[derive(Clone, Copy)]
+pub struct UuidUpper<'a>(&'a [u8; 16]);
+
+/// Display adapter for 16-bit and 32-bit short BLE UUIDs shown as `0xXXXX` / `0xXXXXXXXX`.
+///
+/// Created by [UuidBytes::short16] and [UuidBytes::short32].
+#[derive(Clone, Copy)]
+pub struct ShortUuid {
+    value: u32,
+    digits: usize,
+    uppercase: bool,
+}
+
+impl<'a> UuidBytes<'a> {
+    /// Uses uppercase hex digits.
+    pub fn uppercase(self) -> UuidUpper<'a> {
+        UuidUpper(self.0)
+    }
+
+    /// Creates adapter for a 16-bit short BLE UUID like `0x180f`.
+    pub fn short16(value: u16) -> ShortUuid {
+        ShortUuid {
+            value: value as u32,
+            digits: 4,
+            uppercase: false,
+        }
+    }
+
+    /// Creates adapter for a 32-bit short BLE UUID like `0x0000180f`.
+    pub fn short32(value: u32) -> ShortUuid {
+        ShortUuid {
+            value,
+            digits: 8,
+            uppercase: false,
+        }
+    }
+}
+
+impl ShortUuid {
+    /// Uses uppercase hex digits (the prefix stays `0x`).
+    pub fn uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+}
+
+fn write_uuid(f: &mut fmt::Formatter, bytes: &[u8; 16], uppercase: bool) -> fmt::Result {
+    let braced = f.alternate();
+    if braced {
+        f.write_str("{")?;
+    }
+    for (n, b) in bytes.iter().enumerate() {
+        if n == 4 || n == 6 || n == 8 || n == 10 {
+            f.write_str("-")?;
+        }
+        if uppercase {
+            write!(f, "{:02X}", b)?;
+        } else {
+            write!(f, "{:02x}", b)?;
+        }
+    }
+    if braced {
+        f.write_str("}")?;
+    }
+    Ok(())
+}
+
+impl<'a> fmt::Display for UuidBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_uuid(f, self.0, false)
+    }
+}
+
+impl<'a> fmt::Debug for UuidBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_uuid(f, self.0, false)
+    }
+}
+
+impl<'a> fmt::Display for UuidUpper<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_uuid(f, self.0, true)
+    }
+}
+
+impl<'a> fmt::Debug for UuidUpper<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_uuid(f, self.0, true)
+    }
+}
+
+impl fmt::Display for ShortUuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.uppercase {
+            write!(f, "0x{:01$X}", self.value, self.digits)
+        } else {
+            write!(f, "0x{:01$x}", self.value, self.digits)
+        }
+    }
+}
+
+impl fmt::Debug for ShortUuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UuidBytes;
+
+    // Example from RFC 4122
+    const RFC_UUID: [u8; 16] = [
+        0xf8, 0x1d, 0x4f, 0xae, 0x7d, 0xec, 0x11, 0xd0, 0xa7, 0x65, 0x00, 0xa0, 0xc9, 0x1e, 0x6b,
+        0xf6,
+    ];
+
+    #[test]
+    fn uuid_nil() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", UuidBytes(&[0; 16])));
+        assert_eq!(s, "00000000-0000-0000-0000-000000000000");
+    }
+
+    #[test]
+    fn uuid_rfc_example() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:?}", UuidBytes(&RFC_UUID)));
+        assert_eq!(s, "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", UuidBytes(&RFC_UUID).uppercase()),
+        );
+        assert_eq!(s, "F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6");
+    }
+
+    #[test]
+    fn uuid_braced() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:#}", UuidBytes(&RFC_UUID)));
+        assert_eq!(s, "{f81d4fae-7dec-11d0-a765-00a0c91e6bf6}");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{:#}", UuidBytes(&RFC_UUID).uppercase()),
+        );
+        assert_eq!(s, "{F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6}");
+    }
+
+    #[test]
+    fn uuid_short_forms() {
+        let mut buf = [0u8; 64];
+        let s = crate::fmt_truncate(&mut buf, format_args!("{}", UuidBytes::short16(0x180F)));
+        assert_eq!(s, "0x180f");
+        let s = crate::fmt_truncate(
+            &mut buf,
+            format_args!("{}", UuidBytes::short16(0x2A).uppercase()),
+        );
+        assert_eq!(s, "0x002A");
+        let s = crate::fmt_truncate(&mut buf, format_args!("{:?}", UuidBytes::short32(0xFEAA)));
+        assert_eq!(s, "0x0000feaa");
+    }
+}
@@ -0,0 +1,158 @@
+//  ( /   @ @    ()  VT100 cursor and screen control sequences
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::cell::Cell;
+use core::fmt;
+
+/// Display adapter for a VT100 / ANSI control sequence, for UIs redrawn in place over a
+/// serial terminal.
+///
+/// Rows and columns are 1-based, 0 is shown as 1. The sequences are written directly, so
+/// several of them compose in a single `write!`.
+///
+/// ```
+/// use stackfmt::Vt100;
+///
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_truncate(
+///     &mut buf,
+///     format_args!("{}{}T={}{}", Vt100::ClearScreen, Vt100::cursor_to(2, 10), 21, Vt100::EraseToEol),
+/// );
+/// assert_eq!(s, "\x1b[2J\x1b[2;10HT=21\x1b[K");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Vt100 {
+    /// `CSI row ; col H`: moves the cursor
+    CursorTo { row: u16, col: u16 },
+    /// `CSI H`: moves the cursor to the top left corner
+    Home,
+    /// `CSI 2 J`: erases the whole screen, the cursor stays
+    ClearScreen,
+    /// `CSI 2 K`: erases the line of the cursor
+    ClearLine,
+    /// `CSI K`: erases from the cursor to the end of the line
+    EraseToEol,
+    /// `ESC 7`: saves the cursor position and attributes
+    SaveCursor,
+    /// `ESC 8`: restores what [Vt100::SaveCursor] saved
+    RestoreCursor,
+}
+
+impl Vt100 {
+    /// Moves the cursor to 1-based `row` and `col`.
+    pub fn cursor_to(row: u16, col: u16) -> Self {
+        Vt100::CursorTo {
+            row: row.max(1),
+            col: col.max(1),
+        }
+    }
+}
+
+impl fmt::Display for Vt100 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Vt100::CursorTo { row, col } => {
+                return write!(f, "\x1b[{};{}H", row.max(1), col.max(1));
+            }
+            Vt100::Home => "\x1b[H",
+            Vt100::ClearScreen => "\x1b[2J",
+            Vt100::ClearLine => "\x1b[2K",
+            Vt100::EraseToEol => "\x1b[K",
+            Vt100::SaveCursor => "\x1b7",
+            Vt100::RestoreCursor => "\x1b8",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Writes to fixed places of a terminal screen: each [Screen::put] moves the cursor and
+/// writes a [Cell], so the old value of the place is overwritten entirely.
+///
+/// ```
+/// use core::fmt::Alignment;
+/// use stackfmt::{Cell, Screen};
+///
+/// let mut buf = [0u8; 64];
+/// let mut screen = Screen::new(stackfmt::WriteTo::new(&mut buf));
+/// screen.put(1, 1, Cell::new("temp", 6)).unwrap();
+/// screen.put(1, 7, Cell::new(-5, 4).align(Alignment::Right)).unwrap();
+/// assert_eq!(screen.into_inner().as_str(), "\x1b[1;1Htemp  \x1b[1;7H  -5");
+/// ```
+pub struct Screen<W: fmt::Write> {
+    inner: W,
+}
+
+impl<W: fmt::Write> Screen<W> {
+    /// Creates screen writing into `inner`.
+    pub fn new(inner: W) -> Self {
+        Screen { inner }
+    }
+
+    /// Erases the screen and moves the cursor to the top left corner.
+    pub fn clear(&mut self) -> fmt::Result {
+        write!(self.inner, "{}{}", Vt100::ClearScreen, Vt100::Home)
+    }
+
+    /// Writes `cell` at 1-based `row` and `col`.
+    pub fn put<T: fmt::Display>(&mut self, row: u16, col: u16, cell: Cell<T>) -> fmt::Result {
+        write!(self.inner, "{}{}", Vt100::cursor_to(row, col), cell)
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Screen, Vt100};
+    use crate::{fmt_truncate, Cell, WriteTo};
+
+    #[test]
+    fn vt100_sequences() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!(
+                "{}{}{}{}{}{}",
+                Vt100::Home,
+                Vt100::ClearScreen,
+                Vt100::ClearLine,
+                Vt100::EraseToEol,
+                Vt100::SaveCursor,
+                Vt100::RestoreCursor
+            ),
+        );
+        assert_eq!(s.as_bytes(), b"\x1b[H\x1b[2J\x1b[2K\x1b[K\x1b7\x1b8");
+    }
+
+    #[test]
+    fn vt100_cursor_to() {
+        let mut buf = [0u8; 64];
+        let s = fmt_truncate(&mut buf, format_args!("{}", Vt100::cursor_to(1, 1)));
+        assert_eq!(s.as_bytes(), b"\x1b[1;1H");
+        let s = fmt_truncate(&mut buf, format_args!("{}", Vt100::cursor_to(12, 80)));
+        assert_eq!(s.as_bytes(), b"\x1b[12;80H");
+        let s = fmt_truncate(&mut buf, format_args!("{}", Vt100::cursor_to(100, 9999)));
+        assert_eq!(s.as_bytes(), b"\x1b[100;9999H");
+        let s = fmt_truncate(
+            &mut buf,
+            format_args!("{}", Vt100::CursorTo { row: 0, col: 0 }),
+        );
+        assert_eq!(s.as_bytes(), b"\x1b[1;1H");
+    }
+
+    #[test]
+    fn vt100_screen() {
+        let mut buf = [0u8; 64];
+        let mut screen = Screen::new(WriteTo::new(&mut buf));
+        screen.clear().unwrap();
+        screen.put(10, 15, Cell::new("overheated", 4)).unwrap();
+        assert_eq!(
+            screen.into_inner().as_str().as_bytes(),
+            b"\x1b[2J\x1b[H\x1b[10;15Hover"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//  ( /   @ @    ()  Greedy word wrapping of formatted output
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+use core::str::from_utf8_unchecked;
+
+// Capacity for the word that is not yet placed on a line
+const WORD_BUFFER_SIZE: usize = 64;
+
+/// Impl of [fmt::Write] that wraps lines at spaces so they are at most `width` chars long.
+///
+/// A line is broken at the last space that fits, and the space at the break point is
+/// removed. Words longer than `width` are broken hard. Spaces at the end of a line are
+/// dropped, as well as spaces at the start of a line created by a wrap; spaces at the start
+/// of an input line are kept. Every char counts as one column.
+///
+/// The current word is held back until it is known whether it fits, so
+/// [WordWrapWriter::finish] must be called at the end of the output. At most 64 bytes of a
+/// word are held, a longer word is broken as if it was longer than `width`. Width of 0
+/// disables wrapping.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_word_wrap(&mut buf, format_args!("the quick brown fox"), 10);
+/// assert_eq!(s, "the quick\nbrown fox");
+/// ```
+pub struct WordWrapWriter<W: fmt::Write> {
+    inner: W,
+    width: usize,
+    column: usize,   // Chars already written to the current line
+    spaces: usize,   // Spaces seen before the pending word
+    wrapped: bool,   // If the current line was started by a wrap
+    continued: bool, // If the pending word is a continuation of the written one
+    word: [u8; WORD_BUFFER_SIZE],
+    word_bytes: usize, // Bytes of the pending word
+    word_chars: usize, // Chars of the pending word
+}
+
+impl<W: fmt::Write> WordWrapWriter<W> {
+    /// Creates stream wrapping words at `width` chars.
+    pub fn new(inner: W, width: usize) -> Self {
+        WordWrapWriter {
+            inner,
+            width,
+            column: 0,
+            spaces: 0,
+            wrapped: false,
+            continued: false,
+            word: [0u8; WORD_BUFFER_SIZE],
+            word_bytes: 0,
+            word_chars: 0,
+        }
+    }
+
+    /// Writes the pending word and returns the inner stream, trailing spaces are dropped.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        self.place_word()?;
+        Ok(self.inner)
+    }
+
+    fn new_line(&mut self) -> fmt::Result {
+        self.inner.write_str("\n")?;
+        self.column = 0;
+        self.wrapped = true;
+        Ok(())
+    }
+
+    fn write_spaces(&mut self) -> fmt::Result {
+        for _ in 0..self.spaces {
+            self.inner.write_str(" ")?;
+        }
+        self.column += self.spaces;
+        Ok(())
+    }
+
+    // Writes the pending word either on the current line or on the next one
+    fn place_word(&mut self) -> fmt::Result {
+        if self.word_chars == 0 {
+            return Ok(());
+        }
+
+        if self.continued {
+            if self.column + self.word_chars > self.width {
+                self.new_line()?;
+            }
+        } else if self.column == 0 {
+            if !self.wrapped {
+                self.write_spaces()?;
+            }
+        } else if self.column + self.spaces + self.word_chars <= self.width {
+            self.write_spaces()?;
+        } else {
+            self.new_line()?;
+        }
+
+        // word buffer contains only complete chars
+        let word = unsafe { from_utf8_unchecked(&self.word[..self.word_bytes]) };
+        self.inner.write_str(word)?;
+        self.column += self.word_chars;
+        self.spaces = 0;
+        self.word_bytes = 0;
+        self.word_chars = 0;
+        Ok(())
+    }
+
+    fn push_char(&mut self, ch: char) -> fmt::Result {
+        match ch {
+            '\n' => {
+                self.place_word()?;
+                self.inner.write_str("\n")?;
+                self.column = 0;
+                self.spaces = 0;
+                self.wrapped = false;
+                self.continued = false;
+            }
+            ' ' => {
+                self.place_word()?;
+                self.continued = false;
+                self.spaces += 1;
+            }
+            _ => {
+                if self.word_chars == self.width
+                    || self.word_bytes + ch.len_utf8() > WORD_BUFFER_SIZE
+                {
+                    // the word cannot be held anymore, write what is collected so far
+                    self.place_word()?;
+                    self.continued = true;
+                }
+                ch.encode_utf8(&mut self.word[self.word_bytes..]);
+                self.word_bytes += ch.len_utf8();
+                self.word_chars += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for WordWrapWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.width == 0 {
+            return self.inner.write_str(s);
+        }
+        for ch in s.chars() {
+            self.push_char(ch)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes formatted string word wrapped at `width` chars into the buffer truncating if
+/// needed.
+pub fn fmt_word_wrap<'a>(buffer: &'a mut [u8], args: fmt::Arguments, width: usize) -> &'a str {
+    let mut w = WordWrapWriter::new(WriteTo::new(buffer), width);
+    match fmt::write(&mut w, args) {
+        Ok(_) => match w.finish() {
+            Ok(w) => w.as_str(),
+            Err(_) => "",
+        },
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordWrapWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn word_wrap_sentences() {
+        let mut buf = [0u8; 128];
+        let s = super::fmt_word_wrap(
+            &mut buf,
+            format_args!("Battery low, please connect the charger now"),
+            16,
+        );
+        assert_eq!(s, "Battery low,\nplease connect\nthe charger now");
+    }
+
+    #[test]
+    fn word_wrap_oversize_word() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_word_wrap(&mut buf, format_args!("go abcdefghij ok"), 4);
+        assert_eq!(s, "go\nabcd\nefgh\nij\nok");
+    }
+
+    #[test]
+    fn word_wrap_trailing_and_leading_spaces() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_word_wrap(&mut buf, format_args!("ab   \n  cd  ef   "), 10);
+        assert_eq!(s, "ab\n  cd  ef");
+        let s = super::fmt_word_wrap(&mut buf, format_args!("abc     def"), 5);
+        assert_eq!(s, "abc\ndef");
+    }
+
+    #[test]
+    fn word_wrap_chunk_inside_word() {
+        let mut buf = [0u8; 64];
+        let mut w = WordWrapWriter::new(WriteTo::new(&mut buf), 8);
+        w.write_str("hello wo").unwrap();
+        w.write_str("rld").unwrap();
+        write!(w, " {}", 42).unwrap();
+        assert_eq!(w.finish().unwrap().as_str(), "hello\nworld 42");
+    }
+
+    #[test]
+    fn word_wrap_width_smaller_than_words() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_word_wrap(&mut buf, format_args!("abc de"), 2);
+        assert_eq!(s, "ab\nc\nde");
+        let s = super::fmt_word_wrap(&mut buf, format_args!("\u{20AC}\u{20AC}\u{20AC} a"), 2);
+        assert_eq!(s, "\u{20AC}\u{20AC}\n\u{20AC}\na");
+    }
+
+    #[test]
+    fn word_wrap_long_word_buffer() {
+        // a word longer than the internal buffer but shorter than the width
+        let mut buf = [0u8; 256];
+        let mut w = WordWrapWriter::new(WriteTo::new(&mut buf), 200);
+        w.write_str("a ").unwrap();
+        for _ in 0..100 {
+            w.write_str("x").unwrap();
+        }
+        let s = w.finish().unwrap().as_str();
+        assert_eq!(s.len(), 102);
+        assert!(!s.contains('\n'));
+    }
+}
@@ -0,0 +1,125 @@
+//  ( /   @ @    ()  Hard wraps formatted output at a fixed column
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Impl of [fmt::Write] that inserts `\n` whenever the current line reaches `width` chars.
+///
+/// Every char counts as one column. The counter is reset by `\n` in the input, and no break
+/// is inserted right before an input `\n`, so a line of exactly `width` chars stays intact.
+/// Breaks are inserted between chars so a multi-byte char is never split. Width of 0
+/// disables wrapping.
+///
+/// ```
+/// let mut buf = [0u8; 32];
+/// let s = stackfmt::fmt_wrap(&mut buf, format_args!("{}", "abcdefgh"), 3);
+/// assert_eq!(s, "abc\ndef\ngh");
+/// ```
+pub struct WrapWriter<W: fmt::Write> {
+    inner: W,
+    width: usize,
+    column: usize, // Chars in the current line
+}
+
+impl<W: fmt::Write> WrapWriter<W> {
+    /// Creates stream wrapping lines at `width` chars.
+    pub fn new(inner: W, width: usize) -> Self {
+        WrapWriter {
+            inner,
+            width,
+            column: 0,
+        }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for WrapWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.width == 0 {
+            return self.inner.write_str(s);
+        }
+
+        let mut run_start = 0;
+        for (i, ch) in s.char_indices() {
+            if ch == '\n' {
+                self.column = 0;
+                continue;
+            }
+            if self.column == self.width {
+                self.inner.write_str(&s[run_start..i])?;
+                self.inner.write_str("\n")?;
+                run_start = i;
+                self.column = 0;
+            }
+            self.column += 1;
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+/// Writes formatted string wrapped at `width` chars into the buffer truncating if needed.
+pub fn fmt_wrap<'a>(buffer: &'a mut [u8], args: fmt::Arguments, width: usize) -> &'a str {
+    let mut w = WrapWriter::new(WriteTo::new(buffer), width);
+    match fmt::write(&mut w, args) {
+        Ok(_) => w.into_inner().as_str(),
+        Err(_) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WrapWriter;
+    use crate::WriteTo;
+    use core::fmt::Write;
+
+    #[test]
+    fn wrap_exactly_at_width() {
+        let mut buf = [0u8; 64];
+        assert_eq!(super::fmt_wrap(&mut buf, format_args!("abcd"), 4), "abcd");
+        assert_eq!(
+            super::fmt_wrap(&mut buf, format_args!("abcd\n"), 4),
+            "abcd\n"
+        );
+        assert_eq!(
+            super::fmt_wrap(&mut buf, format_args!("abcde"), 4),
+            "abcd\ne"
+        );
+    }
+
+    #[test]
+    fn wrap_explicit_newlines() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_wrap(&mut buf, format_args!("ab\nabcdef\n\nabc"), 3);
+        assert_eq!(s, "ab\nabc\ndef\n\nabc");
+    }
+
+    #[test]
+    fn wrap_multi_byte() {
+        let mut buf = [0u8; 64];
+        let s = super::fmt_wrap(&mut buf, format_args!("a\u{20AC}\u{10348}b\u{20AC}"), 2);
+        assert_eq!(s, "a\u{20AC}\n\u{10348}b\n\u{20AC}");
+    }
+
+    #[test]
+    fn wrap_width_one() {
+        let mut buf = [0u8; 64];
+        assert_eq!(super::fmt_wrap(&mut buf, format_args!("abc"), 1), "a\nb\nc");
+        assert_eq!(super::fmt_wrap(&mut buf, format_args!("abc"), 0), "abc");
+    }
+
+    #[test]
+    fn wrap_across_chunks() {
+        let mut buf = [0u8; 64];
+        let mut w = WrapWriter::new(WriteTo::new(&mut buf), 3);
+        w.write_str("ab").unwrap();
+        w.write_str("cd").unwrap();
+        write!(w, "{}", 123).unwrap();
+        assert_eq!(w.into_inner().as_str(), "abc\nd12\n3");
+    }
+}
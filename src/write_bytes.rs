@@ -0,0 +1,182 @@
+//  ( /   @ @    ()  Binary frames with embedded text
+//   \  __| |__  /   (c) 2019 - present, Vladimir Zvezda
+//    -/   "   \-
+//
+use crate::stackfmt::WriteTo;
+use core::fmt;
+
+/// Writes binary data and formatted text into one u8 buffer, like a protocol frame.
+///
+/// Binary data is accepted only as a whole: a field that does not fit is dropped. Text
+/// written through [WriteBytesTo::text] is truncated on a char boundary like in [WriteTo].
+/// Once something is dropped or truncated all further data is ignored, so trailing fields
+/// never land at a wrong offset.
+///
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut buf = [0u8; 16];
+/// let mut frame = stackfmt::WriteBytesTo::new(&mut buf);
+/// frame.push_bytes(&[0x7E, 0x01]);
+/// write!(frame.text(), "T={}", 21).unwrap();
+/// let crc: u16 = frame.as_bytes().iter().map(|b| *b as u16).sum();
+/// frame.push_u16_le(crc);
+/// let (bytes, truncated) = frame.finish();
+/// assert_eq!(bytes, b"\x7E\x01T=21\x73\x01");
+/// assert!(!truncated);
+/// ```
+pub struct WriteBytesTo<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+    overflow: bool,
+}
+
+impl<'a> WriteBytesTo<'a> {
+    /// Creates new frame builder over `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        WriteBytesTo {
+            buffer,
+            used: 0,
+            overflow: false,
+        }
+    }
+
+    /// Appends `bytes` if all of them fit, returns false if they were dropped.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> bool {
+        if self.overflow || self.buffer.len() - self.used < bytes.len() {
+            self.overflow = true;
+            return false;
+        }
+        self.buffer[self.used..self.used + bytes.len()].copy_from_slice(bytes);
+        self.used += bytes.len();
+        true
+    }
+
+    /// Appends a byte.
+    pub fn push_u8(&mut self, value: u8) -> bool {
+        self.push_bytes(&[value])
+    }
+
+    /// Appends u16 in little endian.
+    pub fn push_u16_le(&mut self, value: u16) -> bool {
+        self.push_bytes(&value.to_le_bytes())
+    }
+
+    /// Appends u16 in big endian.
+    pub fn push_u16_be(&mut self, value: u16) -> bool {
+        self.push_bytes(&value.to_be_bytes())
+    }
+
+    /// Appends u32 in little endian.
+    pub fn push_u32_le(&mut self, value: u32) -> bool {
+        self.push_bytes(&value.to_le_bytes())
+    }
+
+    /// Appends u32 in big endian.
+    pub fn push_u32_be(&mut self, value: u32) -> bool {
+        self.push_bytes(&value.to_be_bytes())
+    }
+
+    /// Returns [fmt::Write] view appending text after the data written so far.
+    pub fn text(&mut self) -> TextSection<'_, 'a> {
+        TextSection { frame: self }
+    }
+
+    /// Data written so far, e.g. to compute a checksum
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.used]
+    }
+
+    /// How many bytes were written into the buffer
+    pub fn written_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// True if some data did not fit into the buffer and was dropped
+    pub fn is_truncated(&self) -> bool {
+        self.overflow
+    }
+
+    /// Returns the written data and whether anything was dropped or truncated.
+    pub fn finish(self) -> (&'a [u8], bool) {
+        (&self.buffer[..self.used], self.overflow)
+    }
+}
+
+/// Text part of a [WriteBytesTo] frame, see [WriteBytesTo::text].
+pub struct TextSection<'f, 'a> {
+    frame: &'f mut WriteBytesTo<'a>,
+}
+
+impl<'f, 'a> fmt::Write for TextSection<'f, 'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let frame = &mut *self.frame;
+        if frame.overflow {
+            return Ok(()); // skip further inputs
+        }
+        let mut w = WriteTo::new(&mut frame.buffer[frame.used..]);
+        w.write_str(s)?;
+        frame.used += w.written_bytes();
+        frame.overflow = w.is_truncated();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WriteBytesTo;
+    use core::fmt::Write;
+
+    // header 2 bytes, text, crc 4 bytes
+    fn frame<'a>(buf: &'a mut [u8], text: &str) -> (&'a [u8], bool) {
+        let mut frame = WriteBytesTo::new(buf);
+        frame.push_u16_le(0x0201);
+        write!(frame.text(), "{}", text).unwrap();
+        frame.push_u32_be(0xCAFEBABE);
+        frame.finish()
+    }
+
+    #[test]
+    fn write_bytes_full_frame() {
+        let mut buf = [0u8; 16];
+        let (bytes, truncated) = frame(&mut buf, "\u{20AC}42");
+        assert_eq!(bytes, b"\x01\x02\xE2\x82\xAC42\xCA\xFE\xBA\xBE");
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn write_bytes_truncated_header() {
+        let mut buf = [0u8; 1];
+        let (bytes, truncated) = frame(&mut buf, "abc");
+        assert_eq!(bytes, b"");
+        assert!(truncated);
+    }
+
+    #[test]
+    fn write_bytes_truncated_text() {
+        let mut buf = [0u8; 6];
+        let (bytes, truncated) = frame(&mut buf, "ab\u{20AC}");
+        assert_eq!(bytes, b"\x01\x02ab");
+        assert!(truncated);
+    }
+
+    #[test]
+    fn write_bytes_truncated_crc() {
+        let mut buf = [0u8; 8];
+        let (bytes, truncated) = frame(&mut buf, "abc");
+        assert_eq!(bytes, b"\x01\x02abc");
+        assert!(truncated);
+    }
+
+    #[test]
+    fn write_bytes_ignores_data_after_truncation() {
+        let mut buf = [0u8; 4];
+        let mut frame = WriteBytesTo::new(&mut buf);
+        assert!(frame.push_u8(1));
+        assert!(!frame.push_u32_le(2));
+        assert!(!frame.push_u8(3));
+        write!(frame.text(), "x").unwrap();
+        assert_eq!(frame.written_bytes(), 1);
+        assert!(frame.is_truncated());
+    }
+}